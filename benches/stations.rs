@@ -0,0 +1,83 @@
+//! Benchmarks for the search result pipeline: parsing directory responses,
+//! then merging local and global results and ordering them
+//!
+//! Run with `cargo bench`; criterion compares against the previous run, so
+//! slowdowns from new station fields or larger result sets show up there.
+
+use cosmic_ext_applet_radio_lib::api::{self, Station};
+use cosmic_ext_applet_radio_lib::popularity::SortOrder;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::json;
+
+/// A directory response of `count` stations, with the `null`s and long tag
+/// lists real responses have
+fn payload(count: usize) -> Vec<u8> {
+    let stations: Vec<_> = (0..count)
+        .map(|i| {
+            json!({
+                "changeuuid": format!("c{i:08}-0000-0000-0000-000000000000"),
+                "stationuuid": format!("{i:08}-0000-0000-0000-000000000000"),
+                "name": format!("Station {i} FM"),
+                "url": format!("http://stream{i}.example.com/live"),
+                "url_resolved": format!("https://stream{i}.example.com/live.mp3"),
+                "homepage": format!("https://station{i}.example.com/"),
+                "favicon": format!("https://station{i}.example.com/favicon.png"),
+                "tags": "pop,rock,news,talk,80s,90s,charts,local",
+                "country": "Germany",
+                "countrycode": "DE",
+                "state": if i % 3 == 0 { serde_json::Value::Null } else { json!("Berlin") },
+                "language": "german",
+                "languagecodes": "de",
+                "votes": (i * 37) % 5000,
+                "lastchangetime_iso8601": "2024-01-01T00:00:00Z",
+                "codec": "MP3",
+                "bitrate": 128,
+                "hls": 0,
+                "lastcheckok": 1,
+                "clickcount": (i * 13) % 900,
+                "clicktrend": (i as i64 % 21) - 10,
+                "geo_lat": serde_json::Value::Null,
+                "geo_long": serde_json::Value::Null,
+            })
+        })
+        .collect();
+    serde_json::to_vec(&stations).expect("payload serializes")
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_stations");
+    for count in [20, 200, 2000] {
+        let bytes = payload(count);
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &bytes, |b, bytes| {
+            b.iter(|| api::parse_stations(black_box(bytes)).expect("valid payload"));
+        });
+    }
+    group.finish();
+}
+
+fn bench_dedupe_sort(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dedupe_sort");
+    for count in [200, 2000] {
+        let global = api::parse_stations(&payload(count)).expect("valid payload");
+        // Local results are a subset of the global ones, as for a country search
+        let local: Vec<Station> = global.iter().step_by(3).cloned().collect();
+        group.throughput(Throughput::Elements((local.len() + global.len()) as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(count),
+            &(local, global),
+            |b, (local, global)| {
+                b.iter(|| {
+                    let merged = api::local_first(local.clone(), global.clone(), u32::MAX);
+                    let mut sorted: Vec<&Station> = merged.iter().collect();
+                    SortOrder::Votes.sort(&mut sorted);
+                    black_box(sorted.len())
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_dedupe_sort);
+criterion_main!(benches);
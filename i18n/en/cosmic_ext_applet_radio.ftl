@@ -6,6 +6,8 @@ searching-status = Searching...
 error-message = Error:
 favorites-header = My Favorites:
 no-favorites = No favorites saved.
+starter-hint = Popular stations to get you started — star one to add it to your favorites.
+recommendations-header = Because you listen to { $name }
 back-to-favorites = ← Back to Favorites
 search-results-header = Search Results:
 
@@ -14,4 +16,431 @@ volume = Volume:
 not-playing = No station playing
 
 # Keyboard shortcuts
-shortcuts-hint = Space: play/pause • ↑↓: volume • Esc: close
+shortcuts-hint = Space: play/pause • B: previous station • ↑↓: volume • Esc: close
+
+# Casting
+cast-discover = Find speakers
+cast-searching = Looking for speakers...
+casting-to = Casting to
+cast-stop = Stop casting
+
+# Listening statistics
+stats-button = Stats
+discover-button = Discover
+discover-trending = Trending
+discover-new = New & Updated
+discover-empty = Nothing here right now.
+discover-loading = Loading new and trending stations...
+discover-unavailable = New and trending stations are unavailable offline.
+stats-total = Total listening:
+stats-data-used = Data used: { $session } this session, { $day } in 24 hours, { $month } in 30 days
+stats-week = Top this week
+stats-month = Top this month
+stats-empty = Nothing played yet.
+stats-export-csv = Export CSV
+stats-export-json = Export JSON
+stats-exported = Exported to
+stats-no-export-dir = no Downloads or home folder found
+
+# Genres
+genre-filter = Genre:
+discover-genres = Genres
+genre-pop = Pop
+genre-rock = Rock
+genre-metal = Metal
+genre-jazz = Jazz
+genre-classical = Classical
+genre-electronic = Electronic
+genre-dance = Dance
+genre-hiphop = Hip-Hop
+genre-soul = Soul & R&B
+genre-chillout = Chillout
+genre-country = Country
+genre-folk = Folk
+genre-blues = Blues
+genre-reggae = Reggae
+genre-latin = Latin
+genre-oldies = Oldies
+genre-world = World
+genre-news = News
+genre-talk = Talk
+genre-sports = Sports
+genre-religious = Religious
+genre-children = Children
+
+# Profiles
+profile-default = Default
+profile-new-placeholder = New profile…
+
+# Settings: backup and restore
+settings-button = Settings
+backup-header = Backup
+backup-create = Back up everything
+backup-written = Backup saved to
+backup-restore-placeholder = Path to .radio-backup.json
+backup-restore = Restore
+backup-restored = Backup restored.
+
+# Settings: content filter
+filter-header = Blocked keywords
+filter-placeholder = Tag or keyword to hide
+filter-add = Block
+filter-hidden-count = results hidden by your filters
+
+# Settings: hidden stations
+hidden-header = Hidden stations
+hidden-empty = No hidden stations.
+hidden-unhide = Unhide
+
+# Settings: network
+network-header = Network
+network-timeout = Request timeout
+network-timeout-value = { $secs }s
+network-limit = Results per search
+
+# Settings: cache
+cache-header = Cache
+cache-limit = Maximum size
+cache-limit-value = { $mb } MB
+cache-size = In use:
+cache-clear = Clear cache
+downloads-header = Downloads
+downloads-quota = Maximum space
+downloads-delete = Delete downloads
+
+# Settings: logging
+log-header = Logs
+log-level = Level
+log-open = Open log folder
+
+# Settings: preferred country
+country-header = Preferred country
+country-auto = From locale
+country-everywhere = Everywhere
+country-placeholder = Country code, e.g. DE
+country-invalid = Not a two-letter country code:
+language-filter = Only show stations in my languages on the start and Discover pages
+language-filter-desktop = From the desktop: { $languages }
+language-filter-none = The desktop's languages are unknown; add them below
+language-placeholder = Add a language, e.g. Spanish
+
+# Settings: playback
+playback-header = Playback
+resume-playback = Resume playback after a restart
+stream-preference = Station listed several times
+stream-as-listed = As listed
+stream-best-quality = Best quality
+stream-data-saver = Data saver
+normalization = Volume normalization
+normalization-dynamic = Dynamic
+normalization-loudness = Target loudness
+normalization-replaygain = ReplayGain
+normalization-off = Off
+normalization-hint = Dynamic evens out every station but flattens the music; target loudness and ReplayGain keep its dynamics. Not applied when casting.
+loudness-target = Target
+loudness-target-value = { $lufs } LUFS
+channels-mono = Mono (cleaner sound for weak stereo streams)
+channels-balance = Balance
+balance-left = Left { $amount }
+balance-center = Center
+balance-right = Right { $amount }
+fetch-cover-art = Look up album art on MusicBrainz
+show-favicons = Show station logos
+show-lyrics = Show lyrics from LRCLIB
+show-visualizer = Show a level visualizer
+show-visualizer-hint = Hidden while reduced motion is on or when casting.
+
+# Accessibility: names read by screen readers for icon-only controls
+a11y-stop = Stop playback
+a11y-volume = Volume
+a11y-play-station = Play { $name }
+a11y-stop-station = Stop { $name }
+a11y-switch-station = Switch back to { $name }
+a11y-seek = Playback position
+a11y-download = Download for offline listening
+a11y-pause-download = Pause download
+a11y-resume-download = Resume download
+a11y-delete-download = Delete download
+download-hint = Download this episode to listen offline
+download-progress = { $mb } of { $total } MB
+download-progress-unknown = { $mb } MB
+download-done = Downloaded, plays offline
+download-failed = Download failed: { $error }
+a11y-record = Record { $name }
+a11y-stop-recording = Stop recording
+a11y-play-recording = Play { $name }
+a11y-reveal-recording = Show { $name } in the file manager
+a11y-rename-recording = Rename { $name }
+a11y-delete-recording = Delete { $name }
+recording-progress = Recording { $name } · { $mb } MB
+recording-saved = Saved recording { $name }
+recording-failed = Recording failed: { $error }
+recordings-button = Recordings
+recordings-header = Recordings
+recordings-empty = Nothing recorded yet. Start a recording from the playing station's record button.
+recordings-rename = Rename
+recordings-retention = Delete recordings after
+recordings-keep = Never
+recordings-retention-days = { $days } days
+recordings-format = Save recordings as
+recordings-format-copy = Stream as received
+recordings-format-opus = Opus
+recordings-format-mp3 = MP3
+recordings-bitrate = Bitrate (needs ffmpeg)
+recordings-bitrate-kbps = { $kbps } kbit/s
+recording-schedule-header = Scheduled recordings
+recording-schedule-description = Records this station at the times below, while the applet runs, into a folder of the recordings.
+recording-folder-placeholder = Folder (optional)
+recording-rule-add = Record at the days and times above
+recording-rule-overlaps = These times overlap another scheduled recording. Only one recording runs at a time.
+recording-skipped-title = Did not record { $name }
+recording-skipped-body = { $other } was being recorded at the time.
+a11y-remove-recording-rule = Stop recording { $when }
+a11y-favorite-add = Add { $name } to favorites
+a11y-favorite-remove = Remove { $name } from favorites
+a11y-hide = Hide { $name } from search results
+a11y-unblock = Unblock { $keyword }
+
+# Settings: appearance
+appearance-header = Appearance
+icon-style = Panel icon
+icon-style-radio = Radio
+icon-style-player = Media player
+panel-scroll = Scroll over the panel icon to change the volume
+panel-scroll-step = Step per notch
+panel-scroll-modifier = Modifier keys
+panel-scroll-shift-fine = Shift: 1% steps
+panel-scroll-ctrl-required = Only with Ctrl
+panel-scroll-ignored = Ignore
+panel-click-left = Click on the panel icon
+panel-click-middle = Middle click
+panel-click-double = Double click
+click-action-nothing = Nothing
+click-action-popup = Open
+click-action-playback = Play/stop
+click-action-next = Next favorite
+click-action-mute = Mute
+ticker = Show what's playing next to the panel icon
+ticker-width = Maximum width
+ticker-width-value = { $chars } characters
+ticker-speed = Scrolling speed
+ticker-speed-value = { $chars } characters/s
+
+# Like and vote
+a11y-like = Like { $name }
+vote-sent = Added to favorites and voted on radio-browser.info.
+vote-already = In your favorites; you already voted for this station.
+vote-favorited = Added to favorites.
+vote-failed = In your favorites, but the vote failed:
+
+# Sharing
+a11y-share = Copy link to { $name }
+a11y-copy-stream = Copy stream URL of { $name }
+share-link-copied = Link copied
+share-stream-copied = Stream URL copied
+share-no-link = This station has no link to share
+
+# Result ordering
+sort-by = Sort:
+sort-relevance = Relevance
+sort-votes = Votes
+sort-clicks = Popular today
+
+# Track history
+a11y-track-history = Track history
+a11y-copy-track = Copy { $title }
+tracks-session = Heard this session
+tracks-station = Earlier on { $name }
+tracks-empty = No track titles yet.
+tracks-minutes-ago = { $minutes } min ago
+track-copied = Track title copied
+
+# Bookmarked songs
+a11y-bookmark-track = I like this track: { $title }
+a11y-remove-bookmark = Remove { $title } from bookmarks
+bookmark-saved = Song bookmarked
+bookmark-exists = Already bookmarked
+bookmarks-header = Bookmarked songs
+bookmarks-empty = No bookmarked songs yet.
+bookmarks-export-text = Export text
+
+# Stream diagnostics
+a11y-diagnostics = Stream diagnostics
+diagnostics-title = Stream diagnostics
+diagnostics-probing = Checking the stream…
+diagnostics-copy = Copy report
+diagnostics-copied = Diagnostics copied
+
+# Program guide
+a11y-schedule = Program guide of { $name }
+a11y-remove-show = Remove { $title }
+on-air = On air: { $title }
+schedule-header = Program guide of { $name }
+schedule-empty = No shows scheduled.
+schedule-new-show = Add a show (local time)
+schedule-title-placeholder = Show title
+schedule-add = Add
+schedule-invalid = Enter a title, at least one day, and start and end times such as 07:00.
+schedule-ical-placeholder = iCal URL of the station's schedule
+schedule-import = Import
+schedule-importing = Importing…
+schedule-imported = Imported { $count } shows.
+schedule-reminder = Remind me before shows:
+schedule-reminder-off = Off
+schedule-reminder-minutes = { $minutes } min
+schedule-daily = Daily
+schedule-weekdays = Weekdays
+day-mon = Mon
+day-tue = Tue
+day-wed = Wed
+day-thu = Thu
+day-fri = Fri
+day-sat = Sat
+day-sun = Sun
+show-reminder-title = { $title } starts soon
+show-reminder-body = On { $station } at { $time }
+
+# Settings: event hooks
+hooks-header = Event hooks
+hooks-hint = Shell commands run on playback events. Details are passed in RADIO_EVENT, RADIO_STATION, RADIO_STATION_UUID, RADIO_STATION_URL, RADIO_TRACK, and RADIO_ERROR.
+hook-on-play = On play
+hook-on-stop = On stop
+hook-on-track-change = On track change
+hook-on-error = On error
+hook-placeholder = Command, e.g. notify-send "$RADIO_TRACK"
+hooks-save = Save hooks
+hooks-saved = Hooks saved
+
+# Settings: local control endpoint
+control-header = Local control
+control-hint = An HTTP endpoint on this computer for Stream Deck plugins, browser extensions, and scripts. Requests need the token in an "Authorization: Bearer" header.
+control-enable = Enable local control endpoint
+control-address = Listening on { $address }
+control-copy-token = Copy token
+control-token-copied = Token copied to clipboard
+
+# Mini-player window
+a11y-mini-player = Open mini-player window
+mini-player-title = Radio
+
+# Station details tooltip
+station-tags = Tags: { $tags }
+station-country = Country: { $country }
+station-language = Language: { $language }
+station-check-ok = Stream worked at the last check
+station-check-failed = Stream failed the last check
+
+# Settings: privacy
+privacy-header = Privacy
+privacy-hint = Searches are never saved. Listening history feeds the statistics page; track logs feed the track history.
+privacy-disable-history = Don't record listening history or track logs
+privacy-incognito = Incognito listening until the applet restarts
+privacy-clear-history = Clear listening history
+privacy-clear-tracks = Clear track logs
+privacy-history-cleared = Listening history cleared
+privacy-tracks-cleared = Track logs cleared
+
+# Batch selection
+select-button = Select
+select-all = Select all
+select-done = Done
+selected-count = { $count } selected
+batch-favorite = Favorite
+batch-hide = Hide
+batch-queue = Queue
+batch-export = Export
+batch-add-to-profile = Add to { $profile }
+batch-favorited = Added { $count } stations to favorites
+batch-added-to-profile = Added { $count } stations to { $profile }
+batch-hidden = Hid { $count } stations
+
+# Local matches
+local-matches-header = Known stations
+
+# Favorites refresh
+station-broken = ⚠ Failing in the directory
+a11y-find-replacement = Find a replacement for { $name }
+
+# Replacements for broken favorites
+replacement-offer = { $name } seems broken. Replace it with:
+replacement-use = Replace
+replacement-dismiss = Keep
+
+# Favorites health check
+health-check = Check favorites
+health-checking = Checking { $count } favorites…
+health-summary = { $working } working, { $broken } broken
+station-unreachable = ⚠ Stream not answering
+station-not-stream = ⚠ Not an audio stream
+
+# Reliability scores
+reliability-score = { $score }% reliable
+
+# Station queue
+a11y-queue = Add { $name } to the queue
+queue-added = Queued { $count } stations
+queue-count = Queue: { $count } stations
+queue-play = Play queue
+queue-next = Next
+queue-clear = Clear
+queue-dwell = Queued stations play for
+queue-dwell-value = { $secs } s
+queue-dwell-manual = until Next
+
+# Duplicate favorites
+duplicate-prompt = { $name } looks like your favorite { $favorite }. Merge them?
+duplicate-merge = Merge
+duplicate-add = Add anyway
+
+# Lock mode
+kiosk-header = Lock mode
+kiosk-hint = Locking leaves only playing favorites: no search, no settings, no changes to favorites. Set a PIN of 4 to 8 digits to require it for unlocking, or leave it empty.
+kiosk-pin-placeholder = PIN (optional)
+kiosk-lock = Lock
+kiosk-invalid-pin = The PIN must be 4 to 8 digits
+kiosk-locked-header = Locked
+kiosk-unlock = Unlock
+kiosk-unlocked = Unlocked
+kiosk-wrong-pin = Wrong PIN
+
+# Smart preset
+preset-header = Smart preset
+preset-description = When nothing is playing, the play button starts this station at the times below.
+preset-add = Play at the days and times above
+preset-fallback = Play at all other times
+preset-invalid = Pick at least one day and start and end times such as 07:00.
+a11y-remove-preset = Remove { $when }
+
+# Settings: address family
+network-ip-family = Connect streams over
+network-ip-auto = Fastest
+network-ipv4 = IPv4
+network-ipv6 = IPv6
+
+# Stream diagnostics: TLS
+tls-upgrade-https = Use HTTPS when the station offers it
+tls-allow-invalid = Accept invalid certificates for this station
+tls-allow-invalid-warning = ⚠ The certificate is not checked: anyone on the network could alter this stream.
+channels-station = Own mono and balance settings for this station
+
+# Stream content check
+stream-web-page = { $name } links to a web page, not an audio stream.
+stream-not-audio = { $name } does not serve audio ({ $kind }).
+
+# Error notifications
+playback-stopped = Playback stopped
+
+# Settings: ducking
+ducking = Lower the volume during calls and notification sounds
+ducking-amount = Lower by
+
+# Settings: output device
+device-pause = Pause when the output device disconnects
+device-auto-resume = Resume when it reconnects
+
+# Quiet hours
+quiet-hours = Quiet hours: cap the volume and hold back notifications
+quiet-start = From
+quiet-end = Until
+quiet-max-volume = Volume at most
+quiet-capped = Quiet hours: volume capped at { $volume }%
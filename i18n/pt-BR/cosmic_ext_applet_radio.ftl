@@ -0,0 +1,446 @@
+app-title = Rádio para COSMIC
+window-title = Rádio para COSMIC
+search-placeholder = Buscar estações (ex.: Jazz)...
+search-button = Buscar
+searching-status = Buscando...
+error-message = Erro:
+favorites-header = Meus Favoritos:
+no-favorites = Nenhum favorito salvo.
+starter-hint = Estações populares para começar — marque uma com estrela para adicioná-la aos favoritos.
+recommendations-header = Porque você ouve { $name }
+back-to-favorites = ← Voltar aos Favoritos
+search-results-header = Resultados da Busca:
+
+# Volume control
+volume = Volume:
+not-playing = Nenhuma estação tocando
+
+# Keyboard shortcuts
+shortcuts-hint = Espaço: tocar/pausar • B: estação anterior • ↑↓: volume • Esc: fechar
+
+# Casting
+cast-discover = Procurar caixas de som
+cast-searching = Procurando caixas de som...
+casting-to = Transmitindo para
+cast-stop = Parar transmissão
+
+# Listening statistics
+stats-button = Estatísticas
+discover-button = Descobrir
+discover-trending = Em alta
+discover-new = Novas e atualizadas
+discover-empty = Nada por aqui no momento.
+discover-loading = Carregando estações novas e em alta...
+discover-unavailable = Estações novas e em alta não estão disponíveis offline.
+stats-total = Tempo total ouvindo:
+stats-data-used = Dados usados: { $session } nesta sessão, { $day } em 24 horas, { $month } em 30 dias
+stats-week = Mais ouvidas na semana
+stats-month = Mais ouvidas no mês
+stats-empty = Nada tocado ainda.
+stats-export-csv = Exportar CSV
+stats-export-json = Exportar JSON
+stats-exported = Exportado para
+stats-no-export-dir = nenhuma pasta Downloads ou pessoal encontrada
+
+# Genres
+genre-filter = Gênero:
+discover-genres = Gêneros
+genre-pop = Pop
+genre-rock = Rock
+genre-metal = Metal
+genre-jazz = Jazz
+genre-classical = Clássica
+genre-electronic = Eletrônica
+genre-dance = Dance
+genre-hiphop = Hip-hop
+genre-soul = Soul e R&B
+genre-chillout = Chillout
+genre-country = Country
+genre-folk = Folk
+genre-blues = Blues
+genre-reggae = Reggae
+genre-latin = Latina
+genre-oldies = Antigas
+genre-world = Música do mundo
+genre-news = Notícias
+genre-talk = Entrevistas e debates
+genre-sports = Esportes
+genre-religious = Religiosa
+genre-children = Infantil
+
+# Profiles
+profile-default = Padrão
+profile-new-placeholder = Novo perfil…
+
+# Settings: backup and restore
+settings-button = Configurações
+backup-header = Backup
+backup-create = Fazer backup de tudo
+backup-written = Backup salvo em
+backup-restore-placeholder = Caminho para .radio-backup.json
+backup-restore = Restaurar
+backup-restored = Backup restaurado.
+
+# Settings: content filter
+filter-header = Palavras bloqueadas
+filter-placeholder = Tag ou palavra a ocultar
+filter-add = Bloquear
+filter-hidden-count = resultados ocultados pelos seus filtros
+
+# Settings: hidden stations
+hidden-header = Estações ocultas
+hidden-empty = Nenhuma estação oculta.
+hidden-unhide = Mostrar
+
+# Settings: network
+network-header = Rede
+network-timeout = Tempo limite da requisição
+network-timeout-value = { $secs }s
+network-limit = Resultados por busca
+
+# Settings: cache
+cache-header = Cache
+cache-limit = Tamanho máximo
+cache-limit-value = { $mb } MB
+cache-size = Em uso:
+cache-clear = Limpar cache
+downloads-header = Downloads
+downloads-quota = Espaço máximo
+downloads-delete = Excluir downloads
+
+# Settings: logging
+log-header = Logs
+log-level = Nível
+log-open = Abrir pasta de logs
+
+# Settings: preferred country
+country-header = País preferido
+country-auto = Pelo idioma do sistema
+country-everywhere = Todos
+country-placeholder = Código do país, ex.: BR
+country-invalid = Não é um código de país de duas letras:
+language-filter = Mostrar só estações nos meus idiomas no início e em Descobrir
+language-filter-desktop = Do desktop: { $languages }
+language-filter-none = Os idiomas do desktop são desconhecidos; adicione-os abaixo
+language-placeholder = Adicionar um idioma em inglês, p. ex. Spanish
+
+# Settings: playback
+playback-header = Reprodução
+resume-playback = Retomar a reprodução após reiniciar
+stream-preference = Estação listada várias vezes
+stream-as-listed = Como listada
+stream-best-quality = Melhor qualidade
+stream-data-saver = Economia de dados
+normalization = Normalização de volume
+normalization-dynamic = Dinâmica
+normalization-loudness = Loudness alvo
+normalization-replaygain = ReplayGain
+normalization-off = Desligada
+normalization-hint = A dinâmica iguala todas as estações mas achata a música; loudness alvo e ReplayGain preservam a dinâmica. Não se aplica ao transmitir.
+loudness-target = Alvo
+loudness-target-value = { $lufs } LUFS
+channels-mono = Mono (som mais limpo para transmissões estéreo fracas)
+channels-balance = Balanço
+balance-left = Esquerda { $amount }
+balance-center = Centro
+balance-right = Direita { $amount }
+fetch-cover-art = Buscar capas de álbum no MusicBrainz
+show-favicons = Mostrar logos das estações
+show-lyrics = Mostrar letras do LRCLIB
+show-visualizer = Mostrar visualizador de nível
+show-visualizer-hint = Oculto com movimento reduzido ativado ou ao transmitir para outro dispositivo.
+
+# Accessibility: names read by screen readers for icon-only controls
+a11y-stop = Parar reprodução
+a11y-volume = Volume
+a11y-play-station = Tocar { $name }
+a11y-stop-station = Parar { $name }
+a11y-switch-station = Voltar para { $name }
+a11y-seek = Posição da reprodução
+a11y-download = Baixar para ouvir offline
+a11y-pause-download = Pausar download
+a11y-resume-download = Continuar download
+a11y-delete-download = Excluir download
+download-hint = Baixe este episódio para ouvir offline
+download-progress = { $mb } de { $total } MB
+download-progress-unknown = { $mb } MB
+download-done = Baixado, toca offline
+download-failed = Falha no download: { $error }
+a11y-record = Gravar { $name }
+a11y-stop-recording = Parar gravação
+a11y-play-recording = Reproduzir { $name }
+a11y-reveal-recording = Mostrar { $name } no gerenciador de arquivos
+a11y-rename-recording = Renomear { $name }
+a11y-delete-recording = Excluir { $name }
+recording-progress = Gravando { $name } · { $mb } MB
+recording-saved = Gravação { $name } salva
+recording-failed = Falha na gravação: { $error }
+recordings-button = Gravações
+recordings-header = Gravações
+recordings-empty = Nada gravado ainda. Inicie uma gravação pelo botão de gravar da estação em reprodução.
+recordings-rename = Renomear
+recordings-retention = Excluir gravações após
+recordings-keep = Nunca
+recordings-retention-days = { $days } dias
+recordings-format = Salvar gravações como
+recordings-format-copy = Stream como recebido
+recordings-format-opus = Opus
+recordings-format-mp3 = MP3
+recordings-bitrate = Taxa de bits (requer ffmpeg)
+recordings-bitrate-kbps = { $kbps } kbit/s
+recording-schedule-header = Gravações agendadas
+recording-schedule-description = Grava esta estação nos horários abaixo, enquanto o applet estiver em execução, em uma pasta das gravações.
+recording-folder-placeholder = Pasta (opcional)
+recording-rule-add = Gravar nos dias e horários acima
+recording-rule-overlaps = Estes horários coincidem com outra gravação agendada. Só uma gravação é feita por vez.
+recording-skipped-title = { $name } não foi gravada
+recording-skipped-body = { $other } estava sendo gravada no momento.
+a11y-remove-recording-rule = Parar de gravar { $when }
+a11y-favorite-add = Adicionar { $name } aos favoritos
+a11y-favorite-remove = Remover { $name } dos favoritos
+a11y-hide = Ocultar { $name } dos resultados da busca
+a11y-unblock = Desbloquear { $keyword }
+
+# Settings: appearance
+appearance-header = Aparência
+icon-style = Ícone do painel
+icon-style-radio = Rádio
+icon-style-player = Reprodutor de mídia
+panel-scroll = Rolar sobre o ícone do painel muda o volume
+panel-scroll-step = Passo por entalhe
+panel-scroll-modifier = Teclas modificadoras
+panel-scroll-shift-fine = Shift: passos de 1%
+panel-scroll-ctrl-required = Só com Ctrl
+panel-scroll-ignored = Ignorar
+panel-click-left = Clique no ícone do painel
+panel-click-middle = Clique do meio
+panel-click-double = Clique duplo
+click-action-nothing = Nada
+click-action-popup = Abrir
+click-action-playback = Tocar/parar
+click-action-next = Próximo favorito
+click-action-mute = Silenciar
+ticker = Mostrar o que está tocando ao lado do ícone do painel
+ticker-width = Largura máxima
+ticker-width-value = { $chars } caracteres
+ticker-speed = Velocidade de rolagem
+ticker-speed-value = { $chars } caracteres/s
+
+# Like and vote
+a11y-like = Curtir { $name }
+vote-sent = Adicionada aos favoritos e votada no radio-browser.info.
+vote-already = Nos seus favoritos; você já votou nesta estação.
+vote-favorited = Adicionada aos favoritos.
+vote-failed = Nos seus favoritos, mas o voto falhou:
+
+# Sharing
+a11y-share = Copiar link de { $name }
+a11y-copy-stream = Copiar URL do stream de { $name }
+share-link-copied = Link copiado
+share-stream-copied = URL do stream copiada
+share-no-link = Esta estação não tem link para compartilhar
+
+# Result ordering
+sort-by = Ordenar:
+sort-relevance = Relevância
+sort-votes = Votos
+sort-clicks = Populares hoje
+
+# Histórico de faixas
+a11y-track-history = Histórico de faixas
+a11y-copy-track = Copiar { $title }
+tracks-session = Ouvidas nesta sessão
+tracks-station = Antes em { $name }
+tracks-empty = Nenhum título de faixa ainda.
+tracks-minutes-ago = há { $minutes } min
+track-copied = Título da faixa copiado
+
+# Músicas salvas
+a11y-bookmark-track = Gostei desta faixa: { $title }
+a11y-remove-bookmark = Remover { $title } das músicas salvas
+bookmark-saved = Música salva
+bookmark-exists = Música já salva
+bookmarks-header = Músicas salvas
+bookmarks-empty = Nenhuma música salva ainda.
+bookmarks-export-text = Exportar texto
+
+# Stream diagnostics
+a11y-diagnostics = Diagnóstico do stream
+diagnostics-title = Diagnóstico do stream
+diagnostics-probing = Verificando o stream…
+diagnostics-copy = Copiar relatório
+diagnostics-copied = Diagnóstico copiado
+
+# Program guide
+a11y-schedule = Programação de { $name }
+a11y-remove-show = Remover { $title }
+on-air = No ar: { $title }
+schedule-header = Programação de { $name }
+schedule-empty = Nenhum programa agendado.
+schedule-new-show = Adicionar programa (horário local)
+schedule-title-placeholder = Nome do programa
+schedule-add = Adicionar
+schedule-invalid = Informe um nome, pelo menos um dia e horários de início e fim como 07:00.
+schedule-ical-placeholder = URL iCal da programação da estação
+schedule-import = Importar
+schedule-importing = Importando…
+schedule-imported = { $count } programas importados.
+schedule-reminder = Lembrar antes dos programas:
+schedule-reminder-off = Desligado
+schedule-reminder-minutes = { $minutes } min
+schedule-daily = Todos os dias
+schedule-weekdays = Dias úteis
+day-mon = Seg
+day-tue = Ter
+day-wed = Qua
+day-thu = Qui
+day-fri = Sex
+day-sat = Sáb
+day-sun = Dom
+show-reminder-title = { $title } começa em breve
+show-reminder-body = Na { $station } às { $time }
+
+# Settings: event hooks
+hooks-header = Ganchos de eventos
+hooks-hint = Comandos de shell executados em eventos de reprodução. Os detalhes são passados em RADIO_EVENT, RADIO_STATION, RADIO_STATION_UUID, RADIO_STATION_URL, RADIO_TRACK e RADIO_ERROR.
+hook-on-play = Ao tocar
+hook-on-stop = Ao parar
+hook-on-track-change = Ao mudar de faixa
+hook-on-error = Em caso de erro
+hook-placeholder = Comando, ex.: notify-send "$RADIO_TRACK"
+hooks-save = Salvar ganchos
+hooks-saved = Ganchos salvos
+
+# Settings: local control endpoint
+control-header = Controle local
+control-hint = Um endpoint HTTP neste computador para plugins do Stream Deck, extensões de navegador e scripts. As requisições precisam do token em um cabeçalho "Authorization: Bearer".
+control-enable = Ativar endpoint de controle local
+control-address = Escutando em { $address }
+control-copy-token = Copiar token
+control-token-copied = Token copiado para a área de transferência
+
+# Mini-player window
+a11y-mini-player = Abrir janela do minirreprodutor
+mini-player-title = Rádio
+
+# Station details tooltip
+station-tags = Tags: { $tags }
+station-country = País: { $country }
+station-language = Idioma: { $language }
+station-check-ok = A transmissão funcionou na última verificação
+station-check-failed = A transmissão falhou na última verificação
+
+# Settings: privacy
+privacy-header = Privacidade
+privacy-hint = As buscas nunca são salvas. O histórico de audição alimenta a página de estatísticas; os registros de faixas alimentam o histórico de faixas.
+privacy-disable-history = Não registrar histórico de audição nem registros de faixas
+privacy-incognito = Audição anônima até o applet reiniciar
+privacy-clear-history = Limpar histórico de audição
+privacy-clear-tracks = Limpar registros de faixas
+privacy-history-cleared = Histórico de audição limpo
+privacy-tracks-cleared = Registros de faixas limpos
+
+# Seleção em lote
+select-button = Selecionar
+select-all = Selecionar todas
+select-done = Concluir
+selected-count = { $count } selecionadas
+batch-favorite = Favoritar
+batch-hide = Ocultar
+batch-queue = Enfileirar
+batch-export = Exportar
+batch-add-to-profile = Adicionar a { $profile }
+batch-favorited = { $count } estações adicionadas aos favoritos
+batch-added-to-profile = { $count } estações adicionadas a { $profile }
+batch-hidden = { $count } estações ocultadas
+
+# Resultados locais
+local-matches-header = Estações conhecidas
+
+# Atualização dos favoritos
+station-broken = ⚠ Com falha no diretório
+a11y-find-replacement = Procurar substituta para { $name }
+
+# Substitutas para favoritos com falha
+replacement-offer = { $name } parece estar com falha. Substituir por:
+replacement-use = Substituir
+replacement-dismiss = Manter
+
+# Favorites health check
+health-check = Verificar favoritos
+health-checking = Verificando { $count } favoritos…
+health-summary = { $working } funcionando, { $broken } com falha
+station-unreachable = ⚠ Transmissão não responde
+station-not-stream = ⚠ Não é uma transmissão de áudio
+
+# Reliability scores
+reliability-score = { $score }% confiável
+
+# Station queue
+a11y-queue = Adicionar { $name } à fila
+queue-added = { $count } estações adicionadas à fila
+queue-count = Fila: { $count } estações
+queue-play = Tocar fila
+queue-next = Próxima
+queue-clear = Limpar
+queue-dwell = Estações da fila tocam por
+queue-dwell-value = { $secs } s
+queue-dwell-manual = até Próxima
+
+# Favoritos duplicados
+duplicate-prompt = { $name } parece ser o seu favorito { $favorite }. Mesclar?
+duplicate-merge = Mesclar
+duplicate-add = Adicionar mesmo assim
+
+# Modo bloqueado
+kiosk-header = Modo bloqueado
+kiosk-hint = Bloquear deixa apenas a reprodução dos favoritos: sem busca, sem configurações, sem mudanças nos favoritos. Defina um PIN de 4 a 8 dígitos para exigi-lo ao desbloquear, ou deixe em branco.
+kiosk-pin-placeholder = PIN (opcional)
+kiosk-lock = Bloquear
+kiosk-invalid-pin = O PIN deve ter de 4 a 8 dígitos
+kiosk-locked-header = Bloqueado
+kiosk-unlock = Desbloquear
+kiosk-unlocked = Desbloqueado
+kiosk-wrong-pin = PIN incorreto
+
+# Smart preset
+preset-header = Predefinição inteligente
+preset-description = Quando nada estiver tocando, o botão de reproduzir inicia esta estação nos horários abaixo.
+preset-add = Tocar nos dias e horários acima
+preset-fallback = Tocar em todos os outros horários
+preset-invalid = Escolha pelo menos um dia e horários de início e fim como 07:00.
+a11y-remove-preset = Remover { $when }
+
+# Settings: address family
+network-ip-family = Conectar streams por
+network-ip-auto = Mais rápido
+network-ipv4 = IPv4
+network-ipv6 = IPv6
+
+# Stream diagnostics: TLS
+tls-upgrade-https = Usar HTTPS quando a estação oferecer
+tls-allow-invalid = Aceitar certificados inválidos para esta estação
+tls-allow-invalid-warning = ⚠ O certificado não é verificado: qualquer pessoa na rede poderia alterar este stream.
+channels-station = Configurações próprias de mono e balanço para esta estação
+
+# Stream content check
+stream-web-page = { $name } aponta para uma página web, não para um stream de áudio.
+stream-not-audio = { $name } não fornece áudio ({ $kind }).
+
+# Error notifications
+playback-stopped = A reprodução parou
+
+# Settings: ducking
+ducking = Baixar o volume durante chamadas e sons de notificação
+ducking-amount = Baixar em
+
+# Settings: output device
+device-pause = Pausar quando o dispositivo de saída for desconectado
+device-auto-resume = Retomar quando ele for reconectado
+
+# Quiet hours
+quiet-hours = Horas de silêncio: limitar o volume e segurar notificações
+quiet-start = Das
+quiet-end = Até
+quiet-max-volume = Volume no máximo
+quiet-capped = Horas de silêncio: volume limitado a { $volume }%
@@ -1,10 +1,83 @@
+use crate::error::ApiError;
+use crate::ratelimit;
 use reqwest::Error;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, warn};
+use std::ops::RangeInclusive;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tracing::{debug, debug_span, warn, Instrument};
 
-/// Maximum response body size (1MB) to prevent memory exhaustion attacks
-/// This is sufficient for 20 station records with metadata
-const MAX_RESPONSE_SIZE: usize = 1024 * 1024;
+/// Maximum response body size (4MB) to prevent memory exhaustion attacks
+/// This is sufficient for `MAX_SEARCH_LIMIT` station records with metadata
+pub(crate) const MAX_RESPONSE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Default per-request timeout in seconds
+pub const DEFAULT_TIMEOUT_SECS: u32 = 5;
+
+/// Allowed request timeouts in seconds
+pub const TIMEOUT_SECS_RANGE: RangeInclusive<u32> = 1..=60;
+
+/// Default number of results per search
+pub const DEFAULT_SEARCH_LIMIT: u32 = 20;
+
+const MAX_SEARCH_LIMIT: u32 = 200;
+
+/// Allowed results per search
+pub const SEARCH_LIMIT_RANGE: RangeInclusive<u32> = 10..=MAX_SEARCH_LIMIT;
+
+/// Timeout and result limit for directory requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestOptions {
+    pub timeout: Duration,
+    pub limit: u32,
+}
+
+impl RequestOptions {
+    /// Build options from configured values, clamped to the allowed ranges
+    #[must_use]
+    pub fn new(timeout_secs: u32, limit: u32) -> Self {
+        Self {
+            timeout: Duration::from_secs(u64::from(clamp_timeout(timeout_secs))),
+            limit: clamp_limit(limit),
+        }
+    }
+}
+
+/// Identifies the applet to radio-browser.info and other directories
+const USER_AGENT: &str = concat!("cosmic-ext-applet-radio/", env!("CARGO_PKG_VERSION"));
+
+static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(Duration::from_secs(u64::from(*TIMEOUT_SECS_RANGE.end())))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+});
+
+/// Process-wide HTTP client, so connections and TLS sessions are reused
+/// across requests. Set a per-request timeout; the client's own is only a cap.
+#[must_use]
+pub fn http_client() -> &'static reqwest::Client {
+    &HTTP_CLIENT
+}
+
+impl Default for RequestOptions {
+    fn default() -> Self {
+        Self::new(DEFAULT_TIMEOUT_SECS, DEFAULT_SEARCH_LIMIT)
+    }
+}
+
+/// Clamp a timeout to [`TIMEOUT_SECS_RANGE`]
+#[must_use]
+pub fn clamp_timeout(secs: u32) -> u32 {
+    secs.clamp(*TIMEOUT_SECS_RANGE.start(), *TIMEOUT_SECS_RANGE.end())
+}
+
+/// Clamp a result limit to [`SEARCH_LIMIT_RANGE`]
+#[must_use]
+pub fn clamp_limit(limit: u32) -> u32 {
+    limit.clamp(*SEARCH_LIMIT_RANGE.start(), *SEARCH_LIMIT_RANGE.end())
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct Station {
@@ -24,13 +97,38 @@ pub struct Station {
     pub tags: String,
     #[serde(default)]
     pub country: String,
+    /// ISO 3166-1 alpha-2 code of `country`
+    #[serde(default)]
+    pub countrycode: String,
     #[serde(default)]
     pub language: String,
+    /// Label of the provider this station came from (e.g. "Radio Browser")
+    #[serde(default)]
+    pub source: String,
+    /// Votes on radio-browser.info
+    #[serde(default)]
+    pub votes: u32,
+    /// Plays through radio-browser.info in the last 24 hours
+    #[serde(default)]
+    pub clickcount: u32,
+    /// Change in daily plays over the last two days
+    #[serde(default)]
+    pub clicktrend: i32,
+    /// Stream codec, e.g. "MP3" or "AAC"
+    #[serde(default)]
+    pub codec: String,
+    /// Stream bitrate in kbit/s (0 = unknown)
+    #[serde(default)]
+    pub bitrate: u32,
+    /// Whether the stream worked at radio-browser.info's last check
+    /// (`None` = never checked, e.g. stations of other providers)
+    #[serde(default)]
+    pub lastcheckok: Option<bool>,
 }
 
 /// Intermediate struct to handle null values from API JSON
 #[derive(Deserialize)]
-struct ApiStation {
+pub(crate) struct ApiStation {
     #[serde(default)]
     stationuuid: Option<String>,
     #[serde(default)]
@@ -48,7 +146,22 @@ struct ApiStation {
     #[serde(default)]
     country: Option<String>,
     #[serde(default)]
+    countrycode: Option<String>,
+    #[serde(default)]
     language: Option<String>,
+    #[serde(default)]
+    votes: Option<u32>,
+    #[serde(default)]
+    clickcount: Option<u32>,
+    #[serde(default)]
+    clicktrend: Option<i32>,
+    #[serde(default)]
+    codec: Option<String>,
+    #[serde(default)]
+    bitrate: Option<u32>,
+    /// 1 if the last check succeeded, 0 if it failed
+    #[serde(default)]
+    lastcheckok: Option<u8>,
 }
 
 impl From<ApiStation> for Station {
@@ -62,11 +175,25 @@ impl From<ApiStation> for Station {
             favicon: api.favicon.unwrap_or_default(),
             tags: api.tags.unwrap_or_default(),
             country: api.country.unwrap_or_default(),
+            countrycode: api.countrycode.unwrap_or_default(),
             language: api.language.unwrap_or_default(),
+            source: String::new(),
+            votes: api.votes.unwrap_or_default(),
+            clickcount: api.clickcount.unwrap_or_default(),
+            clicktrend: api.clicktrend.unwrap_or_default(),
+            codec: api.codec.unwrap_or_default(),
+            bitrate: api.bitrate.unwrap_or_default(),
+            lastcheckok: api.lastcheckok.map(|ok| ok != 0),
         }
     }
 }
 
+/// Parse a radio-browser.info station list, whose fields may be `null`
+pub fn parse_stations(bytes: &[u8]) -> serde_json::Result<Vec<Station>> {
+    serde_json::from_slice::<Vec<ApiStation>>(bytes)
+        .map(|stations| stations.into_iter().map(Station::from).collect())
+}
+
 /// Mirror servers for radio-browser.info API redundancy
 const API_SERVERS: &[&str] = &[
     "https://all.api.radio-browser.info",
@@ -78,82 +205,272 @@ const API_SERVERS: &[&str] = &[
     "https://es1.api.radio-browser.info",
 ];
 
-/// Search for radio stations by name
-pub async fn search_stations(query: String) -> Result<Vec<Station>, Error> {
+/// Search for radio stations by name.
+///
+/// With a `country_code`, stations from that country are listed first,
+/// followed by matches from everywhere else.
+pub async fn search_stations(
+    query: String,
+    country_code: Option<String>,
+    options: RequestOptions,
+) -> Result<Vec<Station>, Error> {
     if query.trim().is_empty() {
         return Ok(Vec::new());
     }
 
-    debug!("Searching stations for '{}'", query);
+    debug!("Searching stations for '{}' ({:?})", query, country_code);
+
+    let limit = options.limit.to_string();
+    let params = [("name", query.as_str()), ("limit", limit.as_str())];
+    let Some(code) = country_code else {
+        return query_servers(&params, &options).await;
+    };
+
+    let local_params = [
+        ("name", query.as_str()),
+        ("countrycode", code.as_str()),
+        ("limit", limit.as_str()),
+    ];
+    let (local, global) = futures::join!(
+        query_servers(&local_params, &options),
+        query_servers(&params, &options)
+    );
+    match (local, global) {
+        (Err(_), Err(e)) => Err(e),
+        (local, global) => Ok(debug_span!("dedupe").in_scope(|| {
+            local_first(
+                local.unwrap_or_default(),
+                global.unwrap_or_default(),
+                options.limit,
+            )
+        })),
+    }
+}
 
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-        .unwrap_or_else(|_| reqwest::Client::new());
+/// Merge local and global results, local first, without duplicates
+#[must_use]
+pub fn local_first(local: Vec<Station>, global: Vec<Station>, limit: u32) -> Vec<Station> {
+    let mut seen = std::collections::HashSet::new();
+    local
+        .into_iter()
+        .chain(global)
+        .filter(|s| s.stationuuid.is_empty() || seen.insert(s.stationuuid.clone()))
+        .take(limit as usize)
+        .collect()
+}
+
+/// Most listened stations, optionally limited to an ISO 3166-1 country code
+pub async fn popular_stations(
+    country_code: Option<String>,
+    limit: u32,
+    options: RequestOptions,
+) -> Result<Vec<Station>, Error> {
+    debug!("Fetching popular stations for {:?}", country_code);
+
+    let limit = limit.to_string();
+    let mut params = vec![
+        ("order", "clickcount"),
+        ("reverse", "true"),
+        ("hidebroken", "true"),
+        ("limit", limit.as_str()),
+    ];
+    if let Some(code) = &country_code {
+        params.push(("countrycode", code.as_str()));
+    }
+    query_servers(&params, &options).await
+}
+
+/// Most listened stations with a tag
+pub async fn stations_by_tag(
+    tag: String,
+    limit: u32,
+    options: RequestOptions,
+) -> Result<Vec<Station>, Error> {
+    debug!("Fetching stations tagged '{}'", tag);
+
+    let limit = limit.to_string();
+    let params = [
+        ("tag", tag.as_str()),
+        ("order", "clickcount"),
+        ("reverse", "true"),
+        ("hidebroken", "true"),
+        ("limit", limit.as_str()),
+    ];
+    query_servers(&params, &options).await
+}
+
+/// Stations most recently added to or edited in the directory
+pub async fn new_stations(limit: u32, options: RequestOptions) -> Result<Vec<Station>, Error> {
+    debug!("Fetching new stations");
+
+    let limit = limit.to_string();
+    let params = [("hidebroken", "true"), ("limit", limit.as_str())];
+    query_endpoint(API_SERVERS, "lastchange", &params, &options).await
+}
+
+/// Stations whose daily plays grew the most over the last two days
+pub async fn trending_stations(limit: u32, options: RequestOptions) -> Result<Vec<Station>, Error> {
+    debug!("Fetching trending stations");
+
+    let limit = limit.to_string();
+    let params = [
+        ("order", "clicktrend"),
+        ("reverse", "true"),
+        ("hidebroken", "true"),
+        ("limit", limit.as_str()),
+    ];
+    query_servers(&params, &options).await
+}
 
+/// Current directory entries of stations, by UUID
+pub async fn stations_by_uuid(
+    uuids: &[String],
+    options: RequestOptions,
+) -> Result<Vec<Station>, Error> {
+    debug!("Looking up {} stations by UUID", uuids.len());
+
+    let uuids = uuids.join(",");
+    let params = [("uuids", uuids.as_str())];
+    query_endpoint(API_SERVERS, "byuuid", &params, &options).await
+}
+
+/// Reply of the radio-browser.info vote endpoint
+#[derive(Deserialize)]
+struct VoteReply {
+    #[serde(default)]
+    ok: bool,
+    #[serde(default)]
+    message: String,
+}
+
+/// Whether a station comes from radio-browser.info and can be voted for
+#[must_use]
+pub fn is_votable(station: &Station) -> bool {
+    !station.stationuuid.is_empty()
+        && (station.source.is_empty() || station.source == crate::providers::RADIO_BROWSER_LABEL)
+}
+
+/// Vote for a station on radio-browser.info.
+///
+/// The directory counts one vote per station and client every ten minutes and
+/// answers a repeated vote with `ok: false`, reported as
+/// [`ApiError::ErrorResponse`].
+pub async fn vote(stationuuid: String, options: RequestOptions) -> Result<(), ApiError> {
+    vote_on(API_SERVERS, &stationuuid, &options).await
+}
+
+/// Vote for a station on the first of `servers` that answers
+pub async fn vote_on(
+    servers: &[&str],
+    stationuuid: &str,
+    options: &RequestOptions,
+) -> Result<(), ApiError> {
     let mut last_error: Option<Error> = None;
 
-    for server in API_SERVERS {
-        let url = format!("{}/json/stations/search", server);
-        let params = [("name", query.as_str()), ("limit", "20")];
-
-        match client.get(&url).query(&params).send().await {
-            Ok(response) => match response.error_for_status() {
-                Ok(valid_response) => {
-                    // Check Content-Length header first if available (early rejection)
-                    if let Some(content_length) = valid_response.content_length() {
-                        if content_length as usize > MAX_RESPONSE_SIZE {
-                            warn!(
-                                "Response from {} exceeds size limit: {} bytes (max: {})",
-                                server, content_length, MAX_RESPONSE_SIZE
-                            );
-                            continue;
-                        }
-                    }
-
-                    // Read response body as bytes with size validation
-                    match valid_response.bytes().await {
-                        Ok(bytes) => {
-                            if bytes.len() > MAX_RESPONSE_SIZE {
-                                warn!(
-                                    "Response body from {} exceeds size limit: {} bytes (max: {})",
-                                    server,
-                                    bytes.len(),
-                                    MAX_RESPONSE_SIZE
-                                );
-                                continue;
-                            }
-
-                            // Deserialize from validated bytes
-                            match serde_json::from_slice::<Vec<ApiStation>>(&bytes) {
-                                Ok(api_stations) => {
-                                    debug!("Found {} stations from {}", api_stations.len(), server);
-                                    return Ok(api_stations.into_iter().map(Station::from).collect());
-                                }
-                                Err(e) => {
-                                    warn!("JSON parse error from {}: {}", server, e);
-                                    // Continue to next server on parse error
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            warn!("Failed to read response body from {}: {}", server, e);
-                            last_error = Some(e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    warn!("HTTP error from {}: {}", server, e);
-                    last_error = Some(e);
-                }
-            },
+    for server in servers {
+        let mut url = match url::Url::parse(server) {
+            Ok(url) => url,
             Err(e) => {
-                warn!("Connection error to {}: {}", server, e);
+                warn!("Invalid mirror URL {}: {}", server, e);
+                continue;
+            }
+        };
+        url.path_segments_mut()
+            .map_err(|()| ApiError::InvalidResponse(format!("invalid mirror URL {server}")))?
+            .pop_if_empty()
+            .extend(["json", "vote", stationuuid]);
+
+        ratelimit::directory().await;
+        let response = http_client()
+            .get(url)
+            .timeout(options.timeout)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status);
+        match response {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                let reply: VoteReply = response.json().await?;
+                debug!("Vote for {} on {}: {}", stationuuid, server, reply.message);
+                return if reply.ok {
+                    Ok(())
+                } else {
+                    Err(ApiError::ErrorResponse {
+                        status,
+                        message: reply.message,
+                    })
+                };
+            }
+            Err(e) => {
+                warn!("Vote request to {} failed: {}", server, e);
                 last_error = Some(e);
             }
         }
     }
 
+    Err(last_error.map_or_else(
+        || ApiError::InvalidResponse("no directory server to vote on".to_string()),
+        ApiError::from,
+    ))
+}
+
+/// Run a station search against the radio-browser.info mirrors
+async fn query_servers(
+    params: &[(&str, &str)],
+    options: &RequestOptions,
+) -> Result<Vec<Station>, Error> {
+    query_mirrors(API_SERVERS, params, options).await
+}
+
+/// Run a station search against each of `servers` in order until one answers
+pub async fn query_mirrors(
+    servers: &[&str],
+    params: &[(&str, &str)],
+    options: &RequestOptions,
+) -> Result<Vec<Station>, Error> {
+    query_endpoint(servers, "search", params, options).await
+}
+
+/// Query a station list endpoint (`/json/stations/<endpoint>`) on each of
+/// `servers` in order until one answers. Each attempt waits its turn with
+/// the [rate limiter](crate::ratelimit).
+///
+/// Mirrors that fail to connect, time out, or return an error status are
+/// skipped, and the last such error is returned if none answers. Oversized or
+/// malformed bodies are skipped as well but do not count as errors, so if
+/// every mirror sends one the result is empty.
+pub async fn query_endpoint(
+    servers: &[&str],
+    endpoint: &str,
+    params: &[(&str, &str)],
+    options: &RequestOptions,
+) -> Result<Vec<Station>, Error> {
+    let mut last_error: Option<Error> = None;
+
+    for server in servers {
+        let url = format!("{}/json/stations/{}", server, endpoint);
+
+        ratelimit::directory().await;
+        let span = debug_span!("mirror", mirror = %server, endpoint);
+        let started = Instant::now();
+        let result = query_mirror(&url, params, options)
+            .instrument(span.clone())
+            .await;
+        span.in_scope(|| {
+            debug!(
+                duration_ms = started.elapsed().as_millis() as u64,
+                ok = result.as_ref().is_ok_and(Option::is_some),
+                "Mirror attempt finished"
+            );
+        });
+        match result {
+            Ok(Some(stations)) => return Ok(stations),
+            // Continue to next server
+            Ok(None) => {}
+            Err(e) => last_error = Some(e),
+        }
+    }
+
     // All servers failed - return the last error or empty result
     match last_error {
         Some(e) => Err(e),
@@ -161,6 +478,62 @@ pub async fn search_stations(query: String) -> Result<Vec<Station>, Error> {
     }
 }
 
+/// One attempt of [`query_endpoint`]: the stations, `None` for an oversized
+/// or malformed body, or the request error
+async fn query_mirror(
+    url: &str,
+    params: &[(&str, &str)],
+    options: &RequestOptions,
+) -> Result<Option<Vec<Station>>, Error> {
+    let response = http_client()
+        .get(url)
+        .query(params)
+        .timeout(options.timeout)
+        .send()
+        .await
+        .inspect_err(|e| warn!("Connection error: {}", e))?
+        .error_for_status()
+        .inspect_err(|e| warn!("HTTP error: {}", e))?;
+
+    // Check Content-Length header first if available (early rejection)
+    if let Some(content_length) = response.content_length() {
+        if content_length as usize > MAX_RESPONSE_SIZE {
+            warn!(
+                "Response exceeds size limit: {} bytes (max: {})",
+                content_length, MAX_RESPONSE_SIZE
+            );
+            return Ok(None);
+        }
+    }
+
+    // Read response body as bytes with size validation
+    let bytes = response
+        .bytes()
+        .await
+        .inspect_err(|e| warn!("Failed to read response body: {}", e))?;
+    if bytes.len() > MAX_RESPONSE_SIZE {
+        warn!(
+            "Response body exceeds size limit: {} bytes (max: {})",
+            bytes.len(),
+            MAX_RESPONSE_SIZE
+        );
+        return Ok(None);
+    }
+
+    // Deserialize from validated bytes
+    let _parse = debug_span!("parse", bytes = bytes.len()).entered();
+    match parse_stations(&bytes) {
+        Ok(stations) => {
+            debug!("Found {} stations", stations.len());
+            Ok(Some(stations))
+        }
+        Err(e) => {
+            warn!("JSON parse error: {}", e);
+            Ok(None)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -178,6 +551,7 @@ mod tests {
         assert_eq!(station.tags, "");
         assert_eq!(station.country, "");
         assert_eq!(station.language, "");
+        assert_eq!(station.source, "");
     }
 
     #[test]
@@ -267,13 +641,23 @@ mod tests {
             favicon: "http://favicon.com".to_string(),
             tags: "test".to_string(),
             country: "TestLand".to_string(),
+            countrycode: "TL".to_string(),
             language: "TestLang".to_string(),
+            source: "Radio Browser".to_string(),
+            votes: 12,
+            clickcount: 3,
+            clicktrend: -1,
+            codec: "AAC".to_string(),
+            bitrate: 128,
+            lastcheckok: Some(true),
         };
 
         let json = serde_json::to_value(&station).unwrap();
         assert_eq!(json["stationuuid"], "test-uuid");
         assert_eq!(json["name"], "Test Station");
         assert_eq!(json["url"], "http://test.com");
+        assert_eq!(json["source"], "Radio Browser");
+        assert_eq!(json["votes"], 12);
     }
 
     #[test]
@@ -319,7 +703,14 @@ mod tests {
             favicon: None,
             tags: None,
             country: None,
+            countrycode: Some("AT".to_string()),
             language: None,
+            votes: Some(7),
+            clickcount: None,
+            clicktrend: Some(40),
+            codec: None,
+            bitrate: Some(128),
+            lastcheckok: Some(0),
         };
 
         let station: Station = api_station.into();
@@ -327,19 +718,133 @@ mod tests {
         assert_eq!(station.name, "Name");
         assert_eq!(station.url, "");
         assert_eq!(station.url_resolved, "resolved");
+        assert_eq!(station.votes, 7);
+        assert_eq!(station.clickcount, 0);
+        assert_eq!(station.clicktrend, 40);
+        assert_eq!(station.codec, "");
+        assert_eq!(station.bitrate, 128);
+        assert_eq!(station.countrycode, "AT");
+        assert_eq!(station.lastcheckok, Some(false));
+    }
+
+    #[test]
+    fn test_request_options_clamped() {
+        let options = RequestOptions::new(0, 5000);
+        assert_eq!(options.timeout, Duration::from_secs(1));
+        assert_eq!(options.limit, MAX_SEARCH_LIMIT);
+
+        let options = RequestOptions::new(30, 100);
+        assert_eq!(options.timeout, Duration::from_secs(30));
+        assert_eq!(options.limit, 100);
+    }
+
+    #[test]
+    fn test_request_options_default() {
+        let options = RequestOptions::default();
+        assert_eq!(options.timeout, Duration::from_secs(5));
+        assert_eq!(options.limit, 20);
+    }
+
+    #[test]
+    fn test_local_first_orders_and_dedups() {
+        let station = |uuid: &str| Station {
+            stationuuid: uuid.to_string(),
+            ..Default::default()
+        };
+        let merged = local_first(
+            vec![station("de1"), station("de2")],
+            vec![station("us1"), station("de1"), station("us2")],
+            4,
+        );
+        let ids: Vec<&str> = merged.iter().map(|s| s.stationuuid.as_str()).collect();
+        assert_eq!(ids, vec!["de1", "de2", "us1", "us2"]);
+
+        let merged = local_first(vec![], vec![station("a"), station("b")], 1);
+        assert_eq!(merged.len(), 1);
+    }
+
+    #[test]
+    fn test_is_votable() {
+        let mut station = Station {
+            stationuuid: "uuid".to_string(),
+            ..Default::default()
+        };
+        assert!(is_votable(&station));
+        station.source = crate::providers::RADIO_BROWSER_LABEL.to_string();
+        assert!(is_votable(&station));
+        station.source = "SomaFM".to_string();
+        assert!(!is_votable(&station));
+        assert!(!is_votable(&Station::default()));
     }
 
     #[tokio::test]
     async fn test_search_stations_empty_query() {
-        let result = search_stations("".to_string()).await;
+        let result = search_stations("".to_string(), None, RequestOptions::default()).await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap().len(), 0);
     }
 
     #[tokio::test]
     async fn test_search_stations_whitespace_query() {
-        let result = search_stations("   ".to_string()).await;
+        let result = search_stations("   ".to_string(), None, RequestOptions::default()).await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap().len(), 0);
     }
+
+    mod prop {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn field(value: &Option<String>) -> serde_json::Value {
+            value.as_ref().map_or(serde_json::Value::Null, |v| json!(v))
+        }
+
+        proptest! {
+            #[test]
+            fn parse_arbitrary_bytes_never_panics(bytes in any::<Vec<u8>>()) {
+                let _ = serde_json::from_slice::<Vec<ApiStation>>(&bytes);
+            }
+
+            #[test]
+            fn parse_arbitrary_text_never_panics(text in ".*") {
+                let _ = serde_json::from_str::<Vec<ApiStation>>(&text);
+            }
+
+            #[test]
+            fn null_or_string_fields_convert(
+                name in prop::option::of(".*"),
+                url in prop::option::of(".*"),
+                country in prop::option::of(".*"),
+                tags in prop::option::of(".*"),
+            ) {
+                let value = json!([{
+                    "name": field(&name),
+                    "url": field(&url),
+                    "country": field(&country),
+                    "tags": field(&tags),
+                    "unknown_field": 42
+                }]);
+                let bytes = serde_json::to_vec(&value).unwrap();
+                let stations: Vec<Station> = serde_json::from_slice::<Vec<ApiStation>>(&bytes)
+                    .unwrap()
+                    .into_iter()
+                    .map(Station::from)
+                    .collect();
+                prop_assert_eq!(stations.len(), 1);
+                prop_assert_eq!(&stations[0].name, &name.unwrap_or_default());
+                prop_assert_eq!(&stations[0].url, &url.unwrap_or_default());
+                prop_assert_eq!(&stations[0].country, &country.unwrap_or_default());
+                prop_assert_eq!(&stations[0].tags, &tags.unwrap_or_default());
+                prop_assert_eq!(&stations[0].stationuuid, "");
+            }
+
+            #[test]
+            fn station_count_preserved(names in prop::collection::vec(".*", 0..50)) {
+                let value: Vec<_> = names.iter().map(|n| json!({ "name": n })).collect();
+                let bytes = serde_json::to_vec(&value).unwrap();
+                let stations = serde_json::from_slice::<Vec<ApiStation>>(&bytes).unwrap();
+                prop_assert_eq!(stations.len(), names.len());
+            }
+        }
+    }
 }
@@ -1,22 +1,122 @@
-use crate::api::{self, Station};
-use crate::audio::AudioManager;
+use crate::api::{self, RequestOptions, Station};
+use crate::appearance::{self, Appearance};
+use crate::audio::{self, AudioEvent, AudioManager, AudioOutput, PlaybackOptions};
+use crate::autopause::{self, AutoPauseAction};
+use crate::backup::{self, Backup};
+use crate::bandwidth::{self, Meter};
+use crate::bookmarks::{self, BookmarkFormat, SongBookmark};
+use crate::cache::{self, Cache};
+use crate::cast::{self, CastDevice};
+use crate::channels::{self, ChannelConfig};
+use crate::clicks::{self, ClickAction, ClickKind, Clicks, Press};
 use crate::config::Config;
+use crate::control::{self, ControlCommand, ControlEvent, ControlState, StationRef};
+use crate::coverart;
+use crate::diagnostics::{self, Diagnostics, Probe};
+use crate::discover::{self, Discover, Starters};
+use crate::downloads::{self, Download, DownloadEvent, DownloadState};
+use crate::ducking;
+use crate::duplicates;
+use crate::favicons::{self, Favicons};
+use crate::filter;
 use crate::fl;
+use crate::fuzzy;
+use crate::genres::{self, Genre};
+use crate::health::{self, Problem};
+use crate::hooks::{self, HookContext, HookEvent, HooksConfig};
+use crate::i18n;
+use crate::icons::{IconStyle, PanelState};
+use crate::index::StationIndex;
+use crate::instance::{self, Instance, Role};
+use crate::ipfamily::{self, IpPreference};
+use crate::kiosk;
+use crate::locale::{self, CountryFilter};
+use crate::logging::{self, LogLevel};
+use crate::loudness::{self, Normalization};
+use crate::lyrics::{self, Lyrics};
+use crate::migrations;
 use crate::mpris::{self, MprisStateUpdate};
+use crate::mqtt::{self, MqttCommand, MqttState};
+use crate::notify::{self, Notifier};
+use crate::nowplaying;
+use crate::opml;
+use crate::outputs::{self, DeviceAction, Outputs};
+use crate::playlist;
+use crate::popularity::{self, SortOrder};
+use crate::profiles::{self, ProfileData};
+use crate::providers;
+use crate::quality::{self, StreamPreference};
+use crate::queue::{self, Queue};
+use crate::quiet;
+use crate::recommend::{self, Recommendations};
+use crate::recorder::{self, ActiveRecording, RecordEvent, Recording, RecordingFormat};
+use crate::redirect;
+use crate::refresh;
+use crate::reliability;
+use crate::replace;
+use crate::resume;
+use crate::schedule::{self, LocalTime, RecordingRule, ShowDraft};
+use crate::secrets;
+use crate::selection::{self, Selection};
+use crate::session::{self, Page, SessionState};
+use crate::share::{self, ShareTarget};
+use crate::shutdown::{self, ShutdownSignal};
+use crate::sniff::{self, Rejection};
+use crate::startup::{self, Startup, StartupRequest};
+use crate::stats::{self, ExportFormat, ListenSession};
+use crate::streamauth::{self, StreamAuth};
+use crate::suspend::{self, SleepEvent};
+use crate::sync::{self, FollowerEvent, SyncCommand, SyncEvent, SyncState};
+use crate::ticker;
+use crate::tls;
+use crate::tracklog::{self, TrackEntry};
+use crate::visualizer::Visualizer;
+use crate::wheel::{self, ScrollModifier, Wheel};
 use cosmic::cosmic_config::CosmicConfigEntry;
 use cosmic::iced::event::{self, Event};
-use cosmic::iced::keyboard::{key::Named, Key};
-use cosmic::iced::widget::text_input;
-use cosmic::iced::{window::Id, Alignment, Length, Subscription, Task};
+use cosmic::iced::keyboard::{key::Named, Key, Modifiers};
+use cosmic::iced::mouse::ScrollDelta;
+use cosmic::iced::widget::scrollable::{self, AbsoluteOffset, Viewport};
+use cosmic::iced::widget::{focus_next, focus_previous, text_input};
+use cosmic::iced::window::{self, Id};
+use cosmic::iced::{Alignment, Length, Size, Subscription, Task};
 use cosmic::iced_winit::commands::popup::{destroy_popup, get_popup};
 use cosmic::prelude::*;
+use cosmic::widget::toaster::{Toast, ToastId, Toasts};
 use cosmic::widget::{self, icon, slider};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, info_span, warn, Instrument};
+
+/// Number of popular stations suggested while favorites are empty
+const STARTER_STATIONS: u32 = 8;
+
+/// Height of the station list area, and roughly of one station row, for
+/// working out which rows are on screen
+const LIST_HEIGHT: u32 = 250;
+const ROW_HEIGHT: u32 = 44;
+
+/// Genre buttons per row
+const GENRE_CHIPS: usize = 4;
+
+/// Episodes are continued, seeked, and downloaded (the `podcasts` feature)
+const PODCASTS: bool = cfg!(feature = "podcasts");
+
+/// Streams are recorded to files (the `recorder` feature)
+const RECORDER: bool = cfg!(feature = "recorder");
+
+/// Height of the visualizer's bars at full level
+const VISUALIZER_HEIGHT: f32 = 32.0;
 
 pub struct AppModel {
     core: cosmic::Core,
     popup: Option<Id>,
+    // Detached now-playing window
+    mini_player: Option<Id>,
     config: Config,
     config_handler: cosmic::cosmic_config::Config,
     audio: AudioManager,
@@ -25,28 +125,407 @@ pub struct AppModel {
     search_query: String,
     search_results: Vec<Station>,
     is_searching: bool,
-    current_station: Option<Station>,
+    // Known stations matching the query, shown until the network results arrive
+    local_matches: Vec<Station>,
+    // Stations seen in earlier search results, for local matches
+    known_stations: Vec<Station>,
+    // Word index over favorites and known stations for offline searches
+    station_index: Arc<StationIndex>,
+    // Whether the data loaded after init is in
+    started: bool,
+    // Popular stations shown on first run, before any favorite is saved,
+    // cached between runs
+    starter_stations: Vec<Station>,
+    starters_fetched_at: u64,
+    is_loading_starters: bool,
+    // New and trending stations for the discover page, cached between runs
+    discover: Option<Discover>,
+    is_loading_discover: bool,
+    // "Because you listen to X", and the seed station last requested
+    recommendations: Option<Recommendations>,
+    recommendations_seed: String,
+    // Favorites are being looked up in the directory
+    is_refreshing_favorites: bool,
+    // Station whose stream mpv is starting, until it plays
+    connecting: Option<String>,
+    // Stations to play one after another
+    queue: Queue,
+    // Favorites' streams are being checked, and what the last check found
+    is_checking_health: bool,
+    health: health::Report,
+    // A broken favorite and the stations offered in its place, the favorite
+    // being searched for, and those whose offer was dismissed this session
+    replacement: Option<(Station, Vec<Station>)>,
+    finding_replacement: Option<String>,
+    replacements_dismissed: HashSet<String>,
+    // A station being starred and the favorite it seems to duplicate
+    duplicate: Option<(Station, Station)>,
+    // Shared with the messages that refer to it
+    current_station: Option<Arc<Station>>,
+    // The station played before it, for switching back and forth
+    previous_station: Option<Arc<Station>>,
     is_playing: bool,
+    track_title: Option<String>,
+    // Titles heard since the applet started, and the current station's log
+    session_tracks: Vec<TrackEntry>,
+    // Listening without history or track logs until the applet restarts
+    incognito: bool,
+    station_tracks: Vec<TrackEntry>,
+    // Album art of the current track, from the cache
+    cover_art: Option<PathBuf>,
+    // Lyrics of the current track, and when its title appeared
+    lyrics: Option<Lyrics>,
+    track_started_at: u64,
     error_message: Option<String>,
+    // Outcome of the last like/vote, shown under the now-playing row
+    vote_notice: Option<String>,
+    // Stream diagnostics of the current station, and whether they are shown
+    diagnostics: Diagnostics,
+    show_diagnostics: bool,
 
     // MPRIS
     mpris_tx: Option<mpsc::UnboundedSender<MprisStateUpdate>>,
+
+    // MQTT
+    mqtt_tx: Option<mpsc::UnboundedSender<MqttState>>,
+
+    // Local control endpoint
+    control_tx: Option<mpsc::UnboundedSender<ControlState>>,
+    control_token: Option<String>,
+
+    // Other applet instances: mirroring the primary instead of playing
+    follower: bool,
+    // Followers served by this (primary) instance
+    sync_tx: Option<mpsc::UnboundedSender<SyncState>>,
+    // The primary instance, while following it
+    primary_tx: Option<mpsc::UnboundedSender<SyncCommand>>,
+    // Instance bus name claimed when a follower took over from the primary,
+    // held until exit
+    _instance: Option<Instance>,
+
+    // Playback was stopped because another player started
+    paused_by_other_player: bool,
+    // A call or notification sound is playing, and the volume is lowered
+    ducked: bool,
+    // Audio sinks as last listed, while watching for the output device
+    outputs: Option<Outputs>,
+    // Sink whose disappearance stopped playback
+    paused_for_device: Option<String>,
+    // A stream was playing when the machine went to sleep
+    playing_before_sleep: bool,
+
+    // Last line written to the now-playing file
+    last_now_playing: String,
+
+    // Casting
+    cast_devices: Vec<CastDevice>,
+    cast_target: Option<CastDevice>,
+    is_discovering_casts: bool,
+
+    // Which view the station list area shows
+    page: Page,
+    // Station list area, and how far each page was scrolled
+    list_scroll_id: scrollable::Id,
+    scroll_offsets: HashMap<Page, u32>,
+    search_offset: u32,
+    // Station logos, and the downloads running for them
+    favicons: Favicons,
+    favicon_tasks: HashMap<String, cosmic::iced::task::Handle>,
+
+    // Listening statistics
+    history: Vec<ListenSession>,
+    current_session: Option<ListenSession>,
+    // Data received in the current session
+    meter: Meter,
+    stats_notice: Option<String>,
+
+    // Settings: backup/restore
+    restore_path: String,
+    settings_notice: Option<String>,
+    cache_size: u64,
+    downloads_size: u64,
+    country_input: String,
+    language_input: String,
+    // Genre the search results are narrowed to
+    genre_filter: Option<Genre>,
+
+    // Settings: content filter
+    new_blocked_keyword: String,
+
+    // Lock mode: PIN entered in settings, and the unlock prompt while shown
+    kiosk_pin: String,
+    unlock_pin: Option<String>,
+
+    // Checked stations while the lists are in select mode
+    selection: Selection,
+
+    // Settings: hook commands being edited
+    hook_drafts: HooksConfig,
+    // Station playing and track title the hooks last ran for
+    hook_state: (Option<String>, Option<String>),
+
+    // Profiles: default profile's data while a named profile is active
+    home_profile: Option<ProfileData>,
+    new_profile_name: String,
+
+    // Program guide: the favorite whose schedule is shown, the show being
+    // entered, reminders already sent for upcoming shows, and recording
+    // windows already started
+    schedule_station: Option<Station>,
+    show_draft: ShowDraft,
+    ical_url: String,
+    schedule_notice: Option<String>,
+    reminded: HashSet<String>,
+    recorded: HashSet<String>,
+
+    // Desktop notifications, minding Do Not Disturb
+    notifier: Notifier,
+
+    // Last session state written for crash recovery
+    saved_session: SessionState,
+
+    // Short confirmations such as "Link copied"
+    toasts: Toasts<Message>,
+
+    // Search box, focused when the popup opens
+    search_input_id: text_input::Id,
+
+    // Desktop language is written right to left; rows are mirrored
+    rtl: bool,
+
+    // Desktop high-contrast and reduced-motion preferences
+    appearance: Appearance,
+    playing_frame: usize,
+
+    // Scrolling over the panel icon, and the modifier keys held
+    wheel: Wheel,
+    modifiers: Modifiers,
+    // Characters the panel ticker has scrolled
+    ticker_offset: usize,
+    // Left presses on the panel icon that may become double clicks
+    clicks: Clicks,
+    // Volume before muting from the panel icon
+    muted_volume: Option<u8>,
+    // Quiet hours are on: the volume is capped
+    quiet: bool,
+    // Position and duration of a stream that has one (podcast episodes,
+    // archived shows), and the position last written to the config
+    position: u64,
+    duration: Option<u64>,
+    position_saved: u64,
+    // Seek bar value while it is dragged
+    seek_drag: Option<f32>,
+    // Latest levels of the playing stream, while the popup shows them
+    visualizer: Visualizer,
+    // Episode downloads started in this session
+    downloads: Vec<Download>,
+    // The running recording, and how many were started in this session
+    recording: Option<ActiveRecording>,
+    recordings_started: u64,
+    // Recordings on disk, while the recordings page shows them
+    recordings: Vec<Recording>,
+    // Recording being renamed, and the new name typed so far
+    recording_rename: Option<(PathBuf, String)>,
+    recordings_notice: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     TogglePopup,
     PopupClosed(Id),
+    ListScrolled(Viewport),
+    FaviconLoaded(String, Result<Option<widget::image::Handle>, String>),
+    // Open or close the detached now-playing window
+    ToggleMiniPlayer,
 
     // Search
     SearchInputChanged(String),
     PerformSearch,
     SearchCompleted(Result<Vec<Station>, String>),
+    // Narrow search results to a genre, or show them all
+    SetGenreFilter(Option<Genre>),
+    // List the directory's most played stations of a genre
+    BrowseGenre(Genre),
+    StarterStationsLoaded(Result<Starters, String>),
+    DiscoverLoaded(Result<Discover, String>),
+    RecommendationsLoaded(Result<Recommendations, String>),
+    // Favorites looked up in the directory again
+    RefreshFavorites,
+    FavoritesRefreshed(Result<Vec<Station>, String>),
+    // Every favorite's stream requested
+    CheckFavorites,
+    FavoritesChecked(health::Report),
+    FindReplacement(Arc<Station>),
+    // Broken favorite, whether the user asked, and working look-alikes
+    ReplacementsFound(Station, bool, Result<Vec<Station>, String>),
+    ReplaceFavorite(String, Station),
+    DismissReplacement,
 
     // Stations
-    PlayStation(Station),
-    ToggleFavorite(Station),
+    PlayStation(Arc<Station>),
+    // Play the station played before the current one
+    SwitchStation,
+    // Seek bar of streams with a duration
+    SeekDragged(f32),
+    SeekReleased,
+    // Downloads of streams with a duration, by station
+    DownloadEpisode(Arc<Station>),
+    PauseDownload(String),
+    DeleteDownload(String),
+    DownloadEvent(DownloadEvent),
+    // Recording a station, and the recordings page
+    StartRecording(Arc<Station>),
+    // The recording, with the stream URL and its credentials filled in
+    RecordingReady(ActiveRecording),
+    StopRecording,
+    RecordEvent(RecordEvent),
+    PlayRecording(PathBuf),
+    RevealRecording(PathBuf),
+    RecordingRevealed(Result<(), String>),
+    EditRecordingName(PathBuf),
+    RecordingNameChanged(String),
+    RenameRecording,
+    DeleteRecording(PathBuf),
+    SetRecordingRetention(u32),
+    SetRecordingFormat(RecordingFormat),
+    SetRecordingBitrate(u32),
+    ToggleFavorite(Arc<Station>),
+    // Merge the starred station into the favorite it duplicates, or add it anyway
+    ResolveDuplicate(bool),
+    // Add to favorites and vote for the station on radio-browser.info
+    LikeStation(Arc<Station>),
+    VoteResult(String, Result<(), String>),
+    // Copy a station's page link or stream URL to the clipboard
+    ShareStation(Arc<Station>, ShareTarget),
+    CloseToast(ToastId),
+    CopyTrack(String),
+    // Save an ICY title heard on a station
+    BookmarkTrack(String, String),
+    RemoveBookmark(usize),
+    ExportBookmarks(BookmarkFormat),
+    // Stream diagnostics panel
+    ToggleDiagnostics,
+    DiagnosticsProbed(String, Probe),
+    CopyDiagnostics,
+    // TLS options of the playing station
+    SetAllowInvalidCerts(bool),
+    SetUpgradeHttps(bool),
     ClearSearch,
+    ShowPage(Page),
+    SetSortOrder(SortOrder),
+
+    // SIGTERM, SIGINT, or SIGHUP (e.g. logout)
+    Shutdown(ShutdownSignal),
+    // Caches, history, and the search index, loaded after init
+    Started(Arc<Startup>),
+    ExportHistory(ExportFormat),
+
+    // Backup and restore
+    CreateBackup,
+    RestorePathChanged(String),
+    RestoreBackup,
+
+    // Content filter
+    BlockedKeywordChanged(String),
+    AddBlockedKeyword,
+    RemoveBlockedKeyword(String),
+    HideStation(Arc<Station>),
+    UnhideStation(String),
+
+    // Lock mode
+    KioskPinChanged(String),
+    Lock,
+    Locked(Result<(), String>),
+    ShowUnlock,
+    UnlockPinChanged(String),
+    Unlock,
+    UnlockChecked(Result<bool, String>),
+
+    // Batch actions on the stations checked in select mode
+    ToggleSelectMode,
+    ToggleSelected(String),
+    ToggleSelectAll,
+    BatchFavorite,
+    BatchAddToProfile(String),
+    BatchHide,
+    BatchExport,
+    BatchQueue,
+    // Station queue
+    QueueStation(Arc<Station>),
+    PlayQueue,
+    ClearQueue,
+    QueueTick,
+    QueueDwellChanged(f32),
+    RequestTimeoutChanged(f32),
+    SearchLimitChanged(f32),
+    SetIpPreference(IpPreference),
+    CacheLimitChanged(f32),
+    ClearCache,
+    DownloadQuotaChanged(f32),
+    DeleteDownloads,
+    SetLogLevel(LogLevel),
+    OpenLogDir,
+    SetResumePlayback(bool),
+    SetStreamPreference(StreamPreference),
+    SetNormalization(Normalization),
+    LoudnessTargetChanged(f32),
+    // Restart the stream with the new target once the slider is let go
+    LoudnessTargetReleased,
+    // Mono and balance of all stations
+    SetMono(bool),
+    BalanceChanged(f32),
+    // Mono and balance of the playing station, and whether it has its own
+    SetStationChannels(bool),
+    SetStationMono(bool),
+    StationBalanceChanged(f32),
+    // Restart the stream with the new balance once a slider is let go
+    BalanceReleased,
+    SetFetchCoverArt(bool),
+    SetShowFavicons(bool),
+    CoverArtLoaded(String, Result<Option<PathBuf>, String>),
+    SetShowLyrics(bool),
+    SetShowVisualizer(bool),
+    HookInputChanged(HookEvent, String),
+    SetDisableHistory(bool),
+    SetIncognito(bool),
+    ClearListeningHistory,
+    ClearTrackLogs,
+    SaveHooks,
+    LyricsLoaded(String, Result<Option<Lyrics>, String>),
+    LyricsTick,
+    SetIconStyle(IconStyle),
+    // Scrolling over the panel icon
+    PanelScrolled(ScrollDelta),
+    ModifiersChanged(Modifiers),
+    SetPanelScroll(bool),
+    PanelScrollStepChanged(f32),
+    SetScrollModifier(ScrollModifier),
+    // Clicks on the panel icon
+    PanelClicked,
+    PanelMiddleClicked,
+    // No second press followed the numbered left press
+    PanelClickTimeout(u64),
+    RunClickAction(ClickAction),
+    SetClickAction(ClickKind, ClickAction),
+    NextPreset,
+    ToggleMute,
+    // Now-playing ticker next to the panel icon
+    TickerTick,
+    SetTicker(bool),
+    TickerWidthChanged(f32),
+    TickerSpeedChanged(f32),
+    SetCountryFilter(CountryFilter),
+    CountryInputChanged(String),
+    SubmitCountry,
+    SetLanguageFilter(bool),
+    LanguageInputChanged(String),
+    AddLanguage,
+    RemoveLanguage(String),
+
+    // Profiles
+    SwitchProfile(String),
+    NewProfileNameChanged(String),
+    CreateProfile,
 
     // Volume control
     VolumeChanged(f32),
@@ -59,11 +538,89 @@ pub enum Message {
 
     // MPRIS D-Bus
     MprisEvent(mpris::MprisEvent),
+
+    // mpv metadata
+    AudioEvent(AudioEvent),
+
+    // MQTT
+    MqttEvent(mqtt::MqttEvent),
+    ControlEvent(ControlEvent),
+    SetControlEnabled(bool),
+    CopyControlToken,
+    SyncEvent(SyncEvent),
+    FollowerEvent(FollowerEvent),
+    // Result of trying to take over from a primary instance that went away
+    InstanceAcquired(Result<Instance, String>),
+
+    // Other MPRIS players started (true) or stopped (false) playing
+    OtherPlayersPlaying(bool),
+    DuckingStreams(bool),
+    SetDucking(bool),
+    DuckingAmountChanged(f32),
+    OutputsChanged(Outputs),
+    SetDevicePause(bool),
+    SetDeviceAutoResume(bool),
+    // Quiet hours
+    QuietTick,
+    SetQuietHours(bool),
+    QuietStartChanged(f32),
+    QuietEndChanged(f32),
+    QuietMaxVolumeChanged(f32),
+    // The machine is suspending or woke up, and the stream should reconnect
+    Sleep(SleepEvent),
+    ReconnectAfterResume,
+
+    // Casting
+    DiscoverCastDevices,
+    CastDevicesFound(Result<Vec<CastDevice>, String>),
+    SelectCastTarget(Option<CastDevice>),
+    CastResult(Result<(), String>),
+
+    // Stream credentials looked up; start the station if it is still wanted
+    // Station, stream URL with credentials, and host address to connect to
+    StartPlayback(Arc<Station>, String, Option<SocketAddr>),
+    // The station's URL serves something other than audio
+    StreamRejected(Arc<Station>, Rejection),
+    // Credentials from an opened link saved to the keyring
+    StreamCredentialsStored(Station, Result<(), String>),
+
+    // Plaintext MQTT password moved into the keyring
+    MqttPasswordMigrated(Result<(), String>),
+
+    // Program guide
+    EditSchedule(Arc<Station>),
+    ShowTitleChanged(String),
+    ShowStartChanged(String),
+    ShowEndChanged(String),
+    ToggleShowDay(u8),
+    AddShow,
+    RemoveShow(usize),
+    IcalUrlChanged(String),
+    ImportIcal,
+    IcalFetched(String, Result<String, String>),
+    SetShowReminder(u32),
+    // Smart preset windows for the station on the schedule page
+    AddPresetRule,
+    RemovePresetRule(usize),
+    SetPresetFallback(bool),
+    // Recording windows for the station on the schedule page
+    RecordingFolderChanged(String),
+    AddRecordingRule,
+    RemoveRecordingRule(usize),
+    // Check for shows starting soon and recordings due
+    ScheduleTick,
+    // Send errors held back during Do Not Disturb
+    FlushNotifications,
+
+    // Desktop accessibility preferences changed
+    AppearanceChanged(Appearance),
+    // Next frame of the playing-state animation
+    AnimationTick,
 }
 
 impl cosmic::Application for AppModel {
     type Executor = cosmic::executor::Default;
-    type Flags = ();
+    type Flags = Role;
     type Message = Message;
     const APP_ID: &'static str = "com.marcos.RadioApplet";
 
@@ -74,22 +631,23 @@ impl cosmic::Application for AppModel {
         &mut self.core
     }
 
-    fn init(
-        core: cosmic::Core,
-        _flags: Self::Flags,
-    ) -> (Self, Task<cosmic::Action<Self::Message>>) {
+    fn init(core: cosmic::Core, role: Self::Flags) -> (Self, Task<cosmic::Action<Self::Message>>) {
+        migrations::run();
         let config_handler = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION)
             .expect("Failed to create config handler");
 
-        let config = match Config::get_entry(&config_handler) {
+        let mut config = match Config::get_entry(&config_handler) {
             Ok(c) => {
                 info!("Config loaded successfully");
                 c
             }
             Err((errs, c)) => {
                 warn!("Errors loading config: {:?}. Using defaults.", errs);
-                if let Err(e) = c.write_entry(&config_handler) {
-                    error!("Failed to write initial config: {:?}", e);
+                // Only the primary instance writes the config
+                if role == Role::Primary {
+                    if let Err(e) = c.write_entry(&config_handler) {
+                        error!("Failed to write initial config: {:?}", e);
+                    }
                 }
                 c
             }
@@ -101,24 +659,190 @@ impl cosmic::Application for AppModel {
             config.favorites.len()
         );
 
+        let home_profile = profiles::activate(&mut config);
+        if home_profile.is_some() {
+            info!("Using profile {}", config.active_profile);
+        }
+
         let audio = AudioManager::new();
         audio.set_volume(config.volume as f32);
 
-        let app = AppModel {
+        let session = session::load();
+        // Everything else on disk is read once the applet is on the panel
+        let startup_request = StartupRequest {
+            favorites: config.favorites.clone(),
+            country_code: config.country.resolve(),
+            cache_max_mb: config.cache_max_mb,
+            kill_orphans: role == Role::Primary,
+            recording_retention_days: config.recording_retention_days,
+        };
+        // Lock mode starts on the favorites list, without a search
+        let (page, search_query) = if config.kiosk_locked {
+            (Page::Favorites, String::new())
+        } else if session.page == Page::Schedule
+            || (matches!(session.page, Page::Settings | Page::Recordings) && role == Role::Follower)
+        {
+            (Page::Favorites, session.search.clone())
+        } else {
+            (session.page, session.search.clone())
+        };
+
+        let mut app = AppModel {
             core,
             popup: None,
+            mini_player: None,
             config,
             config_handler,
             audio,
-            search_query: String::new(),
+            search_query,
             search_results: Vec::new(),
             is_searching: false,
-            current_station: None,
+            local_matches: Vec::new(),
+            station_index: Arc::new(StationIndex::default()),
+            started: false,
+            known_stations: Vec::new(),
+            starter_stations: Vec::new(),
+            starters_fetched_at: 0,
+            is_loading_starters: false,
+            discover: None,
+            is_loading_discover: false,
+            recommendations: None,
+            recommendations_seed: String::new(),
+            is_refreshing_favorites: false,
+            connecting: None,
+            queue: Queue::default(),
+            is_checking_health: false,
+            health: health::Report::default(),
+            replacement: None,
+            finding_replacement: None,
+            replacements_dismissed: HashSet::new(),
+            duplicate: None,
+            current_station: session.station.clone().map(Arc::new),
+            previous_station: session.previous_station.clone().map(Arc::new),
             is_playing: false,
+            track_title: None,
+            session_tracks: Vec::new(),
+            incognito: false,
+            station_tracks: Vec::new(),
+            cover_art: None,
+            lyrics: None,
+            diagnostics: Diagnostics::default(),
+            show_diagnostics: session.show_diagnostics,
+            track_started_at: 0,
             error_message: None,
+            vote_notice: None,
             mpris_tx: None,
+            mqtt_tx: None,
+            control_tx: None,
+            control_token: None,
+            follower: role == Role::Follower,
+            sync_tx: None,
+            primary_tx: None,
+            _instance: None,
+            paused_by_other_player: false,
+            ducked: false,
+            outputs: None,
+            paused_for_device: None,
+            playing_before_sleep: false,
+            last_now_playing: String::new(),
+            cast_devices: Vec::new(),
+            cast_target: None,
+            is_discovering_casts: false,
+            history: Vec::new(),
+            current_session: None,
+            meter: Meter::default(),
+            // The schedule page needs a station picked in this run; lock
+            // mode only has the favorites list
+            page,
+            list_scroll_id: scrollable::Id::unique(),
+            scroll_offsets: session.scroll.clone(),
+            search_offset: 0,
+            favicons: Favicons::default(),
+            favicon_tasks: HashMap::new(),
+            stats_notice: None,
+            restore_path: String::new(),
+            settings_notice: None,
+            cache_size: 0,
+            downloads_size: 0,
+            country_input: String::new(),
+            language_input: String::new(),
+            genre_filter: None,
+            new_blocked_keyword: String::new(),
+            kiosk_pin: String::new(),
+            unlock_pin: None,
+            selection: Selection::default(),
+            hook_drafts: HooksConfig::default(),
+            hook_state: (None, None),
+            home_profile,
+            new_profile_name: String::new(),
+            schedule_station: None,
+            show_draft: ShowDraft::default(),
+            ical_url: String::new(),
+            schedule_notice: None,
+            reminded: HashSet::new(),
+            recorded: HashSet::new(),
+            notifier: Notifier::default(),
+            saved_session: session.clone(),
+            toasts: Toasts::new(Message::CloseToast),
+            search_input_id: text_input::Id::unique(),
+            rtl: i18n::is_rtl(),
+            appearance: Appearance::default(),
+            playing_frame: 0,
+            wheel: Wheel::default(),
+            modifiers: Modifiers::default(),
+            ticker_offset: 0,
+            clicks: Clicks::default(),
+            muted_volume: None,
+            quiet: false,
+            position: 0,
+            duration: None,
+            position_saved: 0,
+            seek_drag: None,
+            visualizer: Visualizer::default(),
+            downloads: Vec::new(),
+            recording: None,
+            recordings_started: 0,
+            recordings: Vec::new(),
+            recording_rename: None,
+            recordings_notice: None,
+        };
+        app.quiet = app.config.quiet_hours.is_quiet(LocalTime::now());
+        app.notifier.set_quiet(app.quiet);
+        // Older configs stored the MQTT password in plaintext; move it to the keyring
+        let migrate_task = if app.config.mqtt.password.is_empty() {
+            Task::none()
+        } else {
+            let password = app.config.mqtt.password.clone();
+            Task::perform(
+                async move {
+                    secrets::store(secrets::MQTT_PASSWORD_KEY, "Radio MQTT password", &password)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                Message::MqttPasswordMigrated,
+            )
+            .map(Into::into)
+        };
+
+        // Back from a crash or panel restart: pick up where playback left off,
+        // once orphaned mpv processes are gone
+        let resume_task = match session.station {
+            Some(station) if session.playing && app.config.resume_playback && !app.follower => {
+                // The smart preset may want another station at this hour
+                let station = app.preset_station().unwrap_or_else(|| Arc::new(station));
+                info!("Resuming {}", station.name);
+                Task::done(Message::PlayStation(station)).map(Into::into)
+            }
+            _ => Task::none(),
         };
-        (app, Task::none())
+        let startup_task = Task::perform(startup::load(startup_request), |startup| {
+            Message::Started(Arc::new(startup))
+        })
+        .map(Into::into);
+        (
+            app,
+            Task::batch([migrate_task, startup_task.chain(resume_task)]),
+        )
     }
 
     fn on_close_requested(&self, id: Id) -> Option<Message> {
@@ -126,20 +850,209 @@ impl cosmic::Application for AppModel {
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
+        // Keys consumed by a focused widget (e.g. typing a space in the search
+        // box) are not treated as shortcuts
         let keyboard_sub = if self.popup.is_some() {
-            event::listen().map(Message::KeyboardEvent)
+            event::listen_with(|event, status, _window| {
+                (status == event::Status::Ignored).then_some(event)
+            })
+            .map(Message::KeyboardEvent)
         } else {
             Subscription::none()
         };
+        // Modifier keys held while scrolling over the panel icon
+        let modifiers_sub = event::listen_with(|event, _status, _window| match event {
+            Event::Keyboard(cosmic::iced::keyboard::Event::ModifiersChanged(modifiers)) => {
+                Some(modifiers)
+            }
+            _ => None,
+        })
+        .map(Message::ModifiersChanged);
+        // Still with reduced motion: the ticker shows the start of the text
+        let ticker_sub = match self.ticker_text() {
+            Some(text)
+                if ticker::scrolls(text, self.config.ticker.width())
+                    && !self.appearance.reduce_motion =>
+            {
+                cosmic::iced::time::every(self.config.ticker.interval())
+                    .map(|_| Message::TickerTick)
+            }
+            _ => Subscription::none(),
+        };
+        // A follower shows what the primary plays; the primary alone talks
+        // to mpv and the outside world
+        if self.follower {
+            return Subscription::batch([
+                keyboard_sub,
+                modifiers_sub,
+                ticker_sub,
+                sync::follower_subscription().map(Message::FollowerEvent),
+                shutdown::signal_subscription().map(Message::Shutdown),
+                appearance::appearance_subscription().map(Message::AppearanceChanged),
+            ]);
+        }
         let mpris_sub = mpris::mpris_subscription().map(Message::MprisEvent);
-        Subscription::batch([keyboard_sub, mpris_sub])
+        let audio_sub = audio::metadata_subscription().map(Message::AudioEvent);
+        let sync_sub = sync::primary_subscription().map(Message::SyncEvent);
+        let mqtt_sub = if self.config.mqtt.enabled {
+            mqtt::mqtt_subscription(self.config.mqtt.clone()).map(Message::MqttEvent)
+        } else {
+            Subscription::none()
+        };
+        let control_sub = if self.config.control.enabled {
+            control::control_subscription(self.config.control.port).map(Message::ControlEvent)
+        } else {
+            Subscription::none()
+        };
+        let ducking_sub = if self.config.ducking.enabled {
+            ducking::ducking_subscription().map(Message::DuckingStreams)
+        } else {
+            Subscription::none()
+        };
+        // Snapcast output doesn't play on a sink
+        let outputs_sub = if self.config.device_pause.enabled
+            && self.config.audio_output != AudioOutput::Snapcast
+        {
+            outputs::outputs_subscription().map(Message::OutputsChanged)
+        } else {
+            Subscription::none()
+        };
+        let autopause_sub = if self.config.auto_pause.enabled {
+            autopause::other_players_subscription().map(Message::OtherPlayersPlaying)
+        } else {
+            Subscription::none()
+        };
+        let sleep_sub = suspend::sleep_subscription().map(Message::Sleep);
+        let shutdown_sub = shutdown::signal_subscription().map(Message::Shutdown);
+        let appearance_sub = appearance::appearance_subscription().map(Message::AppearanceChanged);
+        let lyrics_sub = if self.popup.is_some()
+            && self.is_playing
+            && self.lyrics.as_ref().is_some_and(|l| !l.synced.is_empty())
+        {
+            cosmic::iced::time::every(lyrics::TICK_INTERVAL).map(|_| Message::LyricsTick)
+        } else {
+            Subscription::none()
+        };
+        let animation_sub =
+            if self.popup.is_some() && self.is_playing && !self.appearance.reduce_motion {
+                cosmic::iced::time::every(appearance::FRAME_INTERVAL)
+                    .map(|_| Message::AnimationTick)
+            } else {
+                Subscription::none()
+            };
+        let reminders = self.config.show_reminder_mins > 0 && !self.config.schedule.is_empty();
+        let recordings = RECORDER && !self.config.recording_schedule.is_empty();
+        let schedule_sub = if reminders || recordings {
+            cosmic::iced::time::every(schedule::TICK_INTERVAL).map(|_| Message::ScheduleTick)
+        } else {
+            Subscription::none()
+        };
+        let notify_sub = if self.notifier.has_held() {
+            cosmic::iced::time::every(notify::FLUSH_INTERVAL).map(|_| Message::FlushNotifications)
+        } else {
+            Subscription::none()
+        };
+        let playing = self
+            .current_station
+            .as_ref()
+            .filter(|_| self.is_playing)
+            .map(|station| station.stationuuid.as_str());
+        let queue_sub = if self.config.queue_dwell_secs > 0
+            && !self.config.kiosk_locked
+            && !self.queue.is_empty()
+            && self.queue.is_touring(playing)
+        {
+            cosmic::iced::time::every(queue::TICK_INTERVAL).map(|_| Message::QueueTick)
+        } else {
+            Subscription::none()
+        };
+        let refresh_sub = if self.config.favorites.is_empty() {
+            Subscription::none()
+        } else {
+            cosmic::iced::time::every(refresh::TICK_INTERVAL).map(|_| Message::RefreshFavorites)
+        };
+        let quiet_sub = if self.config.quiet_hours.enabled {
+            cosmic::iced::time::every(quiet::TICK_INTERVAL).map(|_| Message::QuietTick)
+        } else {
+            Subscription::none()
+        };
+        let downloads_sub = Subscription::batch(
+            self.downloads
+                .iter()
+                .filter(|download| download.state == DownloadState::Running)
+                .map(|download| {
+                    downloads::download_subscription(
+                        download.stationuuid.clone(),
+                        download.url.clone(),
+                        self.config.download_quota_mb,
+                    )
+                    .map(Message::DownloadEvent)
+                }),
+        );
+        let recording_sub = self
+            .recording
+            .clone()
+            .map_or_else(Subscription::none, |recording| {
+                recorder::record_subscription(recording).map(Message::RecordEvent)
+            });
+        Subscription::batch([
+            keyboard_sub,
+            modifiers_sub,
+            ticker_sub,
+            mpris_sub,
+            audio_sub,
+            sync_sub,
+            mqtt_sub,
+            control_sub,
+            autopause_sub,
+            ducking_sub,
+            outputs_sub,
+            shutdown_sub,
+            appearance_sub,
+            animation_sub,
+            lyrics_sub,
+            schedule_sub,
+            notify_sub,
+            refresh_sub,
+            queue_sub,
+            quiet_sub,
+            downloads_sub,
+            recording_sub,
+            sleep_sub,
+        ])
     }
 
     fn view(&self) -> Element<'_, Self::Message> {
+        let state = if self.error_message.is_some() {
+            PanelState::Error
+        } else if self.is_playing {
+            PanelState::Playing
+        } else {
+            PanelState::Idle
+        };
+        let mut content = widget::row()
+            .spacing(6)
+            .align_y(Alignment::Center)
+            .push(icon::icon(self.config.icon_style.panel_icon(state)).size(16));
+        if let Some(text) = self.ticker_text() {
+            let offset = if self.appearance.reduce_motion {
+                0
+            } else {
+                self.ticker_offset
+            };
+            content = content.push(
+                widget::text(ticker::window(text, offset, self.config.ticker.width()))
+                    .font(cosmic::font::mono())
+                    .size(14),
+            );
+        }
+        let button = cosmic::widget::button::custom(content)
+            .on_press(Message::PanelClicked)
+            .class(cosmic::theme::Button::Icon);
         widget::container(
-            cosmic::widget::button::custom(icon::from_name("multimedia-player-symbolic").size(16))
-                .on_press(Message::TogglePopup)
-                .class(cosmic::theme::Button::Icon),
+            cosmic::iced::widget::mouse_area(button)
+                .on_scroll(Message::PanelScrolled)
+                .on_middle_press(Message::PanelMiddleClicked),
         )
         .height(Length::Fill)
         .center_y(Length::Fill)
@@ -147,67 +1060,185 @@ impl cosmic::Application for AppModel {
         .into()
     }
 
-    fn view_window(&self, _id: Id) -> Element<'_, Self::Message> {
+    fn view_window(&self, id: Id) -> Element<'_, Self::Message> {
+        if self.mini_player == Some(id) {
+            return self.view_mini_player();
+        }
         let title = widget::text(fl!("window-title")).size(24);
 
         // Now Playing section (if playing)
         let now_playing: Element<'_, Message> = if let Some(station) = &self.current_station {
-            let status_icon = if self.is_playing {
+            let status_icon = if self.is_playing && !self.appearance.reduce_motion {
+                appearance::PLAYING_FRAMES[self.playing_frame % appearance::PLAYING_FRAMES.len()]
+            } else if self.is_playing {
                 "media-playback-pause-symbolic"
             } else {
                 "media-playback-stop-symbolic"
             };
 
+            let details = widget::column()
+                .width(Length::Fill)
+                .align_x(self.start())
+                .push(widget::text(&station.name).size(16).align_x(self.start()))
+                .push_maybe(
+                    schedule::on_air(
+                        &self.config.schedule,
+                        &station.stationuuid,
+                        LocalTime::now(),
+                    )
+                    .map(|show| {
+                        widget::text(fl!("on-air", title = show.title.as_str()))
+                            .size(12)
+                            .align_x(self.start())
+                    }),
+                )
+                .push_maybe(
+                    self.track_title
+                        .as_deref()
+                        .map(|title| widget::text(title).size(12).align_x(self.start())),
+                );
+
+            // Album art, when known, takes the place of the status icon
+            let art: Element<'_, Message> = match &self.cover_art {
+                Some(path) => widget::image(widget::image::Handle::from_path(path))
+                    .width(Length::Fixed(48.0))
+                    .height(Length::Fixed(48.0))
+                    .into(),
+                None => icon::from_name(status_icon).size(20).into(),
+            };
+
+            let mut row = vec![art, details.into()];
+            let locked = self.config.kiosk_locked;
+            // "I like this track"
+            if let Some(title) = self.track_title.as_ref().filter(|_| !locked) {
+                row.push(
+                    icon_button(
+                        "bookmark-new-symbolic",
+                        fl!("a11y-bookmark-track", title = title.as_str()),
+                    )
+                    .on_press(Message::BookmarkTrack(title.clone(), station.name.clone()))
+                    .into(),
+                );
+            }
+            let recordable = RECORDER
+                && !locked
+                && !self.follower
+                && self.recording.is_none()
+                && !station.stationuuid.starts_with(recorder::UUID_PREFIX);
+            if recordable {
+                row.push(
+                    icon_button(
+                        "media-record-symbolic",
+                        fl!("a11y-record", name = station.name.as_str()),
+                    )
+                    .on_press(Message::StartRecording(station.clone()))
+                    .into(),
+                );
+            }
+            if !locked {
+                row.extend([
+                    icon_button(
+                        "emblem-favorite-symbolic",
+                        fl!("a11y-like", name = station.name.as_str()),
+                    )
+                    .on_press(Message::LikeStation(station.clone()))
+                    .into(),
+                    icon_button(
+                        "emblem-shared-symbolic",
+                        fl!("a11y-share", name = station.name.as_str()),
+                    )
+                    .on_press(Message::ShareStation(station.clone(), ShareTarget::Page))
+                    .into(),
+                    icon_button(
+                        "edit-copy-symbolic",
+                        fl!("a11y-copy-stream", name = station.name.as_str()),
+                    )
+                    .on_press(Message::ShareStation(station.clone(), ShareTarget::Stream))
+                    .into(),
+                    icon_button("document-open-recent-symbolic", fl!("a11y-track-history"))
+                        .on_press(Message::ShowPage(Page::Tracks))
+                        .into(),
+                    icon_button("dialog-information-symbolic", fl!("a11y-diagnostics"))
+                        .on_press(Message::ToggleDiagnostics)
+                        .into(),
+                ]);
+            }
+            if let Some(previous) = self.previous_station.as_ref().filter(|_| !locked) {
+                row.push(
+                    icon_button(
+                        "media-skip-backward-symbolic",
+                        fl!("a11y-switch-station", name = previous.name.as_str()),
+                    )
+                    .on_press(Message::SwitchStation)
+                    .into(),
+                );
+            }
+            row.extend([
+                icon_button("window-new-symbolic", fl!("a11y-mini-player"))
+                    .on_press(Message::ToggleMiniPlayer)
+                    .into(),
+                icon_button("media-playback-stop-symbolic", fl!("a11y-stop"))
+                    .on_press(Message::TogglePlayPause)
+                    .into(),
+            ]);
+
             widget::column()
                 .spacing(8)
                 .push(
-                    widget::row()
+                    self.directional_row(row)
                         .spacing(10)
-                        .align_y(Alignment::Center)
-                        .push(icon::from_name(status_icon).size(20))
-                        .push(
-                            widget::text(&station.name)
-                                .size(16)
-                                .width(Length::Fill),
-                        )
-                        .push(
-                            cosmic::iced::widget::button(icon::from_name(
-                                "media-playback-stop-symbolic",
-                            ))
-                            .on_press(Message::TogglePlayPause),
-                        ),
+                        .align_y(Alignment::Center),
                 )
+                .push_maybe(self.view_visualizer())
+                .push_maybe(self.view_seek_bar())
+                .push_maybe(self.view_download(station))
+                .push_maybe(self.view_recording())
+                .push_maybe(
+                    self.vote_notice
+                        .as_deref()
+                        .map(|notice| widget::text(notice).size(12)),
+                )
+                .push_maybe(self.view_lyrics())
+                .push_maybe(self.view_diagnostics())
                 .into()
         } else {
             widget::text(fl!("not-playing")).size(14).into()
         };
 
         // Volume control section
-        let volume_section = {
-            let volume_label = format!("{} {}%", fl!("volume"), self.config.volume);
-            let volume_icon = if self.config.volume == 0 {
-                "audio-volume-muted-symbolic"
-            } else if self.config.volume < 33 {
-                "audio-volume-low-symbolic"
-            } else if self.config.volume < 66 {
-                "audio-volume-medium-symbolic"
+        let volume_section = self.view_volume();
+
+        // Profiles
+        let mut profile_row = widget::row()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(icon::from_name("system-users-symbolic").size(20));
+        let default_label = fl!("profile-default");
+        for name in std::iter::once("").chain(self.config.profiles.iter().map(String::as_str)) {
+            let label = if name.is_empty() {
+                default_label.as_str()
             } else {
-                "audio-volume-high-symbolic"
+                name
             };
-
-            widget::row()
-                .spacing(10)
-                .align_y(Alignment::Center)
-                .push(icon::from_name(volume_icon).size(20))
-                .push(
-                    slider(0.0..=100.0, self.config.volume as f32, Message::VolumeChanged)
-                        .width(Length::Fill),
+            profile_row = if name == self.config.active_profile {
+                profile_row.push(widget::text(format!("[{label}]")))
+            } else {
+                profile_row.push(
+                    cosmic::iced::widget::button(widget::text(label.to_string()))
+                        .on_press(Message::SwitchProfile(name.to_string())),
                 )
-                .push(widget::text(volume_label).size(14).width(Length::Shrink))
-        };
+            };
+        }
+        profile_row = profile_row.push(
+            text_input(&fl!("profile-new-placeholder"), &self.new_profile_name)
+                .on_input(Message::NewProfileNameChanged)
+                .on_submit(Message::CreateProfile)
+                .width(Length::Fixed(120.0)),
+        );
 
         // Search Bar
         let search_input = text_input(&fl!("search-placeholder"), &self.search_query)
+            .id(self.search_input_id.clone())
             .on_input(Message::SearchInputChanged)
             .on_submit(Message::PerformSearch)
             .padding(10);
@@ -215,26 +1246,130 @@ impl cosmic::Application for AppModel {
         let search_btn = cosmic::iced::widget::button(widget::text(fl!("search-button")))
             .on_press(Message::PerformSearch);
 
-        let search_row = widget::row()
-            .spacing(10)
-            .push(search_input)
-            .push(search_btn);
+        let search_row = self
+            .directional_row(vec![search_input.into(), search_btn.into()])
+            .spacing(10);
 
         // Results List
-        let mut stations_list = widget::column().spacing(5);
+        let mut stations_list = widget::column().spacing(5).align_x(self.start());
 
         if self.is_searching {
             stations_list = stations_list.push(widget::text(fl!("searching-status")));
         } else if let Some(err) = &self.error_message {
             stations_list =
                 stations_list.push(widget::text(format!("{} {}", fl!("error-message"), err)));
-        } else if self.search_query.is_empty() && self.search_results.is_empty() {
-            stations_list = stations_list.push(widget::text(fl!("favorites-header")).size(18));
+        } else if self.config.kiosk_locked {
+            stations_list = stations_list.push(self.view_lock_bar());
             if self.config.favorites.is_empty() {
                 stations_list = stations_list.push(widget::text(fl!("no-favorites")));
             }
             for station in &self.config.favorites {
-                stations_list = stations_list.push(self.view_station_row(station, true));
+                stations_list = stations_list.push(self.view_station_row(station, true, false));
+            }
+        } else if self.page != Page::Favorites {
+            let page = match self.page {
+                Page::Discover => self.view_discover(),
+                Page::Tracks => self.view_tracks(),
+                Page::Stats => self.view_stats(),
+                Page::Schedule => self.view_schedule(),
+                Page::Recordings => self.view_recordings(),
+                _ => self.view_settings(),
+            };
+            stations_list = stations_list
+                .push(
+                    cosmic::iced::widget::button(widget::text(fl!("back-to-favorites")))
+                        .on_press(Message::ShowPage(Page::Favorites)),
+                )
+                .push(page);
+        } else if self.search_query.is_empty() && self.search_results.is_empty() {
+            let mut header = vec![
+                widget::text(fl!("favorites-header"))
+                    .size(18)
+                    .width(Length::Fill)
+                    .align_x(self.start())
+                    .into(),
+                cosmic::iced::widget::button(widget::text(fl!("discover-button")))
+                    .on_press(Message::ShowPage(Page::Discover))
+                    .into(),
+                cosmic::iced::widget::button(widget::text(fl!("stats-button")))
+                    .on_press(Message::ShowPage(Page::Stats))
+                    .into(),
+            ];
+            // Settings are changed in the primary instance, which saves them,
+            // and records
+            if RECORDER && !self.follower {
+                header.push(
+                    cosmic::iced::widget::button(widget::text(fl!("recordings-button")))
+                        .on_press(Message::ShowPage(Page::Recordings))
+                        .into(),
+                );
+            }
+            if !self.follower {
+                header.push(
+                    cosmic::iced::widget::button(widget::text(fl!("settings-button")))
+                        .on_press(Message::ShowPage(Page::Settings))
+                        .into(),
+                );
+            }
+            stations_list = stations_list.push(
+                self.directional_row(header)
+                    .spacing(10)
+                    .align_y(Alignment::Center),
+            );
+            if !self.follower {
+                stations_list = stations_list.push(self.view_selection_bar());
+            }
+            if !self.config.favorites.is_empty() && !self.config.kiosk_locked && !self.follower {
+                stations_list = stations_list.push(self.view_health_check());
+            }
+            if let Some(offer) = self.view_replacement() {
+                stations_list = stations_list.push(offer);
+            }
+            if let Some(prompt) = self.view_duplicate() {
+                stations_list = stations_list.push(prompt);
+            }
+            if self.config.favorites.is_empty() {
+                let starters = self.in_languages(&self.starter_stations);
+                if starters.is_empty() {
+                    stations_list = stations_list.push(widget::text(fl!("no-favorites")));
+                } else {
+                    stations_list = stations_list.push(widget::text(fl!("starter-hint")).size(12));
+                    for station in starters {
+                        stations_list =
+                            stations_list.push(self.view_station_row(station, false, false));
+                    }
+                }
+            }
+            for station in &self.config.favorites {
+                stations_list = stations_list.push(self.view_station_row(station, true, false));
+            }
+            if let Some(recommendations) = &self.recommendations {
+                let visible: Vec<&Station> = recommendations
+                    .stations
+                    .iter()
+                    .filter(|station| {
+                        !filter::is_blocked(station, &self.config.blocked_keywords)
+                            && !filter::is_hidden(station, &self.config.hidden_stations)
+                            && !self
+                                .config
+                                .favorites
+                                .iter()
+                                .any(|s| s.stationuuid == station.stationuuid)
+                    })
+                    .collect();
+                if !visible.is_empty() {
+                    stations_list = stations_list.push(
+                        widget::text(fl!(
+                            "recommendations-header",
+                            name = locale::isolate(&recommendations.seed_name)
+                        ))
+                        .size(18),
+                    );
+                    for station in visible {
+                        stations_list =
+                            stations_list.push(self.view_station_row(station, false, true));
+                    }
+                }
             }
         } else {
             let back_btn = cosmic::iced::widget::button(widget::text(fl!("back-to-favorites")))
@@ -242,42 +1377,183 @@ impl cosmic::Application for AppModel {
 
             stations_list = stations_list.push(back_btn);
             stations_list = stations_list.push(widget::text(fl!("search-results-header")).size(18));
-            for station in &self.search_results {
+            let mut sort_row = widget::row()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(widget::text(fl!("sort-by")).size(12));
+            for order in SortOrder::ALL {
+                let label = match order {
+                    SortOrder::Relevance => fl!("sort-relevance"),
+                    SortOrder::Votes => fl!("sort-votes"),
+                    SortOrder::Clicks => fl!("sort-clicks"),
+                };
+                sort_row = if order == self.config.sort_order {
+                    sort_row.push(widget::text(format!("[{label}]")).size(12))
+                } else {
+                    sort_row.push(
+                        cosmic::iced::widget::button(widget::text(label).size(12))
+                            .on_press(Message::SetSortOrder(order)),
+                    )
+                };
+            }
+            stations_list = stations_list.push(sort_row).push(self.view_selection_bar());
+            if let Some(prompt) = self.view_duplicate() {
+                stations_list = stations_list.push(prompt);
+            }
+            let (mut visible, blocked): (Vec<&Station>, Vec<&Station>) =
+                self.search_results.iter().partition(|station| {
+                    !filter::is_blocked(station, &self.config.blocked_keywords)
+                        && !filter::is_hidden(station, &self.config.hidden_stations)
+                });
+            let common = genres::common(visible.iter().copied());
+            if !common.is_empty() {
+                let mut genre_row = widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("genre-filter")).size(12));
+                for genre in common.into_iter().take(GENRE_CHIPS) {
+                    let label = genre_name(genre);
+                    genre_row = if self.genre_filter == Some(genre) {
+                        genre_row.push(
+                            cosmic::iced::widget::button(
+                                widget::text(format!("[{label}]")).size(12),
+                            )
+                            .on_press(Message::SetGenreFilter(None)),
+                        )
+                    } else {
+                        genre_row.push(
+                            cosmic::iced::widget::button(widget::text(label).size(12))
+                                .on_press(Message::SetGenreFilter(Some(genre))),
+                        )
+                    };
+                }
+                stations_list = stations_list.push(genre_row);
+            }
+            if let Some(genre) = self.genre_filter {
+                visible.retain(|station| genres::has_genre(station, genre));
+            }
+            if !blocked.is_empty() {
+                stations_list = stations_list.push(
+                    widget::text(format!("{} {}", blocked.len(), fl!("filter-hidden-count")))
+                        .size(12),
+                );
+            }
+            // Known stations while the network search is running or found nothing
+            if self.search_results.is_empty() && !self.local_matches.is_empty() {
+                stations_list =
+                    stations_list.push(widget::text(fl!("local-matches-header")).size(12));
+                for station in &self.local_matches {
+                    let is_fav = self
+                        .config
+                        .favorites
+                        .iter()
+                        .any(|s| s.stationuuid == station.stationuuid);
+                    stations_list =
+                        stations_list.push(self.view_station_row(station, is_fav, false));
+                }
+            }
+            self.config.sort_order.sort(&mut visible);
+            for station in visible {
                 let is_fav = self
                     .config
                     .favorites
                     .iter()
                     .any(|s| s.stationuuid == station.stationuuid);
-                stations_list = stations_list.push(self.view_station_row(station, is_fav));
+                stations_list = stations_list.push(self.view_station_row(station, is_fav, true));
+            }
+        }
+
+        // Cast targets
+        let mut cast_section = widget::row()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(icon::from_name("video-display-symbolic").size(20));
+        if let Some(target) = &self.cast_target {
+            cast_section = cast_section
+                .push(
+                    widget::text(format!(
+                        "{} {}",
+                        fl!("casting-to"),
+                        locale::isolate(&target.name)
+                    ))
+                    .width(Length::Fill),
+                )
+                .push(
+                    cosmic::iced::widget::button(widget::text(fl!("cast-stop")))
+                        .on_press(Message::SelectCastTarget(None)),
+                );
+        } else if self.is_discovering_casts {
+            cast_section = cast_section.push(widget::text(fl!("cast-searching")));
+        } else {
+            cast_section = cast_section.push(
+                cosmic::iced::widget::button(widget::text(fl!("cast-discover")))
+                    .on_press(Message::DiscoverCastDevices),
+            );
+            for device in &self.cast_devices {
+                cast_section = cast_section.push(
+                    cosmic::iced::widget::button(widget::text(&device.name))
+                        .on_press(Message::SelectCastTarget(Some(device.clone()))),
+                );
             }
         }
 
-        // Keyboard shortcuts hint
-        let shortcuts_hint = widget::text(fl!("shortcuts-hint"))
-            .size(11)
-            .class(cosmic::theme::Text::Color(cosmic::iced::Color::from_rgb(
+        // Keyboard shortcuts hint, dimmed unless high contrast is on
+        let shortcuts_hint = widget::text(fl!("shortcuts-hint")).size(11);
+        let shortcuts_hint = if self.appearance.high_contrast {
+            shortcuts_hint
+        } else {
+            shortcuts_hint.class(cosmic::theme::Text::Color(cosmic::iced::Color::from_rgb(
                 0.5, 0.5, 0.5,
-            )));
+            )))
+        };
 
         let content = widget::column()
             .padding(20)
             .spacing(12)
+            .align_x(self.start())
             .push(title)
             .push(widget::divider::horizontal::light())
             .push(now_playing)
             .push(volume_section)
+            .push_maybe(self.view_queue())
+            .push_maybe((!self.config.kiosk_locked).then_some(profile_row))
             .push(widget::divider::horizontal::light())
-            .push(search_row)
-            .push(widget::scrollable(stations_list).height(250))
+            .push_maybe((!self.config.kiosk_locked).then_some(search_row))
+            .push(
+                widget::scrollable(stations_list)
+                    .id(self.list_scroll_id.clone())
+                    .on_scroll(Message::ListScrolled)
+                    .height(250),
+            )
+            .push(cast_section)
             .push(shortcuts_hint);
 
-        self.core.applet.popup_container(content).into()
+        self.core
+            .applet
+            .popup_container(widget::toaster(&self.toasts, content))
+            .into()
     }
 
     fn update(&mut self, message: Self::Message) -> Task<cosmic::Action<Self::Message>> {
+        if self.follower {
+            if let Some(task) = self.forward_to_primary(&message) {
+                return task;
+            }
+            if follower_refused(&message) {
+                debug!("Ignoring {:?} in a follower instance", message);
+                return Task::none();
+            }
+        }
+        // The locked view offers none of these, but shortcuts, stale
+        // buttons, and D-Bus clients must not get around it
+        if self.config.kiosk_locked && locked_out(&message) {
+            debug!("Ignoring {:?} in lock mode", message);
+            return Task::none();
+        }
         match message {
             Message::TogglePopup => {
                 return if let Some(p) = self.popup.take() {
+                    self.save_session();
                     destroy_popup(p)
                 } else {
                     let new_id = Id::unique();
@@ -287,7 +1563,21 @@ impl cosmic::Application for AppModel {
                             self.core
                                 .applet
                                 .get_popup_settings(main_id, new_id, None, None, None);
-                        get_popup(popup_settings)
+                        // Start keyboard navigation in the search box, with
+                        // the list scrolled where it was left
+                        let open = get_popup(popup_settings)
+                            .chain(text_input::focus(self.search_input_id.clone()))
+                            .chain(self.restore_scroll());
+                        // Fetches run while the popup appears, showing cached
+                        // content until they are back
+                        Task::batch([
+                            open,
+                            self.update_favicons(),
+                            self.refresh_starters(),
+                            self.refresh_discover(),
+                            self.refresh_recommendations(),
+                            self.refresh_favorites(),
+                        ])
                     } else {
                         warn!("No main window ID available");
                         Task::none()
@@ -297,21 +1587,137 @@ impl cosmic::Application for AppModel {
             Message::PopupClosed(id) => {
                 if self.popup == Some(id) {
                     self.popup = None;
+                    self.save_session();
+                }
+                if self.mini_player == Some(id) {
+                    self.mini_player = None;
+                    return window::close(id);
+                }
+            }
+            Message::ToggleMiniPlayer => {
+                if let Some(id) = self.mini_player.take() {
+                    return window::close(id);
+                }
+                let (id, open) = window::open(window::Settings {
+                    size: Size::new(360.0, 130.0),
+                    resizable: false,
+                    level: window::Level::AlwaysOnTop,
+                    exit_on_close_request: false,
+                    ..window::Settings::default()
+                });
+                self.mini_player = Some(id);
+                return open
+                    .discard()
+                    .chain(self.set_window_title(fl!("mini-player-title"), id));
+            }
+            Message::ListScrolled(viewport) => {
+                let offset = viewport.absolute_offset().y.max(0.0).round() as u32;
+                match self.scroll_page() {
+                    Some(page) => {
+                        self.scroll_offsets.insert(page, offset);
+                    }
+                    None => self.search_offset = offset,
                 }
+                return self.update_favicons();
+            }
+            Message::FaviconLoaded(url, logo) => {
+                self.favicon_tasks.remove(&url);
+                let next = self.favicons.finish(url, logo);
+                return self.start_favicons(next);
             }
             Message::SearchInputChanged(val) => {
                 self.search_query = val;
+                self.update_local_matches();
             }
             Message::PerformSearch => {
                 self.is_searching = true;
                 self.error_message = None;
                 self.search_results.clear();
+                self.genre_filter = None;
                 let query = self.search_query.clone();
+                let providers =
+                    providers::from_config(&self.config.providers, self.config.country.resolve());
+                let options = self.request_options();
+                let cache = Cache::open(self.config.cache_max_mb);
+                let index = self.station_index.clone();
+                let limit = self.config.search_limit as usize;
                 return Task::perform(
                     async move {
-                        api::search_stations(query)
-                            .await
-                            .map_err(|e: reqwest::Error| e.to_string())
+                        let key = cache::search_key(&query);
+                        match providers::search_all(providers, query.clone(), options).await {
+                            Ok(stations) => {
+                                if let Some(cache) = &cache {
+                                    cache.put_json(&key, &stations);
+                                }
+                                Ok(stations)
+                            }
+                            // Offline: fall back to the last results for this query
+                            Err(e) => match cache.and_then(|c| c.get_json(&key)) {
+                                Some(stations) => {
+                                    warn!("Search failed, using cached results: {}", e);
+                                    Ok(stations)
+                                }
+                                None if index.is_empty() => Err(e.to_string()),
+                                // No results cached for this query: search known stations
+                                None => match index.search(&query, limit) {
+                                    stations if stations.is_empty() => Err(e.to_string()),
+                                    stations => {
+                                        warn!("Search failed, using known stations: {}", e);
+                                        Ok(stations)
+                                    }
+                                },
+                            },
+                        }
+                    },
+                    Message::SearchCompleted,
+                )
+                .map(Into::into);
+            }
+            Message::SetGenreFilter(genre) => {
+                self.genre_filter = genre;
+                return self.update_favicons();
+            }
+            Message::BrowseGenre(genre) => {
+                info!("Browsing {:?}", genre);
+                self.page = Page::Favorites;
+                self.search_query = genre_name(genre);
+                self.genre_filter = Some(genre);
+                self.is_searching = true;
+                self.error_message = None;
+                self.search_results.clear();
+                self.local_matches.clear();
+                let options = self.request_options();
+                let limit = self.config.search_limit;
+                return Task::perform(
+                    async move {
+                        let requests = genre
+                            .directory_tags()
+                            .iter()
+                            .map(|tag| api::stations_by_tag(tag.to_string(), limit, options));
+                        let mut stations: Vec<Station> = Vec::new();
+                        let mut error = None;
+                        for result in futures::future::join_all(requests).await {
+                            match result {
+                                Ok(found) => {
+                                    for station in found {
+                                        if !stations
+                                            .iter()
+                                            .any(|s| s.stationuuid == station.stationuuid)
+                                        {
+                                            stations.push(station);
+                                        }
+                                    }
+                                }
+                                Err(e) => error = Some(e.to_string()),
+                            }
+                        }
+                        match error {
+                            Some(e) if stations.is_empty() => Err(e),
+                            _ => {
+                                stations.sort_by(|a, b| b.clickcount.cmp(&a.clickcount));
+                                Ok(stations)
+                            }
+                        }
                     },
                     Message::SearchCompleted,
                 )
@@ -322,7 +1728,17 @@ impl cosmic::Application for AppModel {
                 match res {
                     Ok(stations) => {
                         debug!("Search completed: {} stations found", stations.len());
+                        // Searches leave no trace while history is off
+                        if self.records_history() && !stations.is_empty() {
+                            fuzzy::remember(&mut self.known_stations, &stations);
+                            // Until the cached list is loaded, this would replace it
+                            if self.started {
+                                self.save_known_stations();
+                            }
+                            self.rebuild_station_index();
+                        }
                         self.search_results = stations;
+                        return self.update_favicons();
                     }
                     Err(e) => {
                         error!("Search failed: {}", e);
@@ -330,216 +1746,5998 @@ impl cosmic::Application for AppModel {
                     }
                 }
             }
-            Message::PlayStation(station) => {
-                let is_same = self
-                    .current_station
-                    .as_ref()
-                    .map(|s| s.stationuuid == station.stationuuid)
-                    .unwrap_or(false);
-
-                if self.is_playing && is_same {
-                    self.audio.stop();
-                    self.is_playing = false;
-                    debug!("Stopped playback");
+            Message::Started(startup) => {
+                let startup = Arc::unwrap_or_clone(startup);
+                // Searches and listening since launch are newer than what
+                // was on disk, and kept
+                let searched = std::mem::replace(&mut self.known_stations, startup.known_stations);
+                if searched.is_empty() {
+                    self.station_index = startup.station_index;
                 } else {
-                    self.current_station = Some(station.clone());
-                    self.is_playing = true;
-                    self.audio
-                        .play(station.url_resolved.clone(), self.config.volume);
-                    debug!("Playing: {}", station.name);
+                    fuzzy::remember(&mut self.known_stations, &searched);
+                    self.save_known_stations();
+                    self.rebuild_station_index();
+                }
+                self.discover = self.discover.take().or(startup.discover);
+                self.starter_stations = startup.starters.stations;
+                self.starters_fetched_at = startup.starters.fetched_at;
+                let listened = std::mem::replace(&mut self.history, startup.history);
+                for session in listened {
+                    // Already appended to the file, which may have been read after
+                    if !self.history.contains(&session) {
+                        self.history.push(session);
+                    }
+                }
+                // Downloads started since launch are already tracked
+                for download in startup.downloads {
+                    if self.download_mut(&download.stationuuid).is_none() {
+                        self.downloads.push(download);
+                    }
+                }
+                self.started = true;
+                if !self.search_query.is_empty() {
+                    self.update_local_matches();
+                }
+                // First run: suggest popular stations from the user's country
+                let mut tasks = vec![self.refresh_starters(), self.update_favicons()];
+                if self.popup.is_some() && self.page == Page::Discover {
+                    tasks.push(self.refresh_discover());
                 }
-                self.push_mpris_state();
+                return Task::batch(tasks);
             }
-            Message::ClearSearch => {
-                self.search_query.clear();
-                self.search_results.clear();
-                self.error_message = None;
+            Message::Shutdown(signal) => {
+                info!("Received {:?}, shutting down", signal);
+                return self.shutdown();
             }
-            Message::ToggleFavorite(station) => {
-                if let Some(pos) = self
-                    .config
-                    .favorites
-                    .iter()
-                    .position(|s| s.stationuuid == station.stationuuid)
-                {
-                    self.config.favorites.remove(pos);
-                    debug!("Removed from favorites: {}", station.name);
-                } else {
-                    self.config.favorites.push(station.clone());
-                    debug!("Added to favorites: {}", station.name);
-                }
-                self.save_config();
+            Message::StarterStationsLoaded(res) => {
+                self.is_loading_starters = false;
+                match res {
+                    Ok(starters) => {
+                        debug!("Loaded {} starter stations", starters.stations.len());
+                        self.starter_stations = starters.stations;
+                        self.starters_fetched_at = starters.fetched_at;
+                        return self.update_favicons();
+                    }
+                    Err(e) => warn!("Failed to load starter stations: {}", e),
+                }
             }
-            Message::VolumeChanged(vol) => {
-                let volume = vol.round() as u8;
-                self.config.volume = volume;
-                self.audio.set_volume(volume as f32);
-                debug!("Volume changed to {}%", volume);
-                self.save_config();
-                self.push_mpris_state();
+            Message::DiscoverLoaded(res) => {
+                self.is_loading_discover = false;
+                match res {
+                    Ok(discover) => {
+                        debug!(
+                            "Loaded {} new and {} trending stations",
+                            discover.new.len(),
+                            discover.trending.len()
+                        );
+                        self.discover = Some(discover);
+                        return self.update_favicons();
+                    }
+                    // Keep showing the cached lists
+                    Err(e) => warn!("Failed to load new and trending stations: {}", e),
+                }
             }
-            Message::VolumeUp => {
-                let new_vol = (self.config.volume as i16 + 5).min(100) as u8;
-                self.config.volume = new_vol;
-                self.audio.set_volume(new_vol as f32);
-                debug!("Volume up to {}%", new_vol);
-                self.save_config();
-                self.push_mpris_state();
+            Message::RefreshFavorites => {
+                return self.refresh_favorites();
             }
-            Message::VolumeDown => {
-                let new_vol = (self.config.volume as i16 - 5).max(0) as u8;
-                self.config.volume = new_vol;
-                self.audio.set_volume(new_vol as f32);
-                debug!("Volume down to {}%", new_vol);
-                self.save_config();
-                self.push_mpris_state();
+            Message::FavoritesRefreshed(res) => {
+                self.is_refreshing_favorites = false;
+                match res {
+                    Ok(stations) => {
+                        let changed = refresh::apply(&mut self.config.favorites, &stations);
+                        let broken = self
+                            .config
+                            .favorites
+                            .iter()
+                            .filter(|s| refresh::is_broken(s))
+                            .count();
+                        info!(
+                            "Refreshed favorites: {} updated, {} failing the directory check",
+                            changed, broken
+                        );
+                        self.config.favorites_refreshed_at = stats::now_secs();
+                        self.save_config();
+                        let first_broken = self.config.favorites.iter().find(|s| {
+                            refresh::is_broken(s)
+                                && !self.replacements_dismissed.contains(&s.stationuuid)
+                        });
+                        if let Some(station) = first_broken.cloned() {
+                            return self.find_replacement(station, false);
+                        }
+                    }
+                    // Try again at the next tick
+                    Err(e) => warn!("Failed to refresh favorites: {}", e),
+                }
             }
-            Message::TogglePlayPause => {
-                if self.is_playing {
-                    self.audio.stop();
+            Message::FindReplacement(station) => {
+                return self.find_replacement(Arc::unwrap_or_clone(station), true);
+            }
+            Message::ReplacementsFound(station, asked, res) => {
+                self.finding_replacement = None;
+                match res {
+                    Ok(candidates) if !candidates.is_empty() => {
+                        info!(
+                            "Found {} replacements for {}",
+                            candidates.len(),
+                            station.name
+                        );
+                        self.replacement = Some((station, candidates));
+                    }
+                    Ok(_) => {
+                        debug!("No replacement found for {}", station.name);
+                        // Leave the user with a search they can refine
+                        if asked {
+                            self.search_query = station.name;
+                            self.update_local_matches();
+                            return self.update(Message::PerformSearch);
+                        }
+                    }
+                    Err(e) => warn!("Failed to look for replacements: {}", e),
+                }
+            }
+            Message::ReplaceFavorite(old_uuid, replacement) => {
+                info!("Replacing favorite {} with {}", old_uuid, replacement.name);
+                self.replacement = None;
+                if replace::replace(
+                    &mut self.config.favorites,
+                    &mut self.config.schedule,
+                    &old_uuid,
+                    replacement,
+                ) {
+                    self.save_config();
+                }
+            }
+            Message::CheckFavorites => {
+                if self.is_checking_health || self.config.favorites.is_empty() {
+                    return Task::none();
+                }
+                info!("Checking {} favorites", self.config.favorites.len());
+                self.is_checking_health = true;
+                let favorites = self.config.favorites.clone();
+                let timeout = self.request_options().timeout;
+                return Task::perform(
+                    health::check_all(favorites, timeout),
+                    Message::FavoritesChecked,
+                )
+                .map(Into::into);
+            }
+            Message::FavoritesChecked(report) => {
+                self.is_checking_health = false;
+                info!(
+                    "Checked {} favorites, {} broken",
+                    report.checked,
+                    report.broken.len()
+                );
+                let summary = fl!(
+                    "health-summary",
+                    working = report.working(),
+                    broken = report.broken.len()
+                );
+                self.health = report;
+                return self.toasts.push(Toast::new(summary)).map(Into::into);
+            }
+            Message::DismissReplacement => {
+                if let Some((station, _)) = self.replacement.take() {
+                    self.replacements_dismissed.insert(station.stationuuid);
+                }
+            }
+            Message::RecommendationsLoaded(res) => match res {
+                Ok(recommendations) => {
+                    debug!(
+                        "Loaded {} recommendations based on {}",
+                        recommendations.stations.len(),
+                        recommendations.seed_name
+                    );
+                    self.recommendations = Some(recommendations);
+                }
+                Err(e) => {
+                    warn!("Failed to load recommendations: {}", e);
+                    // Try again the next time the popup opens
+                    self.recommendations_seed.clear();
+                }
+            },
+            Message::PlayStation(mut station) => {
+                self.paused_by_other_player = false;
+                // Every list the station may have been picked from
+                let discover = self
+                    .discover
+                    .iter()
+                    .flat_map(|d| d.new.iter().chain(&d.trending));
+                let recommended = self.recommendations.iter().flat_map(|r| &r.stations);
+                let listed = self
+                    .search_results
+                    .iter()
+                    .chain(&self.starter_stations)
+                    .chain(discover)
+                    .chain(recommended);
+                if let Some(variant) =
+                    quality::pick_variant(&station, listed, self.config.stream_preference)
+                {
+                    info!(
+                        "Playing {} kbit/s variant of {} instead of {} kbit/s",
+                        variant.bitrate, station.name, station.bitrate
+                    );
+                    station = Arc::new(variant.clone());
+                }
+                let is_same = self
+                    .current_station
+                    .as_ref()
+                    .map(|s| s.stationuuid == station.stationuuid)
+                    .unwrap_or(false);
+
+                self.keep_position();
+                let task = if self.is_playing && is_same {
                     self.is_playing = false;
-                    debug!("Paused playback via shortcut");
-                } else if let Some(station) = &self.current_station {
-                    self.audio
-                        .play(station.url_resolved.clone(), self.config.volume);
+                    debug!("Stopped playback");
+                    self.output_stop()
+                } else {
+                    if let Some(previous) = self.current_station.replace(station.clone()) {
+                        if !is_same {
+                            self.previous_station = Some(previous);
+                        }
+                    }
                     self.is_playing = true;
-                    debug!("Resumed playback via shortcut: {}", station.name);
+                    self.vote_notice = None;
+                    debug!("Playing: {}", station.name);
+                    self.output_play(&station)
+                };
+                self.push_state();
+                return task;
+            }
+            Message::SwitchStation => {
+                if let Some(previous) = self.previous_station.clone() {
+                    return self.update(Message::PlayStation(previous));
                 }
-                self.push_mpris_state();
             }
-            Message::MprisEvent(event) => match event {
-                mpris::MprisEvent::Ready(tx) => {
-                    info!("MPRIS server ready");
-                    self.mpris_tx = Some(tx);
-                    self.push_mpris_state();
+            Message::SeekDragged(secs) => {
+                self.seek_drag = Some(secs);
+            }
+            Message::SeekReleased => {
+                if let Some(secs) = self.seek_drag.take() {
+                    self.position = secs as u64;
+                    self.audio.seek(self.position);
                 }
-                mpris::MprisEvent::Command(cmd) => match cmd {
-                    mpris::MprisCommand::Play => {
-                        if !self.is_playing {
-                            if let Some(station) = &self.current_station {
-                                self.audio
-                                    .play(station.url_resolved.clone(), self.config.volume);
-                                self.is_playing = true;
-                                debug!("MPRIS: Play");
-                                self.push_mpris_state();
-                            }
+            }
+            Message::DownloadEpisode(station) => {
+                let uuid = &station.stationuuid;
+                match self.downloads.iter_mut().find(|d| &d.stationuuid == uuid) {
+                    Some(download) => download.state = DownloadState::Running,
+                    None => self
+                        .downloads
+                        .push(Download::new(uuid.clone(), stream_url(&station, None))),
+                }
+                info!("Downloading {}", station.name);
+            }
+            Message::PauseDownload(uuid) => {
+                if let Some(download) = self.download_mut(&uuid) {
+                    download.state = DownloadState::Paused;
+                }
+            }
+            Message::DeleteDownload(uuid) => {
+                self.downloads.retain(|d| d.stationuuid != uuid);
+                if let Err(e) = downloads::delete(&uuid) {
+                    error!("Failed to delete download: {}", e);
+                    self.error_message = Some(e.to_string());
+                }
+            }
+            Message::DownloadEvent(event) => match event {
+                DownloadEvent::Progress {
+                    stationuuid,
+                    bytes,
+                    total,
+                } => {
+                    if let Some(download) = self.download_mut(&stationuuid) {
+                        download.bytes = bytes;
+                        download.total = total;
+                    }
+                }
+                DownloadEvent::Finished(stationuuid) => {
+                    if let Some(download) = self.download_mut(&stationuuid) {
+                        download.state = DownloadState::Done;
+                    }
+                }
+                DownloadEvent::Failed(stationuuid, error) => {
+                    warn!("Download of {} failed: {}", stationuuid, error);
+                    if let Some(download) = self.download_mut(&stationuuid) {
+                        download.state = DownloadState::Failed(error);
+                    }
+                }
+            },
+            Message::StartRecording(station) => {
+                if !RECORDER || self.recording.is_some() {
+                    return Task::none();
+                }
+                return start_recording(station, recorder::MAX_RECORDING_TIME, String::new());
+            }
+            Message::RecordingReady(recording) => {
+                if let Some(running) = &self.recording {
+                    info!(
+                        "Not recording {}: {} is being recorded",
+                        recording.station, running.station
+                    );
+                } else {
+                    info!("Recording {}", recording.station);
+                    self.recordings_started += 1;
+                    self.recording = Some(ActiveRecording {
+                        id: self.recordings_started,
+                        format: self.config.recording_format,
+                        bitrate: self.config.recording_bitrate,
+                        started_at: stats::now_secs(),
+                        ..recording
+                    });
+                }
+            }
+            Message::StopRecording => {
+                // Dropping the subscription ends it; the file keeps what was recorded
+                if let Some(recording) = self.recording.take() {
+                    info!("Stopped recording {}", recording.station);
+                    self.refresh_recordings();
+                }
+            }
+            Message::RecordEvent(event) => {
+                let toast = match event {
+                    RecordEvent::Progress { id, bytes } => {
+                        if let Some(recording) = self.recording.as_mut().filter(|r| r.id == id) {
+                            recording.bytes = bytes;
                         }
+                        return Task::none();
                     }
-                    mpris::MprisCommand::Pause | mpris::MprisCommand::Stop => {
-                        if self.is_playing {
-                            self.audio.stop();
-                            self.is_playing = false;
-                            debug!("MPRIS: Stop");
-                            self.push_mpris_state();
+                    RecordEvent::Finished(id, path) => {
+                        if self.recording.as_ref().is_none_or(|r| r.id != id) {
+                            return Task::none();
                         }
+                        let name = path.file_name().unwrap_or_default().to_string_lossy();
+                        fl!("recording-saved", name = name.as_ref())
                     }
-                    mpris::MprisCommand::PlayPause => {
-                        return self.update(Message::TogglePlayPause);
+                    RecordEvent::Failed(id, error) => {
+                        if self.recording.as_ref().is_none_or(|r| r.id != id) {
+                            return Task::none();
+                        }
+                        warn!("Recording failed: {}", error);
+                        fl!("recording-failed", error = error)
                     }
-                    mpris::MprisCommand::SetVolume(vol) => {
-                        let volume = mpris::volume_from_mpris(vol);
-                        self.config.volume = volume;
-                        self.audio.set_volume(volume as f32);
-                        debug!("MPRIS: Volume set to {}%", volume);
+                };
+                self.recording = None;
+                self.refresh_recordings();
+                return self.toasts.push(Toast::new(toast)).map(Into::into);
+            }
+            Message::PlayRecording(path) => {
+                let station = self
+                    .recordings
+                    .iter()
+                    .find(|recording| recording.path == path)
+                    .and_then(Recording::station);
+                if let Some(station) = station {
+                    return self.update(Message::PlayStation(Arc::new(station)));
+                }
+            }
+            Message::RevealRecording(path) => {
+                return Task::perform(recorder::reveal(path), Message::RecordingRevealed)
+                    .map(Into::into);
+            }
+            Message::RecordingRevealed(res) => {
+                if let Err(e) = res {
+                    error!("Failed to show recording: {}", e);
+                    self.recordings_notice = Some(format!("{} {}", fl!("error-message"), e));
+                }
+            }
+            Message::EditRecordingName(path) => {
+                let name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                self.recording_rename = Some((path, name));
+            }
+            Message::RecordingNameChanged(name) => {
+                if let Some((_, draft)) = self.recording_rename.as_mut() {
+                    *draft = name;
+                }
+            }
+            Message::RenameRecording => {
+                if let Some((path, name)) = self.recording_rename.take() {
+                    self.recordings_notice = match recorder::rename(&path, &name) {
+                        Ok(renamed) => {
+                            info!("Renamed recording to {}", renamed.display());
+                            None
+                        }
+                        Err(e) => {
+                            error!("Failed to rename {}: {}", path.display(), e);
+                            Some(format!("{} {}", fl!("error-message"), e))
+                        }
+                    };
+                    self.refresh_recordings();
+                }
+            }
+            Message::DeleteRecording(path) => {
+                self.recordings_notice = match recorder::delete(&path) {
+                    Ok(()) => {
+                        info!("Deleted recording {}", path.display());
+                        None
+                    }
+                    Err(e) => {
+                        error!("Failed to delete {}: {}", path.display(), e);
+                        Some(format!("{} {}", fl!("error-message"), e))
+                    }
+                };
+                self.refresh_recordings();
+            }
+            Message::SetRecordingRetention(days) => {
+                self.config.recording_retention_days = days;
+                self.save_config();
+            }
+            Message::SetRecordingFormat(format) => {
+                self.config.recording_format = format;
+                self.save_config();
+            }
+            Message::SetRecordingBitrate(kbps) => {
+                self.config.recording_bitrate = kbps;
+                self.save_config();
+            }
+            Message::SetSortOrder(order) => {
+                self.config.sort_order = order;
+                self.save_config();
+            }
+            Message::ShowPage(page) => {
+                self.page = page;
+                self.stats_notice = None;
+                self.settings_notice = None;
+                if page == Page::Settings {
+                    self.refresh_cache_size();
+                    self.hook_drafts = self.config.hooks.clone();
+                }
+                if page == Page::Tracks {
+                    self.load_station_tracks();
+                }
+                if page == Page::Recordings {
+                    self.recording_rename = None;
+                    self.recordings_notice = None;
+                    self.refresh_recordings();
+                }
+                self.save_session();
+                if page == Page::Discover {
+                    return Task::batch([
+                        self.restore_scroll(),
+                        self.update_favicons(),
+                        self.refresh_discover(),
+                    ]);
+                }
+                return Task::batch([self.restore_scroll(), self.update_favicons()]);
+            }
+            Message::CreateBackup => {
+                let notice = match backup::default_path() {
+                    Some(path) => match backup::write(&path, &self.backup()) {
+                        Ok(()) => {
+                            info!("Wrote backup to {}", path.display());
+                            self.restore_path = path.display().to_string();
+                            format!("{} {}", fl!("backup-written"), path.display())
+                        }
+                        Err(e) => {
+                            error!("Failed to write backup: {}", e);
+                            format!("{} {}", fl!("error-message"), e)
+                        }
+                    },
+                    None => format!("{} {}", fl!("error-message"), fl!("stats-no-export-dir")),
+                };
+                self.settings_notice = Some(notice);
+            }
+            Message::BlockedKeywordChanged(keyword) => {
+                self.new_blocked_keyword = keyword;
+            }
+            Message::AddBlockedKeyword => {
+                let keyword = filter::normalize_keyword(&self.new_blocked_keyword);
+                self.new_blocked_keyword.clear();
+                if !keyword.is_empty() && !self.config.blocked_keywords.contains(&keyword) {
+                    self.config.blocked_keywords.push(keyword);
+                    self.save_config();
+                }
+            }
+            Message::RemoveBlockedKeyword(keyword) => {
+                self.config.blocked_keywords.retain(|k| *k != keyword);
+                self.save_config();
+            }
+            Message::HideStation(station) => {
+                if !filter::is_hidden(&station, &self.config.hidden_stations) {
+                    info!("Hiding station {}", station.name);
+                    self.config
+                        .hidden_stations
+                        .push(filter::HiddenStation::from(&*station));
+                    self.save_config();
+                }
+            }
+            Message::UnhideStation(uuid) => {
+                self.config
+                    .hidden_stations
+                    .retain(|h| h.stationuuid != uuid);
+                self.save_config();
+            }
+            Message::KioskPinChanged(pin) => {
+                self.kiosk_pin = pin;
+            }
+            Message::Lock => {
+                let pin = self.kiosk_pin.trim().to_string();
+                if !kiosk::is_valid_pin(&pin) {
+                    self.settings_notice = Some(fl!("kiosk-invalid-pin"));
+                    return Task::none();
+                }
+                // The PIN is stored first, so the applet never locks with
+                // a PIN that cannot be checked
+                return Task::perform(
+                    async move { kiosk::set_pin(pin).await.map_err(|e| e.to_string()) },
+                    Message::Locked,
+                )
+                .map(Into::into);
+            }
+            Message::Locked(res) => match res {
+                Ok(()) => {
+                    info!("Lock mode on");
+                    self.kiosk_pin.clear();
+                    self.enter_lock_mode();
+                    self.save_config();
+                    self.push_remote_state();
+                }
+                Err(e) => {
+                    error!("Failed to store the lock PIN: {}", e);
+                    self.settings_notice = Some(format!("{} {}", fl!("error-message"), e));
+                }
+            },
+            Message::ShowUnlock => {
+                self.unlock_pin = Some(String::new());
+            }
+            Message::UnlockPinChanged(pin) => {
+                self.unlock_pin = Some(pin);
+            }
+            Message::Unlock => {
+                let pin = self.unlock_pin.take().unwrap_or_default();
+                return Task::perform(
+                    async move { kiosk::check_pin(pin).await.map_err(|e| e.to_string()) },
+                    Message::UnlockChecked,
+                )
+                .map(Into::into);
+            }
+            Message::UnlockChecked(res) => {
+                let toast = match res {
+                    Ok(true) => {
+                        info!("Lock mode off");
+                        self.config.kiosk_locked = false;
                         self.save_config();
-                        self.push_mpris_state();
+                        self.push_remote_state();
+                        fl!("kiosk-unlocked")
                     }
-                    mpris::MprisCommand::Raise => {
-                        return self.update(Message::TogglePopup);
+                    Ok(false) => {
+                        warn!("Wrong PIN entered to unlock");
+                        fl!("kiosk-wrong-pin")
                     }
-                    mpris::MprisCommand::Quit => {
-                        if self.is_playing {
-                            self.audio.stop();
-                            self.is_playing = false;
-                            self.push_mpris_state();
+                    Err(e) => {
+                        error!("Failed to check the lock PIN: {}", e);
+                        format!("{} {}", fl!("error-message"), e)
+                    }
+                };
+                return self.toasts.push(Toast::new(toast)).map(Into::into);
+            }
+            Message::ToggleSelectMode => {
+                self.selection.toggle_active();
+            }
+            Message::ToggleSelected(uuid) => {
+                self.selection.toggle(uuid);
+            }
+            Message::ToggleSelectAll => {
+                let listed: Vec<Station> = self.listed_stations().into_iter().cloned().collect();
+                self.selection.toggle_all(&listed);
+            }
+            Message::BatchFavorite => {
+                let stations = self.selected_stations();
+                let added = selection::add_missing(&mut self.config.favorites, &stations);
+                info!("Added {} selected stations to favorites", added);
+                self.save_config();
+                self.selection.clear();
+                return self
+                    .toasts
+                    .push(Toast::new(fl!("batch-favorited", count = added)))
+                    .map(Into::into);
+            }
+            Message::BatchAddToProfile(name) => {
+                let stations = self.selected_stations();
+                let added = if name == self.config.active_profile {
+                    let added = selection::add_missing(&mut self.config.favorites, &stations);
+                    self.save_config();
+                    added
+                } else if let Some(home) = self.home_profile.as_mut().filter(|_| name.is_empty()) {
+                    // The default profile's favorites live in the main config
+                    let added = selection::add_missing(&mut home.favorites, &stations);
+                    self.save_config();
+                    added
+                } else {
+                    let mut data = profiles::load(&name);
+                    let added = selection::add_missing(&mut data.favorites, &stations);
+                    if let Err(e) = profiles::save(&name, &data) {
+                        error!("Failed to save profile {}: {:?}", name, e);
+                        return self
+                            .toasts
+                            .push(Toast::new(format!("{} {:?}", fl!("error-message"), e)))
+                            .map(Into::into);
+                    }
+                    added
+                };
+                info!("Added {} selected stations to profile {:?}", added, name);
+                self.selection.clear();
+                let profile = if name.is_empty() {
+                    fl!("profile-default")
+                } else {
+                    name
+                };
+                return self
+                    .toasts
+                    .push(Toast::new(fl!(
+                        "batch-added-to-profile",
+                        count = added,
+                        profile = profile
+                    )))
+                    .map(Into::into);
+            }
+            Message::BatchHide => {
+                let stations = self.selected_stations();
+                let mut hidden = 0;
+                for station in &stations {
+                    if !filter::is_hidden(station, &self.config.hidden_stations) {
+                        self.config
+                            .hidden_stations
+                            .push(filter::HiddenStation::from(station));
+                        hidden += 1;
+                    }
+                }
+                info!("Hiding {} selected stations", hidden);
+                self.save_config();
+                self.selection.clear();
+                return self
+                    .toasts
+                    .push(Toast::new(fl!("batch-hidden", count = hidden)))
+                    .map(Into::into);
+            }
+            Message::BatchQueue => {
+                let added = self.queue.extend(self.selected_stations());
+                info!("Queued {} selected stations", added);
+                self.selection.clear();
+                return self
+                    .toasts
+                    .push(Toast::new(fl!("queue-added", count = added)))
+                    .map(Into::into);
+            }
+            Message::QueueStation(station) => {
+                let added = self.queue.extend([Arc::unwrap_or_clone(station)]);
+                return self
+                    .toasts
+                    .push(Toast::new(fl!("queue-added", count = added)))
+                    .map(Into::into);
+            }
+            Message::PlayQueue => {
+                let Some(station) = self.queue.take_next() else {
+                    return Task::none();
+                };
+                info!(
+                    "Playing {} from the queue, {} left",
+                    station.name,
+                    self.queue.len()
+                );
+                let on_air = self.is_playing
+                    && self
+                        .current_station
+                        .as_ref()
+                        .is_some_and(|s| s.stationuuid == station.stationuuid);
+                // Playing the station on air again would stop it
+                let task = if on_air {
+                    Task::none()
+                } else {
+                    self.update(Message::PlayStation(Arc::new(station)))
+                };
+                if let Some(current) = &self.current_station {
+                    self.queue
+                        .started(current.stationuuid.clone(), Instant::now());
+                }
+                return task;
+            }
+            Message::ClearQueue => {
+                self.queue.clear();
+            }
+            Message::QueueTick => {
+                let playing = self
+                    .current_station
+                    .as_ref()
+                    .filter(|_| self.is_playing)
+                    .map(|station| station.stationuuid.as_str());
+                if self
+                    .queue
+                    .is_due(Instant::now(), self.config.queue_dwell_secs, playing)
+                {
+                    return self.update(Message::PlayQueue);
+                }
+            }
+            Message::QueueDwellChanged(secs) => {
+                self.config.queue_dwell_secs = queue::clamp_dwell(secs.round() as u32);
+                self.save_config();
+            }
+            Message::BatchExport => {
+                let stations = self.selected_stations();
+                let dir = dirs::download_dir().or_else(dirs::home_dir);
+                let toast = match dir {
+                    Some(dir) => {
+                        let path = dir.join("radio-stations.opml");
+                        let document = opml::to_opml("Radio for COSMIC stations", &stations);
+                        match std::fs::write(&path, document) {
+                            Ok(()) => {
+                                info!("Exported {} stations to {}", stations.len(), path.display());
+                                format!("{} {}", fl!("stats-exported"), path.display())
+                            }
+                            Err(e) => {
+                                error!("Failed to export stations: {}", e);
+                                format!("{} {}", fl!("error-message"), e)
+                            }
                         }
                     }
-                },
-            },
-            Message::KeyboardEvent(event) => {
-                if let Event::Keyboard(cosmic::iced::keyboard::Event::KeyPressed { key, .. }) = event {
-                    match key {
-                        Key::Named(Named::Space) => {
-                            return self.update(Message::TogglePlayPause);
+                    None => format!("{} {}", fl!("error-message"), fl!("stats-no-export-dir")),
+                };
+                return self.toasts.push(Toast::new(toast)).map(Into::into);
+            }
+            Message::RequestTimeoutChanged(secs) => {
+                self.config.request_timeout_secs = api::clamp_timeout(secs.round() as u32);
+                self.save_config();
+            }
+            Message::SearchLimitChanged(limit) => {
+                self.config.search_limit = api::clamp_limit(limit.round() as u32);
+                self.save_config();
+            }
+            Message::SetIpPreference(preference) => {
+                self.config.ip_preference = preference;
+                self.save_config();
+            }
+            Message::CacheLimitChanged(mb) => {
+                self.config.cache_max_mb = cache::clamp_max_mb(mb.round() as u32);
+                self.save_config();
+                if let Some(cache) = Cache::open(self.config.cache_max_mb) {
+                    if let Err(e) = cache.evict() {
+                        warn!("Failed to trim cache: {}", e);
+                    }
+                }
+                self.refresh_cache_size();
+            }
+            Message::ClearCache => {
+                if let Some(cache) = Cache::open(self.config.cache_max_mb) {
+                    match cache.clear() {
+                        Ok(()) => {
+                            info!("Cleared cache");
+                            self.known_stations.clear();
+                            self.rebuild_station_index();
                         }
-                        Key::Named(Named::ArrowUp) => {
-                            return self.update(Message::VolumeUp);
+                        Err(e) => {
+                            error!("Failed to clear cache: {}", e);
+                            self.settings_notice = Some(format!("{} {}", fl!("error-message"), e));
                         }
-                        Key::Named(Named::ArrowDown) => {
-                            return self.update(Message::VolumeDown);
+                    }
+                }
+                self.refresh_cache_size();
+            }
+            Message::DownloadQuotaChanged(mb) => {
+                self.config.download_quota_mb = downloads::clamp_quota_mb(mb.round() as u32);
+                self.save_config();
+            }
+            Message::DeleteDownloads => {
+                self.downloads.clear();
+                match downloads::delete_all() {
+                    Ok(()) => info!("Deleted downloads"),
+                    Err(e) => {
+                        error!("Failed to delete downloads: {}", e);
+                        self.settings_notice = Some(format!("{} {}", fl!("error-message"), e));
+                    }
+                }
+                self.refresh_cache_size();
+            }
+            Message::SetLogLevel(level) => {
+                self.config.log_level = level;
+                logging::set_level(level);
+                info!("Log level set to {}", level.as_str());
+                self.save_config();
+            }
+            Message::OpenLogDir => {
+                if let Some(dir) = logging::log_dir() {
+                    match std::process::Command::new("xdg-open").arg(&dir).spawn() {
+                        Ok(mut child) => {
+                            // Reap the opener so it doesn't linger as a zombie
+                            std::thread::spawn(move || child.wait());
                         }
-                        Key::Named(Named::Escape) => {
-                            if let Some(p) = self.popup.take() {
-                                return destroy_popup(p);
-                            }
+                        Err(e) => {
+                            error!("Failed to open {}: {}", dir.display(), e);
+                            self.settings_notice = Some(format!("{} {}", fl!("error-message"), e));
                         }
-                        _ => {}
                     }
                 }
             }
-        }
-        Task::none()
-    }
+            Message::SetResumePlayback(resume) => {
+                self.config.resume_playback = resume;
+                self.save_config();
+            }
+            Message::SetStreamPreference(preference) => {
+                self.config.stream_preference = preference;
+                self.save_config();
+            }
+            Message::SetNormalization(mode) => {
+                self.config.loudness.mode = mode;
+                self.save_config();
+                return self.restart_stream();
+            }
+            Message::LoudnessTargetChanged(target) => {
+                self.config.loudness.target = loudness::clamp_target(target.round() as i8);
+                self.save_config();
+            }
+            Message::LoudnessTargetReleased => return self.restart_stream(),
+            Message::SetMono(mono) => {
+                self.config.channels.mono = mono;
+                self.save_config();
+                return self.restart_stream();
+            }
+            Message::BalanceChanged(balance) => {
+                self.config.channels.balance = channels::clamp_balance(balance.round() as i8);
+                self.save_config();
+            }
+            Message::SetStationChannels(enabled) => {
+                let global = self.config.channels;
+                self.update_station_channels(|own| *own = enabled.then_some(global));
+                return self.restart_stream();
+            }
+            Message::SetStationMono(mono) => {
+                self.update_station_channels(|own| {
+                    if let Some(own) = own {
+                        own.mono = mono;
+                    }
+                });
+                return self.restart_stream();
+            }
+            Message::StationBalanceChanged(balance) => {
+                self.update_station_channels(|own| {
+                    if let Some(own) = own {
+                        own.balance = channels::clamp_balance(balance.round() as i8);
+                    }
+                });
+            }
+            Message::BalanceReleased => return self.restart_stream(),
+            Message::SetFetchCoverArt(fetch) => {
+                self.config.fetch_cover_art = fetch;
+                self.save_config();
+                if !fetch && self.cover_art.take().is_some() {
+                    self.push_state();
+                }
+            }
+            Message::SetShowFavicons(show) => {
+                self.config.show_favicons = show;
+                self.save_config();
+                return self.update_favicons();
+            }
+            Message::SetShowLyrics(show) => {
+                self.config.show_lyrics = show;
+                self.save_config();
+                if !show {
+                    self.lyrics = None;
+                } else if self.lyrics.is_none() {
+                    return self.lookup_lyrics();
+                }
+            }
+            Message::SetShowVisualizer(show) => {
+                self.config.show_visualizer = show;
+                self.save_config();
+                self.visualizer.clear();
+                // mpv only measures levels when started with the filter
+                return self.restart_stream();
+            }
+            Message::SetDucking(enabled) => {
+                self.config.ducking.enabled = enabled;
+                self.save_config();
+                if !enabled && std::mem::take(&mut self.ducked) {
+                    return self.output_volume(self.config.volume);
+                }
+            }
+            Message::DuckingAmountChanged(amount) => {
+                self.config.ducking.amount = ducking::clamp_amount(amount.round() as u8);
+                self.save_config();
+                if self.ducked {
+                    return self.output_volume(self.config.volume);
+                }
+            }
+            Message::SetDevicePause(enabled) => {
+                self.config.device_pause.enabled = enabled;
+                self.save_config();
+                if !enabled {
+                    self.outputs = None;
+                    self.paused_for_device = None;
+                }
+            }
+            Message::SetDeviceAutoResume(auto_resume) => {
+                self.config.device_pause.auto_resume = auto_resume;
+                self.save_config();
+            }
+            Message::QuietTick => {
+                return self.update_quiet();
+            }
+            Message::SetQuietHours(enabled) => {
+                self.config.quiet_hours.enabled = enabled;
+                self.save_config();
+                return self.update_quiet();
+            }
+            Message::QuietStartChanged(minute) => {
+                self.config.quiet_hours.start = minute.round() as u16;
+                self.save_config();
+                return self.update_quiet();
+            }
+            Message::QuietEndChanged(minute) => {
+                self.config.quiet_hours.end = minute.round() as u16;
+                self.save_config();
+                return self.update_quiet();
+            }
+            Message::QuietMaxVolumeChanged(volume) => {
+                self.config.quiet_hours.max_volume = quiet::clamp_max_volume(volume.round() as u8);
+                self.save_config();
+                if self.quiet {
+                    return self.output_volume(self.config.volume);
+                }
+            }
+            Message::SetDisableHistory(disabled) => {
+                self.config.disable_history = disabled;
+                self.save_config();
+            }
+            Message::SetIncognito(incognito) => {
+                info!("Incognito listening: {}", incognito);
+                self.incognito = incognito;
+            }
+            Message::ClearListeningHistory => {
+                let res = match stats::history_path() {
+                    Some(path) => stats::replace(&path, &[]),
+                    None => Ok(()),
+                };
+                self.settings_notice = Some(match res {
+                    Ok(()) => {
+                        info!("Cleared listening history");
+                        self.history.clear();
+                        self.config.reliability.clear();
+                        self.save_config();
+                        fl!("privacy-history-cleared")
+                    }
+                    Err(e) => {
+                        error!("Failed to clear listening history: {}", e);
+                        format!("{} {}", fl!("error-message"), e)
+                    }
+                });
+            }
+            Message::ClearTrackLogs => {
+                let res = tracklog::tracks_dir().map_or(Ok(()), |dir| tracklog::clear(&dir));
+                self.settings_notice = Some(match res {
+                    Ok(()) => {
+                        info!("Cleared track logs");
+                        self.session_tracks.clear();
+                        self.station_tracks.clear();
+                        fl!("privacy-tracks-cleared")
+                    }
+                    Err(e) => {
+                        error!("Failed to clear track logs: {}", e);
+                        format!("{} {}", fl!("error-message"), e)
+                    }
+                });
+            }
+            Message::HookInputChanged(event, command) => {
+                *self.hook_drafts.command_mut(event) = command;
+            }
+            Message::SaveHooks => {
+                self.config.hooks = self.hook_drafts.clone();
+                self.save_config();
+                self.settings_notice = Some(fl!("hooks-saved"));
+            }
+            Message::LyricsLoaded(title, res) => {
+                if self.track_title.as_deref() == Some(title.as_str()) {
+                    match res {
+                        Ok(lyrics) => self.lyrics = lyrics,
+                        Err(e) => warn!("Lyrics lookup for {} failed: {}", title, e),
+                    }
+                }
+            }
+            // Redraw to follow synced lyrics
+            Message::LyricsTick => {}
+            Message::CoverArtLoaded(title, res) => {
+                // The song may have changed while looking it up
+                if self.track_title.as_deref() == Some(title.as_str()) {
+                    match res {
+                        Ok(art) => self.cover_art = art,
+                        Err(e) => warn!("Cover art lookup for {} failed: {}", title, e),
+                    }
+                    self.push_state();
+                }
+            }
+            Message::SetIconStyle(style) => {
+                self.config.icon_style = style;
+                self.save_config();
+            }
+            Message::PanelScrolled(delta) => {
+                let notches = self.wheel.notches(delta);
+                if let Some(step) = self.config.panel_scroll.step(self.modifiers) {
+                    let volume = wheel::scrolled_volume(self.config.volume, notches, step);
+                    if notches != 0 && volume != self.config.volume {
+                        return self.update(Message::VolumeChanged(f32::from(volume)));
+                    }
+                }
+            }
+            Message::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+            }
+            Message::SetPanelScroll(enabled) => {
+                self.config.panel_scroll.enabled = enabled;
+                self.save_config();
+            }
+            Message::PanelScrollStepChanged(step) => {
+                self.config.panel_scroll.step = wheel::clamp_step(step.round() as u8);
+                self.save_config();
+            }
+            Message::SetScrollModifier(modifier) => {
+                self.config.panel_scroll.modifier = modifier;
+                self.save_config();
+            }
+            Message::PanelClicked => {
+                let double_mapped = self.config.panel_clicks.double != ClickAction::Nothing;
+                match self.clicks.press(Instant::now(), double_mapped) {
+                    Press::Single => {
+                        return self.update(Message::RunClickAction(self.config.panel_clicks.left));
+                    }
+                    Press::Double => {
+                        return self
+                            .update(Message::RunClickAction(self.config.panel_clicks.double));
+                    }
+                    Press::Wait(number) => {
+                        return Task::perform(
+                            tokio::time::sleep(clicks::DOUBLE_CLICK_INTERVAL),
+                            move |()| Message::PanelClickTimeout(number),
+                        )
+                        .map(Into::into);
+                    }
+                }
+            }
+            Message::PanelClickTimeout(number) => {
+                if self.clicks.timeout(number) {
+                    return self.update(Message::RunClickAction(self.config.panel_clicks.left));
+                }
+            }
+            Message::PanelMiddleClicked => {
+                return self.update(Message::RunClickAction(self.config.panel_clicks.middle));
+            }
+            Message::RunClickAction(action) => {
+                let message = match action {
+                    ClickAction::Nothing => return Task::none(),
+                    ClickAction::OpenPopup => Message::TogglePopup,
+                    ClickAction::TogglePlayback => Message::TogglePlayPause,
+                    ClickAction::NextPreset => Message::NextPreset,
+                    ClickAction::Mute => Message::ToggleMute,
+                };
+                return self.update(message);
+            }
+            Message::SetClickAction(kind, action) => {
+                self.config.panel_clicks.set_action(kind, action);
+                self.save_config();
+            }
+            Message::NextPreset => {
+                let favorites = &self.config.favorites;
+                let current = self.current_station.as_ref().and_then(|current| {
+                    favorites
+                        .iter()
+                        .position(|s| s.stationuuid == current.stationuuid)
+                });
+                let next = current.map_or(0, |i| (i + 1) % favorites.len().max(1));
+                let Some(station) = favorites.get(next) else {
+                    return Task::none();
+                };
+                // A single favorite already on air would be stopped
+                if self.is_playing && current == Some(next) {
+                    return Task::none();
+                }
+                return self.update(Message::PlayStation(Arc::new(station.clone())));
+            }
+            Message::ToggleMute => {
+                if let Some(volume) = self.muted_volume.take() {
+                    debug!("Unmuted");
+                    return self.update(Message::VolumeChanged(f32::from(volume)));
+                }
+                if self.config.volume > 0 {
+                    let volume = self.config.volume;
+                    debug!("Muted");
+                    let task = self.update(Message::VolumeChanged(0.0));
+                    self.muted_volume = Some(volume);
+                    return task;
+                }
+            }
+            Message::TickerTick => {
+                self.ticker_offset = self.ticker_offset.wrapping_add(1);
+            }
+            Message::SetTicker(enabled) => {
+                self.config.ticker.enabled = enabled;
+                self.ticker_offset = 0;
+                self.save_config();
+            }
+            Message::TickerWidthChanged(width) => {
+                self.config.ticker.max_width = ticker::clamp_width(width.round() as u32);
+                self.save_config();
+            }
+            Message::TickerSpeedChanged(speed) => {
+                self.config.ticker.speed = ticker::clamp_speed(speed.round() as u32);
+                self.save_config();
+            }
+            Message::SetCountryFilter(filter) => {
+                info!("Preferred country set to {:?}", filter);
+                self.config.country = filter;
+                self.country_input.clear();
+                self.save_config();
+            }
+            Message::CountryInputChanged(input) => {
+                self.country_input = input;
+            }
+            Message::SubmitCountry => match CountryFilter::from_input(&self.country_input) {
+                Some(filter) => return self.update(Message::SetCountryFilter(filter)),
+                None => {
+                    self.settings_notice =
+                        Some(format!("{} {}", fl!("country-invalid"), self.country_input));
+                }
+            },
+            Message::SetLanguageFilter(enabled) => {
+                self.config.language_filter.enabled = enabled;
+                self.save_config();
+                return self.update_favicons();
+            }
+            Message::LanguageInputChanged(input) => {
+                self.language_input = input;
+            }
+            Message::AddLanguage => {
+                if self.config.language_filter.add(&self.language_input) {
+                    self.language_input.clear();
+                    self.save_config();
+                    return self.update_favicons();
+                }
+            }
+            Message::RemoveLanguage(language) => {
+                self.config.language_filter.extra.retain(|l| *l != language);
+                self.save_config();
+                return self.update_favicons();
+            }
+            Message::RestorePathChanged(path) => {
+                self.restore_path = path;
+            }
+            Message::RestoreBackup => {
+                let uri = self.restore_path.trim().to_string();
+                return self.restore_backup(&uri);
+            }
+            Message::ExportHistory(format) => {
+                let dir = dirs::download_dir().or_else(dirs::home_dir);
+                let notice = match dir {
+                    Some(dir) => {
+                        let path = dir.join(format!("radio-history.{}", format.extension()));
+                        match stats::export_to_file(&self.history, format, &path) {
+                            Ok(()) => {
+                                info!("Exported listening history to {}", path.display());
+                                format!("{} {}", fl!("stats-exported"), path.display())
+                            }
+                            Err(e) => {
+                                error!("Failed to export history: {}", e);
+                                format!("{} {}", fl!("error-message"), e)
+                            }
+                        }
+                    }
+                    None => format!("{} {}", fl!("error-message"), fl!("stats-no-export-dir")),
+                };
+                self.stats_notice = Some(notice);
+            }
+            Message::SwitchProfile(name) => {
+                return self.switch_profile(name);
+            }
+            Message::NewProfileNameChanged(name) => {
+                self.new_profile_name = name;
+            }
+            Message::CreateProfile => {
+                let name = self.new_profile_name.trim().to_string();
+                let slug = profiles::slug(&name);
+                let taken = self
+                    .config
+                    .profiles
+                    .iter()
+                    .any(|p| profiles::slug(p) == slug);
+                if slug.is_empty() || taken {
+                    return Task::none();
+                }
+                self.new_profile_name.clear();
+                self.config.profiles.push(name.clone());
+                return self.switch_profile(name);
+            }
+            Message::ClearSearch => {
+                self.search_query.clear();
+                self.search_results.clear();
+                self.genre_filter = None;
+                self.local_matches.clear();
+                self.error_message = None;
+            }
+            Message::ToggleFavorite(station) => {
+                if let Some(pos) = self
+                    .config
+                    .favorites
+                    .iter()
+                    .position(|s| s.stationuuid == station.stationuuid)
+                {
+                    self.config.favorites.remove(pos);
+                    self.config
+                        .smart_preset
+                        .remove_station(&station.stationuuid);
+                    self.config
+                        .recording_schedule
+                        .retain(|rule| rule.stationuuid != station.stationuuid);
+                    debug!("Removed from favorites: {}", station.name);
+                } else if let Some(existing) = duplicates::find(&self.config.favorites, &station) {
+                    debug!("{} looks like favorite {}", station.name, existing.name);
+                    self.duplicate = Some((Arc::unwrap_or_clone(station), existing.clone()));
+                    return Task::none();
+                } else {
+                    debug!("Added to favorites: {}", station.name);
+                    self.config.favorites.push(Arc::unwrap_or_clone(station));
+                }
+                self.save_config();
+            }
+            Message::ResolveDuplicate(merge) => {
+                let Some((station, existing)) = self.duplicate.take() else {
+                    return Task::none();
+                };
+                if merge {
+                    // The new entry takes the favorite's place and program guide,
+                    // under the name the user knows
+                    info!("Merging {} into favorite {}", station.name, existing.name);
+                    let merged = Station {
+                        name: existing.name.clone(),
+                        ..station
+                    };
+                    replace::replace(
+                        &mut self.config.favorites,
+                        &mut self.config.schedule,
+                        &existing.stationuuid,
+                        merged,
+                    );
+                } else {
+                    debug!("Added to favorites: {}", station.name);
+                    self.config.favorites.push(station);
+                }
+                self.save_config();
+            }
+            Message::LikeStation(station) => {
+                if !self
+                    .config
+                    .favorites
+                    .iter()
+                    .any(|s| s.stationuuid == station.stationuuid)
+                {
+                    debug!("Added to favorites: {}", station.name);
+                    self.config.favorites.push(Station::clone(&station));
+                    self.save_config();
+                }
+                if !api::is_votable(&station) {
+                    self.vote_notice = Some(fl!("vote-favorited"));
+                    return Task::none();
+                }
+                if self.config.voted_stations.contains(&station.stationuuid) {
+                    self.vote_notice = Some(fl!("vote-already"));
+                    return Task::none();
+                }
+                let uuid = station.stationuuid.clone();
+                let options = self.request_options();
+                return Task::perform(api::vote(uuid.clone(), options), move |res| {
+                    Message::VoteResult(uuid.clone(), res.map_err(|e| e.to_string()))
+                })
+                .map(Into::into);
+            }
+            Message::ShareStation(station, target) => {
+                let Some(link) = share::link(&station, target) else {
+                    return self
+                        .toasts
+                        .push(Toast::new(fl!("share-no-link")))
+                        .map(Into::into);
+                };
+                debug!("Copying {:?} link for {}", target, station.name);
+                let toast = match target {
+                    ShareTarget::Page => fl!("share-link-copied"),
+                    ShareTarget::Stream => fl!("share-stream-copied"),
+                };
+                return cosmic::iced::clipboard::write(link)
+                    .chain(self.toasts.push(Toast::new(toast)).map(Into::into));
+            }
+            Message::CloseToast(id) => {
+                self.toasts.remove(id);
+            }
+            Message::BookmarkTrack(title, station) => {
+                let bookmark = SongBookmark::from_icy(&title, &station, stats::now_secs());
+                let toast = if self.config.bookmarks.iter().any(|b| b.same_song(&bookmark)) {
+                    fl!("bookmark-exists")
+                } else {
+                    info!("Bookmarked {}", bookmark.label());
+                    self.config.bookmarks.push(bookmark);
+                    self.save_config();
+                    fl!("bookmark-saved")
+                };
+                return self.toasts.push(Toast::new(toast)).map(Into::into);
+            }
+            Message::EditSchedule(station) => {
+                self.schedule_station = Some(Arc::unwrap_or_clone(station));
+                self.show_draft = ShowDraft::default();
+                self.ical_url.clear();
+                self.schedule_notice = None;
+                return self.update(Message::ShowPage(Page::Schedule));
+            }
+            Message::ShowTitleChanged(title) => self.show_draft.title = title,
+            Message::ShowStartChanged(start) => self.show_draft.start = start,
+            Message::ShowEndChanged(end) => self.show_draft.end = end,
+            Message::ToggleShowDay(day) => self.show_draft.toggle_day(day),
+            Message::AddShow => {
+                let Some(station) = &self.schedule_station else {
+                    return Task::none();
+                };
+                match self.show_draft.to_show(&station.stationuuid) {
+                    Some(show) => {
+                        if !self.config.schedule.contains(&show) {
+                            self.config.schedule.push(show);
+                            self.save_config();
+                        }
+                        self.show_draft = ShowDraft::default();
+                        self.schedule_notice = None;
+                    }
+                    None => self.schedule_notice = Some(fl!("schedule-invalid")),
+                }
+            }
+            Message::RemoveShow(index) => {
+                if index < self.config.schedule.len() {
+                    self.config.schedule.remove(index);
+                    self.save_config();
+                }
+            }
+            Message::IcalUrlChanged(url) => self.ical_url = url,
+            Message::ImportIcal => {
+                let url = self.ical_url.trim().to_string();
+                let Some(station) = &self.schedule_station else {
+                    return Task::none();
+                };
+                if url.is_empty() {
+                    return Task::none();
+                }
+                self.schedule_notice = Some(fl!("schedule-importing"));
+                let uuid = station.stationuuid.clone();
+                let timeout = self.request_options().timeout;
+                return Task::perform(
+                    async move {
+                        schedule::fetch_ical(url, timeout)
+                            .await
+                            .map_err(|e| e.to_string())
+                    },
+                    move |res| Message::IcalFetched(uuid.clone(), res),
+                )
+                .map(Into::into);
+            }
+            Message::IcalFetched(uuid, res) => {
+                self.schedule_notice = Some(match res {
+                    Ok(text) => {
+                        let mut added = 0;
+                        for show in schedule::import_ical(&text, &uuid) {
+                            if !self.config.schedule.contains(&show) {
+                                self.config.schedule.push(show);
+                                added += 1;
+                            }
+                        }
+                        info!("Imported {} shows for station {}", added, uuid);
+                        self.save_config();
+                        self.ical_url.clear();
+                        fl!("schedule-imported", count = added)
+                    }
+                    Err(e) => {
+                        warn!("Program guide import failed: {}", e);
+                        format!("{} {}", fl!("error-message"), e)
+                    }
+                });
+            }
+            Message::SetShowReminder(minutes) => {
+                self.config.show_reminder_mins = minutes;
+                self.save_config();
+            }
+            Message::AddPresetRule => {
+                let Some(station) = &self.schedule_station else {
+                    return Task::none();
+                };
+                match self.show_draft.to_preset_rule(&station.stationuuid) {
+                    Some(rule) => {
+                        if !self.config.smart_preset.rules.contains(&rule) {
+                            self.config.smart_preset.rules.push(rule);
+                            self.save_config();
+                        }
+                        self.schedule_notice = None;
+                    }
+                    None => self.schedule_notice = Some(fl!("preset-invalid")),
+                }
+            }
+            Message::RemovePresetRule(index) => {
+                if index < self.config.smart_preset.rules.len() {
+                    self.config.smart_preset.rules.remove(index);
+                    self.save_config();
+                }
+            }
+            Message::SetPresetFallback(enabled) => {
+                let Some(station) = &self.schedule_station else {
+                    return Task::none();
+                };
+                if enabled {
+                    self.config
+                        .smart_preset
+                        .fallback
+                        .clone_from(&station.stationuuid);
+                } else if self.config.smart_preset.fallback == station.stationuuid {
+                    self.config.smart_preset.fallback.clear();
+                }
+                self.save_config();
+            }
+            Message::RecordingFolderChanged(folder) => self.show_draft.folder = folder,
+            Message::AddRecordingRule => {
+                let Some(station) = &self.schedule_station else {
+                    return Task::none();
+                };
+                match self.show_draft.to_recording_rule(&station.stationuuid) {
+                    Some(rule) => {
+                        // One recording runs at a time, so windows may not overlap
+                        self.schedule_notice = if self
+                            .config
+                            .recording_schedule
+                            .iter()
+                            .any(|other| other.overlaps(&rule))
+                        {
+                            Some(fl!("recording-rule-overlaps"))
+                        } else {
+                            self.config.recording_schedule.push(rule);
+                            self.save_config();
+                            None
+                        };
+                    }
+                    None => self.schedule_notice = Some(fl!("preset-invalid")),
+                }
+            }
+            Message::RemoveRecordingRule(index) => {
+                if index < self.config.recording_schedule.len() {
+                    self.config.recording_schedule.remove(index);
+                    self.save_config();
+                }
+            }
+            Message::ScheduleTick => {
+                let due = schedule::due_reminders(
+                    &self.config.schedule,
+                    LocalTime::now(),
+                    self.config.show_reminder_mins,
+                );
+                // Forget shows that started, so next week's airing is announced too
+                self.reminded
+                    .retain(|key| due.iter().any(|show| show.key() == *key));
+                for show in due {
+                    if !self.reminded.insert(show.key()) {
+                        continue;
+                    }
+                    let station = self
+                        .config
+                        .favorites
+                        .iter()
+                        .find(|s| s.stationuuid == show.stationuuid)
+                        .map(|s| s.name.clone())
+                        .unwrap_or_default();
+                    self.notifier.notify(
+                        notify::Kind::Info,
+                        &fl!("show-reminder-title", title = show.title.as_str()),
+                        &fl!(
+                            "show-reminder-body",
+                            station = station,
+                            time = schedule::format_time(show.start)
+                        ),
+                    );
+                }
+                return self.start_scheduled_recordings();
+            }
+            Message::FlushNotifications => self.notifier.flush(),
+            Message::RemoveBookmark(index) => {
+                if index < self.config.bookmarks.len() {
+                    self.config.bookmarks.remove(index);
+                    self.save_config();
+                }
+            }
+            Message::ExportBookmarks(format) => {
+                let dir = dirs::download_dir().or_else(dirs::home_dir);
+                let toast = match dir {
+                    Some(dir) => {
+                        let path = dir.join(format!("radio-bookmarks.{}", format.extension()));
+                        match bookmarks::export_to_file(&self.config.bookmarks, format, &path) {
+                            Ok(()) => {
+                                info!("Exported bookmarks to {}", path.display());
+                                format!("{} {}", fl!("stats-exported"), path.display())
+                            }
+                            Err(e) => {
+                                error!("Failed to export bookmarks: {}", e);
+                                format!("{} {}", fl!("error-message"), e)
+                            }
+                        }
+                    }
+                    None => format!("{} {}", fl!("error-message"), fl!("stats-no-export-dir")),
+                };
+                return self.toasts.push(Toast::new(toast)).map(Into::into);
+            }
+            Message::CopyTrack(title) => {
+                return cosmic::iced::clipboard::write(title).chain(
+                    self.toasts
+                        .push(Toast::new(fl!("track-copied")))
+                        .map(Into::into),
+                );
+            }
+            Message::ToggleDiagnostics => {
+                self.show_diagnostics = !self.show_diagnostics;
+                if self.show_diagnostics {
+                    self.diagnostics.probe = None;
+                    return self.probe_stream();
+                }
+            }
+            Message::DiagnosticsProbed(uuid, probe) => {
+                if self
+                    .current_station
+                    .as_ref()
+                    .is_some_and(|s| s.stationuuid == uuid)
+                {
+                    self.diagnostics.probe = Some(probe);
+                }
+            }
+            Message::SetAllowInvalidCerts(allow) => {
+                return self.update_station_tls(|tls| tls.allow_invalid_certs = allow);
+            }
+            Message::SetUpgradeHttps(upgrade) => {
+                return self.update_station_tls(|tls| tls.upgrade_https = upgrade);
+            }
+            Message::CopyDiagnostics => {
+                return cosmic::iced::clipboard::write(self.diagnostics.report()).chain(
+                    self.toasts
+                        .push(Toast::new(fl!("diagnostics-copied")))
+                        .map(Into::into),
+                );
+            }
+            Message::VoteResult(uuid, res) => match res {
+                Ok(()) => {
+                    info!("Voted for station {}", uuid);
+                    self.vote_notice = Some(fl!("vote-sent"));
+                    if !self.config.voted_stations.contains(&uuid) {
+                        self.config.voted_stations.push(uuid);
+                        self.save_config();
+                    }
+                }
+                Err(e) => {
+                    warn!("Vote for {} failed: {}", uuid, e);
+                    self.vote_notice = Some(format!("{} {}", fl!("vote-failed"), e));
+                }
+            },
+            Message::VolumeChanged(vol) => {
+                let volume = vol.round() as u8;
+                self.config.volume = volume;
+                if volume > 0 {
+                    self.muted_volume = None;
+                }
+                debug!("Volume changed to {}%", volume);
+                self.save_config();
+                self.push_state();
+                return self.output_volume(volume);
+            }
+            Message::VolumeUp => {
+                let new_vol = (self.config.volume as i16 + 5).min(100) as u8;
+                self.config.volume = new_vol;
+                self.muted_volume = None;
+                debug!("Volume up to {}%", new_vol);
+                self.save_config();
+                self.push_state();
+                return self.output_volume(new_vol);
+            }
+            Message::VolumeDown => {
+                let new_vol = (self.config.volume as i16 - 5).max(0) as u8;
+                self.config.volume = new_vol;
+                debug!("Volume down to {}%", new_vol);
+                self.save_config();
+                self.push_state();
+                return self.output_volume(new_vol);
+            }
+            Message::TogglePlayPause => {
+                // Playback paused for another player resumes on the same station
+                let was_paused = std::mem::take(&mut self.paused_by_other_player);
+                let task = if self.is_playing {
+                    self.is_playing = false;
+                    debug!("Paused playback via shortcut");
+                    self.output_stop()
+                } else if let Some(station) = self.preset_station().filter(|_| !was_paused) {
+                    debug!("Smart preset picked {}", station.name);
+                    return self.update(Message::PlayStation(station));
+                } else if let Some(station) = self.current_station.clone() {
+                    self.is_playing = true;
+                    debug!("Resumed playback via shortcut: {}", station.name);
+                    self.output_play(&station)
+                } else {
+                    Task::none()
+                };
+                self.push_state();
+                return task;
+            }
+            Message::MprisEvent(event) => match event {
+                mpris::MprisEvent::Ready(tx) => {
+                    info!("MPRIS server ready");
+                    self.mpris_tx = Some(tx);
+                    self.push_state();
+                }
+                mpris::MprisEvent::Command(cmd) => match cmd {
+                    mpris::MprisCommand::Play => {
+                        if !self.is_playing {
+                            if let Some(station) = self.current_station.clone() {
+                                self.is_playing = true;
+                                debug!("MPRIS: Play");
+                                self.push_state();
+                                return self.output_play(&station);
+                            }
+                        }
+                    }
+                    mpris::MprisCommand::Pause | mpris::MprisCommand::Stop => {
+                        if self.is_playing {
+                            self.is_playing = false;
+                            debug!("MPRIS: Stop");
+                            self.push_state();
+                            return self.output_stop();
+                        }
+                    }
+                    mpris::MprisCommand::PlayPause => {
+                        return self.update(Message::TogglePlayPause);
+                    }
+                    mpris::MprisCommand::SetVolume(vol) => {
+                        let volume = mpris::volume_from_mpris(vol);
+                        self.config.volume = volume;
+                        debug!("MPRIS: Volume set to {}%", volume);
+                        self.save_config();
+                        self.push_state();
+                        return self.output_volume(volume);
+                    }
+                    mpris::MprisCommand::Raise => {
+                        return self.update(Message::TogglePopup);
+                    }
+                    mpris::MprisCommand::OpenUri(uri) if backup::is_backup_uri(&uri) => {
+                        return self.restore_backup(&uri);
+                    }
+                    mpris::MprisCommand::OpenUri(uri) if opml::is_opml_uri(&uri) => {
+                        match opml::import_file(&uri) {
+                            Ok(stations) => self.import_favorites(stations),
+                            Err(e) => {
+                                error!("Cannot import {}: {}", uri, e);
+                                self.error_message = Some(e);
+                            }
+                        }
+                    }
+                    mpris::MprisCommand::OpenUri(uri) => {
+                        return match playlist::resolve_open_uri(&uri) {
+                            Ok(mut station) => {
+                                let credentials = streamauth::take_credentials(&mut station);
+                                info!("Opening {} from URL handler", station.url_resolved);
+                                match credentials {
+                                    Some(auth) => {
+                                        let uuid = station.stationuuid.clone();
+                                        let name = station.name.clone();
+                                        Task::perform(
+                                            async move {
+                                                streamauth::store(uuid, name, auth)
+                                                    .await
+                                                    .map_err(|e| e.to_string())
+                                            },
+                                            move |res| {
+                                                Message::StreamCredentialsStored(
+                                                    station.clone(),
+                                                    res,
+                                                )
+                                            },
+                                        )
+                                        .map(Into::into)
+                                    }
+                                    None => self.update(Message::PlayStation(Arc::new(station))),
+                                }
+                            }
+                            Err(e) => {
+                                error!("Cannot open {}: {}", streamauth::redact_url(&uri), e);
+                                self.error_message = Some(e);
+                                Task::none()
+                            }
+                        };
+                    }
+                    mpris::MprisCommand::Quit => {
+                        if self.is_playing {
+                            self.is_playing = false;
+                            self.push_state();
+                            return self.output_stop();
+                        }
+                    }
+                },
+            },
+            Message::AudioEvent(AudioEvent::CacheState(cache)) => {
+                self.meter.set_rate(cache.input_rate, Instant::now());
+                if cache.duration_ms > 0 {
+                    if let Some(uuid) = self.connecting.take() {
+                        self.record_connection(&uuid, true);
+                    }
+                }
+                self.diagnostics.set_cache(cache);
+            }
+            Message::AudioEvent(AudioEvent::Buffering(buffering)) => {
+                let stalls = self.diagnostics.reconnects;
+                self.diagnostics.set_buffering(buffering);
+                if self.diagnostics.reconnects > stalls && self.records_history() {
+                    if let Some(station) = &self.current_station {
+                        reliability::record_stall(
+                            &mut self.config.reliability,
+                            &station.stationuuid,
+                        );
+                        self.save_config();
+                    }
+                }
+            }
+            Message::AudioEvent(AudioEvent::Duration(duration)) => {
+                self.duration = duration;
+            }
+            Message::AudioEvent(AudioEvent::Position(secs)) => {
+                self.position = secs;
+                self.record_position();
+            }
+            Message::AudioEvent(AudioEvent::Level(level)) => {
+                if self.popup.is_some() && self.shows_visualizer() {
+                    self.visualizer.push(level);
+                }
+            }
+            Message::AudioEvent(AudioEvent::TrackTitle(title)) => {
+                let mut replacement = Task::none();
+                if title.is_none() && self.audio.reap() {
+                    warn!("mpv exited unexpectedly");
+                    self.keep_position();
+                    if let Some(uuid) = self.connecting.take() {
+                        self.record_connection(&uuid, false);
+                    }
+                    self.run_hook(HookEvent::Error, "mpv exited unexpectedly".to_string());
+                    if let Some(name) = self.current_station.as_ref().map(|s| s.name.clone()) {
+                        self.notify_error(name);
+                    }
+                    // A favorite that stopped playing may have moved
+                    let favorite = self.current_station.as_deref().filter(|station| {
+                        self.config
+                            .favorites
+                            .iter()
+                            .any(|s| s.stationuuid == station.stationuuid)
+                    });
+                    if let Some(station) = favorite.cloned() {
+                        replacement = self.find_replacement(station, false);
+                    }
+                }
+                if self.track_title != title {
+                    debug!("Track title: {:?}", title);
+                    self.track_title = title;
+                    self.cover_art = None;
+                    self.lyrics = None;
+                    self.track_started_at = stats::now_secs();
+                    self.log_track();
+                    self.push_state();
+                    return Task::batch([
+                        replacement,
+                        self.lookup_cover_art(),
+                        self.lookup_lyrics(),
+                    ]);
+                }
+                return replacement;
+            }
+            Message::MqttEvent(event) => match event {
+                mqtt::MqttEvent::Ready(tx) => {
+                    info!("MQTT publisher ready");
+                    self.mqtt_tx = Some(tx);
+                    self.push_state();
+                }
+                mqtt::MqttEvent::Command(cmd) => match cmd {
+                    MqttCommand::Play => {
+                        if !self.is_playing {
+                            return self.update(Message::TogglePlayPause);
+                        }
+                    }
+                    MqttCommand::Stop => {
+                        if self.is_playing {
+                            return self.update(Message::TogglePlayPause);
+                        }
+                    }
+                    MqttCommand::Toggle => return self.update(Message::TogglePlayPause),
+                    MqttCommand::VolumeUp => return self.update(Message::VolumeUp),
+                    MqttCommand::VolumeDown => return self.update(Message::VolumeDown),
+                    MqttCommand::SetVolume(vol) => {
+                        return self.update(Message::VolumeChanged(f32::from(vol)));
+                    }
+                },
+            },
+            Message::ControlEvent(event) => match event {
+                ControlEvent::Ready(tx, token) => {
+                    info!("Control endpoint ready");
+                    self.control_tx = Some(tx);
+                    self.control_token = Some(token);
+                    self.push_remote_state();
+                }
+                ControlEvent::Command(ControlCommand::Play(None)) => {
+                    if !self.is_playing {
+                        return self.update(Message::TogglePlayPause);
+                    }
+                }
+                ControlEvent::Command(ControlCommand::Play(Some(uuid))) => {
+                    let playing_it = self.is_playing
+                        && self
+                            .current_station
+                            .as_ref()
+                            .is_some_and(|s| s.stationuuid == uuid);
+                    let favorite = self
+                        .config
+                        .favorites
+                        .iter()
+                        .find(|s| s.stationuuid == uuid)
+                        .cloned();
+                    if let (false, Some(station)) = (playing_it, favorite) {
+                        return self.update(Message::PlayStation(Arc::new(station)));
+                    }
+                }
+                ControlEvent::Command(ControlCommand::Stop) => {
+                    if self.is_playing {
+                        return self.update(Message::TogglePlayPause);
+                    }
+                }
+            },
+            Message::SetControlEnabled(enabled) => {
+                self.config.control.enabled = enabled;
+                if !enabled {
+                    self.control_tx = None;
+                    self.control_token = None;
+                }
+                self.save_config();
+            }
+            Message::CopyControlToken => {
+                if let Some(token) = self.control_token.clone() {
+                    return cosmic::iced::clipboard::write(token).chain(
+                        self.toasts
+                            .push(Toast::new(fl!("control-token-copied")))
+                            .map(Into::into),
+                    );
+                }
+            }
+            Message::SyncEvent(event) => match event {
+                SyncEvent::Ready(tx) => {
+                    self.sync_tx = Some(tx);
+                    self.push_remote_state();
+                }
+                SyncEvent::Command(command) => {
+                    return match command {
+                        SyncCommand::TogglePlayPause => self.update(Message::TogglePlayPause),
+                        SyncCommand::PlayStation(station) => {
+                            self.update(Message::PlayStation(Arc::from(station)))
+                        }
+                        SyncCommand::SetVolume(volume) => {
+                            self.update(Message::VolumeChanged(f32::from(volume)))
+                        }
+                        SyncCommand::ToggleFavorite(station) => {
+                            self.update(Message::ToggleFavorite(Arc::from(station)))
+                        }
+                        SyncCommand::BookmarkTrack(title, station) => {
+                            self.update(Message::BookmarkTrack(title, station))
+                        }
+                        SyncCommand::Unlock(pin) => {
+                            self.unlock_pin = Some(pin);
+                            self.update(Message::Unlock)
+                        }
+                    };
+                }
+            },
+            Message::FollowerEvent(event) => match event {
+                FollowerEvent::Connected(tx) => {
+                    info!("Following the primary applet instance");
+                    self.primary_tx = Some(tx);
+                }
+                FollowerEvent::State(state) => {
+                    self.is_playing = state.playing;
+                    self.current_station = state.station.map(Arc::new);
+                    self.config.volume = state.volume;
+                    self.config.favorites = state.favorites;
+                    if state.kiosk_locked && !self.config.kiosk_locked {
+                        self.enter_lock_mode();
+                    }
+                    self.config.kiosk_locked = state.kiosk_locked;
+                    if self.track_title != state.track {
+                        self.track_title = state.track;
+                        self.cover_art = None;
+                        self.lyrics = None;
+                        self.track_started_at = stats::now_secs();
+                        return Task::batch([self.lookup_cover_art(), self.lookup_lyrics()]);
+                    }
+                }
+                FollowerEvent::Disconnected => {
+                    self.primary_tx = None;
+                    return Task::perform(
+                        async {
+                            tokio::task::spawn_blocking(instance::acquire)
+                                .await
+                                .map_err(|e| e.to_string())?
+                                .map_err(|e| e.to_string())
+                        },
+                        Message::InstanceAcquired,
+                    )
+                    .map(Into::into);
+                }
+            },
+            Message::InstanceAcquired(res) => match res {
+                Ok(instance @ Instance::Primary(_)) if self.follower => {
+                    return self.take_over(instance);
+                }
+                // Another follower took over; the subscription follows it
+                Ok(_) => {}
+                Err(e) => warn!("Cannot take over from the primary instance: {}", e),
+            },
+            Message::OtherPlayersPlaying(others_playing) => {
+                match autopause::decide(
+                    self.config.auto_pause,
+                    others_playing,
+                    self.is_playing,
+                    self.paused_by_other_player,
+                ) {
+                    AutoPauseAction::Pause => {
+                        info!("Another player started, pausing radio");
+                        let task = self.update(Message::TogglePlayPause);
+                        self.paused_by_other_player = true;
+                        return task;
+                    }
+                    AutoPauseAction::Resume => {
+                        info!("Other players stopped, resuming radio");
+                        return self.update(Message::TogglePlayPause);
+                    }
+                    AutoPauseAction::None => {}
+                }
+            }
+            Message::DuckingStreams(active) => {
+                if active != self.ducked {
+                    debug!("Ducking: {}", active);
+                    self.ducked = active;
+                    return self.output_volume(self.config.volume);
+                }
+            }
+            Message::OutputsChanged(outputs) => {
+                // Casts play elsewhere
+                let action = outputs::decide(
+                    self.config.device_pause,
+                    self.outputs.as_ref(),
+                    &outputs,
+                    &self.config.audio_device,
+                    self.is_playing && self.cast_target.is_none(),
+                    self.paused_for_device.as_deref(),
+                );
+                if self
+                    .paused_for_device
+                    .as_deref()
+                    .is_some_and(|sink| outputs.has(sink))
+                {
+                    self.paused_for_device = None;
+                }
+                self.outputs = Some(outputs);
+                match action {
+                    DeviceAction::Pause(sink) => {
+                        info!("Output device {} disconnected, pausing radio", sink);
+                        self.is_playing = false;
+                        self.paused_for_device = Some(sink);
+                        self.push_state();
+                        return self.output_stop();
+                    }
+                    DeviceAction::Resume => {
+                        if let Some(station) = self.current_station.clone() {
+                            info!("Output device is back, resuming radio");
+                            self.is_playing = true;
+                            self.push_state();
+                            return self.output_play(&station);
+                        }
+                    }
+                    DeviceAction::None => {}
+                }
+            }
+            Message::Sleep(SleepEvent::Suspending) => {
+                self.playing_before_sleep = self.is_playing;
+            }
+            Message::Sleep(SleepEvent::Resumed) => {
+                if self.playing_before_sleep {
+                    info!("Woke up from suspend, reconnecting the stream");
+                    return Task::perform(tokio::time::sleep(suspend::RECONNECT_DELAY), |()| {
+                        Message::ReconnectAfterResume
+                    })
+                    .map(Into::into);
+                }
+            }
+            Message::ReconnectAfterResume => {
+                self.playing_before_sleep = false;
+                // Unless playback was stopped in the meantime
+                if let Some(station) = self.current_station.clone().filter(|_| self.is_playing) {
+                    return self.output_stop().chain(self.output_play(&station));
+                }
+            }
+            Message::DiscoverCastDevices => {
+                self.is_discovering_casts = true;
+                return Task::perform(cast::discover(), Message::CastDevicesFound).map(Into::into);
+            }
+            Message::CastDevicesFound(res) => {
+                self.is_discovering_casts = false;
+                match res {
+                    Ok(devices) => {
+                        debug!("Found {} cast devices", devices.len());
+                        self.cast_devices = devices;
+                    }
+                    Err(e) => {
+                        error!("Cast discovery failed: {}", e);
+                        self.error_message = Some(e);
+                    }
+                }
+            }
+            Message::SelectCastTarget(target) => {
+                if self.cast_target == target {
+                    return Task::none();
+                }
+                // Stop whatever is currently producing sound before switching outputs
+                let stop = self.output_stop();
+                self.cast_target = target;
+                if self.is_playing {
+                    if let Some(station) = self.current_station.clone() {
+                        return stop.chain(self.output_play(&station));
+                    }
+                }
+                return stop;
+            }
+            Message::CastResult(res) => {
+                if let Err(e) = res {
+                    error!("Cast command failed: {}", e);
+                    self.run_hook(HookEvent::Error, e.clone());
+                    self.error_message = Some(e);
+                }
+            }
+            Message::StreamRejected(station, rejection) => {
+                let still_wanted = self.is_playing
+                    && self
+                        .current_station
+                        .as_ref()
+                        .is_some_and(|s| s.stationuuid == station.stationuuid);
+                if still_wanted {
+                    warn!("Not playing {}: {:?}", station.name, rejection);
+                    self.record_connection(&station.stationuuid, false);
+                    let message = match rejection {
+                        Rejection::WebPage => fl!("stream-web-page", name = station.name.as_str()),
+                        Rejection::NotAudio(mime) => fl!(
+                            "stream-not-audio",
+                            name = station.name.as_str(),
+                            kind = mime
+                        ),
+                    };
+                    self.is_playing = false;
+                    self.run_hook(HookEvent::Error, message.clone());
+                    self.notify_error(message.clone());
+                    self.error_message = Some(message);
+                    self.push_state();
+                    return self.output_stop();
+                }
+            }
+            Message::StartPlayback(station, url, address) => {
+                let still_wanted = self.is_playing
+                    && self
+                        .current_station
+                        .as_ref()
+                        .is_some_and(|s| s.stationuuid == station.stationuuid);
+                if still_wanted {
+                    self.diagnostics = Diagnostics::default();
+                    self.position = 0;
+                    self.position_saved = 0;
+                    self.seek_drag = None;
+                    self.visualizer.clear();
+                    // Casting devices don't report whether the stream plays
+                    if self.cast_target.is_none() {
+                        self.connecting = Some(station.stationuuid.clone());
+                    }
+                    return Task::batch([
+                        self.start_output(&station, url, address),
+                        self.probe_stream(),
+                    ]);
+                }
+            }
+            Message::StreamCredentialsStored(station, res) => {
+                if let Err(e) = res {
+                    error!("Failed to save stream credentials: {}", e);
+                    self.error_message = Some(e);
+                }
+                return self.update(Message::PlayStation(Arc::new(station)));
+            }
+            Message::MqttPasswordMigrated(res) => match res {
+                Ok(()) => {
+                    info!("Moved MQTT password into the keyring");
+                    self.config.mqtt.password.clear();
+                    self.save_config();
+                }
+                Err(e) => warn!(
+                    "Keeping plaintext MQTT password, keyring unavailable: {}",
+                    e
+                ),
+            },
+            Message::AppearanceChanged(appearance) => {
+                self.appearance = appearance;
+            }
+            Message::AnimationTick => {
+                self.playing_frame = self.playing_frame.wrapping_add(1);
+            }
+            Message::KeyboardEvent(event) => {
+                if let Event::Keyboard(cosmic::iced::keyboard::Event::KeyPressed {
+                    key,
+                    modifiers,
+                    ..
+                }) = event
+                {
+                    match key {
+                        Key::Named(Named::Tab) if modifiers.shift() => {
+                            return focus_previous();
+                        }
+                        Key::Named(Named::Tab) => {
+                            return focus_next();
+                        }
+                        Key::Named(Named::Space) => {
+                            return self.update(Message::TogglePlayPause);
+                        }
+                        Key::Named(Named::ArrowUp) => {
+                            return self.update(Message::VolumeUp);
+                        }
+                        Key::Named(Named::ArrowDown) => {
+                            return self.update(Message::VolumeDown);
+                        }
+                        Key::Character(c) if c.as_str() == "b" => {
+                            return self.update(Message::SwitchStation);
+                        }
+                        Key::Named(Named::Escape) => {
+                            if let Some(p) = self.popup.take() {
+                                return destroy_popup(p);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Task::none()
+    }
 }
 
-impl AppModel {
-    fn view_station_row<'a>(&self, station: &'a Station, is_fav: bool) -> Element<'a, Message> {
-        let play_icon = if self.is_playing
-            && self
-                .current_station
-                .as_ref()
-                .map(|s| s.stationuuid == station.stationuuid)
-                .unwrap_or(false)
-        {
-            "media-playback-pause-symbolic"
-        } else {
-            "media-playback-start-symbolic"
-        };
+impl AppModel {
+    fn view_station_row<'a>(
+        &self,
+        station: &'a Station,
+        is_fav: bool,
+        hideable: bool,
+    ) -> Element<'a, Message> {
+        // Screen readers announce the action together with the station name
+        let station_name = station.name.as_str();
+        let (play_icon, play_label) = if self.is_playing
+            && self
+                .current_station
+                .as_ref()
+                .map(|s| s.stationuuid == station.stationuuid)
+                .unwrap_or(false)
+        {
+            (
+                "media-playback-pause-symbolic",
+                fl!("a11y-stop-station", name = station_name),
+            )
+        } else {
+            (
+                "media-playback-start-symbolic",
+                fl!("a11y-play-station", name = station_name),
+            )
+        };
+
+        let fav_icon = self.appearance.favorite_icon(is_fav);
+        let fav_label = if is_fav {
+            fl!("a11y-favorite-remove", name = station_name)
+        } else {
+            fl!("a11y-favorite-add", name = station_name)
+        };
+
+        // The show on air, if the station has a schedule, then the source so merged
+        // results from several providers stay distinguishable, a quality chip
+        // ("128k AAC"), and votes and recent clicks as compact badges, e.g. "▲1.2k · ▶340"
+        let on_air = schedule::on_air(
+            &self.config.schedule,
+            &station.stationuuid,
+            LocalTime::now(),
+        )
+        .map(|show| fl!("on-air", title = show.title.as_str()))
+        .unwrap_or_default();
+        let mut badges = Vec::new();
+        if station.votes > 0 {
+            badges.push(format!("▲{}", popularity::format_count(station.votes)));
+        }
+        if station.clickcount > 0 {
+            badges.push(format!("▶{}", popularity::format_count(station.clickcount)));
+        }
+        if station.clicktrend > 0 {
+            badges.push(format!(
+                "↗{}",
+                popularity::format_count(station.clicktrend.unsigned_abs())
+            ));
+        }
+        // Flag (or country name, for stations saved without a code) and
+        // language tell same-named stations apart
+        let origin = [
+            locale::flag(&station.countrycode).unwrap_or_else(|| station.country.clone()),
+            locale::language_label(&station.language).unwrap_or_default(),
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+        let problem = self.health.problem(&station.stationuuid).filter(|_| is_fav);
+        let broken = is_fav && (refresh::is_broken(station) || problem.is_some());
+        let details = [
+            match problem {
+                Some(Problem::Unreachable(_)) => fl!("station-unreachable"),
+                Some(Problem::Rejected(_)) => fl!("station-not-stream"),
+                None if broken => fl!("station-broken"),
+                None => String::new(),
+            },
+            on_air,
+            origin,
+            genres::genres(&station.tags)
+                .first()
+                .map(|genre| genre_name(*genre))
+                .unwrap_or_default(),
+            station.source.clone(),
+            quality::chip(station).unwrap_or_default(),
+            reliability::score(&self.config.reliability, &station.stationuuid)
+                .map(|score| fl!("reliability-score", score = score))
+                .unwrap_or_default(),
+            badges.join(" · "),
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" · ");
+
+        // Names are aligned to the UI's reading direction, whatever script they start with
+        let name: Element<'a, Message> = if details.is_empty() {
+            widget::text(&station.name)
+                .width(Length::Fill)
+                .align_x(self.start())
+                .into()
+        } else {
+            widget::column()
+                .width(Length::Fill)
+                .align_x(self.start())
+                .push(widget::text(&station.name).align_x(self.start()))
+                .push(widget::text(details).size(11))
+                .into()
+        };
+        let name = match self.station_tooltip(station) {
+            Some(tooltip) => widget::tooltip(name, tooltip, widget::tooltip::Position::Bottom)
+                .gap(4)
+                .into(),
+            None => name,
+        };
+
+        // One copy for all the row's buttons
+        let shared = Arc::new(station.clone());
+        let mut children = Vec::new();
+        if self.selection.is_active() {
+            let uuid = station.stationuuid.clone();
+            children.push(
+                widget::checkbox("", self.selection.contains(station))
+                    .on_toggle(move |_| Message::ToggleSelected(uuid.clone()))
+                    .into(),
+            );
+        }
+        children.push(
+            icon_button(play_icon, play_label)
+                .on_press(Message::PlayStation(shared.clone()))
+                .into(),
+        );
+        if self.config.show_favicons {
+            // Keep names aligned while logos load or where there is none
+            children.push(match self.favicons.get(&station.favicon) {
+                Some(logo) => widget::image(logo.clone()).width(24).height(24).into(),
+                None => widget::Space::new(24, 24).into(),
+            });
+        }
+        children.push(name);
+        // Lock mode only plays
+        let locked = self.config.kiosk_locked;
+        if !locked {
+            children.extend([
+                icon_button(
+                    "emblem-shared-symbolic",
+                    fl!("a11y-share", name = station_name),
+                )
+                .on_press(Message::ShareStation(shared.clone(), ShareTarget::Page))
+                .into(),
+                icon_button(fav_icon, fav_label)
+                    .on_press(Message::ToggleFavorite(shared.clone()))
+                    .into(),
+                icon_button("list-add-symbolic", fl!("a11y-queue", name = station_name))
+                    .on_press(Message::QueueStation(shared.clone()))
+                    .into(),
+            ]);
+        }
+        if broken && !locked {
+            children.push(
+                icon_button(
+                    "edit-find-replace-symbolic",
+                    fl!("a11y-find-replacement", name = station_name),
+                )
+                .on_press(Message::FindReplacement(shared.clone()))
+                .into(),
+            );
+        }
+        if is_fav && !locked {
+            children.push(
+                icon_button(
+                    "x-office-calendar-symbolic",
+                    fl!("a11y-schedule", name = station_name),
+                )
+                .on_press(Message::EditSchedule(shared.clone()))
+                .into(),
+            );
+        }
+        if hideable {
+            children.push(
+                icon_button(
+                    "view-conceal-symbolic",
+                    fl!("a11y-hide", name = station_name),
+                )
+                .on_press(Message::HideStation(shared))
+                .into(),
+            );
+        }
+        let row = self
+            .directional_row(children)
+            .spacing(10)
+            .align_y(Alignment::Center);
+        // Set rows apart from the popup background in high contrast
+        if self.appearance.high_contrast {
+            widget::container(row)
+                .padding(4)
+                .class(cosmic::theme::Container::Card)
+                .into()
+        } else {
+            row.into()
+        }
+    }
+
+    /// Lock mode notice, with the unlock button or PIN prompt
+    fn view_lock_bar(&self) -> Element<'_, Message> {
+        let mut bar = widget::row()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(icon::from_name("system-lock-screen-symbolic").size(20))
+            .push(
+                widget::text(fl!("kiosk-locked-header"))
+                    .size(18)
+                    .width(Length::Fill),
+            );
+        bar = match &self.unlock_pin {
+            Some(pin) => bar.push(
+                text_input(&fl!("kiosk-pin-placeholder"), pin)
+                    .secure(true)
+                    .on_input(Message::UnlockPinChanged)
+                    .on_submit(Message::Unlock)
+                    .width(Length::Fixed(100.0)),
+            ),
+            None => bar.push(
+                cosmic::iced::widget::button(widget::text(fl!("kiosk-unlock")))
+                    .on_press(Message::ShowUnlock),
+            ),
+        };
+        bar.into()
+    }
+
+    /// Queue length and controls, while stations are queued
+    fn view_queue(&self) -> Option<Element<'_, Message>> {
+        if self.queue.is_empty() || self.config.kiosk_locked {
+            return None;
+        }
+        let playing = self
+            .current_station
+            .as_ref()
+            .filter(|_| self.is_playing)
+            .map(|station| station.stationuuid.as_str());
+        let play_label = if self.queue.is_touring(playing) {
+            fl!("queue-next")
+        } else {
+            fl!("queue-play")
+        };
+        // The next stations, by name
+        let upcoming = self
+            .queue
+            .stations()
+            .take(3)
+            .map(|station| locale::isolate(&station.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(
+            widget::row()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(
+                    widget::column()
+                        .width(Length::Fill)
+                        .push(widget::text(fl!("queue-count", count = self.queue.len())).size(12))
+                        .push(widget::text(upcoming).size(11)),
+                )
+                .push(
+                    cosmic::iced::widget::button(widget::text(play_label).size(12))
+                        .on_press(Message::PlayQueue),
+                )
+                .push(
+                    cosmic::iced::widget::button(widget::text(fl!("queue-clear")).size(12))
+                        .on_press(Message::ClearQueue),
+                )
+                .into(),
+        )
+    }
+
+    /// "Check favorites", or its progress
+    fn view_health_check(&self) -> Element<'_, Message> {
+        if self.is_checking_health {
+            return widget::text(fl!("health-checking", count = self.config.favorites.len()))
+                .size(12)
+                .into();
+        }
+        cosmic::iced::widget::button(widget::text(fl!("health-check")).size(12))
+            .on_press(Message::CheckFavorites)
+            .into()
+    }
+
+    /// Stations offered in place of a broken favorite
+    fn view_replacement(&self) -> Option<Element<'_, Message>> {
+        let (broken, candidates) = self.replacement.as_ref()?;
+        let mut offer = widget::column().spacing(6).push(
+            widget::row()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(
+                    widget::text(fl!(
+                        "replacement-offer",
+                        name = locale::isolate(&broken.name)
+                    ))
+                    .size(12)
+                    .width(Length::Fill),
+                )
+                .push(
+                    cosmic::iced::widget::button(widget::text(fl!("replacement-dismiss")).size(12))
+                        .on_press(Message::DismissReplacement),
+                ),
+        );
+        for candidate in candidates {
+            let details = [
+                candidate.country.clone(),
+                quality::chip(candidate).unwrap_or_default(),
+            ]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" · ");
+            offer = offer.push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(
+                        widget::column()
+                            .width(Length::Fill)
+                            .push(widget::text(&candidate.name))
+                            .push(widget::text(details).size(11)),
+                    )
+                    .push(
+                        cosmic::iced::widget::button(widget::text(fl!("replacement-use")))
+                            .on_press(Message::ReplaceFavorite(
+                                broken.stationuuid.clone(),
+                                candidate.clone(),
+                            )),
+                    ),
+            );
+        }
+        Some(
+            widget::container(offer)
+                .padding(8)
+                .class(cosmic::theme::Container::Card)
+                .into(),
+        )
+    }
+
+    /// Question whether to merge a starred station into a look-alike favorite
+    fn view_duplicate(&self) -> Option<Element<'_, Message>> {
+        let (station, existing) = self.duplicate.as_ref()?;
+        let prompt = widget::column()
+            .spacing(6)
+            .push(
+                widget::text(fl!(
+                    "duplicate-prompt",
+                    name = locale::isolate(&station.name),
+                    favorite = locale::isolate(&existing.name)
+                ))
+                .size(12),
+            )
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .push(
+                        cosmic::iced::widget::button(widget::text(fl!("duplicate-merge")))
+                            .on_press(Message::ResolveDuplicate(true)),
+                    )
+                    .push(
+                        cosmic::iced::widget::button(widget::text(fl!("duplicate-add")))
+                            .on_press(Message::ResolveDuplicate(false)),
+                    ),
+            );
+        Some(
+            widget::container(prompt)
+                .padding(8)
+                .class(cosmic::theme::Container::Card)
+                .into(),
+        )
+    }
+
+    /// Select mode switch, and the batch actions while it is on
+    fn view_selection_bar(&self) -> Element<'_, Message> {
+        let small_button = |label: String, message: Message| {
+            cosmic::iced::widget::button(widget::text(label).size(12)).on_press(message)
+        };
+        if !self.selection.is_active() {
+            return small_button(fl!("select-button"), Message::ToggleSelectMode).into();
+        }
+
+        let count = self.selected_stations().len();
+        let header = widget::row()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(
+                widget::text(fl!("selected-count", count = count))
+                    .size(12)
+                    .width(Length::Fill),
+            )
+            .push(small_button(fl!("select-all"), Message::ToggleSelectAll))
+            .push(small_button(fl!("select-done"), Message::ToggleSelectMode));
+        let mut actions = widget::row().spacing(10).align_y(Alignment::Center);
+        if count > 0 {
+            actions = actions
+                .push(small_button(fl!("batch-favorite"), Message::BatchFavorite))
+                .push(small_button(fl!("batch-hide"), Message::BatchHide))
+                .push(small_button(fl!("batch-queue"), Message::BatchQueue))
+                .push(small_button(fl!("batch-export"), Message::BatchExport));
+            let default_label = fl!("profile-default");
+            for name in std::iter::once("").chain(self.config.profiles.iter().map(String::as_str)) {
+                if name == self.config.active_profile {
+                    continue;
+                }
+                let label = if name.is_empty() {
+                    default_label.as_str()
+                } else {
+                    name
+                };
+                actions = actions.push(small_button(
+                    fl!("batch-add-to-profile", profile = label),
+                    Message::BatchAddToProfile(name.to_string()),
+                ));
+            }
+        }
+        widget::column()
+            .spacing(6)
+            .push(header)
+            .push(actions)
+            .into()
+    }
+
+    /// Stations the list currently shows, which select all and batch actions
+    /// work on: the filtered search results, or favorites and suggestions
+    fn listed_stations(&self) -> Vec<&Station> {
+        if !self.search_query.is_empty() || !self.search_results.is_empty() {
+            return self
+                .search_results
+                .iter()
+                .filter(|station| {
+                    !filter::is_blocked(station, &self.config.blocked_keywords)
+                        && !filter::is_hidden(station, &self.config.hidden_stations)
+                        && self
+                            .genre_filter
+                            .is_none_or(|genre| genres::has_genre(station, genre))
+                })
+                .collect();
+        }
+        let mut listed: Vec<&Station> = self.config.favorites.iter().collect();
+        if self.config.favorites.is_empty() {
+            listed.extend(self.in_languages(&self.starter_stations));
+        }
+        if let Some(recommendations) = &self.recommendations {
+            listed.extend(&recommendations.stations);
+        }
+        listed
+    }
+
+    /// `stations` in the languages the start and Discover pages show
+    fn in_languages<'a>(
+        &self,
+        stations: impl IntoIterator<Item = &'a Station>,
+    ) -> Vec<&'a Station> {
+        let languages = self.config.language_filter.languages();
+        stations
+            .into_iter()
+            .filter(|station| {
+                languages
+                    .as_ref()
+                    .is_none_or(|languages| locale::speaks(&station.language, languages))
+            })
+            .collect()
+    }
+
+    fn selected_stations(&self) -> Vec<Station> {
+        self.selection.stations(self.listed_stations())
+    }
+
+    /// Details a row has no room for, shown when hovering the station name:
+    /// tags, country and language, homepage, and the last stream check
+    fn station_tooltip<'a>(&self, station: &'a Station) -> Option<Element<'a, Message>> {
+        let tags = station
+            .tags
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut lines = Vec::new();
+        if !tags.is_empty() {
+            lines.push(fl!("station-tags", tags = tags));
+        }
+        if !station.country.is_empty() {
+            lines.push(fl!("station-country", country = station.country.as_str()));
+        }
+        if !station.language.is_empty() {
+            lines.push(fl!(
+                "station-language",
+                language = station.language.as_str()
+            ));
+        }
+        if !station.homepage.is_empty() {
+            lines.push(station.homepage.clone());
+        }
+        match station.lastcheckok {
+            Some(true) => lines.push(fl!("station-check-ok")),
+            Some(false) => lines.push(fl!("station-check-failed")),
+            None => {}
+        }
+        if let Some(Problem::Unreachable(error)) = self.health.problem(&station.stationuuid) {
+            lines.push(error.clone());
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        let column = lines.into_iter().fold(
+            widget::column().spacing(2).align_x(self.start()),
+            |column, line| column.push(widget::text(line).size(12)),
+        );
+        Some(column.into())
+    }
+
+    /// Start edge for text and column content: left, or right in RTL languages
+    fn start(&self) -> Alignment {
+        if self.rtl {
+            Alignment::End
+        } else {
+            Alignment::Start
+        }
+    }
+
+    /// Text of the panel ticker: the track title, or the station name until
+    /// one arrives. `None` when the ticker is off, nothing plays, or the
+    /// panel is vertical.
+    fn ticker_text(&self) -> Option<&str> {
+        if !self.config.ticker.enabled || !self.is_playing || !self.core.applet.is_horizontal() {
+            return None;
+        }
+        let station = self.current_station.as_ref()?;
+        Some(self.track_title.as_deref().unwrap_or(&station.name))
+    }
+
+    /// Row laying out `children` from the start edge, mirrored in RTL languages
+    fn directional_row<'a>(
+        &self,
+        mut children: Vec<Element<'a, Message>>,
+    ) -> widget::Row<'a, Message> {
+        if self.rtl {
+            children.reverse();
+        }
+        widget::row::with_children(children)
+    }
+
+    /// Turn lock mode on, leaving the favorites list as all there is to see
+    fn enter_lock_mode(&mut self) {
+        self.config.kiosk_locked = true;
+        self.page = Page::Favorites;
+        self.show_diagnostics = false;
+        // Queued stations may not be favorites
+        self.queue.clear();
+        self.search_query.clear();
+        self.search_results.clear();
+        self.local_matches.clear();
+        if self.selection.is_active() {
+            self.selection.toggle_active();
+        }
+    }
+
+    /// In a follower, send a playback action to the primary instance instead
+    /// of handling it
+    fn forward_to_primary(&mut self, message: &Message) -> Option<Task<cosmic::Action<Message>>> {
+        let command = match message {
+            Message::TogglePlayPause => SyncCommand::TogglePlayPause,
+            Message::PlayStation(station) => {
+                SyncCommand::PlayStation(Box::new(Station::clone(station)))
+            }
+            Message::ToggleFavorite(station) => {
+                SyncCommand::ToggleFavorite(Box::new(Station::clone(station)))
+            }
+            Message::VolumeChanged(vol) => SyncCommand::SetVolume(vol.round() as u8),
+            Message::VolumeUp => SyncCommand::SetVolume((self.config.volume + 5).min(100)),
+            Message::VolumeDown => SyncCommand::SetVolume(self.config.volume.saturating_sub(5)),
+            Message::BookmarkTrack(title, station) => {
+                SyncCommand::BookmarkTrack(title.clone(), station.clone())
+            }
+            Message::Unlock => SyncCommand::Unlock(self.unlock_pin.take().unwrap_or_default()),
+            _ => return None,
+        };
+        match &self.primary_tx {
+            Some(tx) => {
+                let _ = tx.send(command);
+            }
+            None => warn!(
+                "Not connected to the primary instance, dropping {:?}",
+                command
+            ),
+        }
+        Some(Task::none())
+    }
+
+    /// Become the primary instance, continuing what the old one played
+    fn take_over(&mut self, instance: Instance) -> Task<cosmic::Action<Message>> {
+        info!("Primary instance exited, taking over playback");
+        self.follower = false;
+        self._instance = Some(instance);
+        // The old primary saved settings this instance never saw
+        if let Ok(mut config) = Config::get_entry(&self.config_handler) {
+            self.home_profile = profiles::activate(&mut config);
+            self.config = config;
+        }
+        audio::kill_orphans();
+        let task = match self.current_station.clone() {
+            Some(station) if self.is_playing => self.output_play(&station),
+            _ => Task::none(),
+        };
+        self.push_state();
+        task
+    }
+
+    /// Start a station once its stream credentials (if any) are looked up
+    fn output_play(&self, station: &Arc<Station>) -> Task<cosmic::Action<Message>> {
+        // Only the primary instance plays
+        if self.follower {
+            return Task::none();
+        }
+        // Downloaded episodes play from the file, also offline
+        let download = downloads::downloaded(&station.stationuuid)
+            .filter(|_| PODCASTS && self.cast_target.is_none())
+            .and_then(|path| url::Url::from_file_path(path).ok());
+        if let Some(url) = download {
+            info!("Playing the downloaded copy of {}", station.name);
+            return Task::done(Message::StartPlayback(station.clone(), url.into(), None))
+                .map(Into::into);
+        }
+        let station = station.clone();
+        let preference = self.config.ip_preference;
+        let timeout = self.request_options().timeout;
+        // Find a host address that answers before handing the stream to mpv
+        let preflight = self.cast_target.is_none();
+        let upgrade_https =
+            tls::options(&self.config.station_tls, &station.stationuuid).upgrade_https;
+        // Where redirectors lead is a trace of what was played
+        let cache = Cache::open(self.config.cache_max_mb).filter(|_| self.records_history());
+        let span = playback_span(&station);
+        Task::perform(
+            async move {
+                let started = Instant::now();
+                let auth = streamauth::lookup(station.stationuuid.clone()).await;
+                let mut url = stream_url(&station, auth.as_ref());
+                if upgrade_https {
+                    url = tls::upgrade(url, timeout).await;
+                }
+                // Redirectors don't pass credentials on; play those as given
+                let checked = if auth.is_none() {
+                    redirect::final_url(url, cache, timeout).await
+                } else {
+                    sniff::check(url, timeout).await
+                };
+                let url = match checked {
+                    Ok(url) => url,
+                    Err(rejection) => {
+                        debug!(?rejection, "Stream rejected");
+                        return Message::StreamRejected(station, rejection);
+                    }
+                };
+                let address = if preflight {
+                    ipfamily::preflight(url.clone(), preference, timeout).await
+                } else {
+                    None
+                };
+                debug!(
+                    duration_ms = started.elapsed().as_millis() as u64,
+                    "Stream resolved"
+                );
+                Message::StartPlayback(station, url, address)
+            }
+            .instrument(span),
+            |message| message,
+        )
+        .map(Into::into)
+    }
+
+    /// Start a station on the active output: the cast target if one is selected, else mpv
+    fn start_output(
+        &self,
+        station: &Station,
+        url: String,
+        address: Option<SocketAddr>,
+    ) -> Task<cosmic::Action<Message>> {
+        let _span = playback_span(station).entered();
+        if let Some(device) = self.cast_target.clone() {
+            info!(output = "cast", "Starting playback");
+            self.audio.stop();
+            let title = station.name.clone();
+            Task::perform(cast::play(device, title, url), Message::CastResult).map(Into::into)
+        } else {
+            let tls = tls::options(&self.config.station_tls, &station.stationuuid);
+            if tls.allow_invalid_certs {
+                warn!("Not checking the certificate of {}", station.name);
+            }
+            let start_secs = resume::position(&self.config.resume_positions, &station.stationuuid)
+                .filter(|_| PODCASTS);
+            if let Some(start) = start_secs {
+                info!(start, "Continuing where playback was left");
+            }
+            let options = PlaybackOptions {
+                address,
+                allow_invalid_certs: tls.allow_invalid_certs,
+                start_secs,
+                channels: channels::for_station(
+                    self.config.channels,
+                    &self.config.station_channels,
+                    &station.stationuuid,
+                ),
+                ..self.playback_options()
+            };
+            info!(output = "mpv", ?address, "Starting playback");
+            self.audio
+                .play(url, self.effective_volume(self.config.volume), &options);
+            Task::none()
+        }
+    }
+
+    /// Change the TLS options of the playing station and restart its stream
+    fn update_station_tls(
+        &mut self,
+        change: impl FnOnce(&mut tls::StationTls),
+    ) -> Task<cosmic::Action<Message>> {
+        let Some(station) = self.current_station.clone() else {
+            return Task::none();
+        };
+        let mut options = tls::options(&self.config.station_tls, &station.stationuuid);
+        change(&mut options);
+        tls::set(&mut self.config.station_tls, options);
+        self.save_config();
+        if self.is_playing {
+            self.output_stop().chain(self.output_play(&station))
+        } else {
+            Task::none()
+        }
+    }
+
+    /// Request the current stream once for the diagnostics panel, if shown
+    fn probe_stream(&self) -> Task<cosmic::Action<Message>> {
+        if !self.show_diagnostics {
+            return Task::none();
+        }
+        let Some(station) = self.current_station.clone() else {
+            return Task::none();
+        };
+        let uuid = station.stationuuid.clone();
+        let timeout = self.request_options().timeout;
+        Task::perform(
+            async move {
+                let auth = streamauth::lookup(station.stationuuid.clone()).await;
+                diagnostics::probe(stream_url(&station, auth.as_ref()), timeout).await
+            },
+            move |probe| Message::DiagnosticsProbed(uuid.clone(), probe),
+        )
+        .map(Into::into)
+    }
+
+    /// mpv options derived from the current config
+    fn playback_options(&self) -> PlaybackOptions {
+        PlaybackOptions {
+            output: self.config.audio_output,
+            device: Some(self.config.audio_device.clone()).filter(|d| !d.is_empty()),
+            snapcast_fifo: self.config.snapcast_fifo.clone(),
+            address: None,
+            allow_invalid_certs: false,
+            start_secs: None,
+            loudness: self.config.loudness,
+            channels: self.config.channels,
+            visualizer: self.config.show_visualizer,
+        }
+    }
+
+    /// Change the playing station's own mono and balance settings; `None`
+    /// plays it with the global ones
+    fn update_station_channels(&mut self, change: impl FnOnce(&mut Option<ChannelConfig>)) {
+        let Some(uuid) = self.current_station.as_ref().map(|s| s.stationuuid.clone()) else {
+            return;
+        };
+        let mut own = channels::station(&self.config.station_channels, &uuid);
+        change(&mut own);
+        channels::set(&mut self.config.station_channels, &uuid, own);
+        self.save_config();
+    }
+
+    /// Restart the stream mpv plays, so changed options apply
+    fn restart_stream(&self) -> Task<cosmic::Action<Message>> {
+        match &self.current_station {
+            Some(station) if self.is_playing && self.cast_target.is_none() => {
+                self.output_stop().chain(self.output_play(station))
+            }
+            _ => Task::none(),
+        }
+    }
+
+    /// Remember where the playing stream with a duration is, writing it to
+    /// the config every [`resume::SAVE_INTERVAL_SECS`] or once forgotten
+    fn record_position(&mut self) {
+        let Some(duration) = self.duration else {
+            return;
+        };
+        let Some(uuid) = self.current_station.as_ref().map(|s| s.stationuuid.clone()) else {
+            return;
+        };
+        if !PODCASTS || !self.records_history() {
+            return;
+        }
+        let positions = &mut self.config.resume_positions;
+        let kept = resume::position(positions, &uuid).is_some();
+        resume::record(positions, &uuid, self.position, duration, stats::now_secs());
+        let forgotten = kept && resume::position(positions, &uuid).is_none();
+        if forgotten || self.position.abs_diff(self.position_saved) >= resume::SAVE_INTERVAL_SECS {
+            self.position_saved = self.position;
+            self.save_config();
+        }
+    }
+
+    /// Write the position of the stream with a duration that is about to be
+    /// stopped or replaced
+    fn keep_position(&mut self) {
+        if self.duration.take().is_some() && self.records_history() {
+            self.save_config();
+        }
+    }
+
+    /// Fetch new and trending stations unless the cached lists are recent
+    fn refresh_discover(&mut self) -> Task<cosmic::Action<Message>> {
+        let now = stats::now_secs();
+        if !self.started
+            || self.is_loading_discover
+            || self.discover.as_ref().is_some_and(|d| !d.is_stale(now))
+        {
+            return Task::none();
+        }
+        self.is_loading_discover = true;
+        let options = self.request_options();
+        let cache = Cache::open(self.config.cache_max_mb);
+        Task::perform(
+            async move {
+                discover::fetch(now, options, cache)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            Message::DiscoverLoaded,
+        )
+        .map(Into::into)
+    }
+
+    /// Fetch the popular stations suggested while favorites are empty, unless
+    /// those shown are recent
+    fn refresh_starters(&mut self) -> Task<cosmic::Action<Message>> {
+        let now = stats::now_secs();
+        let recent = now.saturating_sub(self.starters_fetched_at) < discover::MAX_AGE_SECS;
+        if !self.started || !self.config.favorites.is_empty() || self.is_loading_starters || recent
+        {
+            return Task::none();
+        }
+        self.is_loading_starters = true;
+        let options = self.request_options();
+        let country = self.config.country.resolve();
+        let cache = Cache::open(self.config.cache_max_mb);
+        Task::perform(
+            async move {
+                discover::fetch_starters(country, STARTER_STATIONS, now, options, cache)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            Message::StarterStationsLoaded,
+        )
+        .map(Into::into)
+    }
+
+    /// Search the directory for stations to replace a broken favorite with.
+    /// Offers the user dismissed are not made again unless they ask.
+    fn find_replacement(&mut self, station: Station, asked: bool) -> Task<cosmic::Action<Message>> {
+        let dismissed = self.replacements_dismissed.contains(&station.stationuuid);
+        if self.finding_replacement.is_some() || (dismissed && !asked) {
+            return Task::none();
+        }
+        debug!("Looking for replacements for {}", station.name);
+        self.finding_replacement = Some(station.stationuuid.clone());
+        let options = self.request_options();
+        Task::perform(
+            async move {
+                let res = replace::find(station.clone(), options)
+                    .await
+                    .map_err(|e| e.to_string());
+                (station, res)
+            },
+            move |(station, res)| Message::ReplacementsFound(station, asked, res),
+        )
+        .map(Into::into)
+    }
+
+    /// Look favorites up in the directory again once a day
+    fn refresh_favorites(&mut self) -> Task<cosmic::Action<Message>> {
+        let due = refresh::is_due(self.config.favorites_refreshed_at, stats::now_secs());
+        if self.is_refreshing_favorites || !due {
+            return Task::none();
+        }
+        let uuids = refresh::uuids(&self.config.favorites);
+        if uuids.is_empty() {
+            return Task::none();
+        }
+        self.is_refreshing_favorites = true;
+        let options = self.request_options();
+        Task::perform(
+            async move {
+                refresh::fetch(uuids, options)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            Message::FavoritesRefreshed,
+        )
+        .map(Into::into)
+    }
+
+    /// Look for stations like the most listened one, when that has changed
+    fn refresh_recommendations(&mut self) -> Task<cosmic::Action<Message>> {
+        let since = stats::now_secs().saturating_sub(stats::MONTH_SECS);
+        let top = stats::top_stations(&self.history, since);
+        let known: Vec<&Station> = self
+            .config
+            .favorites
+            .iter()
+            .chain(self.current_station.as_deref())
+            .chain(&self.search_results)
+            .collect();
+        let Some(seed) = recommend::pick_seed(&top, &known).cloned() else {
+            return Task::none();
+        };
+        if seed.stationuuid == self.recommendations_seed {
+            return Task::none();
+        }
+        self.recommendations_seed.clone_from(&seed.stationuuid);
+
+        // Already familiar stations are not worth recommending
+        let exclude: HashSet<String> = self
+            .history
+            .iter()
+            .map(|s| s.stationuuid.clone())
+            .chain(self.config.favorites.iter().map(|s| s.stationuuid.clone()))
+            .chain(
+                self.config
+                    .hidden_stations
+                    .iter()
+                    .map(|h| h.stationuuid.clone()),
+            )
+            .collect();
+        let options = self.request_options();
+        Task::perform(
+            async move {
+                recommend::fetch(seed, exclude, options)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            Message::RecommendationsLoaded,
+        )
+        .map(Into::into)
+    }
+
+    fn refresh_cache_size(&mut self) {
+        self.cache_size = Cache::open(self.config.cache_max_mb).map_or(0, |c| c.size());
+        self.downloads_size =
+            downloads::downloads_dir().map_or(0, |dir| downloads::used_bytes(&dir));
+    }
+
+    /// Start the recordings whose window opened. A recording already running
+    /// goes on and the scheduled one is skipped, with a notification; playback
+    /// is left alone, as recordings have a connection of their own.
+    fn start_scheduled_recordings(&mut self) -> Task<cosmic::Action<Message>> {
+        if !RECORDER {
+            return Task::none();
+        }
+        let now = LocalTime::now();
+        let due: Vec<RecordingRule> =
+            schedule::due_recordings(&self.config.recording_schedule, now)
+                .into_iter()
+                .cloned()
+                .collect();
+        // Forget windows that closed, so next week's is recorded too
+        self.recorded
+            .retain(|key| due.iter().any(|rule| rule.key() == *key));
+        let mut tasks = Vec::new();
+        for rule in due {
+            if !self.recorded.insert(rule.key()) {
+                continue;
+            }
+            // The favorite may be in the default profile while another is active
+            let Some(station) = self
+                .config
+                .favorites
+                .iter()
+                .chain(self.home_profile.iter().flat_map(|home| &home.favorites))
+                .find(|s| s.stationuuid == rule.stationuuid)
+            else {
+                continue;
+            };
+            if let Some(running) = &self.recording {
+                warn!(
+                    "Skipping scheduled recording of {}: {} is being recorded",
+                    station.name, running.station
+                );
+                self.notifier.notify(
+                    notify::Kind::Info,
+                    &fl!("recording-skipped-title", name = station.name.as_str()),
+                    &fl!("recording-skipped-body", other = running.station.as_str()),
+                );
+                continue;
+            }
+            let minutes = rule.minutes_left(now).unwrap_or_default();
+            tasks.push(start_recording(
+                Arc::new(station.clone()),
+                Duration::from_secs(u64::from(minutes) * 60),
+                rule.folder,
+            ));
+        }
+        Task::batch(tasks)
+    }
+
+    fn refresh_recordings(&mut self) {
+        self.recordings = recorder::recordings_dir()
+            .map(|dir| recorder::list(&dir))
+            .unwrap_or_default();
+    }
+
+    fn download_mut(&mut self, stationuuid: &str) -> Option<&mut Download> {
+        self.downloads
+            .iter_mut()
+            .find(|download| download.stationuuid == stationuuid)
+    }
+
+    /// Directory request limits from the current config
+    fn request_options(&self) -> RequestOptions {
+        RequestOptions::new(self.config.request_timeout_secs, self.config.search_limit)
+    }
+
+    /// Stop playback on the active output
+    fn output_stop(&self) -> Task<cosmic::Action<Message>> {
+        if self.follower {
+            return Task::none();
+        }
+        if let Some(device) = self.cast_target.clone() {
+            Task::perform(cast::stop(device), Message::CastResult).map(Into::into)
+        } else {
+            self.audio.stop();
+            Task::none()
+        }
+    }
+
+    /// Apply a volume change to the active output
+    fn output_volume(&self, volume: u8) -> Task<cosmic::Action<Message>> {
+        if self.follower {
+            return Task::none();
+        }
+        let volume = self.effective_volume(volume);
+        if let Some(device) = self.cast_target.clone() {
+            Task::perform(cast::set_volume(device, volume), Message::CastResult).map(Into::into)
+        } else {
+            self.audio.set_volume(volume as f32);
+            Task::none()
+        }
+    }
+
+    /// `volume` lowered while a call or notification sound plays, and capped
+    /// during quiet hours
+    fn effective_volume(&self, volume: u8) -> u8 {
+        let volume = if self.ducked {
+            ducking::ducked_volume(volume, self.config.ducking.amount)
+        } else {
+            volume
+        };
+        if self.quiet {
+            volume.min(self.config.quiet_hours.max_volume)
+        } else {
+            volume
+        }
+    }
+
+    /// Start or end quiet hours as the clock and settings say, applying or
+    /// lifting the volume cap
+    fn update_quiet(&mut self) -> Task<cosmic::Action<Message>> {
+        let quiet = self.config.quiet_hours.is_quiet(LocalTime::now());
+        if quiet == self.quiet {
+            return Task::none();
+        }
+        info!("Quiet hours {}", if quiet { "started" } else { "ended" });
+        self.quiet = quiet;
+        self.notifier.set_quiet(quiet);
+        self.output_volume(self.config.volume)
+    }
+
+    /// Hand the playback state and favorites to the control endpoint and to
+    /// follower instances
+    fn push_remote_state(&self) {
+        if let Some(tx) = &self.sync_tx {
+            let _ = tx.send(SyncState {
+                playing: self.is_playing,
+                station: self.current_station.as_deref().cloned(),
+                track: self.track_title.clone(),
+                volume: self.config.volume,
+                favorites: self.config.favorites.clone(),
+                kiosk_locked: self.config.kiosk_locked,
+            });
+        }
+        if let Some(tx) = &self.control_tx {
+            let _ = tx.send(ControlState {
+                playing: self.is_playing,
+                station: self.current_station.as_deref().map(StationRef::from),
+                track: self.track_title.clone(),
+                volume: self.config.volume,
+                favorites: self.config.favorites.iter().map(StationRef::from).collect(),
+            });
+        }
+    }
+
+    /// Publish the current playback state to MPRIS, MQTT, the control
+    /// endpoint, and the now-playing file
+    fn push_state(&mut self) {
+        // The primary instance keeps history, runs hooks, and publishes
+        if self.follower {
+            return;
+        }
+        self.track_listening();
+        self.run_hooks();
+        self.write_now_playing();
+        self.save_session();
+
+        if let Some(tx) = &self.mqtt_tx {
+            let _ = tx.send(MqttState {
+                playing: self.is_playing,
+                station: self.current_station.as_ref().map(|s| s.name.clone()),
+                track: self.track_title.clone(),
+                volume: self.config.volume,
+            });
+        }
+
+        self.push_remote_state();
+
+        if let Some(tx) = &self.mpris_tx {
+            let update = if self.is_playing {
+                if let Some(station) = &self.current_station {
+                    MprisStateUpdate::Playing {
+                        station: Box::new(Station::clone(station)),
+                        art_url: self
+                            .cover_art
+                            .as_deref()
+                            .and_then(|path| url::Url::from_file_path(path).ok())
+                            .map(String::from),
+                    }
+                } else {
+                    MprisStateUpdate::Stopped
+                }
+            } else {
+                MprisStateUpdate::Stopped
+            };
+            let _ = tx.send(update);
+            let _ = tx.send(MprisStateUpdate::Volume(self.config.volume));
+        }
+    }
+
+    /// Run the play, stop, and track change hooks for what changed since the last call
+    fn run_hooks(&mut self) {
+        let playing = self
+            .current_station
+            .as_ref()
+            .filter(|_| self.is_playing)
+            .map(|s| s.stationuuid.clone());
+        let track = self.track_title.clone().filter(|_| playing.is_some());
+        let (last_playing, last_track) =
+            std::mem::replace(&mut self.hook_state, (playing.clone(), track.clone()));
+        if playing != last_playing {
+            let event = if playing.is_some() {
+                HookEvent::Play
+            } else {
+                HookEvent::Stop
+            };
+            self.run_hook(event, String::new());
+        }
+        if track.is_some() && track != last_track {
+            self.run_hook(HookEvent::TrackChange, String::new());
+        }
+    }
+
+    /// Run the user's command for `event` with the current station and track
+    fn run_hook(&self, event: HookEvent, error: String) {
+        let station = self.current_station.as_ref();
+        let context = HookContext {
+            station: station.map(|s| s.name.clone()).unwrap_or_default(),
+            stationuuid: station.map(|s| s.stationuuid.clone()).unwrap_or_default(),
+            url: station.map(|s| s.url_resolved.clone()).unwrap_or_default(),
+            track: self.track_title.clone().unwrap_or_default(),
+            error,
+        };
+        hooks::run(&self.config.hooks, event, &context);
+    }
+
+    /// Add a new title to the session list and the station's track log
+    fn log_track(&mut self) {
+        let (Some(title), Some(station)) = (&self.track_title, &self.current_station) else {
+            return;
+        };
+        let entry = TrackEntry {
+            title: title.clone(),
+            station: station.name.clone(),
+            heard_at: stats::now_secs(),
+        };
+        if !tracklog::push_session(&mut self.session_tracks, entry.clone()) {
+            return;
+        }
+        if let Some(dir) = tracklog::tracks_dir().filter(|_| self.records_history()) {
+            let path = tracklog::station_log_path(&dir, &station.stationuuid);
+            if let Err(e) = tracklog::record(&path, &entry) {
+                warn!("Failed to record track: {}", e);
+            }
+        }
+        if self.page == Page::Tracks {
+            self.load_station_tracks();
+        }
+    }
+
+    /// Look up album art for the current title, if enabled and it names a song
+    fn lookup_cover_art(&self) -> Task<cosmic::Action<Message>> {
+        if !self.config.fetch_cover_art {
+            return Task::none();
+        }
+        let Some(title) = self.track_title.clone() else {
+            return Task::none();
+        };
+        let (Some(track), Some(cache)) = (
+            coverart::parse_track(&title),
+            Cache::open(self.config.cache_max_mb),
+        ) else {
+            return Task::none();
+        };
+        let timeout = self.request_options().timeout;
+        Task::perform(
+            async move {
+                coverart::lookup(track, cache, timeout)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            move |res| Message::CoverArtLoaded(title.clone(), res),
+        )
+        .map(Into::into)
+    }
+
+    /// Look up lyrics for the current title, if enabled and it names a song
+    fn lookup_lyrics(&self) -> Task<cosmic::Action<Message>> {
+        if !self.config.show_lyrics {
+            return Task::none();
+        }
+        let Some(title) = self.track_title.clone() else {
+            return Task::none();
+        };
+        let Some(track) = coverart::parse_track(&title) else {
+            return Task::none();
+        };
+        let timeout = self.request_options().timeout;
+        Task::perform(
+            async move {
+                lyrics::fetch(track, timeout)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            move |res| Message::LyricsLoaded(title.clone(), res),
+        )
+        .map(Into::into)
+    }
+
+    /// Read the current station's track log for the track history page
+    fn load_station_tracks(&mut self) {
+        self.station_tracks = match (&self.current_station, tracklog::tracks_dir()) {
+            (Some(station), Some(dir)) => {
+                let path = tracklog::station_log_path(&dir, &station.stationuuid);
+                tracklog::load(&path).unwrap_or_else(|e| {
+                    warn!("Failed to read track log: {}", e);
+                    Vec::new()
+                })
+            }
+            _ => Vec::new(),
+        };
+    }
+
+    fn write_now_playing(&mut self) {
+        if self.config.now_playing_file.is_empty() {
+            return;
+        }
+        let line = nowplaying::format_now_playing(
+            self.is_playing,
+            self.current_station.as_ref().map(|s| s.name.as_str()),
+            self.track_title.as_deref(),
+        );
+        if line == self.last_now_playing {
+            return;
+        }
+        let path = std::path::Path::new(&self.config.now_playing_file);
+        match nowplaying::write_now_playing(path, &line) {
+            Ok(()) => debug!("Wrote now-playing line: {}", line),
+            Err(e) => warn!("Failed to write now-playing file {}: {}", path.display(), e),
+        }
+        self.last_now_playing = line;
+    }
+
+    /// Stop playback and flush state, then exit.
+    ///
+    /// The saved session keeps the station marked as playing, so
+    /// `resume_playback` picks it up again after the next login.
+    fn shutdown(&mut self) -> Task<cosmic::Action<Message>> {
+        if self.follower {
+            return cosmic::iced::exit();
+        }
+        self.save_session();
+        let stop = self.output_stop();
+        self.is_playing = false;
+        self.track_listening();
+        self.write_now_playing();
+        stop.chain(cosmic::iced::exit())
+    }
+
+    /// Persist station, playback, and page so a restart can restore them
+    fn save_session(&mut self) {
+        let state = SessionState {
+            station: self.current_station.as_deref().cloned(),
+            playing: self.is_playing,
+            previous_station: self.previous_station.as_deref().cloned(),
+            page: self.page,
+            search: self.search_query.clone(),
+            scroll: self.scroll_offsets.clone(),
+            show_diagnostics: self.show_diagnostics,
+        };
+        if state == self.saved_session {
+            return;
+        }
+        if let Err(e) = session::save(&state) {
+            warn!("Failed to save session state: {:?}", e);
+        }
+        self.saved_session = state;
+    }
+
+    /// Page whose scroll position the station list area shows; `None` while
+    /// it lists search results
+    fn scroll_page(&self) -> Option<Page> {
+        let searching = !self.search_query.is_empty() || !self.search_results.is_empty();
+        (self.page != Page::Favorites || !searching).then_some(self.page)
+    }
+
+    /// How far the station list area is scrolled
+    fn list_offset(&self) -> u32 {
+        match self.scroll_page() {
+            Some(page) => self.scroll_offsets.get(&page).copied().unwrap_or_default(),
+            None => self.search_offset,
+        }
+    }
+
+    /// Scroll the station list area back to where the page was left
+    fn restore_scroll(&self) -> Task<cosmic::Action<Message>> {
+        scrollable::scroll_to(
+            self.list_scroll_id.clone(),
+            AbsoluteOffset {
+                x: 0.0,
+                y: self.list_offset() as f32,
+            },
+        )
+    }
+
+    /// Logo URLs of the station rows on screen, with a row to spare on each
+    /// side
+    fn visible_favicons(&self) -> Vec<String> {
+        let stations: Vec<&Station> = match self.page {
+            Page::Favorites => self.listed_stations(),
+            Page::Discover => self
+                .discover
+                .iter()
+                .flat_map(|discover| {
+                    self.in_languages(discover.trending.iter().chain(&discover.new))
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let first = (self.list_offset() / ROW_HEIGHT) as usize;
+        stations
+            .into_iter()
+            .skip(first.saturating_sub(1))
+            .take((LIST_HEIGHT / ROW_HEIGHT) as usize + 3)
+            .map(|station| station.favicon.clone())
+            .collect()
+    }
+
+    /// Fetch the logos of the rows on screen, cancelling downloads for rows
+    /// scrolled away
+    fn update_favicons(&mut self) -> Task<cosmic::Action<Message>> {
+        let urls = if self.config.show_favicons && self.popup.is_some() {
+            self.visible_favicons()
+        } else {
+            Vec::new()
+        };
+        let plan = self.favicons.show(urls.iter().map(String::as_str));
+        for url in plan.cancel {
+            if let Some(handle) = self.favicon_tasks.remove(&url) {
+                handle.abort();
+            }
+        }
+        self.start_favicons(plan.start)
+    }
+
+    fn start_favicons(&mut self, urls: Vec<String>) -> Task<cosmic::Action<Message>> {
+        let timeout = self.request_options().timeout;
+        let tasks: Vec<_> = urls
+            .into_iter()
+            .map(|url| {
+                let cache = Cache::open(self.config.cache_max_mb);
+                let fetch = favicons::fetch(url.clone(), cache, timeout);
+                let key = url.clone();
+                let (task, handle) =
+                    Task::perform(fetch, move |logo| Message::FaviconLoaded(url.clone(), logo))
+                        .abortable();
+                self.favicon_tasks.insert(key, handle);
+                task.map(Into::into)
+            })
+            .collect();
+        Task::batch(tasks)
+    }
+
+    /// Match the query against favorites, then stations from the listening
+    /// history, then other known stations
+    fn update_local_matches(&mut self) {
+        let heard: HashSet<&str> = self
+            .history
+            .iter()
+            .map(|session| session.stationuuid.as_str())
+            .collect();
+        let discovered = self
+            .discover
+            .iter()
+            .flat_map(|discover| discover.new.iter().chain(&discover.trending));
+        let (played, other): (Vec<&Station>, Vec<&Station>) = self
+            .known_stations
+            .iter()
+            .chain(discovered)
+            .filter(|station| !filter::is_hidden(station, &self.config.hidden_stations))
+            .partition(|station| heard.contains(station.stationuuid.as_str()));
+        let pool = self.config.favorites.iter().chain(played).chain(other);
+        self.local_matches = fuzzy::rank(&self.search_query, pool, fuzzy::MATCH_LIMIT)
+            .into_iter()
+            .cloned()
+            .collect();
+    }
+
+    /// Index favorites and known stations for searching offline
+    fn save_known_stations(&self) {
+        if let Some(cache) = Cache::open(self.config.cache_max_mb) {
+            cache.put_json(fuzzy::CACHE_KEY, &self.known_stations);
+        }
+    }
+
+    fn rebuild_station_index(&mut self) {
+        let stations = self.config.favorites.iter().chain(&self.known_stations);
+        self.station_index = Arc::new(StationIndex::build(stations.cloned()));
+        debug!(
+            "Indexed {} stations for offline search",
+            self.station_index.len()
+        );
+    }
+
+    /// Tell about a playback failure the closed popup cannot show
+    fn notify_error(&mut self, body: String) {
+        if self.popup.is_none() {
+            self.notifier
+                .notify(notify::Kind::Error, &fl!("playback-stopped"), &body);
+        }
+    }
+
+    /// Whether listening sessions and track titles are saved
+    fn records_history(&self) -> bool {
+        !self.config.disable_history && !self.incognito
+    }
+
+    /// Record whether a station's stream connected, unless history is off
+    fn record_connection(&mut self, stationuuid: &str, connected: bool) {
+        if self.records_history() {
+            reliability::record_attempt(
+                &mut self.config.reliability,
+                stationuuid,
+                connected,
+                stats::now_secs(),
+            );
+            self.save_config();
+        }
+    }
+
+    /// Close the running listening session when playback stops or switches station
+    fn track_listening(&mut self) {
+        let active = self.current_station.as_ref().filter(|_| self.is_playing);
+        // Stopped or switched before the stream played: counts neither way
+        if self
+            .connecting
+            .as_ref()
+            .is_some_and(|uuid| active.is_none_or(|station| station.stationuuid != *uuid))
+        {
+            self.connecting = None;
+        }
+        let unchanged = match (&self.current_session, active) {
+            (Some(session), Some(station)) => session.stationuuid == station.stationuuid,
+            (None, None) => true,
+            _ => false,
+        };
+        if unchanged {
+            return;
+        }
+
+        let now = stats::now_secs();
+        if let Some(mut session) = self.current_session.take() {
+            session.duration_secs = now.saturating_sub(session.started);
+            session.bytes = self.meter.take(Instant::now(), session.duration_secs);
+            info!(
+                station = %session.stationuuid,
+                duration_secs = session.duration_secs,
+                bytes = session.bytes,
+                "Playback ended"
+            );
+            if session.duration_secs >= stats::MIN_SESSION_SECS && self.records_history() {
+                if let Some(path) = stats::history_path() {
+                    if let Err(e) = stats::append(&path, &session) {
+                        warn!("Failed to record listening session: {}", e);
+                    }
+                }
+                self.history.push(session);
+            }
+        }
+        self.meter = Meter::new(active.map_or(0, |station| station.bitrate));
+        self.current_session = active.map(|station| ListenSession {
+            stationuuid: station.stationuuid.clone(),
+            name: station.name.clone(),
+            started: now,
+            duration_secs: 0,
+            bytes: 0,
+        });
+    }
+
+    /// Trending and new stations
+    fn view_discover(&self) -> Element<'_, Message> {
+        let mut column = widget::column()
+            .spacing(5)
+            .align_x(self.start())
+            .push(widget::text(fl!("discover-genres")).size(18));
+        for chunk in Genre::ALL.chunks(GENRE_CHIPS) {
+            let mut row = widget::row().spacing(6);
+            for genre in chunk {
+                row = row.push(
+                    cosmic::iced::widget::button(widget::text(genre_name(*genre)).size(12))
+                        .on_press(Message::BrowseGenre(*genre)),
+                );
+            }
+            column = column.push(row);
+        }
+        let Some(discover) = &self.discover else {
+            let status = if self.is_loading_discover {
+                fl!("discover-loading")
+            } else {
+                fl!("discover-unavailable")
+            };
+            return column.push(widget::text(status)).into();
+        };
+
+        for (header, stations) in [
+            (fl!("discover-trending"), &discover.trending),
+            (fl!("discover-new"), &discover.new),
+        ] {
+            column = column.push(widget::text(header).size(18));
+            let stations = self.in_languages(stations);
+            if stations.is_empty() {
+                column = column.push(widget::text(fl!("discover-empty")));
+            }
+            for station in stations {
+                let is_fav = self
+                    .config
+                    .favorites
+                    .iter()
+                    .any(|s| s.stationuuid == station.stationuuid);
+                column = column.push(self.view_station_row(station, is_fav, true));
+            }
+        }
+        column.into()
+    }
+
+    /// Lyrics under the now-playing row: the current synced line between its
+    /// neighbours, or the plain text
+    fn view_lyrics(&self) -> Option<Element<'_, Message>> {
+        let lyrics = self.lyrics.as_ref()?;
+        if lyrics.synced.is_empty() {
+            return Some(
+                widget::scrollable(widget::text(&lyrics.plain).size(12))
+                    .height(120)
+                    .into(),
+            );
+        }
+
+        let elapsed = Duration::from_secs(stats::now_secs().saturating_sub(self.track_started_at));
+        let current = lyrics::current_line(&lyrics.synced, elapsed);
+        let mut column = widget::column().spacing(2).align_x(self.start());
+        let first = current.map_or(0, |i| i.saturating_sub(1));
+        for (i, line) in lyrics.synced.iter().enumerate().skip(first).take(3) {
+            let size = if Some(i) == current { 16 } else { 12 };
+            column = column.push(widget::text(&line.text).size(size).align_x(self.start()));
+        }
+        Some(column.into())
+    }
+
+    /// Whether the visualizer is drawn; reduced motion and casting turn it off
+    fn shows_visualizer(&self) -> bool {
+        self.config.show_visualizer
+            && self.is_playing
+            && self.cast_target.is_none()
+            && !self.appearance.reduce_motion
+    }
+
+    /// Bars following the level of the playing stream
+    fn view_visualizer(&self) -> Option<Element<'_, Message>> {
+        if !self.shows_visualizer() {
+            return None;
+        }
+        let bars = self.visualizer.bars().map(|level| {
+            let height = (f32::from(level) / 100.0 * VISUALIZER_HEIGHT).max(1.0);
+            widget::container(widget::Space::new(Length::Fill, height))
+                .class(cosmic::theme::Container::custom(|theme| {
+                    let accent = cosmic::iced::Color::from(theme.cosmic().accent_color());
+                    widget::container::Style {
+                        background: Some(accent.into()),
+                        ..Default::default()
+                    }
+                }))
+                .width(Length::Fill)
+                .into()
+        });
+        Some(
+            widget::row::with_children(bars.collect())
+                .spacing(2)
+                .height(Length::Fixed(VISUALIZER_HEIGHT))
+                .align_y(Alignment::End)
+                .into(),
+        )
+    }
+
+    /// Position, seek bar, and duration of a stream that has a duration
+    fn view_seek_bar(&self) -> Option<Element<'_, Message>> {
+        let duration = self.duration.filter(|_| PODCASTS && self.is_playing)?;
+        let position = self.seek_drag.unwrap_or(self.position as f32);
+        Some(
+            self.directional_row(vec![
+                widget::text(resume::format_clock(position as u64))
+                    .size(12)
+                    .into(),
+                slider(0.0..=duration as f32, position, Message::SeekDragged)
+                    .on_release(Message::SeekReleased)
+                    .name(fl!("a11y-seek"))
+                    .width(Length::Fill)
+                    .into(),
+                widget::text(resume::format_clock(duration)).size(12).into(),
+            ])
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .into(),
+        )
+    }
+
+    /// Download progress and controls of the playing station, once it has
+    /// a duration or a download
+    fn view_download(&self, station: &Arc<Station>) -> Option<Element<'_, Message>> {
+        if !PODCASTS || self.follower || self.config.kiosk_locked {
+            return None;
+        }
+        let uuid = &station.stationuuid;
+        let download = self.downloads.iter().find(|d| &d.stationuuid == uuid);
+        let delete = icon_button("edit-delete-symbolic", fl!("a11y-delete-download"))
+            .on_press(Message::DeleteDownload(uuid.clone()));
+        let progress = |download: &Download| {
+            let mb = download.bytes / (1024 * 1024);
+            let label = match download.total {
+                Some(total) => fl!("download-progress", mb = mb, total = total / (1024 * 1024)),
+                None => fl!("download-progress-unknown", mb = mb),
+            };
+            vec![
+                cosmic::iced::widget::progress_bar(0.0..=1.0, download.fraction().unwrap_or(0.0))
+                    .width(Length::Fill)
+                    .into(),
+                widget::text(label).size(12).into(),
+            ]
+        };
+        let row: Vec<Element<'_, Message>> = match download.map(|d| (d, &d.state)) {
+            Some((download, DownloadState::Running)) => {
+                let mut row = progress(download);
+                row.push(
+                    icon_button("media-playback-pause-symbolic", fl!("a11y-pause-download"))
+                        .on_press(Message::PauseDownload(uuid.clone()))
+                        .into(),
+                );
+                row
+            }
+            Some((download, DownloadState::Paused)) => {
+                let mut row = progress(download);
+                row.extend([
+                    icon_button("folder-download-symbolic", fl!("a11y-resume-download"))
+                        .on_press(Message::DownloadEpisode(station.clone()))
+                        .into(),
+                    delete.into(),
+                ]);
+                row
+            }
+            Some((_, DownloadState::Failed(error))) => vec![
+                widget::text(fl!("download-failed", error = error.as_str()))
+                    .size(12)
+                    .width(Length::Fill)
+                    .into(),
+                icon_button("view-refresh-symbolic", fl!("a11y-resume-download"))
+                    .on_press(Message::DownloadEpisode(station.clone()))
+                    .into(),
+                delete.into(),
+            ],
+            Some((_, DownloadState::Done)) => vec![
+                widget::text(fl!("download-done"))
+                    .size(12)
+                    .width(Length::Fill)
+                    .into(),
+                delete.into(),
+            ],
+            _ if self.duration.is_some() => vec![
+                widget::text(fl!("download-hint"))
+                    .size(12)
+                    .width(Length::Fill)
+                    .into(),
+                icon_button("folder-download-symbolic", fl!("a11y-download"))
+                    .on_press(Message::DownloadEpisode(station.clone()))
+                    .into(),
+            ],
+            _ => return None,
+        };
+        Some(
+            self.directional_row(row)
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .into(),
+        )
+    }
+
+    /// The running recording and its stop button; lock mode only shows it
+    fn view_recording(&self) -> Option<Element<'_, Message>> {
+        let recording = self.recording.as_ref()?;
+        let mut row = vec![
+            icon::from_name("media-record-symbolic").size(16).into(),
+            widget::text(fl!(
+                "recording-progress",
+                name = locale::isolate(&recording.station),
+                mb = recording.bytes / (1024 * 1024)
+            ))
+            .size(12)
+            .width(Length::Fill)
+            .into(),
+        ];
+        if !self.config.kiosk_locked {
+            row.push(
+                icon_button("media-playback-stop-symbolic", fl!("a11y-stop-recording"))
+                    .on_press(Message::StopRecording)
+                    .into(),
+            );
+        }
+        Some(
+            self.directional_row(row)
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .into(),
+        )
+    }
+
+    /// Recordings on disk, newest first, with their actions
+    fn view_recordings(&self) -> Element<'_, Message> {
+        let mut column = widget::column()
+            .spacing(5)
+            .align_x(self.start())
+            .push(widget::text(fl!("recordings-header")).size(18))
+            .push_maybe(self.view_recording());
+        if self.recordings.is_empty() {
+            column = column.push(widget::text(fl!("recordings-empty")).size(12));
+        }
+        for recording in &self.recordings {
+            let path = &recording.path;
+            let name = recording.name();
+            let renaming = self
+                .recording_rename
+                .as_ref()
+                .filter(|(renamed, _)| renamed == path);
+            let row: Vec<Element<'_, Message>> = match renaming {
+                Some((_, draft)) => vec![
+                    text_input(&name, draft)
+                        .on_input(Message::RecordingNameChanged)
+                        .on_submit(Message::RenameRecording)
+                        .width(Length::Fill)
+                        .into(),
+                    cosmic::iced::widget::button(widget::text(fl!("recordings-rename")))
+                        .on_press(Message::RenameRecording)
+                        .into(),
+                ],
+                None => vec![
+                    widget::column()
+                        .width(Length::Fill)
+                        .align_x(self.start())
+                        .push(widget::text(name.clone()).align_x(self.start()))
+                        .push(
+                            widget::text(format!(
+                                "{} · {}",
+                                cache::format_size(recording.bytes),
+                                stats::format_timestamp(recording.modified)
+                            ))
+                            .size(12)
+                            .align_x(self.start()),
+                        )
+                        .into(),
+                    icon_button(
+                        "media-playback-start-symbolic",
+                        fl!("a11y-play-recording", name = name.as_str()),
+                    )
+                    .on_press(Message::PlayRecording(path.clone()))
+                    .into(),
+                    icon_button(
+                        "folder-open-symbolic",
+                        fl!("a11y-reveal-recording", name = name.as_str()),
+                    )
+                    .on_press(Message::RevealRecording(path.clone()))
+                    .into(),
+                    icon_button(
+                        "document-edit-symbolic",
+                        fl!("a11y-rename-recording", name = name.as_str()),
+                    )
+                    .on_press(Message::EditRecordingName(path.clone()))
+                    .into(),
+                    icon_button(
+                        "edit-delete-symbolic",
+                        fl!("a11y-delete-recording", name = name.as_str()),
+                    )
+                    .on_press(Message::DeleteRecording(path.clone()))
+                    .into(),
+                ],
+            };
+            column = column.push(
+                self.directional_row(row)
+                    .spacing(10)
+                    .align_y(Alignment::Center),
+            );
+        }
+        column
+            .push_maybe(
+                self.recordings_notice
+                    .as_deref()
+                    .map(|notice| widget::text(notice).size(12)),
+            )
+            .into()
+    }
+
+    /// Mono toggle and balance slider for `channels`
+    fn view_channels(
+        &self,
+        channels: ChannelConfig,
+        on_mono: fn(bool) -> Message,
+        on_balance: fn(f32) -> Message,
+    ) -> widget::Column<'_, Message> {
+        let range = channels::BALANCE_RANGE;
+        widget::column()
+            .spacing(5)
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("channels-mono")).width(Length::Fill))
+                    .push(widget::toggler(channels.mono).on_toggle(on_mono)),
+            )
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("channels-balance")))
+                    .push(
+                        slider(
+                            f32::from(*range.start())..=f32::from(*range.end()),
+                            f32::from(channels.balance),
+                            on_balance,
+                        )
+                        .on_release(Message::BalanceReleased)
+                        .step(10.0)
+                        .width(Length::Fill),
+                    )
+                    .push(widget::text(balance_label(channels.balance))),
+            )
+    }
+
+    /// Volume icon, slider, and level
+    fn view_volume(&self) -> Element<'_, Message> {
+        let volume_label = format!("{} {}%", fl!("volume"), self.config.volume);
+        let volume_icon = if self.config.volume == 0 {
+            "audio-volume-muted-symbolic"
+        } else if self.config.volume < 33 {
+            "audio-volume-low-symbolic"
+        } else if self.config.volume < 66 {
+            "audio-volume-medium-symbolic"
+        } else {
+            "audio-volume-high-symbolic"
+        };
+
+        let row = self
+            .directional_row(vec![
+                icon::from_name(volume_icon).size(20).into(),
+                slider(
+                    0.0..=100.0,
+                    self.config.volume as f32,
+                    Message::VolumeChanged,
+                )
+                .name(fl!("a11y-volume"))
+                .width(Length::Fill)
+                .into(),
+                widget::text(volume_label)
+                    .size(14)
+                    .width(Length::Shrink)
+                    .into(),
+            ])
+            .spacing(10)
+            .align_y(Alignment::Center);
+        widget::column()
+            .spacing(4)
+            .push(row)
+            .push_maybe(self.quiet.then(|| {
+                widget::text(fl!(
+                    "quiet-capped",
+                    volume = self.config.quiet_hours.max_volume
+                ))
+                .size(12)
+            }))
+            .into()
+    }
+
+    /// Content of the detached now-playing window: station, track,
+    /// play/stop, and volume
+    fn view_mini_player(&self) -> Element<'_, Message> {
+        let art: Element<'_, Message> = match &self.cover_art {
+            Some(path) => widget::image(widget::image::Handle::from_path(path))
+                .width(Length::Fixed(48.0))
+                .height(Length::Fixed(48.0))
+                .into(),
+            None => icon::from_name("audio-x-generic-symbolic").size(32).into(),
+        };
+        let mut row = vec![art];
+        if let Some(station) = &self.current_station {
+            row.push(
+                widget::column()
+                    .width(Length::Fill)
+                    .align_x(self.start())
+                    .push(widget::text(&station.name).size(16))
+                    .push_maybe(
+                        self.track_title
+                            .as_deref()
+                            .map(|title| widget::text(title).size(12)),
+                    )
+                    .into(),
+            );
+            let name = station.name.as_str();
+            row.push(if self.is_playing {
+                icon_button(
+                    "media-playback-stop-symbolic",
+                    fl!("a11y-stop-station", name = name),
+                )
+                .on_press(Message::TogglePlayPause)
+                .into()
+            } else {
+                icon_button(
+                    "media-playback-start-symbolic",
+                    fl!("a11y-play-station", name = name),
+                )
+                .on_press(Message::TogglePlayPause)
+                .into()
+            });
+        } else {
+            row.push(
+                widget::text(fl!("not-playing"))
+                    .size(14)
+                    .width(Length::Fill)
+                    .into(),
+            );
+        }
+
+        // The applet style leaves window backgrounds transparent
+        widget::container(
+            widget::column()
+                .padding(12)
+                .spacing(8)
+                .push(
+                    self.directional_row(row)
+                        .spacing(10)
+                        .align_y(Alignment::Center),
+                )
+                .push(self.view_volume()),
+        )
+        .class(cosmic::theme::Container::Background)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+
+    /// Diagnostics panel of the current stream, when expanded
+    fn view_diagnostics(&self) -> Option<Element<'_, Message>> {
+        if !self.show_diagnostics || self.config.kiosk_locked {
+            return None;
+        }
+        let header = self
+            .directional_row(vec![
+                widget::text(fl!("diagnostics-title"))
+                    .size(14)
+                    .width(Length::Fill)
+                    .align_x(self.start())
+                    .into(),
+                cosmic::iced::widget::button(widget::text(fl!("diagnostics-copy")))
+                    .on_press(Message::CopyDiagnostics)
+                    .into(),
+            ])
+            .spacing(10)
+            .align_y(Alignment::Center);
+        // Per-station TLS and channel options, applied by restarting the stream
+        let tls_controls = self.current_station.as_ref().map(|station| {
+            let tls = tls::options(&self.config.station_tls, &station.stationuuid);
+            let own_channels =
+                channels::station(&self.config.station_channels, &station.stationuuid);
+            let toggle_row = |label: String, enabled: bool, on_toggle: fn(bool) -> Message| {
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(label).size(12).width(Length::Fill))
+                    .push(widget::toggler(enabled).on_toggle(on_toggle))
+            };
+            widget::column()
+                .spacing(5)
+                .push(toggle_row(
+                    fl!("tls-upgrade-https"),
+                    tls.upgrade_https,
+                    Message::SetUpgradeHttps,
+                ))
+                .push(toggle_row(
+                    fl!("tls-allow-invalid"),
+                    tls.allow_invalid_certs,
+                    Message::SetAllowInvalidCerts,
+                ))
+                .push_maybe(
+                    tls.allow_invalid_certs
+                        .then(|| widget::text(fl!("tls-allow-invalid-warning")).size(12)),
+                )
+                .push(toggle_row(
+                    fl!("channels-station"),
+                    own_channels.is_some(),
+                    Message::SetStationChannels,
+                ))
+                .push_maybe(own_channels.map(|own| {
+                    self.view_channels(own, Message::SetStationMono, Message::StationBalanceChanged)
+                }))
+        });
+        Some(
+            widget::column()
+                .spacing(5)
+                .align_x(self.start())
+                .push(header)
+                .push_maybe(
+                    self.diagnostics
+                        .probe
+                        .is_none()
+                        .then(|| widget::text(fl!("diagnostics-probing")).size(12)),
+                )
+                .push(widget::text(self.diagnostics.report()).size(12))
+                .push_maybe(tls_controls)
+                .into(),
+        )
+    }
+
+    /// Shows of the favorite picked for the program guide, with forms to add
+    /// a show by hand or import them from an iCal URL
+    fn view_schedule(&self) -> Element<'_, Message> {
+        let Some(station) = &self.schedule_station else {
+            return widget::text(fl!("schedule-empty")).into();
+        };
+        let mut column = widget::column().spacing(5).align_x(self.start()).push(
+            widget::text(fl!(
+                "schedule-header",
+                name = locale::isolate(&station.name)
+            ))
+            .size(18),
+        );
+
+        let now = LocalTime::now();
+        let mut shows = self
+            .config
+            .schedule
+            .iter()
+            .enumerate()
+            .filter(|(_, show)| show.stationuuid == station.stationuuid)
+            .peekable();
+        if shows.peek().is_none() {
+            column = column.push(widget::text(fl!("schedule-empty")).size(12));
+        }
+        for (index, show) in shows {
+            let title = if show.is_on_air(now) {
+                fl!("on-air", title = show.title.as_str())
+            } else {
+                show.title.clone()
+            };
+            let when = format!(
+                "{} {}–{}",
+                days_label(show.days),
+                schedule::format_time(show.start),
+                schedule::format_time(show.end())
+            );
+            let details = widget::column()
+                .width(Length::Fill)
+                .align_x(self.start())
+                .push(widget::text(title).align_x(self.start()))
+                .push(widget::text(when).size(12).align_x(self.start()));
+            column = column.push(
+                self.directional_row(vec![
+                    details.into(),
+                    icon_button(
+                        "edit-delete-symbolic",
+                        fl!("a11y-remove-show", title = show.title.as_str()),
+                    )
+                    .on_press(Message::RemoveShow(index))
+                    .into(),
+                ])
+                .spacing(10)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        // New show: title, days, and local start and end times
+        let mut days_row = widget::row().spacing(5).align_y(Alignment::Center);
+        for (day, name) in day_names().into_iter().enumerate() {
+            let day = day as u8;
+            let label = if self.show_draft.days & (1 << day) != 0 {
+                format!("[{name}]")
+            } else {
+                name
+            };
+            days_row = days_row.push(
+                cosmic::iced::widget::button(widget::text(label).size(12))
+                    .on_press(Message::ToggleShowDay(day)),
+            );
+        }
+        let times_row = self
+            .directional_row(vec![
+                text_input("07:00", &self.show_draft.start)
+                    .on_input(Message::ShowStartChanged)
+                    .width(Length::Fixed(70.0))
+                    .into(),
+                widget::text("–").into(),
+                text_input("09:00", &self.show_draft.end)
+                    .on_input(Message::ShowEndChanged)
+                    .on_submit(Message::AddShow)
+                    .width(Length::Fixed(70.0))
+                    .into(),
+                cosmic::iced::widget::button(widget::text(fl!("schedule-add")))
+                    .on_press(Message::AddShow)
+                    .into(),
+            ])
+            .spacing(10)
+            .align_y(Alignment::Center);
+        column = column
+            .push(widget::text(fl!("schedule-new-show")).size(14))
+            .push(
+                text_input(&fl!("schedule-title-placeholder"), &self.show_draft.title)
+                    .on_input(Message::ShowTitleChanged),
+            )
+            .push(days_row)
+            .push(times_row);
+
+        let import_row = self
+            .directional_row(vec![
+                text_input(&fl!("schedule-ical-placeholder"), &self.ical_url)
+                    .on_input(Message::IcalUrlChanged)
+                    .on_submit(Message::ImportIcal)
+                    .width(Length::Fill)
+                    .into(),
+                cosmic::iced::widget::button(widget::text(fl!("schedule-import")))
+                    .on_press(Message::ImportIcal)
+                    .into(),
+            ])
+            .spacing(10)
+            .align_y(Alignment::Center);
+
+        // Reminders apply to all scheduled shows
+        let mut reminder_row = widget::row()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(widget::text(fl!("schedule-reminder")).size(12));
+        for minutes in schedule::REMINDER_CHOICES {
+            let label = if minutes == 0 {
+                fl!("schedule-reminder-off")
+            } else {
+                fl!("schedule-reminder-minutes", minutes = minutes)
+            };
+            reminder_row = if minutes == self.config.show_reminder_mins {
+                reminder_row.push(widget::text(format!("[{label}]")).size(12))
+            } else {
+                reminder_row.push(
+                    cosmic::iced::widget::button(widget::text(label).size(12))
+                        .on_press(Message::SetShowReminder(minutes)),
+                )
+            };
+        }
+
+        // Smart preset: what the play button starts, by time of day
+        column = column
+            .push(widget::text(fl!("preset-header")).size(14))
+            .push(widget::text(fl!("preset-description")).size(12));
+        for (index, rule) in self.config.smart_preset.rules.iter().enumerate() {
+            if rule.stationuuid != station.stationuuid {
+                continue;
+            }
+            let when = format!(
+                "{} {}–{}",
+                days_label(rule.days),
+                schedule::format_time(rule.start),
+                schedule::format_time(rule.end())
+            );
+            column = column.push(
+                self.directional_row(vec![
+                    widget::text(when.clone())
+                        .size(12)
+                        .width(Length::Fill)
+                        .into(),
+                    icon_button(
+                        "edit-delete-symbolic",
+                        fl!("a11y-remove-preset", when = when),
+                    )
+                    .on_press(Message::RemovePresetRule(index))
+                    .into(),
+                ])
+                .spacing(10)
+                .align_y(Alignment::Center),
+            );
+        }
+        let is_fallback = self.config.smart_preset.fallback == station.stationuuid;
+        column = column
+            .push(
+                cosmic::iced::widget::button(widget::text(fl!("preset-add")).size(12))
+                    .on_press(Message::AddPresetRule),
+            )
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(
+                        widget::text(fl!("preset-fallback"))
+                            .size(12)
+                            .width(Length::Fill),
+                    )
+                    .push(widget::toggler(is_fallback).on_toggle(Message::SetPresetFallback)),
+            );
+
+        // Scheduled recordings of the station, using the same days and times
+        if RECORDER {
+            column = column
+                .push(widget::text(fl!("recording-schedule-header")).size(14))
+                .push(widget::text(fl!("recording-schedule-description")).size(12));
+            for (index, rule) in self.config.recording_schedule.iter().enumerate() {
+                if rule.stationuuid != station.stationuuid {
+                    continue;
+                }
+                let when = format!(
+                    "{} {}–{}",
+                    days_label(rule.days),
+                    schedule::format_time(rule.start),
+                    schedule::format_time(rule.end())
+                );
+                let label = if rule.folder.is_empty() {
+                    when.clone()
+                } else {
+                    format!("{} → {}", when, rule.folder)
+                };
+                column = column.push(
+                    self.directional_row(vec![
+                        widget::text(label).size(12).width(Length::Fill).into(),
+                        icon_button(
+                            "edit-delete-symbolic",
+                            fl!("a11y-remove-recording-rule", when = when),
+                        )
+                        .on_press(Message::RemoveRecordingRule(index))
+                        .into(),
+                    ])
+                    .spacing(10)
+                    .align_y(Alignment::Center),
+                );
+            }
+            column = column.push(
+                self.directional_row(vec![
+                    text_input(
+                        &fl!("recording-folder-placeholder"),
+                        &self.show_draft.folder,
+                    )
+                    .on_input(Message::RecordingFolderChanged)
+                    .on_submit(Message::AddRecordingRule)
+                    .width(Length::Fill)
+                    .into(),
+                    cosmic::iced::widget::button(widget::text(fl!("recording-rule-add")).size(12))
+                        .on_press(Message::AddRecordingRule)
+                        .into(),
+                ])
+                .spacing(10)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        column
+            .push(import_row)
+            .push(reminder_row)
+            .push_maybe(
+                self.schedule_notice
+                    .as_deref()
+                    .map(|notice| widget::text(notice).size(12)),
+            )
+            .into()
+    }
+
+    /// The favorite the smart preset picks for this time of day
+    fn preset_station(&self) -> Option<Arc<Station>> {
+        let uuid = self.config.smart_preset.pick(LocalTime::now())?;
+        self.config
+            .favorites
+            .iter()
+            .find(|station| station.stationuuid == uuid)
+            .cloned()
+            .map(Arc::new)
+    }
+
+    fn view_track_row<'a>(&self, entry: &'a TrackEntry, now: u64) -> Element<'a, Message> {
+        let heard = fl!(
+            "tracks-minutes-ago",
+            minutes = tracklog::minutes_ago(now, entry.heard_at)
+        );
+        let details = widget::column()
+            .width(Length::Fill)
+            .align_x(self.start())
+            .push(widget::text(&entry.title).align_x(self.start()))
+            .push(
+                widget::text(format!("{} · {}", entry.station, heard))
+                    .size(12)
+                    .align_x(self.start()),
+            );
+        self.directional_row(vec![
+            details.into(),
+            icon_button(
+                "bookmark-new-symbolic",
+                fl!("a11y-bookmark-track", title = entry.title.as_str()),
+            )
+            .on_press(Message::BookmarkTrack(
+                entry.title.clone(),
+                entry.station.clone(),
+            ))
+            .into(),
+            icon_button(
+                "edit-copy-symbolic",
+                fl!("a11y-copy-track", title = entry.title.as_str()),
+            )
+            .on_press(Message::CopyTrack(entry.title.clone()))
+            .into(),
+        ])
+        .spacing(10)
+        .align_y(Alignment::Center)
+        .into()
+    }
+
+    /// Titles heard this session and earlier on the station, and bookmarks
+    fn view_tracks(&self) -> Element<'_, Message> {
+        let now = stats::now_secs();
+        let mut column = widget::column()
+            .spacing(5)
+            .align_x(self.start())
+            .push(widget::text(fl!("tracks-session")).size(18));
+        if self.session_tracks.is_empty() {
+            column = column.push(widget::text(fl!("tracks-empty")));
+        }
+        for entry in self.session_tracks.iter().rev() {
+            column = column.push(self.view_track_row(entry, now));
+        }
+
+        // Earlier titles from the station's log, before this session's first one
+        let session_start = self.session_tracks.first().map_or(u64::MAX, |e| e.heard_at);
+        let earlier: Vec<&TrackEntry> = self
+            .station_tracks
+            .iter()
+            .rev()
+            .filter(|e| e.heard_at < session_start)
+            .collect();
+        if let (Some(station), false) = (&self.current_station, earlier.is_empty()) {
+            column = column.push(
+                widget::text(fl!("tracks-station", name = locale::isolate(&station.name))).size(18),
+            );
+            for entry in earlier {
+                column = column.push(self.view_track_row(entry, now));
+            }
+        }
+
+        column = column.push(widget::text(fl!("bookmarks-header")).size(18));
+        if self.config.bookmarks.is_empty() {
+            column = column.push(widget::text(fl!("bookmarks-empty")));
+        }
+        for (index, bookmark) in self.config.bookmarks.iter().enumerate().rev() {
+            let label = bookmark.label();
+            let details = widget::column()
+                .width(Length::Fill)
+                .align_x(self.start())
+                .push(widget::text(label.clone()).align_x(self.start()))
+                .push(
+                    widget::text(format!(
+                        "{} · {}",
+                        bookmark.station,
+                        stats::format_timestamp(bookmark.saved_at)
+                    ))
+                    .size(12)
+                    .align_x(self.start()),
+                );
+            column = column.push(
+                self.directional_row(vec![
+                    details.into(),
+                    icon_button(
+                        "edit-copy-symbolic",
+                        fl!("a11y-copy-track", title = label.as_str()),
+                    )
+                    .on_press(Message::CopyTrack(label.clone()))
+                    .into(),
+                    icon_button(
+                        "edit-delete-symbolic",
+                        fl!("a11y-remove-bookmark", title = label.as_str()),
+                    )
+                    .on_press(Message::RemoveBookmark(index))
+                    .into(),
+                ])
+                .spacing(10)
+                .align_y(Alignment::Center),
+            );
+        }
+        if !self.config.bookmarks.is_empty() {
+            column = column.push(
+                widget::row()
+                    .spacing(10)
+                    .push(
+                        cosmic::iced::widget::button(widget::text(fl!("bookmarks-export-text")))
+                            .on_press(Message::ExportBookmarks(BookmarkFormat::Text)),
+                    )
+                    .push(
+                        cosmic::iced::widget::button(widget::text(fl!("stats-export-csv")))
+                            .on_press(Message::ExportBookmarks(BookmarkFormat::Csv)),
+                    ),
+            );
+        }
+        column.into()
+    }
+
+    /// Top stations this week and month plus total listening time
+    fn view_stats(&self) -> Element<'_, Message> {
+        let now = stats::now_secs();
+        let session_bytes = self.current_session.as_ref().map_or(0, |session| {
+            self.meter
+                .used(Instant::now(), now.saturating_sub(session.started))
+        });
+        let mut column = widget::column()
+            .spacing(5)
+            .align_x(self.start())
+            .push(
+                widget::text(format!(
+                    "{} {}",
+                    fl!("stats-total"),
+                    stats::format_duration(stats::total_seconds(&self.history))
+                ))
+                .size(14),
+            )
+            .push(
+                widget::text(fl!(
+                    "stats-data-used",
+                    session = bandwidth::format_bytes(session_bytes),
+                    day = bandwidth::format_bytes(
+                        stats::bytes_since(&self.history, now.saturating_sub(stats::DAY_SECS))
+                            + session_bytes
+                    ),
+                    month = bandwidth::format_bytes(
+                        stats::bytes_since(&self.history, now.saturating_sub(stats::MONTH_SECS))
+                            + session_bytes
+                    )
+                ))
+                .size(14),
+            );
+
+        for (header, period) in [
+            (fl!("stats-week"), stats::WEEK_SECS),
+            (fl!("stats-month"), stats::MONTH_SECS),
+        ] {
+            column = column.push(widget::text(header).size(18));
+            let top = stats::top_stations(&self.history, now.saturating_sub(period));
+            if top.is_empty() {
+                column = column.push(widget::text(fl!("stats-empty")));
+            }
+            for entry in top.into_iter().take(5) {
+                column = column.push(
+                    widget::row()
+                        .spacing(10)
+                        .push(widget::text(entry.name).width(Length::Fill))
+                        .push(widget::text(format!(
+                            "{} · {}× · {}",
+                            stats::format_duration(entry.seconds),
+                            entry.plays,
+                            bandwidth::format_bytes(entry.bytes)
+                        ))),
+                );
+            }
+        }
+
+        column = column.push(
+            widget::row()
+                .spacing(10)
+                .push(
+                    cosmic::iced::widget::button(widget::text(fl!("stats-export-csv")))
+                        .on_press(Message::ExportHistory(ExportFormat::Csv)),
+                )
+                .push(
+                    cosmic::iced::widget::button(widget::text(fl!("stats-export-json")))
+                        .on_press(Message::ExportHistory(ExportFormat::Json)),
+                ),
+        );
+        if let Some(notice) = &self.stats_notice {
+            column = column.push(widget::text(notice).size(12));
+        }
+
+        column.into()
+    }
+
+    /// Backup and restore controls
+    fn view_settings(&self) -> Element<'_, Message> {
+        let mut column = widget::column()
+            .spacing(8)
+            .align_x(self.start())
+            .push(widget::text(fl!("backup-header")).size(18))
+            .push(
+                cosmic::iced::widget::button(widget::text(fl!("backup-create")))
+                    .on_press(Message::CreateBackup),
+            )
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .push(
+                        text_input(&fl!("backup-restore-placeholder"), &self.restore_path)
+                            .on_input(Message::RestorePathChanged)
+                            .on_submit(Message::RestoreBackup),
+                    )
+                    .push(
+                        cosmic::iced::widget::button(widget::text(fl!("backup-restore")))
+                            .on_press(Message::RestoreBackup),
+                    ),
+            );
+        if let Some(notice) = &self.settings_notice {
+            column = column.push(widget::text(notice).size(12));
+        }
+
+        column = column
+            .push(widget::text(fl!("filter-header")).size(18))
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .push(
+                        text_input(&fl!("filter-placeholder"), &self.new_blocked_keyword)
+                            .on_input(Message::BlockedKeywordChanged)
+                            .on_submit(Message::AddBlockedKeyword),
+                    )
+                    .push(
+                        cosmic::iced::widget::button(widget::text(fl!("filter-add")))
+                            .on_press(Message::AddBlockedKeyword),
+                    ),
+            );
+        for keyword in &self.config.blocked_keywords {
+            column = column.push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(keyword).width(Length::Fill))
+                    .push(
+                        icon_button(
+                            "list-remove-symbolic",
+                            fl!("a11y-unblock", keyword = keyword.as_str()),
+                        )
+                        .on_press(Message::RemoveBlockedKeyword(keyword.clone())),
+                    ),
+            );
+        }
+
+        column = column.push(widget::text(fl!("hidden-header")).size(18));
+        if self.config.hidden_stations.is_empty() {
+            column = column.push(widget::text(fl!("hidden-empty")));
+        }
+        for hidden in &self.config.hidden_stations {
+            column = column.push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(&hidden.name).width(Length::Fill))
+                    .push(
+                        cosmic::iced::widget::button(widget::text(fl!("hidden-unhide")))
+                            .on_press(Message::UnhideStation(hidden.stationuuid.clone())),
+                    ),
+            );
+        }
+
+        let timeout_range = api::TIMEOUT_SECS_RANGE;
+        let limit_range = api::SEARCH_LIMIT_RANGE;
+        column = column
+            .push(widget::text(fl!("network-header")).size(18))
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("network-timeout")))
+                    .push(
+                        slider(
+                            *timeout_range.start() as f32..=*timeout_range.end() as f32,
+                            self.config.request_timeout_secs as f32,
+                            Message::RequestTimeoutChanged,
+                        )
+                        .width(Length::Fill),
+                    )
+                    .push(widget::text(fl!(
+                        "network-timeout-value",
+                        secs = self.config.request_timeout_secs
+                    ))),
+            )
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("network-limit")))
+                    .push(
+                        slider(
+                            *limit_range.start() as f32..=*limit_range.end() as f32,
+                            self.config.search_limit as f32,
+                            Message::SearchLimitChanged,
+                        )
+                        .step(10.0)
+                        .width(Length::Fill),
+                    )
+                    .push(widget::text(self.config.search_limit.to_string())),
+            );
+        let mut family_row = widget::row()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(widget::text(fl!("network-ip-family")).width(Length::Fill));
+        for preference in IpPreference::ALL {
+            let label = match preference {
+                IpPreference::Auto => fl!("network-ip-auto"),
+                IpPreference::Ipv4 => fl!("network-ipv4"),
+                IpPreference::Ipv6 => fl!("network-ipv6"),
+            };
+            family_row = if preference == self.config.ip_preference {
+                family_row.push(widget::text(format!("[{label}]")))
+            } else {
+                family_row.push(
+                    cosmic::iced::widget::button(widget::text(label))
+                        .on_press(Message::SetIpPreference(preference)),
+                )
+            };
+        }
+        column = column.push(family_row);
+
+        let cache_range = cache::MAX_MB_RANGE;
+        column = column
+            .push(widget::text(fl!("cache-header")).size(18))
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("cache-limit")))
+                    .push(
+                        slider(
+                            *cache_range.start() as f32..=*cache_range.end() as f32,
+                            self.config.cache_max_mb as f32,
+                            Message::CacheLimitChanged,
+                        )
+                        .step(10.0)
+                        .width(Length::Fill),
+                    )
+                    .push(widget::text(fl!(
+                        "cache-limit-value",
+                        mb = self.config.cache_max_mb
+                    ))),
+            )
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(
+                        widget::text(format!(
+                            "{} {}",
+                            fl!("cache-size"),
+                            cache::format_size(self.cache_size)
+                        ))
+                        .width(Length::Fill),
+                    )
+                    .push(
+                        cosmic::iced::widget::button(widget::text(fl!("cache-clear")))
+                            .on_press(Message::ClearCache),
+                    ),
+            );
+
+        if PODCASTS {
+            let quota_range = downloads::QUOTA_MB_RANGE;
+            column = column
+                .push(widget::text(fl!("downloads-header")).size(18))
+                .push(
+                    widget::row()
+                        .spacing(10)
+                        .align_y(Alignment::Center)
+                        .push(widget::text(fl!("downloads-quota")))
+                        .push(
+                            slider(
+                                *quota_range.start() as f32..=*quota_range.end() as f32,
+                                self.config.download_quota_mb as f32,
+                                Message::DownloadQuotaChanged,
+                            )
+                            .step(100.0)
+                            .width(Length::Fill),
+                        )
+                        .push(widget::text(fl!(
+                            "cache-limit-value",
+                            mb = self.config.download_quota_mb
+                        ))),
+                )
+                .push(
+                    widget::row()
+                        .spacing(10)
+                        .align_y(Alignment::Center)
+                        .push(
+                            widget::text(format!(
+                                "{} {}",
+                                fl!("cache-size"),
+                                cache::format_size(self.downloads_size)
+                            ))
+                            .width(Length::Fill),
+                        )
+                        .push(
+                            cosmic::iced::widget::button(widget::text(fl!("downloads-delete")))
+                                .on_press(Message::DeleteDownloads),
+                        ),
+                );
+        }
 
-        let fav_icon = if is_fav {
-            "starred-symbolic"
-        } else {
-            "non-starred-symbolic"
+        if RECORDER {
+            let mut retention_row = widget::row()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(widget::text(fl!("recordings-retention")).width(Length::Fill));
+            for days in recorder::RETENTION_CHOICES {
+                let label = if days == 0 {
+                    fl!("recordings-keep")
+                } else {
+                    fl!("recordings-retention-days", days = days)
+                };
+                retention_row = if days == self.config.recording_retention_days {
+                    retention_row.push(widget::text(format!("[{label}]")).size(12))
+                } else {
+                    retention_row.push(
+                        cosmic::iced::widget::button(widget::text(label).size(12))
+                            .on_press(Message::SetRecordingRetention(days)),
+                    )
+                };
+            }
+            let mut format_row = widget::row()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(widget::text(fl!("recordings-format")).width(Length::Fill));
+            for format in RecordingFormat::ALL {
+                let label = match format {
+                    RecordingFormat::Copy => fl!("recordings-format-copy"),
+                    RecordingFormat::Opus => fl!("recordings-format-opus"),
+                    RecordingFormat::Mp3 => fl!("recordings-format-mp3"),
+                };
+                format_row = if format == self.config.recording_format {
+                    format_row.push(widget::text(format!("[{label}]")).size(12))
+                } else {
+                    format_row.push(
+                        cosmic::iced::widget::button(widget::text(label).size(12))
+                            .on_press(Message::SetRecordingFormat(format)),
+                    )
+                };
+            }
+            // The bitrate only applies when re-encoding
+            let bitrate_row = (self.config.recording_format != RecordingFormat::Copy).then(|| {
+                let mut row = widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("recordings-bitrate")).width(Length::Fill));
+                for kbps in recorder::BITRATE_CHOICES {
+                    let label = fl!("recordings-bitrate-kbps", kbps = kbps);
+                    row = if kbps == self.config.recording_bitrate {
+                        row.push(widget::text(format!("[{label}]")).size(12))
+                    } else {
+                        row.push(
+                            cosmic::iced::widget::button(widget::text(label).size(12))
+                                .on_press(Message::SetRecordingBitrate(kbps)),
+                        )
+                    };
+                }
+                row
+            });
+            column = column
+                .push(widget::text(fl!("recordings-header")).size(18))
+                .push(retention_row)
+                .push(format_row)
+                .push_maybe(bitrate_row);
+        }
+
+        column = column
+            .push(widget::text(fl!("privacy-header")).size(18))
+            .push(widget::text(fl!("privacy-hint")).size(12))
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("privacy-disable-history")).width(Length::Fill))
+                    .push(
+                        widget::toggler(self.config.disable_history)
+                            .on_toggle(Message::SetDisableHistory),
+                    ),
+            )
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("privacy-incognito")).width(Length::Fill))
+                    .push(widget::toggler(self.incognito).on_toggle(Message::SetIncognito)),
+            )
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .push(
+                        cosmic::iced::widget::button(widget::text(fl!("privacy-clear-history")))
+                            .on_press(Message::ClearListeningHistory),
+                    )
+                    .push(
+                        cosmic::iced::widget::button(widget::text(fl!("privacy-clear-tracks")))
+                            .on_press(Message::ClearTrackLogs),
+                    ),
+            );
+
+        column = column
+            .push(widget::text(fl!("kiosk-header")).size(18))
+            .push(widget::text(fl!("kiosk-hint")).size(12))
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(
+                        text_input(&fl!("kiosk-pin-placeholder"), &self.kiosk_pin)
+                            .secure(true)
+                            .on_input(Message::KioskPinChanged)
+                            .on_submit(Message::Lock)
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        cosmic::iced::widget::button(widget::text(fl!("kiosk-lock")))
+                            .on_press(Message::Lock),
+                    ),
+            );
+
+        let mut level_row = widget::row()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(widget::text(fl!("log-level")));
+        for level in LogLevel::ALL {
+            level_row = if level == self.config.log_level {
+                level_row.push(widget::text(format!("[{}]", level.as_str())))
+            } else {
+                level_row.push(
+                    cosmic::iced::widget::button(widget::text(level.as_str()))
+                        .on_press(Message::SetLogLevel(level)),
+                )
+            };
+        }
+        let detected = locale::country_code().unwrap_or_else(|| "?".to_string());
+        let auto_label = format!("{} ({})", fl!("country-auto"), detected);
+        let everywhere_label = fl!("country-everywhere");
+        let country_button = |label: String, filter: CountryFilter| {
+            if filter == self.config.country {
+                Element::from(widget::text(format!("[{label}]")))
+            } else {
+                cosmic::iced::widget::button(widget::text(label))
+                    .on_press(Message::SetCountryFilter(filter))
+                    .into()
+            }
         };
+        let mut country_row = widget::row()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(country_button(auto_label, CountryFilter::Auto))
+            .push(country_button(everywhere_label, CountryFilter::Everywhere));
+        if let CountryFilter::Country(code) = &self.config.country {
+            country_row = country_row.push(widget::text(format!("[{code}]")));
+        }
+        column = column
+            .push(widget::text(fl!("country-header")).size(18))
+            .push(country_row)
+            .push(
+                text_input(&fl!("country-placeholder"), &self.country_input)
+                    .on_input(Message::CountryInputChanged)
+                    .on_submit(Message::SubmitCountry),
+            )
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("language-filter")).width(Length::Fill))
+                    .push(
+                        widget::toggler(self.config.language_filter.enabled)
+                            .on_toggle(Message::SetLanguageFilter),
+                    ),
+            );
+        if self.config.language_filter.enabled {
+            let desktop = locale::desktop_languages();
+            let mut language_row = widget::row().spacing(10).align_y(Alignment::Center).push(
+                widget::text(if desktop.is_empty() {
+                    fl!("language-filter-none")
+                } else {
+                    fl!("language-filter-desktop", languages = desktop.join(", "))
+                })
+                .size(12),
+            );
+            for language in &self.config.language_filter.extra {
+                language_row = language_row.push(
+                    cosmic::iced::widget::button(widget::text(format!("{language} ✕")))
+                        .on_press(Message::RemoveLanguage(language.clone())),
+                );
+            }
+            column = column.push(language_row).push(
+                text_input(&fl!("language-placeholder"), &self.language_input)
+                    .on_input(Message::LanguageInputChanged)
+                    .on_submit(Message::AddLanguage),
+            );
+        }
+
+        let mut stream_row = widget::row()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(widget::text(fl!("stream-preference")).width(Length::Fill));
+        for preference in StreamPreference::ALL {
+            let label = match preference {
+                StreamPreference::AsListed => fl!("stream-as-listed"),
+                StreamPreference::BestQuality => fl!("stream-best-quality"),
+                StreamPreference::DataSaver => fl!("stream-data-saver"),
+            };
+            stream_row = if preference == self.config.stream_preference {
+                stream_row.push(widget::text(format!("[{label}]")))
+            } else {
+                stream_row.push(
+                    cosmic::iced::widget::button(widget::text(label))
+                        .on_press(Message::SetStreamPreference(preference)),
+                )
+            };
+        }
 
-        widget::row()
+        let mut normalization_row = widget::row()
             .spacing(10)
             .align_y(Alignment::Center)
+            .push(widget::text(fl!("normalization")).width(Length::Fill));
+        for mode in Normalization::ALL {
+            let label = match mode {
+                Normalization::Dynamic => fl!("normalization-dynamic"),
+                Normalization::Loudness => fl!("normalization-loudness"),
+                Normalization::ReplayGain => fl!("normalization-replaygain"),
+                Normalization::Off => fl!("normalization-off"),
+            };
+            normalization_row = if mode == self.config.loudness.mode {
+                normalization_row.push(widget::text(format!("[{label}]")))
+            } else {
+                normalization_row.push(
+                    cosmic::iced::widget::button(widget::text(label))
+                        .on_press(Message::SetNormalization(mode)),
+                )
+            };
+        }
+        let loudness = self.config.loudness;
+        let target_row = loudness.mode.has_target().then(|| {
+            widget::row()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(widget::text(fl!("loudness-target")))
+                .push(
+                    slider(
+                        f32::from(*loudness::TARGET_RANGE.start())
+                            ..=f32::from(*loudness::TARGET_RANGE.end()),
+                        f32::from(loudness.target),
+                        Message::LoudnessTargetChanged,
+                    )
+                    .on_release(Message::LoudnessTargetReleased)
+                    .step(1.0)
+                    .width(Length::Fill),
+                )
+                .push(widget::text(fl!(
+                    "loudness-target-value",
+                    lufs = loudness.target
+                )))
+        });
+
+        column = column
+            .push(widget::text(fl!("playback-header")).size(18))
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("resume-playback")).width(Length::Fill))
+                    .push(
+                        widget::toggler(self.config.resume_playback)
+                            .on_toggle(Message::SetResumePlayback),
+                    ),
+            )
+            .push(stream_row)
+            .push(normalization_row)
+            .push_maybe(target_row)
+            .push(widget::text(fl!("normalization-hint")).size(12))
+            .push(self.view_channels(
+                self.config.channels,
+                Message::SetMono,
+                Message::BalanceChanged,
+            ))
             .push(
-                cosmic::iced::widget::button(icon::from_name(play_icon))
-                    .on_press(Message::PlayStation(station.clone())),
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("queue-dwell")))
+                    .push(
+                        slider(
+                            *queue::DWELL_SECS_RANGE.start() as f32
+                                ..=*queue::DWELL_SECS_RANGE.end() as f32,
+                            self.config.queue_dwell_secs as f32,
+                            Message::QueueDwellChanged,
+                        )
+                        .step(10.0)
+                        .width(Length::Fill),
+                    )
+                    .push(widget::text(if self.config.queue_dwell_secs == 0 {
+                        fl!("queue-dwell-manual")
+                    } else {
+                        fl!("queue-dwell-value", secs = self.config.queue_dwell_secs)
+                    })),
             )
-            .push(widget::text(&station.name).width(cosmic::iced::Length::Fill))
             .push(
-                cosmic::iced::widget::button(icon::from_name(fav_icon))
-                    .on_press(Message::ToggleFavorite(station.clone())),
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("fetch-cover-art")).width(Length::Fill))
+                    .push(
+                        widget::toggler(self.config.fetch_cover_art)
+                            .on_toggle(Message::SetFetchCoverArt),
+                    ),
             )
-            .into()
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("show-favicons")).width(Length::Fill))
+                    .push(
+                        widget::toggler(self.config.show_favicons)
+                            .on_toggle(Message::SetShowFavicons),
+                    ),
+            )
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("show-lyrics")).width(Length::Fill))
+                    .push(
+                        widget::toggler(self.config.show_lyrics).on_toggle(Message::SetShowLyrics),
+                    ),
+            )
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("show-visualizer")).width(Length::Fill))
+                    .push(
+                        widget::toggler(self.config.show_visualizer)
+                            .on_toggle(Message::SetShowVisualizer),
+                    ),
+            )
+            .push(widget::text(fl!("show-visualizer-hint")).size(12))
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("ducking")).width(Length::Fill))
+                    .push(
+                        widget::toggler(self.config.ducking.enabled).on_toggle(Message::SetDucking),
+                    ),
+            );
+        if self.config.ducking.enabled {
+            let amount_range = ducking::AMOUNT_RANGE;
+            column = column.push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("ducking-amount")))
+                    .push(
+                        slider(
+                            f32::from(*amount_range.start())..=f32::from(*amount_range.end()),
+                            f32::from(self.config.ducking.amount),
+                            Message::DuckingAmountChanged,
+                        )
+                        .step(10.0)
+                        .width(Length::Fill),
+                    )
+                    .push(widget::text(format!("{}%", self.config.ducking.amount))),
+            );
+        }
+        column = column.push(
+            widget::row()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(widget::text(fl!("device-pause")).width(Length::Fill))
+                .push(
+                    widget::toggler(self.config.device_pause.enabled)
+                        .on_toggle(Message::SetDevicePause),
+                ),
+        );
+        if self.config.device_pause.enabled {
+            column = column.push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("device-auto-resume")).width(Length::Fill))
+                    .push(
+                        widget::toggler(self.config.device_pause.auto_resume)
+                            .on_toggle(Message::SetDeviceAutoResume),
+                    ),
+            );
+        }
+
+        column = column.push(
+            widget::row()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(widget::text(fl!("quiet-hours")).width(Length::Fill))
+                .push(
+                    widget::toggler(self.config.quiet_hours.enabled)
+                        .on_toggle(Message::SetQuietHours),
+                ),
+        );
+        if self.config.quiet_hours.enabled {
+            let quiet_hours = self.config.quiet_hours;
+            let max_range = quiet::MAX_VOLUME_RANGE;
+            let time_slider = |label: String, minute: u16, on_change: fn(f32) -> Message| {
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(label))
+                    .push(
+                        slider(0.0..=1410.0, f32::from(minute), on_change)
+                            .step(30.0)
+                            .width(Length::Fill),
+                    )
+                    .push(widget::text(schedule::format_time(minute)))
+            };
+            column = column
+                .push(time_slider(
+                    fl!("quiet-start"),
+                    quiet_hours.start,
+                    Message::QuietStartChanged,
+                ))
+                .push(time_slider(
+                    fl!("quiet-end"),
+                    quiet_hours.end,
+                    Message::QuietEndChanged,
+                ))
+                .push(
+                    widget::row()
+                        .spacing(10)
+                        .align_y(Alignment::Center)
+                        .push(widget::text(fl!("quiet-max-volume")))
+                        .push(
+                            slider(
+                                f32::from(*max_range.start())..=f32::from(*max_range.end()),
+                                f32::from(quiet_hours.max_volume),
+                                Message::QuietMaxVolumeChanged,
+                            )
+                            .step(5.0)
+                            .width(Length::Fill),
+                        )
+                        .push(widget::text(format!("{}%", quiet_hours.max_volume))),
+                );
+        }
+
+        let mut icon_row = widget::row()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(widget::text(fl!("icon-style")).width(Length::Fill));
+        for style in IconStyle::ALL {
+            let label = match style {
+                IconStyle::Radio => fl!("icon-style-radio"),
+                IconStyle::Player => fl!("icon-style-player"),
+            };
+            icon_row = icon_row.push(
+                cosmic::iced::widget::button(
+                    widget::row()
+                        .spacing(6)
+                        .align_y(Alignment::Center)
+                        .push(icon::icon(style.panel_icon(PanelState::Idle)).size(16))
+                        .push(widget::text(if style == self.config.icon_style {
+                            format!("[{label}]")
+                        } else {
+                            label
+                        })),
+                )
+                .on_press(Message::SetIconStyle(style)),
+            );
+        }
+        column = column
+            .push(widget::text(fl!("appearance-header")).size(18))
+            .push(icon_row)
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("panel-scroll")).width(Length::Fill))
+                    .push(
+                        widget::toggler(self.config.panel_scroll.enabled)
+                            .on_toggle(Message::SetPanelScroll),
+                    ),
+            );
+        if self.config.panel_scroll.enabled {
+            let step_range = wheel::STEP_RANGE;
+            let mut modifier_row = widget::row()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(widget::text(fl!("panel-scroll-modifier")).width(Length::Fill));
+            for modifier in ScrollModifier::ALL {
+                let label = match modifier {
+                    ScrollModifier::ShiftFine => fl!("panel-scroll-shift-fine"),
+                    ScrollModifier::CtrlRequired => fl!("panel-scroll-ctrl-required"),
+                    ScrollModifier::Ignored => fl!("panel-scroll-ignored"),
+                };
+                modifier_row = modifier_row.push(
+                    cosmic::iced::widget::button(widget::text(
+                        if modifier == self.config.panel_scroll.modifier {
+                            format!("[{label}]")
+                        } else {
+                            label
+                        },
+                    ))
+                    .on_press(Message::SetScrollModifier(modifier)),
+                );
+            }
+            column = column
+                .push(
+                    widget::row()
+                        .spacing(10)
+                        .align_y(Alignment::Center)
+                        .push(widget::text(fl!("panel-scroll-step")))
+                        .push(
+                            slider(
+                                f32::from(*step_range.start())..=f32::from(*step_range.end()),
+                                f32::from(self.config.panel_scroll.step),
+                                Message::PanelScrollStepChanged,
+                            )
+                            .step(1.0)
+                            .width(Length::Fill),
+                        )
+                        .push(widget::text(format!("{}%", self.config.panel_scroll.step))),
+                )
+                .push(modifier_row);
+        }
+        for kind in ClickKind::ALL {
+            let label = match kind {
+                ClickKind::Left => fl!("panel-click-left"),
+                ClickKind::Middle => fl!("panel-click-middle"),
+                ClickKind::Double => fl!("panel-click-double"),
+            };
+            let selected = self.config.panel_clicks.action(kind);
+            let mut actions = widget::row().spacing(6);
+            for action in ClickAction::ALL {
+                let name = match action {
+                    ClickAction::Nothing => fl!("click-action-nothing"),
+                    ClickAction::OpenPopup => fl!("click-action-popup"),
+                    ClickAction::TogglePlayback => fl!("click-action-playback"),
+                    ClickAction::NextPreset => fl!("click-action-next"),
+                    ClickAction::Mute => fl!("click-action-mute"),
+                };
+                actions = actions.push(
+                    cosmic::iced::widget::button(widget::text(if action == selected {
+                        format!("[{name}]")
+                    } else {
+                        name
+                    }))
+                    .on_press(Message::SetClickAction(kind, action)),
+                );
+            }
+            column = column.push(
+                widget::column()
+                    .spacing(4)
+                    .push(widget::text(label))
+                    .push(actions),
+            );
+        }
+        column = column.push(
+            widget::row()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(widget::text(fl!("ticker")).width(Length::Fill))
+                .push(widget::toggler(self.config.ticker.enabled).on_toggle(Message::SetTicker)),
+        );
+        if self.config.ticker.enabled {
+            let width_range = ticker::WIDTH_RANGE;
+            let speed_range = ticker::SPEED_RANGE;
+            column = column
+                .push(
+                    widget::row()
+                        .spacing(10)
+                        .align_y(Alignment::Center)
+                        .push(widget::text(fl!("ticker-width")))
+                        .push(
+                            slider(
+                                *width_range.start() as f32..=*width_range.end() as f32,
+                                self.config.ticker.max_width as f32,
+                                Message::TickerWidthChanged,
+                            )
+                            .step(1.0)
+                            .width(Length::Fill),
+                        )
+                        .push(widget::text(fl!(
+                            "ticker-width-value",
+                            chars = self.config.ticker.max_width
+                        ))),
+                )
+                .push(
+                    widget::row()
+                        .spacing(10)
+                        .align_y(Alignment::Center)
+                        .push(widget::text(fl!("ticker-speed")))
+                        .push(
+                            slider(
+                                *speed_range.start() as f32..=*speed_range.end() as f32,
+                                self.config.ticker.speed as f32,
+                                Message::TickerSpeedChanged,
+                            )
+                            .step(1.0)
+                            .width(Length::Fill),
+                        )
+                        .push(widget::text(fl!(
+                            "ticker-speed-value",
+                            chars = self.config.ticker.speed
+                        ))),
+                );
+        }
+
+        column = column
+            .push(widget::text(fl!("hooks-header")).size(18))
+            .push(widget::text(fl!("hooks-hint")).size(12));
+        for event in HookEvent::ALL {
+            let label = match event {
+                HookEvent::Play => fl!("hook-on-play"),
+                HookEvent::Stop => fl!("hook-on-stop"),
+                HookEvent::TrackChange => fl!("hook-on-track-change"),
+                HookEvent::Error => fl!("hook-on-error"),
+            };
+            column = column.push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(label).width(Length::Fixed(120.0)))
+                    .push(
+                        text_input(&fl!("hook-placeholder"), self.hook_drafts.command(event))
+                            .on_input(move |command| Message::HookInputChanged(event, command))
+                            .on_submit(Message::SaveHooks),
+                    ),
+            );
+        }
+        column = column.push(
+            cosmic::iced::widget::button(widget::text(fl!("hooks-save")))
+                .on_press(Message::SaveHooks),
+        );
+
+        column = column
+            .push(widget::text(fl!("control-header")).size(18))
+            .push(widget::text(fl!("control-hint")).size(12))
+            .push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(fl!("control-enable")).width(Length::Fill))
+                    .push(
+                        widget::toggler(self.config.control.enabled)
+                            .on_toggle(Message::SetControlEnabled),
+                    ),
+            );
+        if self.config.control.enabled {
+            let address = format!("http://127.0.0.1:{}", self.config.control.port);
+            column = column.push(
+                widget::row()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(
+                        widget::text(fl!("control-address", address = address)).width(Length::Fill),
+                    )
+                    .push(
+                        cosmic::iced::widget::button(widget::text(fl!("control-copy-token")))
+                            .on_press_maybe(
+                                self.control_token
+                                    .as_ref()
+                                    .map(|_| Message::CopyControlToken),
+                            ),
+                    ),
+            );
+        }
+
+        column = column
+            .push(widget::text(fl!("log-header")).size(18))
+            .push(level_row)
+            .push(
+                cosmic::iced::widget::button(widget::text(fl!("log-open")))
+                    .on_press(Message::OpenLogDir),
+            );
+        column.into()
     }
 
-    fn push_mpris_state(&self) {
-        if let Some(tx) = &self.mpris_tx {
-            let update = if self.is_playing {
-                if let Some(station) = &self.current_station {
-                    MprisStateUpdate::Playing {
-                        station: Box::new(station.clone()),
+    /// Add imported stations to favorites, skipping ones already saved
+    fn import_favorites(&mut self, stations: Vec<Station>) {
+        let mut added = 0;
+        for station in stations {
+            let exists = self.config.favorites.iter().any(|f| {
+                f.stationuuid == station.stationuuid || f.url_resolved == station.url_resolved
+            });
+            if !exists {
+                self.config.favorites.push(station);
+                added += 1;
+            }
+        }
+        info!("Imported {} new favorites", added);
+        if added > 0 {
+            self.save_config();
+        }
+    }
+
+    /// Switch to another profile (empty name = default), swapping favorites and volume
+    fn switch_profile(&mut self, name: String) -> Task<cosmic::Action<Message>> {
+        if name == self.config.active_profile {
+            return Task::none();
+        }
+        let current = ProfileData {
+            favorites: std::mem::take(&mut self.config.favorites),
+            volume: self.config.volume,
+        };
+        if self.config.active_profile.is_empty() {
+            self.home_profile = Some(current);
+        }
+
+        let data = if name.is_empty() {
+            self.home_profile.take().unwrap_or_default()
+        } else {
+            profiles::load(&name)
+        };
+        info!("Switched to profile {:?}", name);
+        self.config.favorites = data.favorites;
+        self.config.volume = data.volume;
+        self.config.active_profile = name;
+        self.save_config();
+        self.output_volume(self.config.volume)
+    }
+
+    /// The main config as stored on disk: with a named profile active it holds
+    /// the default profile's favorites and volume
+    fn persisted_config(&self) -> Config {
+        let mut main = self.config.clone();
+        if let Some(home) = &self.home_profile {
+            main.favorites.clone_from(&home.favorites);
+            main.volume = home.volume;
+        }
+        main
+    }
+
+    /// Snapshot of config, profiles, and history for a backup
+    fn backup(&self) -> Backup {
+        let profiles = self
+            .config
+            .profiles
+            .iter()
+            .map(|name| {
+                let data = if *name == self.config.active_profile {
+                    ProfileData {
+                        favorites: self.config.favorites.clone(),
+                        volume: self.config.volume,
                     }
                 } else {
-                    MprisStateUpdate::Stopped
-                }
-            } else {
-                MprisStateUpdate::Stopped
-            };
-            let _ = tx.send(update);
-            let _ = tx.send(MprisStateUpdate::Volume(self.config.volume));
+                    profiles::load(name)
+                };
+                (name.clone(), data)
+            })
+            .collect();
+        Backup::new(self.persisted_config(), profiles, self.history.clone())
+    }
+
+    /// Replace all settings, profiles, and history with a backup file's contents
+    fn restore_backup(&mut self, uri: &str) -> Task<cosmic::Action<Message>> {
+        let restored = playlist::local_path(uri).and_then(|path| backup::read(&path));
+        let backup = match restored {
+            Ok(backup) => backup,
+            Err(e) => {
+                error!("Cannot restore backup: {}", e);
+                self.settings_notice = Some(format!("{} {}", fl!("error-message"), e));
+                return Task::none();
+            }
+        };
+        if let Err(e) = backup.restore_data() {
+            error!("Failed to restore profiles and history: {}", e);
         }
+
+        let mut config = backup.config;
+        // A backup must not unlock the applet; the PIN stays in the keyring
+        config.kiosk_locked = self.config.kiosk_locked;
+        self.home_profile = profiles::activate(&mut config);
+        self.config = config;
+        self.history = backup.history;
+        self.current_session = None;
+        self.save_config();
+        info!("Restored backup from {}", uri);
+        self.settings_notice = Some(fl!("backup-restored"));
+        self.output_volume(self.config.volume)
     }
 
     fn save_config(&self) {
-        if let Err(e) = self.config.write_entry(&self.config_handler) {
+        // The primary owns the config; a follower's copy is stale apart
+        // from what the primary sends it
+        if self.follower {
+            return;
+        }
+        // With a named profile active, its favorites and volume go to the profile
+        // document while the main config keeps the default profile's
+        if self.home_profile.is_some() {
+            let profile = ProfileData {
+                favorites: self.config.favorites.clone(),
+                volume: self.config.volume,
+            };
+            if let Err(e) = profiles::save(&self.config.active_profile, &profile) {
+                error!(
+                    "Failed to save profile {}: {:?}",
+                    self.config.active_profile, e
+                );
+            }
+        }
+        if let Err(e) = self.persisted_config().write_entry(&self.config_handler) {
             error!("Failed to save config: {:?}", e);
         } else {
             debug!("Config saved");
         }
+        // Favorites are only ever changed through here
+        self.push_remote_state();
+    }
+}
+
+/// Messages lock mode ignores: searching, changing favorites or profiles,
+/// and opening any page but the favorites list
+fn locked_out(message: &Message) -> bool {
+    match message {
+        Message::ShowPage(page) => *page != Page::Favorites,
+        _ => matches!(
+            message,
+            Message::SearchInputChanged(_)
+                | Message::PerformSearch
+                | Message::ToggleFavorite(_)
+                | Message::ResolveDuplicate(_)
+                | Message::LikeStation(_)
+                | Message::HideStation(_)
+                | Message::SwitchProfile(_)
+                | Message::CreateProfile
+                | Message::EditSchedule(_)
+                | Message::FindReplacement(_)
+                | Message::ReplaceFavorite(..)
+                | Message::CheckFavorites
+                | Message::BrowseGenre(_)
+                | Message::QueueStation(_)
+                | Message::BatchQueue
+                | Message::ClearQueue
+                | Message::PlayQueue
+                | Message::QueueTick
+                | Message::ToggleSelectMode
+                | Message::BatchFavorite
+                | Message::BatchAddToProfile(_)
+                | Message::BatchHide
+                | Message::BatchExport
+                | Message::ToggleDiagnostics
+                | Message::SetAllowInvalidCerts(_)
+                | Message::SetUpgradeHttps(_)
+                | Message::SetStationChannels(_)
+                | Message::SetStationMono(_)
+                | Message::StationBalanceChanged(_)
+                | Message::BalanceReleased
+                | Message::DownloadEpisode(_)
+                | Message::PauseDownload(_)
+                | Message::DeleteDownload(_)
+                | Message::DeleteDownloads
+                | Message::StartRecording(_)
+                | Message::StopRecording
+                | Message::PlayRecording(_)
+                | Message::RevealRecording(_)
+                | Message::EditRecordingName(_)
+                | Message::RenameRecording
+                | Message::DeleteRecording(_)
+                | Message::SetRecordingRetention(_)
+                | Message::SetRecordingFormat(_)
+                | Message::SetRecordingBitrate(_)
+                | Message::AddRecordingRule
+                | Message::RemoveRecordingRule(_)
+                // The previous station may not be a favorite
+                | Message::SwitchStation
+                // Playlists, OPML, and backups opened from outside
+                | Message::MprisEvent(mpris::MprisEvent::Command(
+                    mpris::MprisCommand::OpenUri(_)
+                ))
+                | Message::Lock
+        ),
+    }
+}
+
+/// Messages a follower ignores. Only the primary saves the config, so
+/// settings are changed there; playback actions are forwarded to it instead
+/// (see `AppModel::forward_to_primary`).
+fn follower_refused(message: &Message) -> bool {
+    match message {
+        // Recording runs in the primary alone
+        Message::ShowPage(page) => matches!(page, Page::Settings | Page::Recordings),
+        // Searching and browsing only change what the follower shows
+        Message::SearchInputChanged(_) | Message::PerformSearch | Message::BrowseGenre(_) => false,
+        _ => locked_out(message),
+    }
+}
+
+/// Icon-only button with `label` as its accessible name for screen readers
+fn icon_button<'a>(
+    name: &'static str,
+    label: String,
+) -> cosmic::iced::widget::Button<'a, Message, cosmic::Theme, cosmic::Renderer> {
+    cosmic::iced::widget::button(icon::from_name(name)).name(label)
+}
+
+/// Stream URL of a station with its credentials, if any, applied
+/// Record a station for `length` into `folder` of the recordings directory,
+/// once its stream credentials are looked up. The ID, format, and start are
+/// filled in when it starts.
+fn start_recording(
+    station: Arc<Station>,
+    length: Duration,
+    folder: String,
+) -> Task<cosmic::Action<Message>> {
+    let recording = ActiveRecording {
+        id: 0,
+        station: station.name.clone(),
+        url: String::new(),
+        codec: station.codec.clone(),
+        folder,
+        format: RecordingFormat::Copy,
+        bitrate: 0,
+        started_at: 0,
+        length,
+        bytes: 0,
+    };
+    Task::perform(
+        async move {
+            let auth = streamauth::lookup(station.stationuuid.clone()).await;
+            ActiveRecording {
+                url: stream_url(&station, auth.as_ref()),
+                ..recording
+            }
+        },
+        Message::RecordingReady,
+    )
+    .map(Into::into)
+}
+
+fn stream_url(station: &Station, auth: Option<&StreamAuth>) -> String {
+    match auth.map(|auth| streamauth::apply(&station.url_resolved, auth)) {
+        Some(Ok(url)) => url,
+        Some(Err(e)) => {
+            error!("Cannot apply stream credentials: {}", e);
+            station.url_resolved.clone()
+        }
+        None => station.url_resolved.clone(),
+    }
+}
+
+/// Translated balance, e.g. "Left 30"
+fn balance_label(balance: i8) -> String {
+    match balance.cmp(&0) {
+        std::cmp::Ordering::Less => fl!("balance-left", amount = balance.unsigned_abs()),
+        std::cmp::Ordering::Equal => fl!("balance-center"),
+        std::cmp::Ordering::Greater => fl!("balance-right", amount = balance),
+    }
+}
+
+/// Translated name of a genre
+fn genre_name(genre: Genre) -> String {
+    match genre {
+        Genre::Pop => fl!("genre-pop"),
+        Genre::Rock => fl!("genre-rock"),
+        Genre::Metal => fl!("genre-metal"),
+        Genre::Jazz => fl!("genre-jazz"),
+        Genre::Classical => fl!("genre-classical"),
+        Genre::Electronic => fl!("genre-electronic"),
+        Genre::Dance => fl!("genre-dance"),
+        Genre::HipHop => fl!("genre-hiphop"),
+        Genre::Soul => fl!("genre-soul"),
+        Genre::Chillout => fl!("genre-chillout"),
+        Genre::Country => fl!("genre-country"),
+        Genre::Folk => fl!("genre-folk"),
+        Genre::Blues => fl!("genre-blues"),
+        Genre::Reggae => fl!("genre-reggae"),
+        Genre::Latin => fl!("genre-latin"),
+        Genre::Oldies => fl!("genre-oldies"),
+        Genre::World => fl!("genre-world"),
+        Genre::News => fl!("genre-news"),
+        Genre::Talk => fl!("genre-talk"),
+        Genre::Sports => fl!("genre-sports"),
+        Genre::Religious => fl!("genre-religious"),
+        Genre::Children => fl!("genre-children"),
+    }
+}
+
+/// Span grouping the log lines of starting a station's stream
+fn playback_span(station: &Station) -> tracing::Span {
+    info_span!("playback", station = %station.stationuuid)
+}
+
+/// Short names of the days of the week, Monday first
+fn day_names() -> [String; 7] {
+    [
+        fl!("day-mon"),
+        fl!("day-tue"),
+        fl!("day-wed"),
+        fl!("day-thu"),
+        fl!("day-fri"),
+        fl!("day-sat"),
+        fl!("day-sun"),
+    ]
+}
+
+/// Days a show airs, e.g. "Mon, Wed" or "Weekdays"
+fn days_label(days: u8) -> String {
+    match days {
+        schedule::EVERY_DAY => fl!("schedule-daily"),
+        0b001_1111 => fl!("schedule-weekdays"),
+        _ => day_names()
+            .into_iter()
+            .enumerate()
+            .filter(|(day, _)| days & (1 << day) != 0)
+            .map(|(_, name)| name)
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locked_out_settings() {
+        // update() drops these in lock mode before they reach the config
+        let settings = [
+            Message::ToggleDiagnostics,
+            Message::SetAllowInvalidCerts(true),
+            Message::SetUpgradeHttps(true),
+            Message::SetStationChannels(true),
+            Message::SetStationMono(true),
+            Message::StationBalanceChanged(50.0),
+            Message::BalanceReleased,
+            Message::DownloadEpisode(Arc::default()),
+            Message::PauseDownload("uuid".to_string()),
+            Message::DeleteDownload("uuid".to_string()),
+            Message::DeleteDownloads,
+            Message::StartRecording(Arc::default()),
+            Message::StopRecording,
+            Message::PlayRecording(PathBuf::new()),
+            Message::DeleteRecording(PathBuf::new()),
+            Message::SetRecordingRetention(7),
+            Message::SetRecordingFormat(RecordingFormat::Opus),
+            Message::SetRecordingBitrate(96),
+            Message::AddRecordingRule,
+            Message::RemoveRecordingRule(0),
+            Message::SwitchStation,
+            Message::PlayQueue,
+            Message::QueueTick,
+        ];
+        for message in &settings {
+            assert!(locked_out(message), "{message:?} allowed in lock mode");
+        }
+        assert!(!locked_out(&Message::ShowPage(Page::Favorites)));
+        assert!(!locked_out(&Message::TogglePlayPause));
+    }
+
+    #[test]
+    fn test_locked_out_open_uri() {
+        let open = |uri: &str| {
+            Message::MprisEvent(mpris::MprisEvent::Command(mpris::MprisCommand::OpenUri(
+                uri.to_string(),
+            )))
+        };
+        for uri in [
+            "radio://example.com/live",
+            "file:///tmp/stations.pls",
+            "file:///tmp/stations.opml",
+            "file:///tmp/radio-backup.json",
+        ] {
+            assert!(locked_out(&open(uri)), "{uri} opened in lock mode");
+        }
+        let play_pause = mpris::MprisEvent::Command(mpris::MprisCommand::PlayPause);
+        assert!(!locked_out(&Message::MprisEvent(play_pause)));
+    }
+
+    #[test]
+    fn test_follower_refused() {
+        for message in [
+            Message::ShowPage(Page::Settings),
+            Message::Lock,
+            Message::ToggleDiagnostics,
+            Message::SwitchStation,
+        ] {
+            assert!(follower_refused(&message), "{message:?} allowed");
+        }
+        for message in [
+            Message::ShowPage(Page::Discover),
+            Message::ShowPage(Page::Favorites),
+            Message::PerformSearch,
+            Message::TogglePlayPause,
+        ] {
+            assert!(!follower_refused(&message), "{message:?} refused");
+        }
     }
 }
@@ -0,0 +1,147 @@
+//! Desktop accessibility preferences
+//!
+//! High contrast and reduced motion are read from the XDG desktop portal's
+//! settings (`org.freedesktop.appearance` `contrast`, and the
+//! `org.gnome.desktop.interface` `enable-animations` key most portals
+//! forward) and polled so changes apply without a restart. Without a portal
+//! both stay off.
+
+use futures::SinkExt;
+use mpris_server::zbus::{self, zvariant, Connection};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Frames of the playing-state animation in the now-playing row
+pub const PLAYING_FRAMES: &[&str] = &[
+    "audio-volume-low-symbolic",
+    "audio-volume-medium-symbolic",
+    "audio-volume-high-symbolic",
+    "audio-volume-medium-symbolic",
+];
+
+/// Delay between animation frames
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(400);
+
+/// Accessibility preferences that change how the popup is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Appearance {
+    /// Stronger row backgrounds and unambiguous icons
+    pub high_contrast: bool,
+    /// No animations
+    pub reduce_motion: bool,
+}
+
+impl Appearance {
+    /// Favorite toggle icon; the outline star is hard to tell from the filled
+    /// one in high contrast, so an add icon replaces it
+    #[must_use]
+    pub fn favorite_icon(self, is_fav: bool) -> &'static str {
+        match (is_fav, self.high_contrast) {
+            (true, _) => "starred-symbolic",
+            (false, false) => "non-starred-symbolic",
+            (false, true) => "list-add-symbolic",
+        }
+    }
+}
+
+/// Map the portal's `contrast` value (0 = no preference, 1 = high)
+#[must_use]
+pub fn is_high_contrast(contrast: u32) -> bool {
+    contrast == 1
+}
+
+async fn read_setting(
+    connection: &Connection,
+    namespace: &str,
+    key: &str,
+) -> Result<zvariant::OwnedValue, zbus::Error> {
+    let reply = connection
+        .call_method(
+            Some("org.freedesktop.portal.Desktop"),
+            "/org/freedesktop/portal/desktop",
+            Some("org.freedesktop.portal.Settings"),
+            "ReadOne",
+            &(namespace, key),
+        )
+        .await?;
+    reply.body().deserialize()
+}
+
+/// Current preferences; settings the portal does not know keep their default
+async fn query(connection: &Connection) -> Appearance {
+    let high_contrast = read_setting(connection, "org.freedesktop.appearance", "contrast")
+        .await
+        .ok()
+        .and_then(|value| u32::try_from(value).ok())
+        .is_some_and(is_high_contrast);
+    let reduce_motion = read_setting(
+        connection,
+        "org.gnome.desktop.interface",
+        "enable-animations",
+    )
+    .await
+    .ok()
+    .and_then(|value| bool::try_from(value).ok())
+    .is_some_and(|enabled| !enabled);
+    Appearance {
+        high_contrast,
+        reduce_motion,
+    }
+}
+
+/// Subscription yielding the preferences on start and whenever they change
+pub fn appearance_subscription() -> cosmic::iced::Subscription<Appearance> {
+    cosmic::iced::Subscription::run(|| {
+        cosmic::iced::stream::channel(4, |mut output| async move {
+            let connection = match Connection::session().await {
+                Ok(c) => c,
+                Err(e) => {
+                    warn!(
+                        "Cannot read accessibility preferences, no session bus: {}",
+                        e
+                    );
+                    return;
+                }
+            };
+
+            let mut last = None;
+            loop {
+                let appearance = query(&connection).await;
+                if last != Some(appearance) {
+                    debug!("Accessibility preferences: {:?}", appearance);
+                    last = Some(appearance);
+                    if output.send(appearance).await.is_err() {
+                        return;
+                    }
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_high_contrast() {
+        assert!(!is_high_contrast(0));
+        assert!(is_high_contrast(1));
+        assert!(!is_high_contrast(2));
+    }
+
+    #[test]
+    fn test_favorite_icon_swapped_in_high_contrast() {
+        let normal = Appearance::default();
+        let high = Appearance {
+            high_contrast: true,
+            ..Default::default()
+        };
+        assert_eq!(normal.favorite_icon(false), "non-starred-symbolic");
+        assert_eq!(high.favorite_icon(false), "list-add-symbolic");
+        assert_eq!(normal.favorite_icon(true), high.favorite_icon(true));
+    }
+}
@@ -0,0 +1,167 @@
+//! Auto-pause while other media players are playing
+//!
+//! Other MPRIS players on the session bus (browsers playing video, music
+//! players, ...) are polled for their `PlaybackStatus`. When one starts
+//! playing, the radio stops; once they are all quiet again it can optionally
+//! resume.
+
+use crate::mpris::BUS_NAME;
+use futures::SinkExt;
+use mpris_server::zbus::{self, zvariant, Connection};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Auto-pause settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct AutoPauseConfig {
+    /// Stop the radio when another player starts playing
+    #[serde(default)]
+    pub enabled: bool,
+    /// Resume once no other player is playing anymore
+    #[serde(default)]
+    pub auto_resume: bool,
+}
+
+/// What the app should do after other players changed state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoPauseAction {
+    None,
+    Pause,
+    Resume,
+}
+
+/// Decide how to react to another player starting or stopping
+#[must_use]
+pub fn decide(
+    config: AutoPauseConfig,
+    others_playing: bool,
+    is_playing: bool,
+    paused_by_us: bool,
+) -> AutoPauseAction {
+    if !config.enabled {
+        return AutoPauseAction::None;
+    }
+    if others_playing && is_playing {
+        AutoPauseAction::Pause
+    } else if !others_playing && paused_by_us && !is_playing && config.auto_resume {
+        AutoPauseAction::Resume
+    } else {
+        AutoPauseAction::None
+    }
+}
+
+async fn other_player_is_playing(connection: &Connection) -> Result<bool, zbus::Error> {
+    let names: Vec<String> = connection
+        .call_method(
+            Some("org.freedesktop.DBus"),
+            "/org/freedesktop/DBus",
+            Some("org.freedesktop.DBus"),
+            "ListNames",
+            &(),
+        )
+        .await?
+        .body()
+        .deserialize()?;
+
+    for name in names
+        .iter()
+        .filter(|n| n.starts_with(MPRIS_PREFIX) && n.as_str() != BUS_NAME)
+    {
+        let reply = connection
+            .call_method(
+                Some(name.as_str()),
+                "/org/mpris/MediaPlayer2",
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &("org.mpris.MediaPlayer2.Player", "PlaybackStatus"),
+            )
+            .await;
+        let Ok(reply) = reply else {
+            continue;
+        };
+        let value: zvariant::OwnedValue = reply.body().deserialize()?;
+        if let zvariant::Value::Str(status) = &*value {
+            if status.as_str() == "Playing" {
+                debug!("Other player playing: {}", name);
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Subscription yielding `true`/`false` whenever other players start or stop
+pub fn other_players_subscription() -> cosmic::iced::Subscription<bool> {
+    cosmic::iced::Subscription::run(|| {
+        cosmic::iced::stream::channel(4, |mut output| async move {
+            let connection = match Connection::session().await {
+                Ok(c) => c,
+                Err(e) => {
+                    warn!("Auto-pause disabled, no session bus: {}", e);
+                    return;
+                }
+            };
+
+            let mut last = false;
+            loop {
+                match other_player_is_playing(&connection).await {
+                    Ok(playing) if playing != last => {
+                        last = playing;
+                        if output.send(playing).await.is_err() {
+                            return;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Failed to query MPRIS players: {}", e),
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENABLED: AutoPauseConfig = AutoPauseConfig {
+        enabled: true,
+        auto_resume: true,
+    };
+
+    #[test]
+    fn test_disabled_never_acts() {
+        let config = AutoPauseConfig::default();
+        assert_eq!(decide(config, true, true, false), AutoPauseAction::None);
+        assert_eq!(decide(config, false, false, true), AutoPauseAction::None);
+    }
+
+    #[test]
+    fn test_pause_when_other_starts() {
+        assert_eq!(decide(ENABLED, true, true, false), AutoPauseAction::Pause);
+    }
+
+    #[test]
+    fn test_no_pause_when_not_playing() {
+        assert_eq!(decide(ENABLED, true, false, false), AutoPauseAction::None);
+    }
+
+    #[test]
+    fn test_resume_only_if_paused_by_us() {
+        assert_eq!(decide(ENABLED, false, false, true), AutoPauseAction::Resume);
+        assert_eq!(decide(ENABLED, false, false, false), AutoPauseAction::None);
+    }
+
+    #[test]
+    fn test_no_resume_without_auto_resume() {
+        let config = AutoPauseConfig {
+            enabled: true,
+            auto_resume: false,
+        };
+        assert_eq!(decide(config, false, false, true), AutoPauseAction::None);
+    }
+}
@@ -0,0 +1,194 @@
+//! Full settings backup and restore
+//!
+//! A backup is one JSON document holding the main config (favorites, custom
+//! providers, integrations), every named profile, and the listening history.
+//! Secrets stay in the keyring and are never included. Backups carry a
+//! `format_version`; older documents are upgraded step by step on restore.
+
+use crate::config::Config;
+use crate::profiles::{self, ProfileData};
+use crate::stats::{self, ListenSession};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Current backup document version
+pub const FORMAT_VERSION: u32 = 1;
+
+/// File name suffix that marks a backup for the URL handler
+pub const FILE_SUFFIX: &str = ".radio-backup.json";
+
+/// Everything needed to recreate the applet's state on another machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Backup {
+    pub format_version: u32,
+    /// Creation time, seconds since the Unix epoch
+    #[serde(default)]
+    pub created: u64,
+    pub config: Config,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProfileData>,
+    #[serde(default)]
+    pub history: Vec<ListenSession>,
+}
+
+impl Backup {
+    /// Snapshot the given state
+    #[must_use]
+    pub fn new(
+        config: Config,
+        profiles: BTreeMap<String, ProfileData>,
+        history: Vec<ListenSession>,
+    ) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            created: stats::now_secs(),
+            config,
+            profiles,
+            history,
+        }
+    }
+
+    /// Snapshot the state persisted on disk
+    #[must_use]
+    pub fn from_disk(config: Config) -> Self {
+        let profiles = config
+            .profiles
+            .iter()
+            .map(|name| (name.clone(), profiles::load(name)))
+            .collect();
+        let history = stats::history_path()
+            .and_then(|path| stats::load(&path).ok())
+            .unwrap_or_default();
+        Self::new(config, profiles, history)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Parse a backup, upgrading older format versions
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let value: serde_json::Value =
+            serde_json::from_str(text).map_err(|e| format!("Not a backup file: {e}"))?;
+        let version = value
+            .get("format_version")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or("Not a backup file: missing format_version")?;
+        if version > u64::from(FORMAT_VERSION) {
+            return Err(format!(
+                "Backup format {version} is newer than supported ({FORMAT_VERSION})"
+            ));
+        }
+        let value = migrate(value, version);
+        serde_json::from_value(value).map_err(|e| format!("Invalid backup: {e}"))
+    }
+
+    /// Write profiles and history back to disk (the caller saves `config`)
+    pub fn restore_data(&self) -> io::Result<()> {
+        for (name, data) in &self.profiles {
+            profiles::save(name, data).map_err(|e| io::Error::other(format!("{e:?}")))?;
+        }
+        if let Some(path) = stats::history_path() {
+            stats::replace(&path, &self.history)?;
+        }
+        Ok(())
+    }
+}
+
+/// Upgrade a backup document from `version` to [`FORMAT_VERSION`]
+fn migrate(value: serde_json::Value, _version: u64) -> serde_json::Value {
+    // Version 1 is the first format; later upgrades are chained here
+    value
+}
+
+/// Whether a URI handed to the applet is a backup file
+#[must_use]
+pub fn is_backup_uri(uri: &str) -> bool {
+    uri.trim().to_lowercase().ends_with(FILE_SUFFIX)
+}
+
+/// Default path for a new backup, e.g. `~/Downloads/radio-2024-03-01.radio-backup.json`
+#[must_use]
+pub fn default_path() -> Option<PathBuf> {
+    let dir = dirs::download_dir().or_else(dirs::home_dir)?;
+    let date = stats::format_timestamp(stats::now_secs());
+    Some(dir.join(format!("radio-{}{}", &date[..10], FILE_SUFFIX)))
+}
+
+pub fn write(path: &Path, backup: &Backup) -> io::Result<()> {
+    fs::write(path, backup.to_json())
+}
+
+pub fn read(path: &Path) -> Result<Backup, String> {
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    Backup::from_json(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Station;
+
+    fn sample() -> Backup {
+        let config = Config {
+            favorites: vec![Station {
+                stationuuid: "abc".to_string(),
+                name: "Jazz24".to_string(),
+                ..Default::default()
+            }],
+            volume: 70,
+            profiles: vec!["Work".to_string()],
+            ..Default::default()
+        };
+        let profiles = BTreeMap::from([("Work".to_string(), ProfileData::default())]);
+        let history = vec![ListenSession {
+            stationuuid: "abc".to_string(),
+            name: "Jazz24".to_string(),
+            started: 100,
+            duration_secs: 600,
+            bytes: 0,
+        }];
+        Backup::new(config, profiles, history)
+    }
+
+    #[test]
+    fn test_backup_roundtrip() {
+        let backup = sample();
+        assert_eq!(Backup::from_json(&backup.to_json()).unwrap(), backup);
+    }
+
+    #[test]
+    fn test_backup_rejects_newer_format() {
+        let mut value: serde_json::Value = serde_json::from_str(&sample().to_json()).unwrap();
+        value["format_version"] = serde_json::json!(FORMAT_VERSION + 1);
+        let err = Backup::from_json(&value.to_string()).unwrap_err();
+        assert!(err.contains("newer"));
+    }
+
+    #[test]
+    fn test_backup_rejects_other_json() {
+        assert!(Backup::from_json(r#"{"favorites": []}"#).is_err());
+        assert!(Backup::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_backup_missing_sections_default() {
+        let config = serde_json::to_value(Config::default()).unwrap();
+        let text = serde_json::json!({ "format_version": 1, "config": config }).to_string();
+        let backup = Backup::from_json(&text).unwrap();
+        assert!(backup.profiles.is_empty());
+        assert!(backup.history.is_empty());
+    }
+
+    #[test]
+    fn test_is_backup_uri() {
+        assert!(is_backup_uri(
+            "file:///home/me/radio-2024-03-01.radio-backup.json"
+        ));
+        assert!(!is_backup_uri("/home/me/stations.json"));
+    }
+}
@@ -0,0 +1,139 @@
+//! Approximate data usage
+//!
+//! mpv reports the network input rate of the stream with its demuxer cache
+//! state. The rate is integrated over time into a byte count for the current
+//! listening session, which is stored with the session in the history log.
+//! When no rate was reported (e.g. while casting), the station's listed
+//! bitrate gives an estimate.
+
+use std::time::Instant;
+
+const KB: u64 = 1000;
+const MB: u64 = 1000 * KB;
+const GB: u64 = 1000 * MB;
+
+/// Bytes received since the meter was started
+#[derive(Debug, Clone, Default)]
+pub struct Meter {
+    /// Last reported rate in bytes per second
+    rate: u64,
+    /// When `rate` was reported
+    since: Option<Instant>,
+    bytes: u64,
+    /// Listed bitrate of the stream in kbit/s, for estimates
+    bitrate_kbps: u32,
+}
+
+impl Meter {
+    /// Meter for a stream listed at `bitrate_kbps` (0 = unknown)
+    #[must_use]
+    pub fn new(bitrate_kbps: u32) -> Self {
+        Self {
+            bitrate_kbps,
+            ..Default::default()
+        }
+    }
+
+    /// Count the time since the last report at its rate, then continue at `rate`
+    pub fn set_rate(&mut self, rate: u64, now: Instant) {
+        self.accumulate(now);
+        self.rate = rate;
+    }
+
+    fn accumulate(&mut self, now: Instant) {
+        if let Some(since) = self.since {
+            let millis =
+                u64::try_from(now.saturating_duration_since(since).as_millis()).unwrap_or(u64::MAX);
+            self.bytes = self
+                .bytes
+                .saturating_add(self.rate.saturating_mul(millis) / 1000);
+        }
+        self.since = Some(now);
+    }
+
+    /// Bytes received up to `now`
+    #[must_use]
+    pub fn bytes(&self, now: Instant) -> u64 {
+        let mut meter = self.clone();
+        meter.accumulate(now);
+        meter.bytes
+    }
+
+    /// Bytes received up to `now`, or estimated for `secs` of listening if
+    /// no rate was reported
+    #[must_use]
+    pub fn used(&self, now: Instant, secs: u64) -> u64 {
+        if self.since.is_some() {
+            self.bytes(now)
+        } else {
+            estimate(self.bitrate_kbps, secs)
+        }
+    }
+
+    /// [`Meter::used`], resetting the meter
+    pub fn take(&mut self, now: Instant, secs: u64) -> u64 {
+        let bytes = self.used(now, secs);
+        *self = Self::default();
+        bytes
+    }
+}
+
+/// Bytes a stream of `bitrate_kbps` uses in `secs` seconds
+#[must_use]
+pub fn estimate(bitrate_kbps: u32, secs: u64) -> u64 {
+    u64::from(bitrate_kbps) * KB / 8 * secs
+}
+
+/// Human-readable amount of data, e.g. `850 KB`, `56.3 MB`, or `1.20 GB`
+#[must_use]
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes >= GB {
+        format!("{:.2} GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else {
+        format!("{} KB", bytes.div_ceil(KB))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_meter_integrates_rate() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut meter = Meter::new(128);
+
+        meter.set_rate(16_000, at(0));
+        meter.set_rate(32_000, at(10));
+        assert_eq!(meter.bytes(at(15)), 160_000 + 160_000);
+
+        assert_eq!(meter.take(at(20), 20), 160_000 + 320_000);
+        assert_eq!(meter.bytes(at(30)), 0);
+    }
+
+    #[test]
+    fn test_meter_estimates_without_reports() {
+        let mut meter = Meter::new(128);
+        assert_eq!(meter.take(Instant::now(), 3600), 57_600_000);
+        assert_eq!(Meter::default().take(Instant::now(), 3600), 0);
+    }
+
+    #[test]
+    fn test_estimate() {
+        // 128 kbit/s for an hour is 57.6 MB
+        assert_eq!(estimate(128, 3600), 57_600_000);
+        assert_eq!(estimate(0, 3600), 0);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 KB");
+        assert_eq!(format_bytes(850_000), "850 KB");
+        assert_eq!(format_bytes(57_600_000), "57.6 MB");
+        assert_eq!(format_bytes(1_200_000_000), "1.20 GB");
+    }
+}
@@ -0,0 +1,157 @@
+//! Bookmarked songs
+//!
+//! "I like this track" saves the current ICY title, split into artist and
+//! title where possible, with the station and time. Bookmarks are kept in
+//! the config, so they are part of backups, and can be exported as plain
+//! text or CSV.
+
+use crate::coverart;
+use crate::stats::{self, csv_field};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A saved song
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SongBookmark {
+    /// Empty when the stream title could not be split
+    pub artist: String,
+    pub title: String,
+    pub station: String,
+    /// Seconds since the Unix epoch
+    pub saved_at: u64,
+}
+
+impl SongBookmark {
+    /// Bookmark an ICY stream title heard on `station`
+    #[must_use]
+    pub fn from_icy(icy_title: &str, station: &str, saved_at: u64) -> Self {
+        let (artist, title) = match coverart::parse_track(icy_title) {
+            Some(track) => (track.artist, track.title),
+            None => (String::new(), icy_title.trim().to_string()),
+        };
+        Self {
+            artist,
+            title,
+            station: station.to_string(),
+            saved_at,
+        }
+    }
+
+    /// "Artist - Title", or the title alone
+    #[must_use]
+    pub fn label(&self) -> String {
+        if self.artist.is_empty() {
+            self.title.clone()
+        } else {
+            format!("{} - {}", self.artist, self.title)
+        }
+    }
+
+    /// Same song, whatever station or time it was saved from
+    #[must_use]
+    pub fn same_song(&self, other: &Self) -> bool {
+        self.artist.to_lowercase() == other.artist.to_lowercase()
+            && self.title.to_lowercase() == other.title.to_lowercase()
+    }
+}
+
+/// Export formats for bookmarks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookmarkFormat {
+    Text,
+    Csv,
+}
+
+impl BookmarkFormat {
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// Render bookmarks in the given export format
+#[must_use]
+pub fn export(bookmarks: &[SongBookmark], format: BookmarkFormat) -> String {
+    let mut out = String::new();
+    match format {
+        BookmarkFormat::Text => {
+            for b in bookmarks {
+                out.push_str(&format!(
+                    "{} ({}, {})\n",
+                    b.label(),
+                    b.station,
+                    stats::format_timestamp(b.saved_at)
+                ));
+            }
+        }
+        BookmarkFormat::Csv => {
+            out.push_str("artist,title,station,saved_utc\n");
+            for b in bookmarks {
+                out.push_str(&format!(
+                    "{},{},{},{}\n",
+                    csv_field(&b.artist),
+                    csv_field(&b.title),
+                    csv_field(&b.station),
+                    stats::format_timestamp(b.saved_at)
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// Write the bookmarks export to `path`
+pub fn export_to_file(
+    bookmarks: &[SongBookmark],
+    format: BookmarkFormat,
+    path: &Path,
+) -> io::Result<()> {
+    fs::write(path, export(bookmarks, format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_icy() {
+        let b = SongBookmark::from_icy("Nina Simone - Feeling Good", "Jazz FM", 0);
+        assert_eq!(b.artist, "Nina Simone");
+        assert_eq!(b.title, "Feeling Good");
+        assert_eq!(b.label(), "Nina Simone - Feeling Good");
+
+        let b = SongBookmark::from_icy(" Morning Show ", "Jazz FM", 0);
+        assert_eq!(b.artist, "");
+        assert_eq!(b.label(), "Morning Show");
+    }
+
+    #[test]
+    fn test_same_song_ignores_case_and_station() {
+        let a = SongBookmark::from_icy("Nina Simone - Feeling Good", "Jazz FM", 0);
+        let b = SongBookmark::from_icy("NINA SIMONE - feeling good", "Soul Radio", 99);
+        assert!(a.same_song(&b));
+    }
+
+    #[test]
+    fn test_export() {
+        let bookmarks = vec![SongBookmark::from_icy(
+            "Crosby, Stills & Nash - Helplessly Hoping",
+            "Folk FM",
+            1_709_317_800,
+        )];
+        assert_eq!(
+            export(&bookmarks, BookmarkFormat::Text),
+            "Crosby, Stills & Nash - Helplessly Hoping (Folk FM, 2024-03-01T18:30:00Z)\n"
+        );
+        assert_eq!(
+            export(&bookmarks, BookmarkFormat::Csv),
+            "artist,title,station,saved_utc\n\
+             \"Crosby, Stills & Nash\",Helplessly Hoping,Folk FM,2024-03-01T18:30:00Z\n"
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//! On-disk cache with a size limit
+//!
+//! Cached data lives under `~/.cache/cosmic-ext-applet-radio/`, one file per
+//! entry. Keys are namespaced by prefix (`search:<query>`, ...) and hashed
+//! into file names. Reading an entry refreshes its modification time, so
+//! eviction drops the least recently used entries first once the total size
+//! exceeds the configured limit.
+//!
+//! Everything here is safe to lose: the cache can be cleared at any time from
+//! the settings page.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use tracing::{debug, warn};
+
+/// Default cache size limit in megabytes
+pub const DEFAULT_MAX_MB: u32 = 50;
+
+/// Allowed cache size limits in megabytes
+pub const MAX_MB_RANGE: RangeInclusive<u32> = 10..=500;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Clamp a size limit to [`MAX_MB_RANGE`]
+#[must_use]
+pub fn clamp_max_mb(mb: u32) -> u32 {
+    mb.clamp(*MAX_MB_RANGE.start(), *MAX_MB_RANGE.end())
+}
+
+/// Default cache directory
+#[must_use]
+pub fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("cosmic-ext-applet-radio"))
+}
+
+/// Cache key for the results of a search query
+#[must_use]
+pub fn search_key(query: &str) -> String {
+    format!("search:{}", query.trim().to_lowercase())
+}
+
+/// A size-limited directory of cached entries
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl Cache {
+    #[must_use]
+    pub fn new(dir: PathBuf, max_mb: u32) -> Self {
+        Self {
+            dir,
+            max_bytes: u64::from(clamp_max_mb(max_mb)) * BYTES_PER_MB,
+        }
+    }
+
+    /// The cache in its default location
+    #[must_use]
+    pub fn open(max_mb: u32) -> Option<Self> {
+        cache_dir().map(|dir| Self::new(dir, max_mb))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        self.dir.join(format!("{:016x}", hasher.finish()))
+    }
+
+    /// Read an entry, marking it as recently used
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.path(key);
+        let bytes = fs::read(&path).ok()?;
+        if let Err(e) = File::options()
+            .append(true)
+            .open(&path)
+            .and_then(|f| f.set_modified(SystemTime::now()))
+        {
+            debug!("Failed to touch cache entry: {}", e);
+        }
+        Some(bytes)
+    }
+
+    /// Store an entry, then evict old ones if the cache grew too large
+    pub fn put(&self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path(key);
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, bytes)?;
+        fs::rename(&tmp, &path)?;
+        self.evict()?;
+        Ok(())
+    }
+
+    /// File holding an entry, for consumers that need a path (e.g. MPRIS art)
+    #[must_use]
+    pub fn file(&self, key: &str) -> Option<PathBuf> {
+        let path = self.path(key);
+        path.is_file().then_some(path)
+    }
+
+    #[must_use]
+    pub fn get_json<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        serde_json::from_slice(&self.get(key)?).ok()
+    }
+
+    /// Store a value as JSON, logging instead of failing
+    pub fn put_json<T: Serialize>(&self, key: &str, value: &T) {
+        let result = serde_json::to_vec(value)
+            .map_err(io::Error::other)
+            .and_then(|bytes| self.put(key, &bytes));
+        if let Err(e) = result {
+            warn!("Failed to write cache entry: {}", e);
+        }
+    }
+
+    fn entries(&self) -> Vec<(PathBuf, u64, SystemTime)> {
+        let Ok(dir) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        dir.filter_map(|entry| {
+            let entry = entry.ok()?;
+            let meta = entry.metadata().ok()?;
+            meta.is_file().then(|| {
+                let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                (entry.path(), meta.len(), modified)
+            })
+        })
+        .collect()
+    }
+
+    /// Total size of all entries in bytes
+    #[must_use]
+    pub fn size(&self) -> u64 {
+        self.entries().iter().map(|(_, len, _)| len).sum()
+    }
+
+    /// Remove least recently used entries until the cache fits its limit.
+    /// Returns the number of bytes freed.
+    pub fn evict(&self) -> io::Result<u64> {
+        let mut entries = self.entries();
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        entries.sort_by_key(|(_, _, modified)| *modified);
+
+        let mut freed = 0;
+        for (path, len, _) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(&path)?;
+            total -= len;
+            freed += len;
+        }
+        if freed > 0 {
+            debug!("Evicted {} bytes from the cache", freed);
+        }
+        Ok(freed)
+    }
+
+    /// Remove every entry
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Human-readable size, e.g. `12.3 MB` or `640 KB`
+#[must_use]
+pub fn format_size(bytes: u64) -> String {
+    if bytes >= BYTES_PER_MB {
+        format!("{:.1} MB", bytes as f64 / BYTES_PER_MB as f64)
+    } else {
+        format!("{} KB", bytes.div_ceil(1024))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn temp_cache(name: &str) -> Cache {
+        let dir = std::env::temp_dir().join(format!("radio-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        Cache::new(dir, 10)
+    }
+
+    #[test]
+    fn test_put_get_roundtrip() {
+        let cache = temp_cache("roundtrip");
+        assert!(cache.get("search:jazz").is_none());
+
+        cache.put("search:jazz", b"[]").unwrap();
+        assert_eq!(cache.get("search:jazz").unwrap(), b"[]");
+        assert_eq!(cache.size(), 2);
+
+        cache.put_json("search:rock", &vec!["a".to_string()]);
+        let value: Vec<String> = cache.get_json("search:rock").unwrap();
+        assert_eq!(value, vec!["a".to_string()]);
+
+        cache.clear().unwrap();
+        assert_eq!(cache.size(), 0);
+        assert!(cache.clear().is_ok());
+    }
+
+    #[test]
+    fn test_evict_drops_least_recently_used() {
+        let mut cache = temp_cache("evict");
+        cache.put("old", &[0; 600]).unwrap();
+        cache.put("new", &[0; 600]).unwrap();
+        let past = SystemTime::now() - Duration::from_secs(3600);
+        File::options()
+            .append(true)
+            .open(cache.path("old"))
+            .unwrap()
+            .set_modified(past)
+            .unwrap();
+
+        cache.max_bytes = 1000;
+        assert_eq!(cache.evict().unwrap(), 600);
+        assert!(cache.get("old").is_none());
+        assert!(cache.get("new").is_some());
+        let _ = cache.clear();
+    }
+
+    #[test]
+    fn test_clamp_and_format() {
+        assert_eq!(clamp_max_mb(0), 10);
+        assert_eq!(clamp_max_mb(9999), 500);
+        assert_eq!(format_size(0), "0 KB");
+        assert_eq!(format_size(1500), "2 KB");
+        assert_eq!(format_size(5 * BYTES_PER_MB + BYTES_PER_MB / 2), "5.5 MB");
+    }
+
+    #[test]
+    fn test_search_key_normalizes_query() {
+        assert_eq!(search_key("  Jazz "), search_key("jazz"));
+    }
+}
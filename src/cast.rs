@@ -0,0 +1,403 @@
+//! Casting to UPnP-AV / DLNA media renderers and Chromecast devices on the
+//! local network
+//!
+//! Renderers are discovered via SSDP multicast, their device description is
+//! fetched to locate the AVTransport and RenderingControl services, and
+//! playback is driven with plain SOAP calls. Chromecasts are found via mDNS
+//! at the same time and driven over CASTV2 (see [`crate::chromecast`]).
+//! Either way the stream URL is handed to the device, which then fetches the
+//! audio itself.
+
+use crate::api::http_client;
+use crate::chromecast;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tracing::{debug, warn};
+use url::Url;
+
+const SSDP_ADDR: &str = "239.255.255.250:1900";
+const AV_TRANSPORT: &str = "urn:schemas-upnp-org:service:AVTransport:1";
+const RENDERING_CONTROL: &str = "urn:schemas-upnp-org:service:RenderingControl:1";
+
+/// How long to collect SSDP and mDNS replies
+pub(crate) const DISCOVERY_WINDOW: Duration = Duration::from_secs(3);
+
+/// A discovered media renderer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CastDevice {
+    pub name: String,
+    pub renderer: Renderer,
+}
+
+/// How a device is driven
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Renderer {
+    Dlna {
+        av_transport_url: String,
+        rendering_control_url: Option<String>,
+    },
+    /// Address of the device's CASTV2 endpoint
+    Chromecast(SocketAddr),
+}
+
+/// Timeout for requests to renderers on the local network
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Discover DLNA renderers and Chromecasts
+pub async fn discover() -> Result<Vec<CastDevice>, String> {
+    let (dlna, chromecasts) = tokio::join!(discover_dlna(), chromecast::discover());
+    match (dlna, chromecasts) {
+        (Err(e), Err(_)) => Err(e),
+        (dlna, chromecasts) => {
+            if let Err(e) = &chromecasts {
+                warn!("Chromecast discovery failed: {}", e);
+            }
+            if let Err(e) = &dlna {
+                warn!("DLNA discovery failed: {}", e);
+            }
+            Ok(dlna
+                .unwrap_or_default()
+                .into_iter()
+                .chain(chromecasts.unwrap_or_default())
+                .collect())
+        }
+    }
+}
+
+/// Discover renderers that expose an AVTransport service
+async fn discover_dlna() -> Result<Vec<CastDevice>, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let request = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {SSDP_ADDR}\r\nMAN: \"ssdp:discover\"\r\nMX: 2\r\nST: {AV_TRANSPORT}\r\n\r\n"
+    );
+    socket
+        .send_to(request.as_bytes(), SSDP_ADDR)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut locations = Vec::new();
+    let mut seen = HashSet::new();
+    let mut buf = [0u8; 2048];
+    let deadline = tokio::time::Instant::now() + DISCOVERY_WINDOW;
+
+    while let Ok(Ok((len, from))) =
+        tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await
+    {
+        let reply = String::from_utf8_lossy(&buf[..len]);
+        if let Some(location) = parse_location(&reply) {
+            if seen.insert(location.clone()) {
+                debug!("SSDP reply from {} at {}", from, location);
+                locations.push(location);
+            }
+        }
+    }
+
+    let mut devices = Vec::new();
+    for location in locations {
+        match describe(&location).await {
+            Ok(Some(device)) => devices.push(device),
+            Ok(None) => debug!("{} has no AVTransport service", location),
+            Err(e) => warn!("Failed to read device description at {}: {}", location, e),
+        }
+    }
+    Ok(devices)
+}
+
+/// Extract the LOCATION header from an SSDP reply
+fn parse_location(reply: &str) -> Option<String> {
+    reply.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("location")
+            .then(|| value.trim().to_string())
+    })
+}
+
+async fn describe(location: &str) -> Result<Option<CastDevice>, String> {
+    let xml = http_client()
+        .get(location)
+        .timeout(HTTP_TIMEOUT)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+    let base = Url::parse(location).map_err(|e| e.to_string())?;
+    Ok(parse_description(&xml, &base))
+}
+
+/// Text content of the first `<tag>...</tag>` in `xml`
+fn extract_tag<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let start = xml.find(&open)? + open.len();
+    let end = xml[start..].find(&close)? + start;
+    Some(xml[start..end].trim())
+}
+
+fn parse_description(xml: &str, base: &Url) -> Option<CastDevice> {
+    let name = extract_tag(xml, "friendlyName").unwrap_or("Unknown renderer");
+
+    let mut av_transport_url = None;
+    let mut rendering_control_url = None;
+    for service in xml.split("<service>").skip(1) {
+        let (Some(service_type), Some(control)) = (
+            extract_tag(service, "serviceType"),
+            extract_tag(service, "controlURL"),
+        ) else {
+            continue;
+        };
+        let Ok(control_url) = base.join(control) else {
+            continue;
+        };
+        if service_type == AV_TRANSPORT {
+            av_transport_url = Some(control_url.to_string());
+        } else if service_type == RENDERING_CONTROL {
+            rendering_control_url = Some(control_url.to_string());
+        }
+    }
+
+    Some(CastDevice {
+        name: xml_unescape(name),
+        renderer: Renderer::Dlna {
+            av_transport_url: av_transport_url?,
+            rendering_control_url,
+        },
+    })
+}
+
+pub(crate) fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+pub(crate) fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Minimal DIDL-Lite item describing a live audio broadcast
+fn didl_metadata(title: &str, url: &str) -> String {
+    format!(
+        concat!(
+            r#"<DIDL-Lite xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/" "#,
+            r#"xmlns:dc="http://purl.org/dc/elements/1.1/" "#,
+            r#"xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/">"#,
+            r#"<item id="0" parentID="-1" restricted="1"><dc:title>{}</dc:title>"#,
+            r#"<upnp:class>object.item.audioItem.audioBroadcast</upnp:class>"#,
+            r#"<res protocolInfo="http-get:*:audio/mpeg:*">{}</res></item></DIDL-Lite>"#
+        ),
+        xml_escape(title),
+        xml_escape(url)
+    )
+}
+
+fn soap_envelope(service: &str, action: &str, args: &[(&str, String)]) -> String {
+    let body: String = args
+        .iter()
+        .map(|(name, value)| format!("<{name}>{}</{name}>", xml_escape(value)))
+        .collect();
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="utf-8"?>"#,
+            r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" "#,
+            r#"s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/">"#,
+            r#"<s:Body><u:{action} xmlns:u="{service}">{body}</u:{action}></s:Body></s:Envelope>"#
+        ),
+        action = action,
+        service = service,
+        body = body
+    )
+}
+
+async fn soap_call(
+    control_url: &str,
+    service: &str,
+    action: &str,
+    args: &[(&str, String)],
+) -> Result<(), String> {
+    let response = http_client()
+        .post(control_url)
+        .header("Content-Type", r#"text/xml; charset="utf-8""#)
+        .header("SOAPACTION", format!("\"{service}#{action}\""))
+        .body(soap_envelope(service, action, args))
+        .timeout(HTTP_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        debug!("SOAP {} succeeded at {}", action, control_url);
+        Ok(())
+    } else {
+        Err(format!("{action} failed with HTTP {}", response.status()))
+    }
+}
+
+/// Hand a stream to the device and start playback
+pub async fn play(device: CastDevice, title: String, url: String) -> Result<(), String> {
+    let av_transport_url = match device.renderer {
+        Renderer::Dlna {
+            av_transport_url, ..
+        } => av_transport_url,
+        Renderer::Chromecast(address) => return chromecast::play(address, title, url).await,
+    };
+    soap_call(
+        &av_transport_url,
+        AV_TRANSPORT,
+        "SetAVTransportURI",
+        &[
+            ("InstanceID", "0".to_string()),
+            ("CurrentURI", url.clone()),
+            ("CurrentURIMetaData", didl_metadata(&title, &url)),
+        ],
+    )
+    .await?;
+    soap_call(
+        &av_transport_url,
+        AV_TRANSPORT,
+        "Play",
+        &[("InstanceID", "0".to_string()), ("Speed", "1".to_string())],
+    )
+    .await
+}
+
+/// Stop playback on the device
+pub async fn stop(device: CastDevice) -> Result<(), String> {
+    let av_transport_url = match device.renderer {
+        Renderer::Dlna {
+            av_transport_url, ..
+        } => av_transport_url,
+        Renderer::Chromecast(address) => return chromecast::stop(address).await,
+    };
+    soap_call(
+        &av_transport_url,
+        AV_TRANSPORT,
+        "Stop",
+        &[("InstanceID", "0".to_string())],
+    )
+    .await
+}
+
+/// Set the device's master volume (0-100)
+pub async fn set_volume(device: CastDevice, volume: u8) -> Result<(), String> {
+    let control_url = match &device.renderer {
+        Renderer::Dlna {
+            rendering_control_url: Some(control_url),
+            ..
+        } => control_url,
+        Renderer::Dlna { .. } => {
+            return Err(format!("{} does not support volume control", device.name));
+        }
+        Renderer::Chromecast(address) => return chromecast::set_volume(*address, volume).await,
+    };
+    soap_call(
+        control_url,
+        RENDERING_CONTROL,
+        "SetVolume",
+        &[
+            ("InstanceID", "0".to_string()),
+            ("Channel", "Master".to_string()),
+            ("DesiredVolume", volume.min(100).to_string()),
+        ],
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIPTION: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <friendlyName>Living Room &amp; Kitchen</friendlyName>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:RenderingControl:1</serviceType>
+        <controlURL>/upnp/control/rendering1</controlURL>
+      </service>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:AVTransport:1</serviceType>
+        <controlURL>/upnp/control/avtransport1</controlURL>
+      </service>
+    </serviceList>
+  </device>
+</root>"#;
+
+    #[test]
+    fn test_parse_location() {
+        let reply = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=1800\r\nLocation: http://192.168.1.20:49152/description.xml\r\nST: urn:schemas-upnp-org:service:AVTransport:1\r\n\r\n";
+        assert_eq!(
+            parse_location(reply).as_deref(),
+            Some("http://192.168.1.20:49152/description.xml")
+        );
+    }
+
+    #[test]
+    fn test_parse_location_missing() {
+        assert_eq!(parse_location("HTTP/1.1 200 OK\r\n\r\n"), None);
+    }
+
+    #[test]
+    fn test_parse_description() {
+        let base = Url::parse("http://192.168.1.20:49152/description.xml").unwrap();
+        let device = parse_description(DESCRIPTION, &base).unwrap();
+        assert_eq!(device.name, "Living Room & Kitchen");
+        assert_eq!(
+            device.renderer,
+            Renderer::Dlna {
+                av_transport_url: "http://192.168.1.20:49152/upnp/control/avtransport1".to_string(),
+                rendering_control_url: Some(
+                    "http://192.168.1.20:49152/upnp/control/rendering1".to_string()
+                ),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_description_without_av_transport() {
+        let base = Url::parse("http://192.168.1.20/").unwrap();
+        let xml = "<root><device><friendlyName>TV</friendlyName><serviceList><service><serviceType>urn:schemas-upnp-org:service:ConnectionManager:1</serviceType><controlURL>/cm</controlURL></service></serviceList></device></root>";
+        assert!(parse_description(xml, &base).is_none());
+    }
+
+    #[test]
+    fn test_soap_envelope_escapes_arguments() {
+        let envelope = soap_envelope(
+            AV_TRANSPORT,
+            "SetAVTransportURI",
+            &[("CurrentURI", "http://example.com/a?b=1&c=2".to_string())],
+        );
+        assert!(envelope.contains("<u:SetAVTransportURI xmlns:u=\"urn:schemas-upnp-org:service:AVTransport:1\">"));
+        assert!(envelope.contains("<CurrentURI>http://example.com/a?b=1&amp;c=2</CurrentURI>"));
+    }
+
+    #[test]
+    fn test_didl_metadata_escapes_title() {
+        let didl = didl_metadata("Rock & Roll <Live>", "http://example.com/stream");
+        assert!(didl.contains("<dc:title>Rock &amp; Roll &lt;Live&gt;</dc:title>"));
+        assert!(didl.contains("audioBroadcast"));
+    }
+
+    #[test]
+    fn test_xml_escape_roundtrip() {
+        let raw = r#"a & b < c > d "e" 'f'"#;
+        assert_eq!(xml_unescape(&xml_escape(raw)), raw);
+    }
+}
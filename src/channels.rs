@@ -0,0 +1,145 @@
+//! Mono downmix and left/right balance
+//!
+//! A weak or noisy stereo stream often sounds cleaner downmixed to mono, and
+//! a listener with one speaker or uneven hearing may want the balance
+//! moved. Both are set for all stations under Settings → Playback and can
+//! be set apart for the playing station from its diagnostics panel. They
+//! are applied as an mpv audio filter, so they do not reach cast devices.
+
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// Allowed balances, from left only to right only
+pub const BALANCE_RANGE: RangeInclusive<i8> = -100..=100;
+
+/// Channel settings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelConfig {
+    /// Downmix to mono, played on both speakers
+    #[serde(default)]
+    pub mono: bool,
+    /// -100 (left only) to 100 (right only); 0 is centered
+    #[serde(default)]
+    pub balance: i8,
+}
+
+impl ChannelConfig {
+    /// lavfi filter graph applying the settings, if they change anything
+    #[must_use]
+    pub fn filter(&self) -> Option<String> {
+        let balance = clamp_balance(self.balance);
+        if !self.mono && balance == 0 {
+            return None;
+        }
+        let balance = f32::from(balance) / 100.0;
+        let left = 1.0 - balance.max(0.0);
+        let right = 1.0 + balance.min(0.0);
+        let (c0, c1) = if self.mono {
+            (
+                format!("{:.2}*c0+{:.2}*c1", left / 2.0, left / 2.0),
+                format!("{:.2}*c0+{:.2}*c1", right / 2.0, right / 2.0),
+            )
+        } else {
+            (format!("{left:.2}*c0"), format!("{right:.2}*c1"))
+        };
+        // Mono streams are made stereo first, so both channels exist
+        Some(format!(
+            "aformat=channel_layouts=stereo,pan=stereo|c0={c0}|c1={c1}"
+        ))
+    }
+}
+
+/// Channel settings of one station, used instead of the global ones
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StationChannels {
+    pub stationuuid: String,
+    #[serde(default)]
+    pub channels: ChannelConfig,
+}
+
+/// The station's own settings, if it has them
+#[must_use]
+pub fn station(stations: &[StationChannels], stationuuid: &str) -> Option<ChannelConfig> {
+    stations
+        .iter()
+        .find(|s| s.stationuuid == stationuuid)
+        .map(|s| s.channels)
+}
+
+/// Settings to play a station with: its own, else `global`
+#[must_use]
+pub fn for_station(
+    global: ChannelConfig,
+    stations: &[StationChannels],
+    stationuuid: &str,
+) -> ChannelConfig {
+    station(stations, stationuuid).unwrap_or(global)
+}
+
+/// Store a station's own settings, or drop them for `None`
+pub fn set(
+    stations: &mut Vec<StationChannels>,
+    stationuuid: &str,
+    channels: Option<ChannelConfig>,
+) {
+    stations.retain(|s| s.stationuuid != stationuuid);
+    if let Some(channels) = channels {
+        stations.push(StationChannels {
+            stationuuid: stationuuid.to_string(),
+            channels,
+        });
+    }
+}
+
+/// Clamp a balance to [`BALANCE_RANGE`]
+#[must_use]
+pub fn clamp_balance(balance: i8) -> i8 {
+    balance.clamp(*BALANCE_RANGE.start(), *BALANCE_RANGE.end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        assert_eq!(ChannelConfig::default().filter(), None);
+        let mono = ChannelConfig {
+            mono: true,
+            balance: 0,
+        };
+        assert_eq!(
+            mono.filter().as_deref(),
+            Some("aformat=channel_layouts=stereo,pan=stereo|c0=0.50*c0+0.50*c1|c1=0.50*c0+0.50*c1")
+        );
+        let right = ChannelConfig {
+            mono: false,
+            balance: 40,
+        };
+        assert_eq!(
+            right.filter().as_deref(),
+            Some("aformat=channel_layouts=stereo,pan=stereo|c0=0.60*c0|c1=1.00*c1")
+        );
+    }
+
+    #[test]
+    fn test_station_overrides() {
+        let global = ChannelConfig {
+            mono: false,
+            balance: -20,
+        };
+        let mut stations = Vec::new();
+        assert_eq!(for_station(global, &stations, "a"), global);
+
+        let own = ChannelConfig {
+            mono: true,
+            balance: 0,
+        };
+        set(&mut stations, "a", Some(own));
+        assert_eq!(for_station(global, &stations, "a"), own);
+        assert_eq!(for_station(global, &stations, "b"), global);
+
+        set(&mut stations, "a", None);
+        assert!(stations.is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+//! Casting to Chromecast devices
+//!
+//! Chromecasts announce themselves via mDNS as `_googlecast._tcp`, with
+//! their friendly name in the `fn` TXT record. Playback is driven over
+//! CASTV2: the Default Media Receiver app is launched and given the stream
+//! URL, which the device then fetches itself. The device keeps playing
+//! after the connection is closed, so each call connects anew.
+//!
+//! Needs the `chromecast` Cargo feature (on by default). Without it no
+//! Chromecasts are found and DLNA renderers are the only cast targets.
+#![cfg_attr(not(feature = "chromecast"), allow(dead_code, unused_imports))]
+
+use crate::cast::{CastDevice, Renderer, DISCOVERY_WINDOW};
+#[cfg(feature = "chromecast")]
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+#[cfg(feature = "chromecast")]
+use rust_cast::channels::media::{Media, StreamType};
+#[cfg(feature = "chromecast")]
+use rust_cast::channels::receiver::CastDeviceApp;
+use std::net::SocketAddr;
+#[cfg(feature = "chromecast")]
+use tracing::debug;
+
+/// mDNS service type of Chromecasts
+const SERVICE: &str = "_googlecast._tcp.local.";
+
+/// Receiver platform every CASTV2 connection starts with
+const RECEIVER: &str = "receiver-0";
+
+/// App ID of the Default Media Receiver
+const DEFAULT_MEDIA_RECEIVER: &str = "CC1AD845";
+
+/// MIME type announced for streams; the device sniffs the real one
+const CONTENT_TYPE: &str = "audio/mpeg";
+
+/// Name to show for a device: its friendly name, else the instance part of
+/// its mDNS name
+fn device_name(friendly: Option<&str>, fullname: &str) -> String {
+    friendly
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| {
+            fullname
+                .strip_suffix(SERVICE)
+                .unwrap_or(fullname)
+                .trim_end_matches('.')
+        })
+        .to_string()
+}
+
+#[cfg(feature = "chromecast")]
+fn device(info: &ServiceInfo) -> Option<CastDevice> {
+    // IPv4 first: not every device answers on its link-local IPv6 address
+    let ip = info.get_addresses().iter().min_by_key(|ip| ip.is_ipv6())?;
+    Some(CastDevice {
+        name: device_name(info.get_property_val_str("fn"), info.get_fullname()),
+        renderer: Renderer::Chromecast(SocketAddr::new(*ip, info.get_port())),
+    })
+}
+
+/// Discover Chromecasts for [`DISCOVERY_WINDOW`]
+#[cfg(feature = "chromecast")]
+pub async fn discover() -> Result<Vec<CastDevice>, String> {
+    let daemon = ServiceDaemon::new().map_err(|e| e.to_string())?;
+    let events = daemon.browse(SERVICE).map_err(|e| e.to_string())?;
+    let mut devices = Vec::new();
+    let deadline = tokio::time::Instant::now() + DISCOVERY_WINDOW;
+    while let Ok(Ok(event)) = tokio::time::timeout_at(deadline, events.recv_async()).await {
+        if let ServiceEvent::ServiceResolved(info) = event {
+            if let Some(device) = device(&info).filter(|d| !devices.contains(d)) {
+                debug!("Found Chromecast {}", device.name);
+                devices.push(device);
+            }
+        }
+    }
+    let _ = daemon.shutdown();
+    Ok(devices)
+}
+
+#[cfg(not(feature = "chromecast"))]
+pub async fn discover() -> Result<Vec<CastDevice>, String> {
+    Ok(Vec::new())
+}
+
+/// Run a CASTV2 session, which blocks, off the async runtime
+#[cfg(feature = "chromecast")]
+async fn session<T: Send + 'static>(
+    address: SocketAddr,
+    f: impl FnOnce(&rust_cast::CastDevice<'static>) -> Result<T, rust_cast::errors::Error>
+        + Send
+        + 'static,
+) -> Result<T, String> {
+    tokio::task::spawn_blocking(move || {
+        let device = rust_cast::CastDevice::connect_without_host_verification(
+            address.ip().to_string(),
+            address.port(),
+        )?;
+        device.connection.connect(RECEIVER)?;
+        f(&device)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Launch the Default Media Receiver and have it play `url`
+#[cfg(feature = "chromecast")]
+pub async fn play(address: SocketAddr, title: String, url: String) -> Result<(), String> {
+    session(address, move |device| {
+        let app = device
+            .receiver
+            .launch_app(&CastDeviceApp::DefaultMediaReceiver)?;
+        device.connection.connect(app.transport_id.as_str())?;
+        device.media.load(
+            app.transport_id.as_str(),
+            app.session_id.as_str(),
+            &Media {
+                content_id: url,
+                content_type: CONTENT_TYPE.to_string(),
+                stream_type: StreamType::Live,
+                duration: None,
+                metadata: None,
+            },
+        )?;
+        debug!("Chromecast at {} plays {}", address, title);
+        Ok(())
+    })
+    .await
+}
+
+/// Stop the Default Media Receiver, leaving other apps on the device alone
+#[cfg(feature = "chromecast")]
+pub async fn stop(address: SocketAddr) -> Result<(), String> {
+    session(address, |device| {
+        let status = device.receiver.get_status()?;
+        for app in status
+            .applications
+            .iter()
+            .filter(|app| app.app_id == DEFAULT_MEDIA_RECEIVER)
+        {
+            device.receiver.stop_app(app.session_id.as_str())?;
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Set the device volume (0-100)
+#[cfg(feature = "chromecast")]
+pub async fn set_volume(address: SocketAddr, volume: u8) -> Result<(), String> {
+    let level = f32::from(volume.min(100)) / 100.0;
+    session(address, move |device| {
+        device.receiver.set_volume(level).map(|_| ())
+    })
+    .await
+}
+
+#[cfg(not(feature = "chromecast"))]
+pub async fn play(_address: SocketAddr, _title: String, _url: String) -> Result<(), String> {
+    Err("Built without the chromecast feature".to_string())
+}
+
+#[cfg(not(feature = "chromecast"))]
+pub async fn stop(_address: SocketAddr) -> Result<(), String> {
+    Err("Built without the chromecast feature".to_string())
+}
+
+#[cfg(not(feature = "chromecast"))]
+pub async fn set_volume(_address: SocketAddr, _volume: u8) -> Result<(), String> {
+    Err("Built without the chromecast feature".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_name() {
+        assert_eq!(
+            device_name(
+                Some("Living Room speaker"),
+                "Google-Home-1a2b._googlecast._tcp.local."
+            ),
+            "Living Room speaker"
+        );
+        assert_eq!(
+            device_name(None, "Google-Home-1a2b._googlecast._tcp.local."),
+            "Google-Home-1a2b"
+        );
+        assert_eq!(device_name(Some(" "), "Chromecast"), "Chromecast");
+    }
+}
@@ -0,0 +1,185 @@
+//! What clicks on the panel icon do
+//!
+//! Left click, middle click, and double click each run a configurable
+//! action. By default a left click opens the popup, a middle click plays or
+//! stops, and a double click does nothing. While a double click is mapped,
+//! a single left click waits [`DOUBLE_CLICK_INTERVAL`] for a second one
+//! before running its own action.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Longest pause between the presses of a double click
+pub const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
+/// What a click on the panel icon does
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ClickAction {
+    Nothing,
+    OpenPopup,
+    TogglePlayback,
+    /// Play the favorite after the current one
+    NextPreset,
+    /// Mute, or restore the volume from before muting
+    Mute,
+}
+
+impl ClickAction {
+    pub const ALL: [ClickAction; 5] = [
+        ClickAction::Nothing,
+        ClickAction::OpenPopup,
+        ClickAction::TogglePlayback,
+        ClickAction::NextPreset,
+        ClickAction::Mute,
+    ];
+}
+
+/// Ways of clicking the panel icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickKind {
+    Left,
+    Middle,
+    Double,
+}
+
+impl ClickKind {
+    pub const ALL: [ClickKind; 3] = [ClickKind::Left, ClickKind::Middle, ClickKind::Double];
+}
+
+/// Actions of the panel icon's clicks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClickConfig {
+    #[serde(default = "default_left")]
+    pub left: ClickAction,
+    #[serde(default = "default_middle")]
+    pub middle: ClickAction,
+    #[serde(default = "default_double")]
+    pub double: ClickAction,
+}
+
+fn default_left() -> ClickAction {
+    ClickAction::OpenPopup
+}
+
+fn default_middle() -> ClickAction {
+    ClickAction::TogglePlayback
+}
+
+fn default_double() -> ClickAction {
+    ClickAction::Nothing
+}
+
+impl Default for ClickConfig {
+    fn default() -> Self {
+        Self {
+            left: default_left(),
+            middle: default_middle(),
+            double: default_double(),
+        }
+    }
+}
+
+impl ClickConfig {
+    #[must_use]
+    pub fn action(&self, kind: ClickKind) -> ClickAction {
+        match kind {
+            ClickKind::Left => self.left,
+            ClickKind::Middle => self.middle,
+            ClickKind::Double => self.double,
+        }
+    }
+
+    pub fn set_action(&mut self, kind: ClickKind, action: ClickAction) {
+        match kind {
+            ClickKind::Left => self.left = action,
+            ClickKind::Middle => self.middle = action,
+            ClickKind::Double => self.double = action,
+        }
+    }
+}
+
+/// What a left press turned out to be
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Press {
+    Single,
+    Double,
+    /// Possibly the first of a double click: wait for the timeout with this
+    /// number
+    Wait(u64),
+}
+
+/// Left presses waiting to become single or double clicks
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Clicks {
+    pending: Option<(u64, Instant)>,
+    count: u64,
+}
+
+impl Clicks {
+    /// A left press at `now`; `double_mapped` when double clicks have an
+    /// action
+    pub fn press(&mut self, now: Instant, double_mapped: bool) -> Press {
+        if !double_mapped {
+            return Press::Single;
+        }
+        match self.pending.take() {
+            Some((_, at)) if now.saturating_duration_since(at) <= DOUBLE_CLICK_INTERVAL => {
+                Press::Double
+            }
+            _ => {
+                self.count += 1;
+                self.pending = Some((self.count, now));
+                Press::Wait(self.count)
+            }
+        }
+    }
+
+    /// The wait for a second press ended; true if the press it was started
+    /// for stayed a single click
+    pub fn timeout(&mut self, number: u64) -> bool {
+        if self.pending.is_some_and(|(pending, _)| pending == number) {
+            self.pending = None;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_double_click() {
+        let start = Instant::now();
+        let mut clicks = Clicks::default();
+        assert_eq!(clicks.press(start, false), Press::Single);
+
+        let Press::Wait(first) = clicks.press(start, true) else {
+            panic!("first press should wait");
+        };
+        assert_eq!(
+            clicks.press(start + Duration::from_millis(200), true),
+            Press::Double
+        );
+        // The double click consumed the first press
+        assert!(!clicks.timeout(first));
+
+        let Press::Wait(single) = clicks.press(start + Duration::from_secs(2), true) else {
+            panic!("press should wait");
+        };
+        assert!(clicks.timeout(single));
+    }
+
+    #[test]
+    fn test_slow_presses_are_single() {
+        let start = Instant::now();
+        let mut clicks = Clicks::default();
+        let Press::Wait(first) = clicks.press(start, true) else {
+            panic!("first press should wait");
+        };
+        let late = start + DOUBLE_CLICK_INTERVAL + Duration::from_millis(1);
+        assert!(matches!(clicks.press(late, true), Press::Wait(second) if second != first));
+    }
+}
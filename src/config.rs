@@ -1,4 +1,34 @@
-use crate::api::Station;
+use crate::api::{Station, DEFAULT_SEARCH_LIMIT, DEFAULT_TIMEOUT_SECS};
+use crate::audio::{AudioOutput, DEFAULT_SNAPCAST_FIFO};
+use crate::autopause::AutoPauseConfig;
+use crate::bookmarks::SongBookmark;
+use crate::cache;
+use crate::channels::{ChannelConfig, StationChannels};
+use crate::clicks::ClickConfig;
+use crate::control::ControlConfig;
+use crate::downloads;
+use crate::ducking::DuckingConfig;
+use crate::filter::HiddenStation;
+use crate::hooks::HooksConfig;
+use crate::icons::IconStyle;
+use crate::ipfamily::IpPreference;
+use crate::locale::{CountryFilter, LanguageFilter};
+use crate::logging::LogLevel;
+use crate::loudness::LoudnessConfig;
+use crate::mqtt::MqttConfig;
+use crate::outputs::DevicePauseConfig;
+use crate::popularity::SortOrder;
+use crate::providers::ProviderConfig;
+use crate::quality::StreamPreference;
+use crate::queue;
+use crate::quiet::QuietHours;
+use crate::recorder::{self, RecordingFormat};
+use crate::reliability::StationReliability;
+use crate::resume::ResumePosition;
+use crate::schedule::{RecordingRule, Show, SmartPreset};
+use crate::ticker::TickerConfig;
+use crate::tls::StationTls;
+use crate::wheel::ScrollConfig;
 use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +39,196 @@ pub struct Config {
     pub favorites: Vec<Station>,
     #[serde(default)]
     pub volume: u8, // 0-100
+    /// Additional station providers queried alongside radio-browser.info
+    #[serde(default)]
+    pub providers: Vec<ProviderConfig>,
+    /// Optional MQTT state publishing for home automation
+    #[serde(default)]
+    pub mqtt: MqttConfig,
+    /// File or FIFO receiving the now-playing line (empty = disabled)
+    #[serde(default)]
+    pub now_playing_file: String,
+    /// Pause while other media players are playing
+    #[serde(default)]
+    pub auto_pause: AutoPauseConfig,
+    /// Lower the volume during calls and notification sounds
+    #[serde(default)]
+    pub ducking: DuckingConfig,
+    /// Pause when the output device disappears
+    #[serde(default)]
+    pub device_pause: DevicePauseConfig,
+    /// Hours with a volume cap and no notifications
+    #[serde(default)]
+    pub quiet_hours: QuietHours,
+    /// Audio output driver for mpv
+    #[serde(default)]
+    pub audio_output: AudioOutput,
+    /// Sink or device for the output driver (empty = system default)
+    #[serde(default)]
+    pub audio_device: String,
+    /// Snapcast pipe source written by the `Snapcast` output
+    #[serde(default = "default_snapcast_fifo")]
+    pub snapcast_fifo: String,
+    /// How mpv evens out the volume of streams
+    #[serde(default)]
+    pub loudness: LoudnessConfig,
+    /// Mono downmix and balance of all stations
+    #[serde(default)]
+    pub channels: ChannelConfig,
+    /// Names of the extra profiles; each has its own favorites and volume
+    #[serde(default)]
+    pub profiles: Vec<String>,
+    /// Profile in use (empty = default profile stored in this config)
+    #[serde(default)]
+    pub active_profile: String,
+    /// Keywords hiding matching stations (by tag or name) from search results
+    #[serde(default)]
+    pub blocked_keywords: Vec<String>,
+    /// Stations hidden from all search results
+    #[serde(default)]
+    pub hidden_stations: Vec<HiddenStation>,
+    /// Stations with relaxed certificate checks or HTTPS upgrades
+    #[serde(default)]
+    pub station_tls: Vec<StationTls>,
+    /// Stations with their own mono and balance settings
+    #[serde(default)]
+    pub station_channels: Vec<StationChannels>,
+    /// Recent connection attempts per station, for reliability scores
+    #[serde(default)]
+    pub reliability: Vec<StationReliability>,
+    /// Where podcast episodes and archived shows were left
+    #[serde(default)]
+    pub resume_positions: Vec<ResumePosition>,
+    /// Timeout for directory and provider requests, in seconds
+    #[serde(default = "default_request_timeout")]
+    pub request_timeout_secs: u32,
+    /// Maximum number of results per search and provider
+    #[serde(default = "default_search_limit")]
+    pub search_limit: u32,
+    /// Address family to connect to plain HTTP streams over
+    #[serde(default)]
+    pub ip_preference: IpPreference,
+    /// Size limit for the on-disk cache, in megabytes
+    #[serde(default = "default_cache_max_mb")]
+    pub cache_max_mb: u32,
+    /// Space downloaded episodes may take up, in megabytes
+    #[serde(default = "default_download_quota_mb")]
+    pub download_quota_mb: u32,
+    /// Days recordings are kept before being deleted at startup (0 = keep)
+    #[serde(default)]
+    pub recording_retention_days: u32,
+    /// Whether recordings keep the stream as received or are re-encoded
+    #[serde(default)]
+    pub recording_format: RecordingFormat,
+    /// Bitrate of re-encoded recordings, in kbit/s
+    #[serde(default = "default_recording_bitrate")]
+    pub recording_bitrate: u32,
+    /// Verbosity of the log files
+    #[serde(default)]
+    pub log_level: LogLevel,
+    /// Country whose stations come first in searches and suggestions
+    #[serde(default)]
+    pub country: CountryFilter,
+    /// Languages of the stations on the start and Discover pages
+    #[serde(default)]
+    pub language_filter: LanguageFilter,
+    /// Resume the last station after the applet or panel restarts
+    #[serde(default)]
+    pub resume_playback: bool,
+    /// Look of the panel icon
+    #[serde(default)]
+    pub icon_style: IconStyle,
+    /// Volume by scrolling over the panel icon
+    #[serde(default)]
+    pub panel_scroll: ScrollConfig,
+    /// What clicks on the panel icon do
+    #[serde(default)]
+    pub panel_clicks: ClickConfig,
+    /// Scrolling now-playing text next to the panel icon
+    #[serde(default)]
+    pub ticker: TickerConfig,
+    /// Stations already voted for on radio-browser.info
+    #[serde(default)]
+    pub voted_stations: Vec<String>,
+    /// Order of search results
+    #[serde(default)]
+    pub sort_order: SortOrder,
+    /// Which variant to play of a station that is listed several times
+    #[serde(default)]
+    pub stream_preference: StreamPreference,
+    /// Look up album art for ICY titles on MusicBrainz and the Cover Art Archive
+    #[serde(default)]
+    pub fetch_cover_art: bool,
+    /// Seconds each queued station plays before the next; 0 waits for "Next"
+    #[serde(default = "default_queue_dwell")]
+    pub queue_dwell_secs: u32,
+    /// Show station logos in the lists, downloaded from the directory's favicon URLs
+    #[serde(default)]
+    pub show_favicons: bool,
+    /// Look up lyrics for ICY titles on LRCLIB and show them under the now-playing row
+    #[serde(default)]
+    pub show_lyrics: bool,
+    /// Draw the level of the playing stream as bars in the now-playing area
+    #[serde(default)]
+    pub show_visualizer: bool,
+    /// Songs saved with "I like this track", oldest first
+    #[serde(default)]
+    pub bookmarks: Vec<SongBookmark>,
+    /// Weekly shows of favorite stations, for "on air" info and reminders
+    #[serde(default)]
+    pub schedule: Vec<Show>,
+    /// Minutes before a scheduled show to send a reminder (0 = no reminders)
+    #[serde(default)]
+    pub show_reminder_mins: u32,
+    /// Station the play button starts depending on the time of day
+    #[serde(default)]
+    pub smart_preset: SmartPreset,
+    /// Weekly windows in which favorites are recorded
+    #[serde(default)]
+    pub recording_schedule: Vec<RecordingRule>,
+    /// Commands run on playback events
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    /// Local HTTP control endpoint
+    #[serde(default)]
+    pub control: ControlConfig,
+    /// Keep no listening history or track logs
+    #[serde(default)]
+    pub disable_history: bool,
+    /// Unix time favorites were last looked up in the directory
+    #[serde(default)]
+    pub favorites_refreshed_at: u64,
+    /// Lock mode: only favorites can be played
+    #[serde(default)]
+    pub kiosk_locked: bool,
+}
+
+fn default_snapcast_fifo() -> String {
+    DEFAULT_SNAPCAST_FIFO.to_string()
+}
+
+fn default_request_timeout() -> u32 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+fn default_queue_dwell() -> u32 {
+    queue::DEFAULT_DWELL_SECS
+}
+
+fn default_search_limit() -> u32 {
+    DEFAULT_SEARCH_LIMIT
+}
+
+fn default_cache_max_mb() -> u32 {
+    cache::DEFAULT_MAX_MB
+}
+
+fn default_download_quota_mb() -> u32 {
+    downloads::DEFAULT_QUOTA_MB
+}
+
+fn default_recording_bitrate() -> u32 {
+    recorder::DEFAULT_BITRATE
 }
 
 impl Default for Config {
@@ -16,6 +236,60 @@ impl Default for Config {
         Self {
             favorites: Vec::new(),
             volume: 50,
+            providers: Vec::new(),
+            mqtt: MqttConfig::default(),
+            now_playing_file: String::new(),
+            auto_pause: AutoPauseConfig::default(),
+            ducking: DuckingConfig::default(),
+            device_pause: DevicePauseConfig::default(),
+            quiet_hours: QuietHours::default(),
+            audio_output: AudioOutput::Auto,
+            audio_device: String::new(),
+            snapcast_fifo: default_snapcast_fifo(),
+            loudness: LoudnessConfig::default(),
+            channels: ChannelConfig::default(),
+            profiles: Vec::new(),
+            active_profile: String::new(),
+            blocked_keywords: Vec::new(),
+            hidden_stations: Vec::new(),
+            station_tls: Vec::new(),
+            station_channels: Vec::new(),
+            reliability: Vec::new(),
+            resume_positions: Vec::new(),
+            request_timeout_secs: default_request_timeout(),
+            search_limit: default_search_limit(),
+            ip_preference: IpPreference::default(),
+            cache_max_mb: default_cache_max_mb(),
+            download_quota_mb: default_download_quota_mb(),
+            recording_retention_days: 0,
+            recording_format: RecordingFormat::default(),
+            recording_bitrate: default_recording_bitrate(),
+            log_level: LogLevel::default(),
+            country: CountryFilter::default(),
+            language_filter: LanguageFilter::default(),
+            resume_playback: false,
+            icon_style: IconStyle::default(),
+            panel_scroll: ScrollConfig::default(),
+            panel_clicks: ClickConfig::default(),
+            ticker: TickerConfig::default(),
+            voted_stations: Vec::new(),
+            sort_order: SortOrder::default(),
+            stream_preference: StreamPreference::default(),
+            fetch_cover_art: false,
+            queue_dwell_secs: queue::DEFAULT_DWELL_SECS,
+            show_favicons: false,
+            show_lyrics: false,
+            show_visualizer: false,
+            bookmarks: Vec::new(),
+            schedule: Vec::new(),
+            smart_preset: SmartPreset::default(),
+            recording_schedule: Vec::new(),
+            show_reminder_mins: 0,
+            hooks: HooksConfig::default(),
+            control: ControlConfig::default(),
+            disable_history: false,
+            favorites_refreshed_at: 0,
+            kiosk_locked: false,
         }
     }
 }
@@ -29,6 +303,10 @@ mod tests {
         let config = Config::default();
         assert_eq!(config.favorites.len(), 0);
         assert_eq!(config.volume, 50);
+        assert!(config.providers.is_empty());
+        assert!(!config.mqtt.enabled);
+        assert!(config.audio_device.is_empty());
+        assert_eq!(config.snapcast_fifo, "/tmp/snapfifo");
     }
 
     #[test]
@@ -55,6 +333,7 @@ mod tests {
         let config = Config {
             favorites: vec![station.clone()],
             volume: 75,
+            ..Default::default()
         };
 
         assert_eq!(config.favorites.len(), 1);
@@ -71,6 +350,7 @@ mod tests {
         let config1 = Config {
             favorites: vec![station],
             volume: 60,
+            ..Default::default()
         };
         let config2 = config1.clone();
 
@@ -87,14 +367,17 @@ mod tests {
         let config1 = Config {
             favorites: vec![station.clone()],
             volume: 50,
+            ..Default::default()
         };
         let config2 = Config {
             favorites: vec![station],
             volume: 50,
+            ..Default::default()
         };
         let config3 = Config {
             favorites: vec![],
             volume: 50,
+            ..Default::default()
         };
 
         assert_eq!(config1, config2);
@@ -112,6 +395,7 @@ mod tests {
         let config = Config {
             favorites: vec![station],
             volume: 80,
+            ..Default::default()
         };
 
         let serialized = serde_json::to_string(&config).unwrap();
@@ -125,10 +409,12 @@ mod tests {
         let config_min = Config {
             favorites: vec![],
             volume: 0,
+            ..Default::default()
         };
         let config_max = Config {
             favorites: vec![],
             volume: 100,
+            ..Default::default()
         };
 
         assert_eq!(config_min.volume, 0);
@@ -153,6 +439,7 @@ mod tests {
         let config = Config {
             favorites: vec![station1, station2, station3],
             volume: 50,
+            ..Default::default()
         };
 
         assert_eq!(config.favorites.len(), 3);
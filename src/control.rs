@@ -0,0 +1,531 @@
+//! Local HTTP control endpoint
+//!
+//! When enabled, a small HTTP server on `127.0.0.1` lets tools that cannot
+//! talk D-Bus (Stream Deck plugins, browser extensions, shell scripts with
+//! `curl`) control playback. Every request needs the token from the keyring
+//! in an `Authorization: Bearer <token>` header; it is generated on first use
+//! and can be copied from the settings.
+//!
+//! Routes (JSON responses):
+//! - `GET /status` — playback state, station, track, and volume
+//! - `GET /favorites` — `uuid` and `name` of each favorite
+//! - `POST /play` — resume the last station, or play the favorite given as
+//!   `{"uuid": "..."}`
+//! - `POST /stop`
+//!
+//! ```text
+//! curl -H "Authorization: Bearer $TOKEN" http://127.0.0.1:8989/status
+//! ```
+
+use crate::api::Station;
+use crate::secrets;
+use futures::SinkExt;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+/// Largest request head accepted
+const MAX_HEAD: usize = 8 * 1024;
+
+/// Largest request body accepted
+const MAX_BODY: usize = 4 * 1024;
+
+/// Time a client gets to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Control endpoint settings
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ControlConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_port")]
+    pub port: u16,
+}
+
+fn default_port() -> u16 {
+    8989
+}
+
+impl Default for ControlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_port(),
+        }
+    }
+}
+
+/// A station as listed by the endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub struct StationRef {
+    pub uuid: String,
+    pub name: String,
+}
+
+impl From<&Station> for StationRef {
+    fn from(station: &Station) -> Self {
+        Self {
+            uuid: station.stationuuid.clone(),
+            name: station.name.clone(),
+        }
+    }
+}
+
+/// Snapshot of the player state served to clients
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ControlState {
+    pub playing: bool,
+    pub station: Option<StationRef>,
+    pub track: Option<String>,
+    pub volume: u8,
+    pub favorites: Vec<StationRef>,
+}
+
+/// Commands received from clients
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// Play the favorite with this UUID, or resume the last station
+    Play(Option<String>),
+    Stop,
+}
+
+/// Events yielded by the control subscription
+#[derive(Debug, Clone)]
+pub enum ControlEvent {
+    /// The server is listening; carries the state channel and the token
+    Ready(mpsc::UnboundedSender<ControlState>, String),
+    Command(ControlCommand),
+}
+
+/// Request line and the headers the endpoint cares about
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct Request {
+    method: String,
+    /// Path without the query string
+    path: String,
+    token: Option<String>,
+    content_length: usize,
+}
+
+/// Body of `POST /play`
+#[derive(Debug, Deserialize)]
+struct PlayRequest {
+    uuid: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn json(status: u16, body: &serde_json::Value) -> Self {
+        Self {
+            status,
+            body: body.to_string(),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &serde_json::json!({ "error": message }))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let reason = match self.status {
+            200 => "OK",
+            202 => "Accepted",
+            204 => "No Content",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            _ => "Error",
+        };
+        // Browser extensions send the token from their own origin
+        let mut head = format!(
+            "HTTP/1.1 {} {reason}\r\n\
+             Access-Control-Allow-Origin: *\r\n\
+             Access-Control-Allow-Headers: Authorization, Content-Type\r\n\
+             Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+             Connection: close\r\n",
+            self.status
+        );
+        if self.status == 204 {
+            head.push_str("\r\n");
+            return head.into_bytes();
+        }
+        head.push_str(&format!(
+            "Content-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+            self.body.len()
+        ));
+        let mut bytes = head.into_bytes();
+        bytes.extend_from_slice(self.body.as_bytes());
+        bytes
+    }
+}
+
+/// Parse the request line and headers, without the blank line ending them
+fn parse_head(head: &str) -> Option<Request> {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split(' ');
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?;
+    let mut request = Request {
+        method,
+        path: target.split('?').next().unwrap_or_default().to_string(),
+        ..Request::default()
+    };
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("authorization") {
+            request.token = value.strip_prefix("Bearer ").map(|t| t.trim().to_string());
+        } else if name.eq_ignore_ascii_case("content-length") {
+            request.content_length = value.parse().ok()?;
+        }
+    }
+    Some(request)
+}
+
+/// Compare tokens in time independent of where they differ
+#[must_use]
+pub fn token_matches(given: &str, token: &str) -> bool {
+    !token.is_empty()
+        && given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Answer a request, and the command it asks for, if any
+fn route(
+    request: &Request,
+    body: &[u8],
+    token: &str,
+    state: &ControlState,
+) -> (Response, Option<ControlCommand>) {
+    // CORS preflight requests carry no credentials
+    if request.method == "OPTIONS" {
+        return (Response::json(204, &serde_json::Value::Null), None);
+    }
+    if !request
+        .token
+        .as_deref()
+        .is_some_and(|given| token_matches(given, token))
+    {
+        return (Response::error(401, "invalid token"), None);
+    }
+
+    let accepted = || Response::json(202, &serde_json::json!({ "accepted": true }));
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/status") => {
+            let status = serde_json::json!({
+                "state": if state.playing { "playing" } else { "stopped" },
+                "station": state.station,
+                "track": state.track,
+                "volume": state.volume,
+            });
+            (Response::json(200, &status), None)
+        }
+        ("GET", "/favorites") => (
+            Response::json(200, &serde_json::json!(state.favorites)),
+            None,
+        ),
+        ("POST", "/play") => {
+            if body.iter().all(u8::is_ascii_whitespace) {
+                return (accepted(), Some(ControlCommand::Play(None)));
+            }
+            match serde_json::from_slice::<PlayRequest>(body) {
+                Ok(play) if state.favorites.iter().any(|f| f.uuid == play.uuid) => {
+                    (accepted(), Some(ControlCommand::Play(Some(play.uuid))))
+                }
+                Ok(_) => (Response::error(404, "not a favorite"), None),
+                Err(e) => (Response::error(400, &e.to_string()), None),
+            }
+        }
+        ("POST", "/stop") => (accepted(), Some(ControlCommand::Stop)),
+        (_, "/status" | "/favorites" | "/play" | "/stop") => {
+            (Response::error(405, "method not allowed"), None)
+        }
+        _ => (Response::error(404, "not found"), None),
+    }
+}
+
+/// Read one request; `None` if it is malformed or too large
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Option<(Request, Vec<u8>)>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0; 1024];
+    let head_len = loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end;
+        }
+        if buf.len() > MAX_HEAD {
+            return Ok(None);
+        }
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    };
+    let Some(request) = std::str::from_utf8(&buf[..head_len])
+        .ok()
+        .and_then(parse_head)
+    else {
+        return Ok(None);
+    };
+    if request.content_length > MAX_BODY {
+        return Ok(None);
+    }
+    let mut body = buf.split_off(head_len + 4);
+    while body.len() < request.content_length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(request.content_length);
+    Ok(Some((request, body)))
+}
+
+/// Answer one connection
+async fn serve(
+    mut stream: TcpStream,
+    token: &str,
+    state: &ControlState,
+) -> std::io::Result<Option<ControlCommand>> {
+    let (response, command) = match read_request(&mut stream).await? {
+        Some((request, body)) => {
+            debug!("Control request {} {}", request.method, request.path);
+            route(&request, &body, token, state)
+        }
+        None => (Response::error(400, "malformed request"), None),
+    };
+    stream.write_all(&response.to_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(command)
+}
+
+/// 128 random bits, hex encoded
+fn new_token() -> std::io::Result<String> {
+    let mut bytes = [0u8; 16];
+    std::fs::File::open("/dev/urandom")?.read_exact(&mut bytes)?;
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// The token from the keyring, created on first use. Without a keyring the
+/// token only lasts until the applet restarts.
+async fn load_token() -> std::io::Result<String> {
+    match secrets::lookup(secrets::CONTROL_TOKEN_KEY).await {
+        Ok(Some(token)) if !token.is_empty() => return Ok(token),
+        Ok(_) => {}
+        Err(e) => {
+            warn!("Keyring unavailable, control token is temporary: {}", e);
+            return new_token();
+        }
+    }
+    let token = new_token()?;
+    if let Err(e) = secrets::store(secrets::CONTROL_TOKEN_KEY, "Radio control token", &token).await
+    {
+        warn!("Failed to store control token in keyring: {}", e);
+    }
+    Ok(token)
+}
+
+/// Create an iced Subscription that serves the control endpoint.
+///
+/// The subscription is keyed by its port, so changing it restarts the server.
+/// Requests are answered one at a time; each is small, and a slow client is
+/// cut off after [`REQUEST_TIMEOUT`].
+pub fn control_subscription(port: u16) -> cosmic::iced::Subscription<ControlEvent> {
+    cosmic::iced::Subscription::run_with_id(
+        ("control", port),
+        cosmic::iced::stream::channel(32, move |mut output| async move {
+            let token = match load_token().await {
+                Ok(token) => token,
+                Err(e) => {
+                    warn!("Cannot create control token: {}", e);
+                    return;
+                }
+            };
+            let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    warn!("Cannot listen on 127.0.0.1:{}: {}", port, e);
+                    return;
+                }
+            };
+            info!("Control endpoint listening on 127.0.0.1:{}", port);
+
+            let (state_tx, mut state_rx) = mpsc::unbounded_channel::<ControlState>();
+            if output
+                .send(ControlEvent::Ready(state_tx, token.clone()))
+                .await
+                .is_err()
+            {
+                return;
+            }
+
+            let mut state = ControlState::default();
+            loop {
+                tokio::select! {
+                    update = state_rx.recv() => {
+                        let Some(update) = update else { break };
+                        state = update;
+                    }
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => {
+                            match tokio::time::timeout(REQUEST_TIMEOUT, serve(stream, &token, &state)).await {
+                                Ok(Ok(Some(command))) => {
+                                    if output.send(ControlEvent::Command(command)).await.is_err() {
+                                        break;
+                                    }
+                                }
+                                Ok(Ok(None)) => {}
+                                Ok(Err(e)) => debug!("Control connection failed: {}", e),
+                                Err(_) => debug!("Control request timed out"),
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Control endpoint accept failed: {}", e);
+                            tokio::time::sleep(Duration::from_secs(1)).await;
+                        }
+                    }
+                }
+            }
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> ControlState {
+        ControlState {
+            playing: true,
+            station: Some(StationRef {
+                uuid: "jazz".to_string(),
+                name: "Jazz FM".to_string(),
+            }),
+            track: Some("Miles Davis - So What".to_string()),
+            volume: 40,
+            favorites: vec![StationRef {
+                uuid: "jazz".to_string(),
+                name: "Jazz FM".to_string(),
+            }],
+        }
+    }
+
+    fn request(method: &str, path: &str, token: Option<&str>) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            token: token.map(str::to_string),
+            content_length: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_head() {
+        let request = parse_head(
+            "POST /play?src=deck HTTP/1.1\r\nHost: 127.0.0.1\r\n\
+             authorization: Bearer abc123\r\nContent-Length: 16",
+        )
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/play");
+        assert_eq!(request.token.as_deref(), Some("abc123"));
+        assert_eq!(request.content_length, 16);
+
+        assert!(parse_head("GET /status HTTP/1.1\r\nContent-Length: lots").is_none());
+        assert!(parse_head("").is_none());
+    }
+
+    #[test]
+    fn test_token_matches() {
+        assert!(token_matches("abc123", "abc123"));
+        assert!(!token_matches("abc124", "abc123"));
+        assert!(!token_matches("abc", "abc123"));
+        assert!(!token_matches("", ""));
+    }
+
+    #[test]
+    fn test_route_requires_token() {
+        let (response, command) = route(&request("POST", "/stop", None), b"", "t0k", &state());
+        assert_eq!(response.status, 401);
+        assert!(command.is_none());
+
+        let wrong = request("GET", "/status", Some("nope"));
+        assert_eq!(route(&wrong, b"", "t0k", &state()).0.status, 401);
+
+        let preflight = request("OPTIONS", "/play", None);
+        assert_eq!(route(&preflight, b"", "t0k", &state()).0.status, 204);
+    }
+
+    #[test]
+    fn test_route_status_and_favorites() {
+        let get = |path| route(&request("GET", path, Some("t0k")), b"", "t0k", &state()).0;
+        let response = get("/status");
+        let status: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(status["state"], "playing");
+        assert_eq!(status["station"]["name"], "Jazz FM");
+        assert_eq!(status["volume"], 40);
+
+        assert_eq!(
+            get("/favorites").body,
+            r#"[{"uuid":"jazz","name":"Jazz FM"}]"#
+        );
+    }
+
+    #[test]
+    fn test_route_commands() {
+        let play = request("POST", "/play", Some("t0k"));
+        assert_eq!(
+            route(&play, b"", "t0k", &state()).1,
+            Some(ControlCommand::Play(None))
+        );
+        assert_eq!(
+            route(&play, br#"{"uuid": "jazz"}"#, "t0k", &state()).1,
+            Some(ControlCommand::Play(Some("jazz".to_string())))
+        );
+        let status = |body: &[u8]| route(&play, body, "t0k", &state()).0.status;
+        assert_eq!(status(br#"{"uuid": "rock"}"#), 404);
+        assert_eq!(status(b"{"), 400);
+
+        let stop = request("POST", "/stop", Some("t0k"));
+        assert_eq!(
+            route(&stop, b"", "t0k", &state()).1,
+            Some(ControlCommand::Stop)
+        );
+        let get_stop = request("GET", "/stop", Some("t0k"));
+        assert_eq!(route(&get_stop, b"", "t0k", &state()).0.status, 405);
+    }
+
+    #[test]
+    fn test_response_bytes() {
+        let bytes = Response::error(404, "not found").to_bytes();
+        let text = String::from_utf8(bytes).unwrap();
+        assert!(text.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(text.contains("Content-Length: 21\r\n"));
+        assert!(text.ends_with("\r\n\r\n{\"error\":\"not found\"}"));
+    }
+
+    #[test]
+    fn test_config_serde() {
+        let config: ControlConfig = serde_json::from_str(r#"{"enabled": true}"#).unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.port, 8989);
+    }
+}
@@ -0,0 +1,211 @@
+//! Album art for the current track
+//!
+//! When the ICY title looks like "Artist - Title", the recording is looked up
+//! on MusicBrainz and the front cover of its first release is fetched from
+//! the Cover Art Archive. Covers (and misses, as empty entries) are kept in
+//! the on-disk cache, so a song is looked up only once. MusicBrainz allows
+//! one request per second per client; lookups are spaced accordingly.
+
+use crate::api::http_client;
+use crate::cache::Cache;
+use crate::error::ApiError;
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::debug;
+
+const MUSICBRAINZ_RECORDINGS: &str = "https://musicbrainz.org/ws/2/recording";
+const COVER_ART_ARCHIVE: &str = "https://coverartarchive.org/release";
+
+/// Minimum spacing of MusicBrainz requests
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Covers larger than this are not cached
+const MAX_COVER_BYTES: usize = 2 * 1024 * 1024;
+
+static LAST_REQUEST: LazyLock<Mutex<Option<Instant>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Artist and title parsed from an ICY stream title
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackInfo {
+    pub artist: String,
+    pub title: String,
+}
+
+/// Split an ICY title of the form "Artist - Title"
+#[must_use]
+pub fn parse_track(icy_title: &str) -> Option<TrackInfo> {
+    let (artist, title) = icy_title.split_once(" - ")?;
+    let (artist, title) = (artist.trim(), title.trim());
+    (!artist.is_empty() && !title.is_empty()).then(|| TrackInfo {
+        artist: artist.to_string(),
+        title: title.to_string(),
+    })
+}
+
+/// Cache key of a track's cover
+#[must_use]
+pub fn cache_key(track: &TrackInfo) -> String {
+    format!(
+        "cover:{}|{}",
+        track.artist.to_lowercase(),
+        track.title.to_lowercase()
+    )
+}
+
+/// Lucene query for a recording by artist and title
+fn recording_query(track: &TrackInfo) -> String {
+    let quote = |text: &str| text.replace(['"', '\\'], " ");
+    format!(
+        "recording:\"{}\" AND artist:\"{}\"",
+        quote(&track.title),
+        quote(&track.artist)
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct RecordingSearch {
+    #[serde(default)]
+    recordings: Vec<Recording>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Recording {
+    #[serde(default)]
+    releases: Vec<Release>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    id: String,
+}
+
+/// Release of the best matching recording
+fn first_release(search: &RecordingSearch) -> Option<&str> {
+    search
+        .recordings
+        .iter()
+        .flat_map(|r| &r.releases)
+        .map(|r| r.id.as_str())
+        .next()
+}
+
+/// Wait until the previous MusicBrainz request is [`MIN_INTERVAL`] ago
+async fn throttle() {
+    let mut last = LAST_REQUEST.lock().await;
+    if let Some(previous) = *last {
+        tokio::time::sleep_until(previous + MIN_INTERVAL).await;
+    }
+    *last = Some(Instant::now());
+}
+
+async fn front_cover(release: &str, timeout: Duration) -> Result<Option<Vec<u8>>, ApiError> {
+    let response = http_client()
+        .get(format!("{COVER_ART_ARCHIVE}/{release}/front-250"))
+        .timeout(timeout)
+        .send()
+        .await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let bytes = response.error_for_status()?.bytes().await?;
+    if bytes.len() > MAX_COVER_BYTES {
+        return Err(ApiError::InvalidResponse(format!(
+            "cover of {} bytes is too large",
+            bytes.len()
+        )));
+    }
+    Ok(Some(bytes.to_vec()))
+}
+
+/// File of the track's cover in `cache`, looking it up if not cached yet.
+/// `None` if no cover is known.
+pub async fn lookup(
+    track: TrackInfo,
+    cache: Cache,
+    timeout: Duration,
+) -> Result<Option<PathBuf>, ApiError> {
+    let key = cache_key(&track);
+    if let Some(bytes) = cache.get(&key) {
+        return Ok(if bytes.is_empty() {
+            None
+        } else {
+            cache.file(&key)
+        });
+    }
+
+    throttle().await;
+    debug!("Looking up cover art for {:?}", track);
+    let search: RecordingSearch = http_client()
+        .get(MUSICBRAINZ_RECORDINGS)
+        .query(&[
+            ("query", recording_query(&track).as_str()),
+            ("fmt", "json"),
+            ("limit", "5"),
+        ])
+        .timeout(timeout)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let cover = match first_release(&search) {
+        Some(release) => front_cover(release, timeout).await?,
+        None => None,
+    };
+
+    // Remember misses too, so they are not looked up again
+    cache
+        .put(&key, cover.as_deref().unwrap_or_default())
+        .map_err(|e| ApiError::InvalidResponse(format!("cannot cache cover: {e}")))?;
+    Ok(cover.and_then(|_| cache.file(&key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_track() {
+        assert_eq!(
+            parse_track("Miles Davis - So What"),
+            Some(TrackInfo {
+                artist: "Miles Davis".to_string(),
+                title: "So What".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_track("A-ha - Take On Me - Remix").map(|t| t.title),
+            Some("Take On Me - Remix".to_string())
+        );
+        assert_eq!(parse_track("Station jingle"), None);
+        assert_eq!(parse_track(" - Untitled"), None);
+    }
+
+    #[test]
+    fn test_recording_query_strips_quotes() {
+        let track = TrackInfo {
+            artist: "The \"Band\"".to_string(),
+            title: "Song".to_string(),
+        };
+        assert_eq!(
+            recording_query(&track),
+            "recording:\"Song\" AND artist:\"The  Band \""
+        );
+    }
+
+    #[test]
+    fn test_first_release() {
+        let search: RecordingSearch = serde_json::from_str(
+            r#"{"recordings": [{"releases": []}, {"releases": [{"id": "r1"}, {"id": "r2"}]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(first_release(&search), Some("r1"));
+
+        let empty: RecordingSearch = serde_json::from_str("{}").unwrap();
+        assert_eq!(first_release(&empty), None);
+    }
+}
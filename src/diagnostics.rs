@@ -0,0 +1,257 @@
+//! Stream diagnostics
+//!
+//! When a station "doesn't work", the diagnostics panel shows what the
+//! applet can find out about its stream: the addresses its host resolves to,
+//! whether the connection is encrypted, the HTTP status and ICY headers of a
+//! fresh request, and, from mpv, the cache fill and how often playback
+//! stalled and reconnected. The same information can be copied as a plain
+//! text report for bug reports.
+
+use crate::api::http_client;
+use crate::audio::CacheState;
+use crate::bandwidth;
+use crate::streamauth::redact_url;
+use reqwest::header::HeaderMap;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use tracing::debug;
+use url::Url;
+
+/// TLS implementation reqwest was built with
+const TLS_BACKEND: &str = if cfg!(feature = "rustls") {
+    "rustls"
+} else {
+    "native-tls"
+};
+
+/// Response headers worth showing besides the `icy-*` ones
+const PLAIN_HEADERS: [&str; 3] = ["content-type", "server", "location"];
+
+/// Result of requesting a stream once
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Probe {
+    /// Stream URL, with credentials redacted
+    pub url: String,
+    /// Addresses the host name resolved to
+    pub addresses: Vec<IpAddr>,
+    /// Address the response came from
+    pub remote: Option<SocketAddr>,
+    /// Whether the final URL was HTTPS; a response means the certificate
+    /// was verified
+    pub https: bool,
+    /// Status line, e.g. `200 OK`
+    pub status: Option<String>,
+    /// Content type, server, and ICY headers
+    pub headers: Vec<(String, String)>,
+    pub error: Option<String>,
+}
+
+/// Diagnostics of the running stream
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    /// `None` while the probe is running
+    pub probe: Option<Probe>,
+    pub cache: CacheState,
+    /// Times playback stalled waiting for data since it started
+    pub reconnects: u32,
+    buffering: bool,
+}
+
+impl Diagnostics {
+    pub fn set_cache(&mut self, cache: CacheState) {
+        self.cache = cache;
+    }
+
+    /// Count stalls; the initial fill of the cache is not one
+    pub fn set_buffering(&mut self, buffering: bool) {
+        let playing = self.cache.duration_ms > 0 || self.reconnects > 0;
+        if buffering && !self.buffering && playing {
+            self.reconnects += 1;
+        }
+        self.buffering = buffering;
+    }
+
+    /// Plain text report, in English so it can be pasted into bug reports
+    #[must_use]
+    pub fn report(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(probe) = &self.probe {
+            lines.push(format!("URL: {}", probe.url));
+            let resolved = probe
+                .addresses
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            lines.push(match probe.remote {
+                Some(remote) => format!("Address: {remote} (resolved: {resolved})"),
+                None if resolved.is_empty() => "Address: not resolved".to_string(),
+                None => format!("Address: {resolved}"),
+            });
+            lines.push(if probe.https {
+                format!("TLS: {TLS_BACKEND}, certificate verified")
+            } else {
+                "TLS: none".to_string()
+            });
+            if let Some(status) = &probe.status {
+                lines.push(format!("HTTP: {status}"));
+            }
+            for (name, value) in &probe.headers {
+                lines.push(format!("{name}: {value}"));
+            }
+            if let Some(error) = &probe.error {
+                lines.push(format!("Error: {error}"));
+            }
+        }
+        lines.push(format!(
+            "Cache: {:.1} s, {}",
+            self.cache.duration_ms as f64 / 1000.0,
+            bandwidth::format_bytes(self.cache.bytes)
+        ));
+        lines.push(format!(
+            "Input rate: {}/s",
+            bandwidth::format_bytes(self.cache.input_rate)
+        ));
+        lines.push(format!("Reconnects: {}", self.reconnects));
+        lines.join("\n")
+    }
+}
+
+/// Content type, server, and `icy-*` headers, in that order
+fn stream_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    let mut plain = Vec::new();
+    let mut icy = Vec::new();
+    for (name, value) in headers {
+        let name = name.as_str();
+        let value = String::from_utf8_lossy(value.as_bytes()).trim().to_string();
+        if name.starts_with("icy-") {
+            icy.push((name.to_string(), value));
+        } else if let Some(rank) = PLAIN_HEADERS.iter().position(|h| *h == name) {
+            plain.push((rank, name.to_string(), value));
+        }
+    }
+    plain.sort_by_key(|(rank, _, _)| *rank);
+    icy.sort();
+    plain
+        .into_iter()
+        .map(|(_, name, value)| (name, value))
+        .chain(icy)
+        .collect()
+}
+
+/// Resolve the stream's host and request it once, without reading the body
+pub async fn probe(url: String, timeout: Duration) -> Probe {
+    let mut probe = Probe {
+        url: redact_url(&url),
+        ..Probe::default()
+    };
+    let parsed = match Url::parse(&url) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            probe.error = Some(e.to_string());
+            return probe;
+        }
+    };
+    probe.https = parsed.scheme() == "https";
+
+    if let (Some(host), Some(port)) = (parsed.host_str(), parsed.port_or_known_default()) {
+        match tokio::time::timeout(timeout, tokio::net::lookup_host((host, port))).await {
+            Ok(Ok(addresses)) => {
+                probe.addresses = addresses.map(|a| a.ip()).collect();
+                probe.addresses.dedup();
+            }
+            Ok(Err(e)) => debug!("Cannot resolve {}: {}", host, e),
+            Err(_) => debug!("Resolving {} timed out", host),
+        }
+    }
+
+    debug!("Probing stream {}", probe.url);
+    match http_client()
+        .get(parsed)
+        .header("Icy-MetaData", "1")
+        .timeout(timeout)
+        .send()
+        .await
+    {
+        Ok(response) => {
+            let status = response.status();
+            probe.remote = response.remote_addr();
+            probe.https = response.url().scheme() == "https";
+            probe.status = Some(format!(
+                "{} {}",
+                status.as_u16(),
+                status.canonical_reason().unwrap_or_default()
+            ));
+            probe.headers = stream_headers(response.headers());
+        }
+        Err(e) => probe.error = Some(e.to_string()),
+    }
+    probe
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_stream_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("icy-name", HeaderValue::from_static("Jazz FM"));
+        headers.insert("icy-br", HeaderValue::from_static("128"));
+        headers.insert("server", HeaderValue::from_static("Icecast 2.4.4"));
+        headers.insert("cache-control", HeaderValue::from_static("no-cache"));
+        headers.insert("content-type", HeaderValue::from_static("audio/mpeg"));
+
+        let headers = stream_headers(&headers);
+        let names: Vec<&str> = headers.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["content-type", "server", "icy-br", "icy-name"]);
+    }
+
+    #[test]
+    fn test_reconnects_skip_initial_fill() {
+        let mut diagnostics = Diagnostics::default();
+        diagnostics.set_buffering(true);
+        diagnostics.set_buffering(false);
+        assert_eq!(diagnostics.reconnects, 0);
+
+        diagnostics.set_cache(CacheState {
+            duration_ms: 5000,
+            ..CacheState::default()
+        });
+        diagnostics.set_buffering(true);
+        diagnostics.set_buffering(true);
+        diagnostics.set_buffering(false);
+        diagnostics.set_buffering(true);
+        assert_eq!(diagnostics.reconnects, 2);
+    }
+
+    #[test]
+    fn test_report() {
+        let diagnostics = Diagnostics {
+            probe: Some(Probe {
+                url: "https://stream.example.com/jazz".to_string(),
+                addresses: vec!["192.0.2.7".parse().unwrap()],
+                remote: Some("192.0.2.7:443".parse().unwrap()),
+                https: true,
+                status: Some("200 OK".to_string()),
+                headers: vec![("icy-br".to_string(), "128".to_string())],
+                error: None,
+            }),
+            cache: CacheState {
+                input_rate: 16_000,
+                duration_ms: 9_300,
+                bytes: 148_000,
+            },
+            reconnects: 1,
+            buffering: false,
+        };
+        let report = diagnostics.report();
+        assert!(report.contains("Address: 192.0.2.7:443 (resolved: 192.0.2.7)"));
+        assert!(report.contains("certificate verified"));
+        assert!(report.contains("HTTP: 200 OK\nicy-br: 128"));
+        assert!(report.contains("Cache: 9.3 s, 148 KB"));
+        assert!(report.contains("Input rate: 16 KB/s"));
+        assert!(report.ends_with("Reconnects: 1"));
+    }
+}
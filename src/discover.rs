@@ -0,0 +1,161 @@
+//! New and trending stations
+//!
+//! The discover page lists stations recently added to radio-browser.info and
+//! those whose plays grew the most over the last two days. Both lists are
+//! kept in the on-disk cache, shown right away when the popup opens, and
+//! fetched again once they are older than [`MAX_AGE_SECS`].
+//!
+//! The popular stations suggested while there are no favorites yet are
+//! cached and refreshed the same way, per country.
+
+use crate::api::{self, RequestOptions, Station};
+use crate::cache::Cache;
+use serde::{Deserialize, Serialize};
+
+/// Cache key of the last fetched lists
+pub const CACHE_KEY: &str = "discover";
+
+/// Age after which the lists are fetched again
+pub const MAX_AGE_SECS: u64 = 60 * 60;
+
+/// Stations per list
+pub const LIST_LENGTH: u32 = 10;
+
+/// New and trending stations as of `fetched_at`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Discover {
+    /// Unix time of the fetch
+    pub fetched_at: u64,
+    /// Recently added or updated stations, newest first
+    pub new: Vec<Station>,
+    /// Fastest growing stations first
+    pub trending: Vec<Station>,
+}
+
+impl Discover {
+    /// Whether the lists should be fetched again at unix time `now`
+    #[must_use]
+    pub fn is_stale(&self, now: u64) -> bool {
+        now.saturating_sub(self.fetched_at) >= MAX_AGE_SECS
+    }
+}
+
+/// Popular stations suggested while favorites are empty, as of `fetched_at`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Starters {
+    /// Unix time of the fetch
+    pub fetched_at: u64,
+    /// Most listened first
+    pub stations: Vec<Station>,
+}
+
+fn starters_key(country_code: Option<&str>) -> String {
+    format!("starters:{}", country_code.unwrap_or_default())
+}
+
+/// The popular stations from the last fetch for a country, if cached
+#[must_use]
+pub fn load_cached_starters(cache: Option<&Cache>, country_code: Option<&str>) -> Option<Starters> {
+    cache?.get_json(&starters_key(country_code))
+}
+
+/// Fetch the most listened stations of a country and cache them
+pub async fn fetch_starters(
+    country_code: Option<String>,
+    limit: u32,
+    now: u64,
+    options: RequestOptions,
+    cache: Option<Cache>,
+) -> Result<Starters, reqwest::Error> {
+    let key = starters_key(country_code.as_deref());
+    let starters = Starters {
+        fetched_at: now,
+        stations: api::popular_stations(country_code, limit, options).await?,
+    };
+    if let Some(cache) = &cache {
+        cache.put_json(&key, &starters);
+    }
+    Ok(starters)
+}
+
+/// The lists from the last fetch, if cached
+#[must_use]
+pub fn load_cached(cache: Option<&Cache>) -> Option<Discover> {
+    cache?.get_json(CACHE_KEY)
+}
+
+/// Fetch both lists and cache them. Fails only if both requests fail.
+pub async fn fetch(
+    now: u64,
+    options: RequestOptions,
+    cache: Option<Cache>,
+) -> Result<Discover, reqwest::Error> {
+    let (new, trending) = futures::join!(
+        api::new_stations(LIST_LENGTH, options),
+        api::trending_stations(LIST_LENGTH, options)
+    );
+    let discover = match (new, trending) {
+        (Err(e), Err(_)) => return Err(e),
+        (new, trending) => Discover {
+            fetched_at: now,
+            new: new.unwrap_or_default(),
+            trending: trending
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|s| s.clicktrend > 0)
+                .collect(),
+        },
+    };
+    if let Some(cache) = &cache {
+        cache.put_json(CACHE_KEY, &discover);
+    }
+    Ok(discover)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_stale() {
+        let discover = Discover {
+            fetched_at: 1_000,
+            ..Default::default()
+        };
+        assert!(!discover.is_stale(1_000));
+        assert!(!discover.is_stale(1_000 + MAX_AGE_SECS - 1));
+        assert!(discover.is_stale(1_000 + MAX_AGE_SECS));
+        assert!(Discover::default().is_stale(MAX_AGE_SECS));
+    }
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("radio-discover-{}", std::process::id()));
+        let cache = Cache::new(dir, 10);
+        assert!(load_cached(Some(&cache)).is_none());
+        assert!(load_cached(None).is_none());
+
+        let discover = Discover {
+            fetched_at: 42,
+            new: vec![Station {
+                name: "Fresh FM".to_string(),
+                ..Default::default()
+            }],
+            trending: Vec::new(),
+        };
+        cache.put_json(CACHE_KEY, &discover);
+        assert_eq!(load_cached(Some(&cache)), Some(discover));
+
+        let starters = Starters {
+            fetched_at: 42,
+            stations: Vec::new(),
+        };
+        cache.put_json(&starters_key(Some("PT")), &starters);
+        assert_eq!(
+            load_cached_starters(Some(&cache), Some("PT")),
+            Some(starters)
+        );
+        assert!(load_cached_starters(Some(&cache), None).is_none());
+        let _ = cache.clear();
+    }
+}
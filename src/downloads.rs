@@ -0,0 +1,339 @@
+//! Downloaded podcast episodes and archived shows
+//!
+//! Streams with a duration can be downloaded from the now-playing view, so
+//! they play without a network connection. Each download runs in the
+//! background as its own subscription and reports its progress; pausing
+//! drops the subscription and keeps the partial file, which resuming
+//! continues with an HTTP range request. A download fails rather than let
+//! the files outgrow the configured quota. Stations with a finished
+//! download play from the local file.
+//!
+//! Downloading needs the `podcasts` Cargo feature (on by default), which
+//! also covers continuing episodes and the seek bar. Without it the
+//! settings are kept but nothing is downloaded.
+#![cfg_attr(not(feature = "podcasts"), allow(dead_code, unused_imports))]
+
+use crate::api::http_client;
+use futures::channel::mpsc::Sender;
+use futures::SinkExt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// Default quota for downloads in megabytes
+pub const DEFAULT_QUOTA_MB: u32 = 1000;
+
+/// Allowed quotas in megabytes
+pub const QUOTA_MB_RANGE: RangeInclusive<u32> = 100..=20_000;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Bytes downloaded between progress reports
+const PROGRESS_STEP: u64 = 256 * 1024;
+
+/// Longest a download may take; a stopped one can be resumed
+const MAX_DOWNLOAD_TIME: Duration = Duration::from_secs(6 * 3600);
+
+/// Clamp a quota to [`QUOTA_MB_RANGE`]
+#[must_use]
+pub fn clamp_quota_mb(mb: u32) -> u32 {
+    mb.clamp(*QUOTA_MB_RANGE.start(), *QUOTA_MB_RANGE.end())
+}
+
+/// Directory of the downloaded files
+#[must_use]
+pub fn downloads_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("cosmic-ext-applet-radio").join("downloads"))
+}
+
+/// File name for a station's download, safe whatever the UUID holds
+fn file_name(stationuuid: &str) -> String {
+    stationuuid
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect()
+}
+
+fn partial_path(dir: &Path, stationuuid: &str) -> PathBuf {
+    dir.join(format!("{}.part", file_name(stationuuid)))
+}
+
+/// The finished download of a station, if there is one
+#[must_use]
+pub fn downloaded(stationuuid: &str) -> Option<PathBuf> {
+    let name = file_name(stationuuid);
+    if name.is_empty() {
+        return None;
+    }
+    let path = downloads_dir()?.join(name);
+    path.is_file().then_some(path)
+}
+
+/// Bytes taken up by downloads, finished or partial
+#[must_use]
+pub fn used_bytes(dir: &Path) -> u64 {
+    fs::read_dir(dir).map_or(0, |entries| {
+        entries
+            .flatten()
+            .filter_map(|entry| entry.metadata().ok())
+            .filter(fs::Metadata::is_file)
+            .map(|metadata| metadata.len())
+            .sum()
+    })
+}
+
+/// Finished downloads in `dir`, for the downloads of earlier sessions
+#[must_use]
+pub fn finished(dir: &Path) -> Vec<Download> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let metadata = entry.metadata().ok()?;
+            (metadata.is_file() && !name.ends_with(".part"))
+                .then(|| Download::finished(name, metadata.len()))
+        })
+        .collect()
+}
+
+fn remove(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Delete a station's download, finished or partial
+pub fn delete(stationuuid: &str) -> io::Result<()> {
+    let Some(dir) = downloads_dir() else {
+        return Ok(());
+    };
+    let name = file_name(stationuuid);
+    if name.is_empty() {
+        return Ok(());
+    }
+    remove(&dir.join(name))?;
+    remove(&partial_path(&dir, stationuuid))
+}
+
+/// Delete all downloads
+pub fn delete_all() -> io::Result<()> {
+    let Some(dir) = downloads_dir() else {
+        return Ok(());
+    };
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Where a download stands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadState {
+    Running,
+    Paused,
+    Done,
+    Failed(String),
+}
+
+/// A download started in this session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Download {
+    pub stationuuid: String,
+    pub url: String,
+    pub bytes: u64,
+    /// Size of the whole file, when the server tells
+    pub total: Option<u64>,
+    pub state: DownloadState,
+}
+
+impl Download {
+    #[must_use]
+    pub fn new(stationuuid: String, url: String) -> Self {
+        Self {
+            stationuuid,
+            url,
+            bytes: 0,
+            total: None,
+            state: DownloadState::Running,
+        }
+    }
+
+    /// A download already on disk
+    #[must_use]
+    pub fn finished(stationuuid: String, bytes: u64) -> Self {
+        Self {
+            stationuuid,
+            url: String::new(),
+            bytes,
+            total: Some(bytes),
+            state: DownloadState::Done,
+        }
+    }
+
+    /// Share downloaded, from 0 to 1, when the size is known
+    #[must_use]
+    pub fn fraction(&self) -> Option<f32> {
+        self.total
+            .filter(|total| *total > 0)
+            .map(|total| (self.bytes as f32 / total as f32).min(1.0))
+    }
+}
+
+/// Events reported by a running download
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadEvent {
+    Progress {
+        stationuuid: String,
+        bytes: u64,
+        total: Option<u64>,
+    },
+    Finished(String),
+    /// The station and what went wrong
+    Failed(String, String),
+}
+
+/// Create an iced Subscription that downloads a station's stream.
+///
+/// The subscription is keyed by the station, so it keeps running while the
+/// app asks for it and is cancelled, keeping the partial file, when it no
+/// longer does.
+#[cfg(feature = "podcasts")]
+pub fn download_subscription(
+    stationuuid: String,
+    url: String,
+    quota_mb: u32,
+) -> cosmic::iced::Subscription<DownloadEvent> {
+    cosmic::iced::Subscription::run_with_id(
+        ("download", stationuuid.clone()),
+        cosmic::iced::stream::channel(8, move |mut output| async move {
+            let event = match fetch(&stationuuid, &url, quota_mb, &mut output).await {
+                Ok(()) => {
+                    info!("Downloaded {}", stationuuid);
+                    DownloadEvent::Finished(stationuuid)
+                }
+                Err(e) => DownloadEvent::Failed(stationuuid, e),
+            };
+            let _ = output.send(event).await;
+        }),
+    )
+}
+
+#[cfg(not(feature = "podcasts"))]
+pub fn download_subscription(
+    _stationuuid: String,
+    _url: String,
+    _quota_mb: u32,
+) -> cosmic::iced::Subscription<DownloadEvent> {
+    cosmic::iced::Subscription::none()
+}
+
+/// Download `url` into the station's partial file, continuing where an
+/// earlier attempt stopped, and move it into place once complete
+#[cfg(feature = "podcasts")]
+async fn fetch(
+    stationuuid: &str,
+    url: &str,
+    quota_mb: u32,
+    output: &mut Sender<DownloadEvent>,
+) -> Result<(), String> {
+    let dir = downloads_dir().ok_or_else(|| "No data directory".to_string())?;
+    let name = file_name(stationuuid);
+    if name.is_empty() {
+        return Err("Station has no ID".to_string());
+    }
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let part = partial_path(&dir, stationuuid);
+    let offset = fs::metadata(&part).map_or(0, |metadata| metadata.len());
+
+    let mut request = http_client().get(url).timeout(MAX_DOWNLOAD_TIME);
+    if offset > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={offset}-"));
+    }
+    let mut response = request
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| e.to_string())?;
+    // Servers that ignore the range send the whole file again
+    let resumed = response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    let mut bytes = if resumed { offset } else { 0 };
+    debug!(resumed, offset, "Downloading {}", stationuuid);
+    let total = response.content_length().map(|len| len + bytes);
+    let allowed = (u64::from(clamp_quota_mb(quota_mb)) * BYTES_PER_MB)
+        .saturating_sub(used_bytes(&dir).saturating_sub(offset));
+    if total.is_some_and(|total| total > allowed) {
+        return Err("Download quota exceeded".to_string());
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(&part)
+        .map_err(|e| e.to_string())?;
+    let mut reported = bytes;
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        bytes += chunk.len() as u64;
+        if bytes > allowed {
+            drop(file);
+            let _ = fs::remove_file(&part);
+            return Err("Download quota exceeded".to_string());
+        }
+        file.write_all(&chunk).map_err(|e| e.to_string())?;
+        if bytes - reported >= PROGRESS_STEP {
+            reported = bytes;
+            let progress = DownloadEvent::Progress {
+                stationuuid: stationuuid.to_string(),
+                bytes,
+                total,
+            };
+            if output.send(progress).await.is_err() {
+                return Err("Download cancelled".to_string());
+            }
+        }
+    }
+    file.flush().map_err(|e| e.to_string())?;
+    fs::rename(&part, dir.join(name)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(
+            file_name("96062a7b-0601-11e8-ae97-52543be04c81"),
+            "96062a7b-0601-11e8-ae97-52543be04c81"
+        );
+        assert_eq!(file_name("../../etc/passwd"), "etcpasswd");
+        assert_eq!(downloaded("/.."), None);
+    }
+
+    #[test]
+    fn test_fraction() {
+        let mut download = Download::new("uuid".to_string(), "https://example.com".to_string());
+        assert_eq!(download.fraction(), None);
+        download.bytes = 25;
+        download.total = Some(100);
+        assert_eq!(download.fraction(), Some(0.25));
+    }
+
+    #[test]
+    fn test_finished() {
+        let dir = std::env::temp_dir().join(format!("radio-downloads-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("done"), b"episode").unwrap();
+        fs::write(dir.join("partial.part"), b"epi").unwrap();
+        assert_eq!(finished(&dir), [Download::finished("done".to_string(), 7)]);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,143 @@
+//! Lowering the radio during calls and notification sounds
+//!
+//! PipeWire streams carry a `media.role`. While a stream with a call or
+//! notification role is running (video calls, softphones, notification
+//! sounds), the radio's volume is lowered by a configurable amount and
+//! restored once they end. Streams are listed with `pw-dump`, polled once a
+//! second; without PipeWire nothing is ever ducked.
+
+use crate::audio;
+use futures::SinkExt;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Roles of streams the radio makes way for (compared ignoring case)
+const DUCKING_ROLES: [&str; 4] = ["communication", "phone", "notification", "event"];
+
+/// Default volume reduction, in percent
+pub const DEFAULT_AMOUNT: u8 = 60;
+
+/// Allowed volume reductions, in percent
+pub const AMOUNT_RANGE: RangeInclusive<u8> = 10..=100;
+
+/// Ducking settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DuckingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How much to lower the volume, in percent
+    #[serde(default = "default_amount")]
+    pub amount: u8,
+}
+
+fn default_amount() -> u8 {
+    DEFAULT_AMOUNT
+}
+
+impl Default for DuckingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            amount: DEFAULT_AMOUNT,
+        }
+    }
+}
+
+/// Clamp a reduction to [`AMOUNT_RANGE`]
+#[must_use]
+pub fn clamp_amount(amount: u8) -> u8 {
+    amount.clamp(*AMOUNT_RANGE.start(), *AMOUNT_RANGE.end())
+}
+
+/// Volume while ducked by `amount` percent
+#[must_use]
+pub fn ducked_volume(volume: u8, amount: u8) -> u8 {
+    (u16::from(volume) * u16::from(100 - clamp_amount(amount)) / 100) as u8
+}
+
+/// Whether a `pw-dump` document lists a running stream with a ducking role
+#[must_use]
+pub fn has_ducking_stream(dump: &serde_json::Value) -> bool {
+    let Some(objects) = dump.as_array() else {
+        return false;
+    };
+    objects.iter().any(|object| {
+        let info = &object["info"];
+        let role = info["props"]["media.role"].as_str().unwrap_or_default();
+        object["type"] == "PipeWire:Interface:Node"
+            && info["state"] == "running"
+            && DUCKING_ROLES
+                .iter()
+                .any(|ducking| role.eq_ignore_ascii_case(ducking))
+    })
+}
+
+fn query() -> Result<bool, String> {
+    audio::pw_dump().map(|dump| has_ducking_stream(&dump))
+}
+
+/// Subscription yielding `true`/`false` whenever call or notification
+/// streams start or stop
+pub fn ducking_subscription() -> cosmic::iced::Subscription<bool> {
+    cosmic::iced::Subscription::run(|| {
+        cosmic::iced::stream::channel(4, |mut output| async move {
+            let mut last = false;
+            loop {
+                match tokio::task::spawn_blocking(query).await {
+                    Ok(Ok(active)) if active != last => {
+                        debug!("Ducking streams active: {}", active);
+                        last = active;
+                        if output.send(active).await.is_err() {
+                            return;
+                        }
+                    }
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) => {
+                        warn!("Ducking disabled: {}", e);
+                        return;
+                    }
+                    Err(e) => warn!("Ducking query panicked: {}", e),
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn node(role: &str, state: &str) -> serde_json::Value {
+        json!({
+            "type": "PipeWire:Interface:Node",
+            "info": { "state": state, "props": { "media.role": role } }
+        })
+    }
+
+    #[test]
+    fn test_has_ducking_stream() {
+        assert!(has_ducking_stream(&json!([
+            node("Music", "running"),
+            node("Communication", "running")
+        ])));
+        assert!(!has_ducking_stream(&json!([
+            node("Music", "running"),
+            node("Communication", "idle")
+        ])));
+        assert!(has_ducking_stream(&json!([node("event", "running")])));
+        assert!(!has_ducking_stream(&json!({})));
+    }
+
+    #[test]
+    fn test_ducked_volume() {
+        assert_eq!(ducked_volume(80, 50), 40);
+        assert_eq!(ducked_volume(80, 100), 0);
+        assert_eq!(ducked_volume(100, 0), 90);
+    }
+}
@@ -0,0 +1,106 @@
+//! Duplicate favorites
+//!
+//! The directory often lists one station several times (per stream
+//! quality, or added by different people). When a station is starred that
+//! plays the same stream as a favorite, or has nearly the same name, the
+//! user is asked whether to merge it into that favorite instead.
+
+use crate::api::Station;
+use crate::replace;
+
+/// Edits allowed between names of this many characters or more
+/// ("Radio Paradise" and "Radio Paradise!" or "Radio Paradize")
+const FUZZY_NAME_MIN_LEN: usize = 8;
+
+/// Levenshtein distance, giving up once it exceeds `max`
+fn distance_within(a: &str, b: &str, max: usize) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return false;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        if current.iter().all(|d| *d > max) {
+            return false;
+        }
+        previous = current;
+    }
+    previous[b.len()] <= max
+}
+
+/// Whether two station names are the same up to case, punctuation, and a
+/// typo in longer names
+fn similar_names(a: &str, b: &str) -> bool {
+    let (a, b) = (replace::normalize_name(a), replace::normalize_name(b));
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    a == b || (a.chars().count() >= FUZZY_NAME_MIN_LEN && distance_within(&a, &b, 1))
+}
+
+/// The favorite `station` duplicates: same stream, or a near-identical name
+#[must_use]
+pub fn find<'a>(favorites: &'a [Station], station: &Station) -> Option<&'a Station> {
+    let stream = |s: &Station| {
+        if s.url_resolved.is_empty() {
+            s.url.clone()
+        } else {
+            s.url_resolved.clone()
+        }
+    };
+    let url = stream(station);
+    favorites
+        .iter()
+        .filter(|favorite| favorite.stationuuid != station.stationuuid)
+        .find(|favorite| {
+            (!url.is_empty() && stream(favorite) == url)
+                || similar_names(&favorite.name, &station.name)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn station(uuid: &str, name: &str, url: &str) -> Station {
+        Station {
+            stationuuid: uuid.to_string(),
+            name: name.to_string(),
+            url_resolved: url.to_string(),
+            ..Station::default()
+        }
+    }
+
+    #[test]
+    fn test_distance_within() {
+        assert!(distance_within("paradise", "paradize", 1));
+        assert!(distance_within("paradise", "paradise 2", 2));
+        assert!(!distance_within("paradise", "paradise 2", 1));
+        assert!(!distance_within("jazz", "rock", 1));
+    }
+
+    #[test]
+    fn test_find() {
+        let favorites = [
+            station("a", "Radio Paradise", "http://paradise/main"),
+            station("b", "Jazz FM", "http://jazz/128"),
+        ];
+        // Same stream under another name
+        let same_stream = station("c", "RP Main Mix", "http://paradise/main");
+        assert_eq!(find(&favorites, &same_stream).unwrap().stationuuid, "a");
+        // Punctuation and a typo
+        let typo = station("d", "Radio Paradize!", "http://paradise/aac");
+        assert_eq!(find(&favorites, &typo).unwrap().stationuuid, "a");
+        // Short names must match exactly
+        assert!(find(&favorites, &station("e", "Jazz FN", "http://other")).is_none());
+        assert!(find(&favorites, &station("f", "jazz-fm", "http://other")).is_some());
+        // A favorite is not its own duplicate
+        assert!(find(&favorites, &favorites[1]).is_none());
+    }
+}
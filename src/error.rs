@@ -1,8 +1,8 @@
 //! Custom error types for cosmic-ext-applet-radio
 //!
-//! These error types are defined for future integration. Currently the application
-//! uses simpler error handling, but these types provide a foundation for more
-//! robust error handling as the codebase evolves.
+//! These error types are being integrated incrementally. Station providers
+//! report failures through [`ApiError`]; other modules still use simpler error
+//! handling, but these types provide a foundation as the codebase evolves.
 
 #![allow(dead_code)]
 
@@ -26,6 +26,10 @@ pub enum AppError {
     /// Input validation errors (invalid URLs, malformed data)
     #[error("Validation error: {0}")]
     Validation(#[from] ValidationError),
+
+    /// Keyring (Secret Service) errors
+    #[error("Secret storage error: {0}")]
+    Secret(#[from] SecretError),
 }
 
 /// Configuration-specific errors
@@ -110,5 +114,21 @@ pub enum ValidationError {
     StationNameTooLong { max: usize, actual: usize },
 }
 
+/// Secret Service (keyring) errors
+#[derive(Error, Debug)]
+pub enum SecretError {
+    #[error("Secret Service D-Bus call failed: {0}")]
+    Dbus(#[from] mpris_server::zbus::Error),
+
+    #[error("Keyring is locked")]
+    Locked,
+
+    #[error("No default keyring collection")]
+    NoCollection,
+
+    #[error("Stored secret is not valid UTF-8")]
+    InvalidEncoding,
+}
+
 /// Type alias for Results using AppError
 pub type Result<T> = std::result::Result<T, AppError>;
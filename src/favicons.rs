@@ -0,0 +1,259 @@
+//! Station logos in the lists
+//!
+//! Favicons are fetched only for the rows on screen, at most
+//! [`MAX_CONCURRENT`] at a time; rows scrolled out of view before their
+//! download started are dropped from the queue, and running downloads for
+//! them are cancelled. Decoded logos stay in a small in-memory LRU cache,
+//! and the downloaded files (and misses, as empty entries) in the on-disk
+//! cache, so a logo is downloaded once. Only a definite answer counts as a
+//! miss; a logo that failed to download for a timeout or while offline is
+//! tried again the next time its row is shown.
+
+use crate::api::http_client;
+use crate::cache::Cache;
+use crate::error::ApiError;
+use cosmic::widget::image::Handle;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+use tracing::debug;
+
+/// Downloads running at once
+pub const MAX_CONCURRENT: usize = 4;
+
+/// Logos kept in memory
+const MEMORY_ENTRIES: usize = 128;
+
+/// Logos larger than this are not shown
+const MAX_FAVICON_BYTES: usize = 256 * 1024;
+
+fn cache_key(url: &str) -> String {
+    format!("favicon:{url}")
+}
+
+/// Downloads to start and to cancel after the visible rows changed
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Plan {
+    pub start: Vec<String>,
+    pub cancel: Vec<String>,
+}
+
+/// Which logos are loaded, loading, or waiting
+#[derive(Debug, Default)]
+pub struct Favicons {
+    /// Loaded logos, and their URLs from least to most recently shown
+    loaded: HashMap<String, Handle>,
+    recent: VecDeque<String>,
+    /// URLs with no usable logo
+    missing: HashSet<String>,
+    in_flight: HashSet<String>,
+    queue: VecDeque<String>,
+}
+
+impl Favicons {
+    /// The logo at `url`, if loaded
+    #[must_use]
+    pub fn get(&self, url: &str) -> Option<&Handle> {
+        self.loaded.get(url)
+    }
+
+    /// Mark `url` as the most recently shown
+    fn touch(&mut self, url: &str) {
+        if let Some(pos) = self.recent.iter().position(|u| u == url) {
+            if let Some(url) = self.recent.remove(pos) {
+                self.recent.push_back(url);
+            }
+        }
+    }
+
+    /// The rows on screen now show the logos at `urls`: queue those not
+    /// loaded yet, and cancel work for the others
+    pub fn show<'a>(&mut self, urls: impl IntoIterator<Item = &'a str>) -> Plan {
+        let mut wanted = Vec::new();
+        for url in urls {
+            if url.is_empty() || self.missing.contains(url) || wanted.contains(&url) {
+                continue;
+            }
+            if self.loaded.contains_key(url) {
+                self.touch(url);
+            } else {
+                wanted.push(url);
+            }
+        }
+        let cancel: Vec<String> = self
+            .in_flight
+            .iter()
+            .filter(|url| !wanted.contains(&url.as_str()))
+            .cloned()
+            .collect();
+        for url in &cancel {
+            self.in_flight.remove(url);
+        }
+        self.queue = wanted
+            .into_iter()
+            .filter(|url| !self.in_flight.contains(*url))
+            .map(str::to_string)
+            .collect();
+        Plan {
+            start: self.next(),
+            cancel,
+        }
+    }
+
+    /// Record a finished download and return the downloads to start next.
+    /// Results of cancelled downloads are ignored, and failed ones are
+    /// forgotten so they are tried again.
+    pub fn finish(&mut self, url: String, logo: Result<Option<Handle>, String>) -> Vec<String> {
+        if self.in_flight.remove(&url) {
+            match logo {
+                Ok(Some(logo)) => {
+                    self.recent.push_back(url.clone());
+                    self.loaded.insert(url, logo);
+                    while self.recent.len() > MEMORY_ENTRIES {
+                        if let Some(old) = self.recent.pop_front() {
+                            self.loaded.remove(&old);
+                        }
+                    }
+                }
+                Ok(None) => {
+                    self.missing.insert(url);
+                }
+                Err(e) => debug!("Favicon at {} failed: {}", url, e),
+            }
+        }
+        self.next()
+    }
+
+    /// Take queued URLs while there is room
+    fn next(&mut self) -> Vec<String> {
+        let mut start = Vec::new();
+        while self.in_flight.len() < MAX_CONCURRENT {
+            let Some(url) = self.queue.pop_front() else {
+                break;
+            };
+            self.in_flight.insert(url.clone());
+            start.push(url);
+        }
+        start
+    }
+}
+
+/// The logo at `url`, or `None` if the server says there is none to show.
+/// Errors are worth trying again later.
+async fn download(url: &str, timeout: Duration) -> Result<Option<Vec<u8>>, ApiError> {
+    let response = http_client().get(url).timeout(timeout).send().await?;
+    if response.status().is_client_error() {
+        debug!("No favicon at {}: {}", url, response.status());
+        return Ok(None);
+    }
+    let response = response.error_for_status()?;
+    let is_image = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_none_or(|value| value.starts_with("image/"));
+    if !is_image {
+        debug!("No favicon at {}: not an image", url);
+        return Ok(None);
+    }
+    if response
+        .content_length()
+        .is_some_and(|length| length as usize > MAX_FAVICON_BYTES)
+    {
+        debug!("No favicon at {}: logo too large", url);
+        return Ok(None);
+    }
+    let bytes = response.bytes().await?;
+    if bytes.len() > MAX_FAVICON_BYTES {
+        debug!("No favicon at {}: logo too large", url);
+        return Ok(None);
+    }
+    Ok(Some(bytes.to_vec()))
+}
+
+/// The logo at `url`, from `cache` or downloaded into it. `None` if there
+/// is none to show; an error, left out of the cache, if the download failed.
+pub async fn fetch(
+    url: String,
+    cache: Option<Cache>,
+    timeout: Duration,
+) -> Result<Option<Handle>, String> {
+    let key = cache_key(&url);
+    let bytes = match cache.as_ref().and_then(|cache| cache.get(&key)) {
+        Some(bytes) => bytes,
+        None => {
+            let bytes = download(&url, timeout)
+                .await
+                .map_err(|e| e.to_string())?
+                .unwrap_or_default();
+            if let Some(cache) = &cache {
+                if let Err(e) = cache.put(&key, &bytes) {
+                    debug!("Failed to cache favicon: {}", e);
+                }
+            }
+            bytes
+        }
+    };
+    Ok((!bytes.is_empty()).then(|| Handle::from_bytes(bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(count: usize) -> Vec<String> {
+        (0..count)
+            .map(|i| format!("https://logo.example/{i}.png"))
+            .collect()
+    }
+
+    #[test]
+    fn test_show_bounds_concurrency() {
+        let urls = urls(6);
+        let mut favicons = Favicons::default();
+        let plan = favicons.show(urls.iter().map(String::as_str));
+        assert_eq!(plan.start, urls[..MAX_CONCURRENT]);
+        assert!(plan.cancel.is_empty());
+
+        // One finished: the next queued one starts
+        let next = favicons.finish(urls[0].clone(), Ok(Some(Handle::from_bytes(vec![1]))));
+        assert_eq!(next, [urls[MAX_CONCURRENT].clone()]);
+        assert!(favicons.get(&urls[0]).is_some());
+    }
+
+    #[test]
+    fn test_show_cancels_hidden_rows() {
+        let urls = urls(6);
+        let mut favicons = Favicons::default();
+        favicons.show(urls[..2].iter().map(String::as_str));
+        let plan = favicons.show(urls[1..3].iter().map(String::as_str));
+        assert_eq!(plan.cancel, [urls[0].clone()]);
+        assert_eq!(plan.start, [urls[2].clone()]);
+
+        // The cancelled download's result is dropped
+        favicons.finish(urls[0].clone(), Ok(Some(Handle::from_bytes(vec![1]))));
+        assert!(favicons.get(&urls[0]).is_none());
+
+        // Misses are not asked for again
+        favicons.finish(urls[1].clone(), Ok(None));
+        let plan = favicons.show(urls[1..3].iter().map(String::as_str));
+        assert!(plan.start.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_download_not_cached() {
+        let dir = std::env::temp_dir().join(format!("radio-favicon-test-{}", std::process::id()));
+        let cache = Cache::new(dir.clone(), 10);
+        // Nothing listens on the discard port
+        let url = "http://127.0.0.1:9/logo.png".to_string();
+        let logo = fetch(url.clone(), Some(cache.clone()), Duration::from_secs(1)).await;
+        assert!(logo.is_err());
+        assert_eq!(cache.get(&cache_key(&url)), None);
+
+        // and the logo is asked for again when its row is shown
+        let mut favicons = Favicons::default();
+        favicons.show([url.as_str()]);
+        favicons.finish(url.clone(), logo);
+        assert_eq!(favicons.show([url.as_str()]).start, [url]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,114 @@
+//! Content filter for search results
+//!
+//! Stations whose tags or name contain a blocked keyword (case-insensitive)
+//! are left out of every result list. Handy on shared family machines and
+//! kiosk setups; favorites are never filtered. Individual stations can also be
+//! hidden by UUID, e.g. known-broken or duplicate entries.
+
+use crate::api::Station;
+use serde::{Deserialize, Serialize};
+
+/// A station hidden from results; the name is kept for the review list
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HiddenStation {
+    pub stationuuid: String,
+    #[serde(default)]
+    pub name: String,
+}
+
+impl From<&Station> for HiddenStation {
+    fn from(station: &Station) -> Self {
+        Self {
+            stationuuid: station.stationuuid.clone(),
+            name: station.name.clone(),
+        }
+    }
+}
+
+/// Whether a station was hidden by the user
+#[must_use]
+pub fn is_hidden(station: &Station, hidden: &[HiddenStation]) -> bool {
+    hidden.iter().any(|h| h.stationuuid == station.stationuuid)
+}
+
+/// Normalize a keyword for storage and matching
+#[must_use]
+pub fn normalize_keyword(keyword: &str) -> String {
+    keyword.trim().to_lowercase()
+}
+
+/// Whether a station matches any blocked keyword
+#[must_use]
+pub fn is_blocked(station: &Station, keywords: &[String]) -> bool {
+    if keywords.is_empty() {
+        return false;
+    }
+    let name = station.name.to_lowercase();
+    let tags: Vec<String> = station
+        .tags
+        .split(',')
+        .map(normalize_keyword)
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    keywords
+        .iter()
+        .map(|k| normalize_keyword(k))
+        .filter(|k| !k.is_empty())
+        .any(|k| name.contains(&k) || tags.iter().any(|t| t.contains(&k)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn station(name: &str, tags: &str) -> Station {
+        Station {
+            name: name.to_string(),
+            tags: tags.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_blocked_by_tag() {
+        let keywords = vec!["Explicit".to_string()];
+        assert!(is_blocked(
+            &station("Rap FM", "hip-hop, EXPLICIT rap"),
+            &keywords
+        ));
+        assert!(!is_blocked(&station("Jazz24", "jazz,smooth"), &keywords));
+    }
+
+    #[test]
+    fn test_blocked_by_name() {
+        let keywords = vec!["talk".to_string()];
+        assert!(is_blocked(&station("Sports Talk 24/7", ""), &keywords));
+    }
+
+    #[test]
+    fn test_empty_keywords_block_nothing() {
+        assert!(!is_blocked(&station("Anything", "news"), &[]));
+        assert!(!is_blocked(
+            &station("Anything", "news"),
+            &["  ".to_string()]
+        ));
+    }
+
+    #[test]
+    fn test_is_hidden_by_uuid() {
+        let mut jazz = station("Jazz24", "jazz");
+        jazz.stationuuid = "uuid-1".to_string();
+        let hidden = vec![HiddenStation::from(&jazz)];
+        assert_eq!(hidden[0].name, "Jazz24");
+        assert!(is_hidden(&jazz, &hidden));
+
+        jazz.stationuuid = "uuid-2".to_string();
+        assert!(!is_hidden(&jazz, &hidden));
+    }
+
+    #[test]
+    fn test_normalize_keyword() {
+        assert_eq!(normalize_keyword("  Death Metal "), "death metal");
+    }
+}
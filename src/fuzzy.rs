@@ -0,0 +1,150 @@
+//! Instant local matches while typing a search
+//!
+//! Favorites, stations from the listening history, and stations seen in
+//! earlier search results are filtered as the query changes, so a known
+//! station shows up before the network search returns. Matching is
+//! skim-style: the query's characters must appear in order, and runs of
+//! consecutive characters and matches at word starts score higher
+//! (`bbc4` finds "BBC Radio 4").
+//!
+//! Stations from search results are remembered in the cache, so the pool
+//! survives restarts and is cleared with the rest of the cache.
+
+use crate::api::Station;
+use crate::cache::Cache;
+use std::collections::HashSet;
+
+pub const CACHE_KEY: &str = "known-stations";
+
+/// Stations remembered from search results
+const KNOWN_LIMIT: usize = 500;
+
+/// Local matches listed above the network results
+pub const MATCH_LIMIT: usize = 8;
+
+/// How well `query` matches `text`, or `None` if it doesn't
+#[must_use]
+pub fn score(query: &str, text: &str) -> Option<u32> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return None;
+    }
+
+    let mut score = 0;
+    let mut matched = 0;
+    let mut previous_matched = false;
+    let mut previous: Option<char> = None;
+    for c in text.chars() {
+        let hit = matched < query.len() && c.to_lowercase().eq(std::iter::once(query[matched]));
+        if hit {
+            score += 1;
+            if previous_matched {
+                score += 4;
+            }
+            if !matches!(previous, Some(p) if p.is_alphanumeric()) {
+                score += 3;
+            }
+            matched += 1;
+        }
+        previous_matched = hit;
+        previous = Some(c);
+    }
+    (matched == query.len()).then_some(score)
+}
+
+/// The best matches for `query` among `stations` by name, or more weakly by
+/// tags. Each station counts once; equal scores keep the given order.
+#[must_use]
+pub fn rank<'a>(
+    query: &str,
+    stations: impl IntoIterator<Item = &'a Station>,
+    limit: usize,
+) -> Vec<&'a Station> {
+    let mut seen = HashSet::new();
+    let mut scored: Vec<(u32, &Station)> = stations
+        .into_iter()
+        .filter(|station| seen.insert(station.stationuuid.as_str()))
+        .filter_map(|station| {
+            let by_name = score(query, &station.name);
+            let by_tags = score(query, &station.tags).map(|score| score / 2);
+            by_name.max(by_tags).map(|score| (score, station))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, station)| station)
+        .collect()
+}
+
+/// Put `stations` in front of the remembered ones, dropping the oldest
+pub fn remember(known: &mut Vec<Station>, stations: &[Station]) {
+    let fresh: HashSet<&str> = stations.iter().map(|s| s.stationuuid.as_str()).collect();
+    known.retain(|s| !fresh.contains(s.stationuuid.as_str()));
+    known.splice(0..0, stations.iter().cloned());
+    known.truncate(KNOWN_LIMIT);
+}
+
+/// Stations remembered from earlier searches
+#[must_use]
+pub fn load_cached(cache: Option<&Cache>) -> Vec<Station> {
+    cache
+        .and_then(|cache| cache.get_json(CACHE_KEY))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn station(uuid: &str, name: &str) -> Station {
+        Station {
+            stationuuid: uuid.to_string(),
+            name: name.to_string(),
+            ..Station::default()
+        }
+    }
+
+    #[test]
+    fn test_score() {
+        assert!(score("bbc4", "BBC Radio 4").is_some());
+        assert!(score("jazz", "Smooth Jaz").is_none());
+        assert!(score("", "Anything").is_none());
+        // Consecutive and word-start matches beat scattered ones
+        assert!(score("rock", "Rock Antenne") > score("rock", "Radio Clock"));
+    }
+
+    #[test]
+    fn test_rank() {
+        let stations = [
+            station("a", "Radio Clock"),
+            station("b", "Rock Antenne"),
+            station("b", "Rock Antenne (copy)"),
+            station("c", "Jazz FM"),
+            Station {
+                tags: "rock,metal".to_string(),
+                ..station("d", "Metal Hammer")
+            },
+        ];
+        let ranked: Vec<&str> = rank("rock", &stations, 10)
+            .iter()
+            .map(|s| s.stationuuid.as_str())
+            .collect();
+        assert_eq!(ranked, ["b", "a", "d"]);
+        assert_eq!(rank("rock", &stations, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_remember() {
+        let mut known = vec![station("a", "A"), station("b", "B")];
+        remember(&mut known, &[station("b", "B2"), station("c", "C")]);
+        let uuids: Vec<&str> = known.iter().map(|s| s.stationuuid.as_str()).collect();
+        assert_eq!(uuids, ["b", "c", "a"]);
+        assert_eq!(known[0].name, "B2");
+    }
+}
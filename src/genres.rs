@@ -0,0 +1,293 @@
+//! A small genre taxonomy over the directory's free-form tags
+//!
+//! Station tags are whatever the submitter typed: "hiphop", "hip-hop",
+//! "Hip Hop", and "rap" all mean the same genre. Tags are folded to
+//! lowercase letters and digits and looked up in an alias table, giving
+//! each station a few [`Genre`]s that rows show (translated) and search
+//! results can be narrowed to. Browsing a genre asks the directory for its
+//! common spellings, see [`Genre::directory_tags`]. The raw tags stay in
+//! the station details.
+
+use crate::api::Station;
+
+/// A normalized genre
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Genre {
+    Pop,
+    Rock,
+    Metal,
+    Jazz,
+    Classical,
+    Electronic,
+    Dance,
+    HipHop,
+    Soul,
+    Chillout,
+    Country,
+    Folk,
+    Blues,
+    Reggae,
+    Latin,
+    Oldies,
+    World,
+    News,
+    Talk,
+    Sports,
+    Religious,
+    Children,
+}
+
+impl Genre {
+    pub const ALL: [Genre; 22] = [
+        Genre::Pop,
+        Genre::Rock,
+        Genre::Metal,
+        Genre::Jazz,
+        Genre::Classical,
+        Genre::Electronic,
+        Genre::Dance,
+        Genre::HipHop,
+        Genre::Soul,
+        Genre::Chillout,
+        Genre::Country,
+        Genre::Folk,
+        Genre::Blues,
+        Genre::Reggae,
+        Genre::Latin,
+        Genre::Oldies,
+        Genre::World,
+        Genre::News,
+        Genre::Talk,
+        Genre::Sports,
+        Genre::Religious,
+        Genre::Children,
+    ];
+
+    /// Common directory spellings of the genre, queried when browsing it
+    #[must_use]
+    pub fn directory_tags(self) -> &'static [&'static str] {
+        match self {
+            Genre::Pop => &["pop", "top 40", "hits"],
+            Genre::Rock => &["rock", "classic rock", "alternative"],
+            Genre::Metal => &["metal", "heavy metal"],
+            Genre::Jazz => &["jazz", "smooth jazz"],
+            Genre::Classical => &["classical", "opera"],
+            Genre::Electronic => &["electronic", "techno", "house"],
+            Genre::Dance => &["dance", "disco"],
+            Genre::HipHop => &["hip hop", "hiphop", "rap"],
+            Genre::Soul => &["soul", "rnb", "funk"],
+            Genre::Chillout => &["chillout", "ambient", "lounge"],
+            Genre::Country => &["country"],
+            Genre::Folk => &["folk"],
+            Genre::Blues => &["blues"],
+            Genre::Reggae => &["reggae", "ska"],
+            Genre::Latin => &["latin", "salsa", "reggaeton"],
+            Genre::Oldies => &["oldies", "80s", "70s"],
+            Genre::World => &["world music", "world"],
+            Genre::News => &["news"],
+            Genre::Talk => &["talk", "comedy"],
+            Genre::Sports => &["sports", "sport"],
+            Genre::Religious => &["christian", "religious", "gospel"],
+            Genre::Children => &["kids", "children"],
+        }
+    }
+}
+
+/// Folded tags and the genre each means
+const ALIASES: &[(&str, Genre)] = &[
+    ("pop", Genre::Pop),
+    ("top40", Genre::Pop),
+    ("hits", Genre::Pop),
+    ("charts", Genre::Pop),
+    ("adultcontemporary", Genre::Pop),
+    ("rock", Genre::Rock),
+    ("classicrock", Genre::Rock),
+    ("alternative", Genre::Rock),
+    ("alternativerock", Genre::Rock),
+    ("indie", Genre::Rock),
+    ("indierock", Genre::Rock),
+    ("punk", Genre::Rock),
+    ("hardrock", Genre::Rock),
+    ("metal", Genre::Metal),
+    ("heavymetal", Genre::Metal),
+    ("deathmetal", Genre::Metal),
+    ("blackmetal", Genre::Metal),
+    ("jazz", Genre::Jazz),
+    ("smoothjazz", Genre::Jazz),
+    ("swing", Genre::Jazz),
+    ("bebop", Genre::Jazz),
+    ("classical", Genre::Classical),
+    ("klassik", Genre::Classical),
+    ("opera", Genre::Classical),
+    ("baroque", Genre::Classical),
+    ("orchestral", Genre::Classical),
+    ("electronic", Genre::Electronic),
+    ("electronica", Genre::Electronic),
+    ("electro", Genre::Electronic),
+    ("techno", Genre::Electronic),
+    ("house", Genre::Electronic),
+    ("trance", Genre::Electronic),
+    ("edm", Genre::Electronic),
+    ("dubstep", Genre::Electronic),
+    ("drumandbass", Genre::Electronic),
+    ("drumnbass", Genre::Electronic),
+    ("dnb", Genre::Electronic),
+    ("dance", Genre::Dance),
+    ("disco", Genre::Dance),
+    ("eurodance", Genre::Dance),
+    ("club", Genre::Dance),
+    ("hiphop", Genre::HipHop),
+    ("rap", Genre::HipHop),
+    ("trap", Genre::HipHop),
+    ("urban", Genre::HipHop),
+    ("soul", Genre::Soul),
+    ("rnb", Genre::Soul),
+    ("rb", Genre::Soul),
+    ("randb", Genre::Soul),
+    ("funk", Genre::Soul),
+    ("motown", Genre::Soul),
+    ("chillout", Genre::Chillout),
+    ("chill", Genre::Chillout),
+    ("ambient", Genre::Chillout),
+    ("lounge", Genre::Chillout),
+    ("downtempo", Genre::Chillout),
+    ("relax", Genre::Chillout),
+    ("relaxing", Genre::Chillout),
+    ("meditation", Genre::Chillout),
+    ("country", Genre::Country),
+    ("bluegrass", Genre::Country),
+    ("americana", Genre::Country),
+    ("folk", Genre::Folk),
+    ("singersongwriter", Genre::Folk),
+    ("acoustic", Genre::Folk),
+    ("blues", Genre::Blues),
+    ("reggae", Genre::Reggae),
+    ("ska", Genre::Reggae),
+    ("dancehall", Genre::Reggae),
+    ("dub", Genre::Reggae),
+    ("latin", Genre::Latin),
+    ("latino", Genre::Latin),
+    ("salsa", Genre::Latin),
+    ("reggaeton", Genre::Latin),
+    ("bachata", Genre::Latin),
+    ("cumbia", Genre::Latin),
+    ("tango", Genre::Latin),
+    ("samba", Genre::Latin),
+    ("bossanova", Genre::Latin),
+    ("mpb", Genre::Latin),
+    ("oldies", Genre::Oldies),
+    ("retro", Genre::Oldies),
+    ("classichits", Genre::Oldies),
+    ("50s", Genre::Oldies),
+    ("60s", Genre::Oldies),
+    ("70s", Genre::Oldies),
+    ("80s", Genre::Oldies),
+    ("90s", Genre::Oldies),
+    ("world", Genre::World),
+    ("worldmusic", Genre::World),
+    ("ethnic", Genre::World),
+    ("celtic", Genre::World),
+    ("african", Genre::World),
+    ("news", Genre::News),
+    ("noticias", Genre::News),
+    ("nachrichten", Genre::News),
+    ("information", Genre::News),
+    ("talk", Genre::Talk),
+    ("talkradio", Genre::Talk),
+    ("spoken", Genre::Talk),
+    ("comedy", Genre::Talk),
+    ("sport", Genre::Sports),
+    ("sports", Genre::Sports),
+    ("football", Genre::Sports),
+    ("soccer", Genre::Sports),
+    ("christian", Genre::Religious),
+    ("religious", Genre::Religious),
+    ("religion", Genre::Religious),
+    ("gospel", Genre::Religious),
+    ("worship", Genre::Religious),
+    ("catholic", Genre::Religious),
+    ("children", Genre::Children),
+    ("kids", Genre::Children),
+];
+
+/// A tag folded to lowercase letters and digits (`Hip-Hop` → `hiphop`)
+#[must_use]
+pub fn fold(tag: &str) -> String {
+    tag.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The genre a single tag means, if any
+#[must_use]
+pub fn genre_of(tag: &str) -> Option<Genre> {
+    let folded = fold(tag);
+    ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == folded)
+        .map(|(_, genre)| *genre)
+}
+
+/// Genres of a comma-separated tag list, in tag order without repeats
+#[must_use]
+pub fn genres(tags: &str) -> Vec<Genre> {
+    let mut genres = Vec::new();
+    for genre in tags.split(',').filter_map(genre_of) {
+        if !genres.contains(&genre) {
+            genres.push(genre);
+        }
+    }
+    genres
+}
+
+/// Whether a station's tags mean `genre`
+#[must_use]
+pub fn has_genre(station: &Station, genre: Genre) -> bool {
+    genres(&station.tags).contains(&genre)
+}
+
+/// Genres among `stations`, the most common first
+#[must_use]
+pub fn common<'a>(stations: impl IntoIterator<Item = &'a Station>) -> Vec<Genre> {
+    let mut counts: Vec<(Genre, usize)> = Vec::new();
+    for station in stations {
+        for genre in genres(&station.tags) {
+            match counts.iter_mut().find(|(g, _)| *g == genre) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((genre, 1)),
+            }
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1));
+    counts.into_iter().map(|(genre, _)| genre).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_genres_fold_spellings() {
+        assert_eq!(genres("hiphop,Hip-Hop, hip hop,rap"), [Genre::HipHop]);
+        assert_eq!(
+            genres("news,talk,R&B,unknown tag"),
+            [Genre::News, Genre::Talk, Genre::Soul]
+        );
+        assert!(genres("").is_empty());
+    }
+
+    #[test]
+    fn test_common() {
+        let station = |tags: &str| Station {
+            tags: tags.to_string(),
+            ..Station::default()
+        };
+        let stations = [
+            station("jazz"),
+            station("rock,jazz"),
+            station("smooth jazz"),
+        ];
+        assert_eq!(common(&stations), [Genre::Jazz, Genre::Rock]);
+    }
+}
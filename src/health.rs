@@ -0,0 +1,120 @@
+//! Checking all favorites' streams on demand
+//!
+//! The directory's checker visits stations about once a day, and favorites
+//! from other providers are never checked. "Check favorites" requests every
+//! favorite's stream, [`CONCURRENCY`] at a time, reading only the first
+//! chunk like the check before playback does (see [`sniff`](crate::sniff)).
+//! Favorites that fail are marked broken, with the button to find a
+//! replacement, until the next check.
+
+use crate::api::Station;
+use crate::sniff::{self, Rejection, Sniffed};
+use crate::streamauth;
+use futures::{stream, StreamExt};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Streams requested at once
+pub const CONCURRENCY: usize = 8;
+
+/// Why a favorite's stream failed the check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// No connection, a timeout, or an error status
+    Unreachable(String),
+    /// Answered with something other than audio
+    Rejected(Rejection),
+}
+
+/// Outcome of checking a set of favorites
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub checked: usize,
+    /// Problems by station UUID
+    pub broken: HashMap<String, Problem>,
+}
+
+impl Report {
+    #[must_use]
+    pub fn from_results(results: impl IntoIterator<Item = (String, Option<Problem>)>) -> Self {
+        let mut report = Self::default();
+        for (uuid, problem) in results {
+            report.checked += 1;
+            if let Some(problem) = problem {
+                report.broken.insert(uuid, problem);
+            }
+        }
+        report
+    }
+
+    /// Number of favorites whose streams answered with audio
+    #[must_use]
+    pub fn working(&self) -> usize {
+        self.checked - self.broken.len()
+    }
+
+    /// The problem found with a station, if any
+    #[must_use]
+    pub fn problem(&self, stationuuid: &str) -> Option<&Problem> {
+        self.broken.get(stationuuid)
+    }
+}
+
+/// Request one station's stream, with its credentials if it has any
+pub async fn check(station: Station, timeout: Duration) -> Option<Problem> {
+    let url = match streamauth::lookup(station.stationuuid.clone()).await {
+        Some(auth) => streamauth::apply(&station.url_resolved, &auth)
+            .unwrap_or_else(|_| station.url_resolved.clone()),
+        None => station.url_resolved.clone(),
+    };
+    match sniff::sniff(&url, timeout).await {
+        Ok(Sniffed {
+            rejection: Some(rejection),
+            ..
+        }) => Some(Problem::Rejected(rejection)),
+        Ok(_) => None,
+        Err(e) => Some(Problem::Unreachable(e.to_string())),
+    }
+}
+
+/// Check all `stations`' streams concurrently
+pub async fn check_all(stations: Vec<Station>, timeout: Duration) -> Report {
+    let results: Vec<_> = stream::iter(stations)
+        .map(|station| async move {
+            let problem = check(station.clone(), timeout).await;
+            match &problem {
+                Some(problem) => warn!("Favorite {} failed the check: {:?}", station.name, problem),
+                None => debug!("Favorite {} is working", station.name),
+            }
+            (station.stationuuid, problem)
+        })
+        .buffer_unordered(CONCURRENCY)
+        .collect()
+        .await;
+    Report::from_results(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_from_results() {
+        let report = Report::from_results([
+            ("a".to_string(), None),
+            (
+                "b".to_string(),
+                Some(Problem::Unreachable("timed out".to_string())),
+            ),
+            ("c".to_string(), Some(Problem::Rejected(Rejection::WebPage))),
+        ]);
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.working(), 1);
+        assert_eq!(report.problem("a"), None);
+        assert_eq!(
+            report.problem("c"),
+            Some(&Problem::Rejected(Rejection::WebPage))
+        );
+    }
+}
@@ -1,4 +1,5 @@
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::LazyLock;
 use i18n_embed::{
     fluent::{fluent_language_loader, FluentLanguageLoader},
@@ -7,12 +8,23 @@ use i18n_embed::{
 };
 use rust_embed::RustEmbed;
 
+static RIGHT_TO_LEFT: AtomicBool = AtomicBool::new(false);
+
 pub fn init(requested_languages: &[LanguageIdentifier]) {
+    let rtl = requested_languages
+        .first()
+        .is_some_and(|lang| crate::locale::is_rtl_language(lang.language.as_str()));
+    RIGHT_TO_LEFT.store(rtl, Ordering::Relaxed);
     if let Err(why) = localizer().select(requested_languages) {
         eprintln!("error while loading fluent localizations: {why}");
     }
 }
 
+/// Whether the desktop language is written right to left
+pub fn is_rtl() -> bool {
+    RIGHT_TO_LEFT.load(Ordering::Relaxed)
+}
+
 pub fn localizer() -> Box<dyn Localizer> {
     Box::from(DefaultLocalizer::new(&*LANGUAGE_LOADER, &Localizations))
 }
@@ -30,4 +42,7 @@ pub static LANGUAGE_LOADER: LazyLock<FluentLanguageLoader> = LazyLock::new(|| {
 #[macro_export]
 macro_rules! fl {
     ($message_id:literal) => { i18n_embed_fl::fl!($crate::i18n::LANGUAGE_LOADER, $message_id) };
+    ($message_id:literal, $($args:expr),*) => {
+        i18n_embed_fl::fl!($crate::i18n::LANGUAGE_LOADER, $message_id, $($args),*)
+    };
 }
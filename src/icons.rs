@@ -0,0 +1,82 @@
+//! Panel icon set
+//!
+//! The panel button shows whether the radio is idle, playing, or failed. The
+//! default style uses the symbolic SVGs in `resources/icons/`, embedded in the
+//! binary; being symbolic, they are recolored with the COSMIC theme (light,
+//! dark, or accent) like every other panel icon. The alternative style uses
+//! the generic media icons of the system icon theme.
+
+use cosmic::widget::icon;
+use serde::{Deserialize, Serialize};
+
+const RADIO_IDLE: &[u8] = include_bytes!("../resources/icons/radio-idle-symbolic.svg");
+const RADIO_PLAYING: &[u8] = include_bytes!("../resources/icons/radio-playing-symbolic.svg");
+const RADIO_ERROR: &[u8] = include_bytes!("../resources/icons/radio-error-symbolic.svg");
+
+/// What the panel icon reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelState {
+    Idle,
+    Playing,
+    Error,
+}
+
+/// Look of the panel icon
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum IconStyle {
+    /// The applet's own radio icons
+    #[default]
+    Radio,
+    /// Media player icons from the system icon theme
+    Player,
+}
+
+impl IconStyle {
+    pub const ALL: [IconStyle; 2] = [IconStyle::Radio, IconStyle::Player];
+
+    /// System icon name for a state, for the [`IconStyle::Player`] style
+    #[must_use]
+    pub fn theme_icon_name(state: PanelState) -> &'static str {
+        match state {
+            PanelState::Idle => "multimedia-player-symbolic",
+            PanelState::Playing => "media-playback-start-symbolic",
+            PanelState::Error => "dialog-warning-symbolic",
+        }
+    }
+
+    /// Icon for the panel button in `state`
+    #[must_use]
+    pub fn panel_icon(self, state: PanelState) -> icon::Handle {
+        match self {
+            IconStyle::Radio => {
+                let svg = match state {
+                    PanelState::Idle => RADIO_IDLE,
+                    PanelState::Playing => RADIO_PLAYING,
+                    PanelState::Error => RADIO_ERROR,
+                };
+                icon::from_svg_bytes(svg).symbolic(true)
+            }
+            IconStyle::Player => icon::from_name(Self::theme_icon_name(state)).handle(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_icons_are_svg() {
+        for svg in [RADIO_IDLE, RADIO_PLAYING, RADIO_ERROR] {
+            let text = std::str::from_utf8(svg).unwrap();
+            assert!(text.trim_start().starts_with("<svg"));
+        }
+    }
+
+    #[test]
+    fn test_theme_icons_are_symbolic() {
+        for state in [PanelState::Idle, PanelState::Playing, PanelState::Error] {
+            assert!(IconStyle::theme_icon_name(state).ends_with("-symbolic"));
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! Offline search over known stations
+//!
+//! When the network search fails and the cache holds no results for the
+//! exact query, searches fall back to a word index over every station the
+//! applet knows locally: favorites and stations from earlier search results
+//! (see [`crate::fuzzy`]). Query words match the start of a word in the
+//! station name or a tag; `tag:jazz` only matches tags. All words must match.
+
+use crate::api::Station;
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Debug, Default)]
+pub struct StationIndex {
+    stations: Vec<Station>,
+    // Lowercase word → stations with it in the name or among the tags
+    names: BTreeMap<String, Vec<usize>>,
+    tags: BTreeMap<String, Vec<usize>>,
+}
+
+/// Lowercase words of `text`
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Stations with a word starting with `prefix`
+fn starting_with(map: &BTreeMap<String, Vec<usize>>, prefix: &str) -> HashSet<usize> {
+    map.range(prefix.to_string()..)
+        .take_while(|(word, _)| word.starts_with(prefix))
+        .flat_map(|(_, ids)| ids.iter().copied())
+        .collect()
+}
+
+impl StationIndex {
+    /// Index `stations`, keeping the first of any with the same UUID
+    #[must_use]
+    pub fn build(stations: impl IntoIterator<Item = Station>) -> Self {
+        let mut index = Self::default();
+        let mut seen = HashSet::new();
+        for station in stations {
+            if !seen.insert(station.stationuuid.clone()) {
+                continue;
+            }
+            let id = index.stations.len();
+            for word in words(&station.name) {
+                index.names.entry(word).or_default().push(id);
+            }
+            for word in words(&station.tags) {
+                index.tags.entry(word).or_default().push(id);
+            }
+            index.stations.push(station);
+        }
+        index
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.stations.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stations.is_empty()
+    }
+
+    /// Stations matching every word of `query`, name matches and popular
+    /// stations first
+    #[must_use]
+    pub fn search(&self, query: &str, limit: usize) -> Vec<Station> {
+        let mut name_hits = vec![0u32; self.stations.len()];
+        let mut matching: Option<HashSet<usize>> = None;
+        for term in query.split_whitespace() {
+            let found = match term.to_lowercase().strip_prefix("tag:") {
+                Some(tag) => words(tag)
+                    .map(|word| starting_with(&self.tags, &word))
+                    .reduce(|a, b| &a & &b)
+                    .unwrap_or_default(),
+                None => {
+                    let mut found = HashSet::new();
+                    for word in words(term) {
+                        let by_name = starting_with(&self.names, &word);
+                        for id in &by_name {
+                            name_hits[*id] += 1;
+                        }
+                        found.extend(by_name);
+                        found.extend(starting_with(&self.tags, &word));
+                    }
+                    found
+                }
+            };
+            matching = Some(match matching {
+                Some(matching) => &matching & &found,
+                None => found,
+            });
+        }
+
+        let mut ids: Vec<usize> = matching.unwrap_or_default().into_iter().collect();
+        ids.sort_by(|a, b| {
+            name_hits[*b]
+                .cmp(&name_hits[*a])
+                .then(self.stations[*b].votes.cmp(&self.stations[*a].votes))
+                .then(a.cmp(b))
+        });
+        ids.into_iter()
+            .take(limit)
+            .map(|id| self.stations[id].clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn station(uuid: &str, name: &str, tags: &str, votes: u32) -> Station {
+        Station {
+            stationuuid: uuid.to_string(),
+            name: name.to_string(),
+            tags: tags.to_string(),
+            votes,
+            ..Station::default()
+        }
+    }
+
+    fn uuids(stations: &[Station]) -> Vec<&str> {
+        stations.iter().map(|s| s.stationuuid.as_str()).collect()
+    }
+
+    fn index() -> StationIndex {
+        StationIndex::build([
+            station("a", "Jazz FM", "jazz,smooth jazz", 10),
+            station("b", "Radio Swiss Jazz", "jazz,swiss", 50),
+            station("c", "Classic Rock", "rock,classic rock", 5),
+            station("d", "Late Night", "jazz,ambient", 99),
+            station("a", "Jazz FM (duplicate)", "", 0),
+        ])
+    }
+
+    #[test]
+    fn test_build_skips_duplicates() {
+        assert_eq!(index().len(), 4);
+    }
+
+    #[test]
+    fn test_search_by_word_prefix() {
+        let index = index();
+        // Name matches first, then by votes
+        assert_eq!(uuids(&index.search("jaz", 10)), ["b", "a", "d"]);
+        assert_eq!(uuids(&index.search("swiss jazz", 10)), ["b"]);
+        assert_eq!(uuids(&index.search("jazz", 1)), ["b"]);
+        assert!(index.search("polka", 10).is_empty());
+        assert!(index.search("", 10).is_empty());
+    }
+
+    #[test]
+    fn test_search_by_tag() {
+        let index = index();
+        assert_eq!(uuids(&index.search("tag:ambient", 10)), ["d"]);
+        assert_eq!(uuids(&index.search("tag:rock classic", 10)), ["c"]);
+        // "Late Night" has no "late" tag
+        assert!(index.search("tag:late", 10).is_empty());
+    }
+}
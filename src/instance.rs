@@ -0,0 +1,54 @@
+//! Single-instance guard
+//!
+//! The first applet process owns the `com.marcos.RadioApplet` bus name for
+//! its whole lifetime. A second process finds the name taken. Started by the
+//! panel (the applet is on another panel), it follows the running one (see
+//! [`crate::sync`]); started any other way, it asks the running one to show
+//! its popup over MPRIS and exits instead of starting a second mpv that would
+//! fight over playback.
+
+use mpris_server::zbus::blocking::Connection;
+use mpris_server::zbus::fdo::{RequestNameFlags, RequestNameReply};
+use mpris_server::zbus::Error;
+
+/// Bus name claimed by the running instance
+pub const INSTANCE_BUS_NAME: &str = "com.marcos.RadioApplet";
+
+/// Whether a process plays itself or mirrors the primary instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Role {
+    #[default]
+    Primary,
+    Follower,
+}
+
+/// Result of trying to become the running instance
+#[derive(Debug, Clone)]
+pub enum Instance {
+    /// This process is the only instance; keep the connection alive to hold the name
+    Primary(Connection),
+    /// Another instance already runs
+    Secondary,
+}
+
+/// Claim the instance bus name without queueing behind a current owner
+pub fn acquire() -> Result<Instance, Error> {
+    let connection = Connection::session()?;
+    let reply =
+        connection.request_name_with_flags(INSTANCE_BUS_NAME, RequestNameFlags::DoNotQueue.into());
+    match reply {
+        Ok(RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner) => {
+            Ok(Instance::Primary(connection))
+        }
+        Ok(RequestNameReply::Exists | RequestNameReply::InQueue) | Err(Error::NameTaken) => {
+            Ok(Instance::Secondary)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether the panel started this process
+#[must_use]
+pub fn in_panel() -> bool {
+    std::env::var_os("COSMIC_PANEL_NAME").is_some()
+}
@@ -0,0 +1,192 @@
+//! IPv4/IPv6 preference for streams
+//!
+//! Some stream hosts publish AAAA records that lead nowhere, and mpv stalls
+//! until its connection attempt times out. Before a plain HTTP stream starts,
+//! its host is resolved and connected to happy-eyeballs style (RFC 8305):
+//! attempts start a short delay apart, alternating address families, and the
+//! first address to accept a TCP connection wins. mpv is then pointed at that
+//! address, with the original `Host` header. HTTPS streams are left to mpv,
+//! since the certificate is checked against the host name.
+//!
+//! With a preferred family set, its addresses are tried first and the other
+//! family only once they all failed.
+
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use tracing::debug;
+use url::{Host, Url};
+
+/// Head start of each connection attempt over the next (RFC 8305's default)
+const ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// Address family to connect to streams over
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum IpPreference {
+    /// Race both families, IPv6 first
+    #[default]
+    Auto,
+    Ipv4,
+    Ipv6,
+}
+
+impl IpPreference {
+    pub const ALL: [IpPreference; 3] = [IpPreference::Auto, IpPreference::Ipv4, IpPreference::Ipv6];
+}
+
+/// Addresses in connection order: those raced first, and those tried only
+/// if none of the first answered
+fn order(addresses: &[SocketAddr], preference: IpPreference) -> (Vec<SocketAddr>, Vec<SocketAddr>) {
+    let (v6, v4): (Vec<SocketAddr>, Vec<SocketAddr>) =
+        addresses.iter().partition(|address| address.is_ipv6());
+    match preference {
+        IpPreference::Auto => {
+            let mut interleaved = Vec::with_capacity(addresses.len());
+            let (mut v6, mut v4) = (v6.into_iter(), v4.into_iter());
+            loop {
+                match (v6.next(), v4.next()) {
+                    (None, None) => break,
+                    (a, b) => interleaved.extend(a.into_iter().chain(b)),
+                }
+            }
+            (interleaved, Vec::new())
+        }
+        IpPreference::Ipv4 => (v4, v6),
+        IpPreference::Ipv6 => (v6, v4),
+    }
+}
+
+/// Connect to `addresses`, starting one attempt every [`ATTEMPT_DELAY`] or
+/// as soon as the previous one failed. Returns the first to connect.
+async fn race(addresses: Vec<SocketAddr>, deadline: Instant) -> Option<SocketAddr> {
+    let mut pending = addresses.into_iter();
+    let mut attempts = JoinSet::new();
+    loop {
+        if let Some(address) = pending.next() {
+            attempts.spawn(async move { TcpStream::connect(address).await.map(|_| address) });
+        } else if attempts.is_empty() {
+            return None;
+        }
+        let next_attempt = (Instant::now() + ATTEMPT_DELAY).min(deadline);
+        match tokio::time::timeout_at(next_attempt, attempts.join_next()).await {
+            Ok(Some(Ok(Ok(address)))) => return Some(address),
+            Ok(Some(Ok(Err(e)))) => debug!("Connection attempt failed: {}", e),
+            Ok(Some(Err(_)) | None) => {}
+            Err(_) if Instant::now() >= deadline => return None,
+            Err(_) => {}
+        }
+    }
+}
+
+/// Resolve a plain HTTP stream's host and find an address that accepts
+/// connections. `None` for HTTPS, IP literals, and unreachable hosts, which
+/// are left to mpv.
+pub async fn preflight(
+    url: String,
+    preference: IpPreference,
+    timeout: Duration,
+) -> Option<SocketAddr> {
+    let parsed = Url::parse(&url).ok()?;
+    if parsed.scheme() != "http" || !matches!(parsed.host(), Some(Host::Domain(_))) {
+        return None;
+    }
+    let host = parsed.host_str()?;
+    let port = parsed.port_or_known_default()?;
+    let deadline = Instant::now() + timeout;
+    let addresses: Vec<SocketAddr> =
+        match tokio::time::timeout_at(deadline, tokio::net::lookup_host((host, port))).await {
+            Ok(Ok(addresses)) => addresses.collect(),
+            Ok(Err(e)) => {
+                debug!("Cannot resolve {}: {}", host, e);
+                return None;
+            }
+            Err(_) => return None,
+        };
+    let (first, fallback) = order(&addresses, preference);
+    let address = match race(first, deadline).await {
+        Some(address) => Some(address),
+        None => race(fallback, deadline).await,
+    };
+    debug!("Connecting to {} at {:?}", host, address);
+    address
+}
+
+/// The stream URL pointed at `address`, and the `Host` header to send with
+/// it. `None` unless the URL is plain HTTP with a host name.
+#[must_use]
+pub fn pin(url: &str, address: SocketAddr) -> Option<(String, String)> {
+    let mut parsed = Url::parse(url).ok()?;
+    if parsed.scheme() != "http" || !matches!(parsed.host(), Some(Host::Domain(_))) {
+        return None;
+    }
+    let host = match parsed.port() {
+        Some(port) => format!("{}:{port}", parsed.host_str()?),
+        None => parsed.host_str()?.to_string(),
+    };
+    parsed.set_ip_host(address.ip()).ok()?;
+    Some((parsed.to_string(), host))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    fn addr(text: &str) -> SocketAddr {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_order() {
+        let addresses = [
+            addr("192.0.2.1:80"),
+            addr("192.0.2.2:80"),
+            addr("[2001:db8::1]:80"),
+        ];
+        let (first, fallback) = order(&addresses, IpPreference::Auto);
+        assert_eq!(
+            first,
+            [
+                addr("[2001:db8::1]:80"),
+                addr("192.0.2.1:80"),
+                addr("192.0.2.2:80")
+            ]
+        );
+        assert!(fallback.is_empty());
+
+        let (first, fallback) = order(&addresses, IpPreference::Ipv4);
+        assert_eq!(first, [addr("192.0.2.1:80"), addr("192.0.2.2:80")]);
+        assert_eq!(fallback, [addr("[2001:db8::1]:80")]);
+    }
+
+    #[test]
+    fn test_pin() {
+        let (url, host) = pin(
+            "http://user:pw@stream.example:8000/live?x=1",
+            addr("[2001:db8::1]:8000"),
+        )
+        .unwrap();
+        assert_eq!(url, "http://user:pw@[2001:db8::1]:8000/live?x=1");
+        assert_eq!(host, "stream.example:8000");
+        assert!(pin("https://stream.example/live", addr("192.0.2.1:443")).is_none());
+        assert!(pin("http://192.0.2.1/live", addr("192.0.2.1:80")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_race_skips_refused() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let open = listener.local_addr().unwrap();
+        // Bound then dropped: connecting is refused
+        let closed = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        assert_eq!(race(vec![closed, open], deadline).await, Some(open));
+        assert_eq!(race(vec![closed], deadline).await, None);
+    }
+}
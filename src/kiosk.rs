@@ -0,0 +1,49 @@
+//! Lock mode for shared computers and kiosks
+//!
+//! While locked, the popup only plays favorites: searching, editing
+//! favorites, profiles, and every page but the favorites list are gone. The
+//! lock persists across restarts. An optional PIN, kept in the keyring next
+//! to the other secrets, is asked for to unlock. A lost PIN can be bypassed
+//! by setting `kiosk_locked` to `false` in the applet's cosmic-config.
+
+use crate::control;
+use crate::error::SecretError;
+use crate::secrets;
+use std::ops::RangeInclusive;
+
+/// Allowed PIN lengths
+pub const PIN_LENGTH: RangeInclusive<usize> = 4..=8;
+
+/// An empty PIN (no PIN) or 4–8 digits
+#[must_use]
+pub fn is_valid_pin(pin: &str) -> bool {
+    pin.is_empty() || (PIN_LENGTH.contains(&pin.len()) && pin.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Replace the stored PIN; an empty one unlocks without asking
+pub async fn set_pin(pin: String) -> Result<(), SecretError> {
+    secrets::store(secrets::KIOSK_PIN_KEY, "Radio lock PIN", &pin).await
+}
+
+/// Whether `given` unlocks the applet
+pub async fn check_pin(given: String) -> Result<bool, SecretError> {
+    Ok(match secrets::lookup(secrets::KIOSK_PIN_KEY).await? {
+        Some(pin) if !pin.is_empty() => control::token_matches(&given, &pin),
+        _ => true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_pin() {
+        assert!(is_valid_pin(""));
+        assert!(is_valid_pin("1234"));
+        assert!(is_valid_pin("12345678"));
+        assert!(!is_valid_pin("123"));
+        assert!(!is_valid_pin("123456789"));
+        assert!(!is_valid_pin("12a4"));
+    }
+}
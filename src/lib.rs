@@ -1,10 +1,86 @@
 // Library exports for testing
+
+// reqwest is built without default features, so HTTPS needs one of these
+#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+compile_error!("enable the `native-tls` or `rustls` feature for HTTPS support");
+
 pub mod api;
+pub mod appearance;
 pub mod audio;
+pub mod autopause;
+pub mod backup;
+pub mod bandwidth;
+pub mod bookmarks;
+pub mod cache;
+pub mod cast;
+pub mod channels;
+pub mod chromecast;
+pub mod clicks;
 pub mod config;
+pub mod control;
+pub mod coverart;
+pub mod diagnostics;
+pub mod discover;
+pub mod downloads;
+pub mod ducking;
+pub mod duplicates;
+pub mod error;
+pub mod favicons;
+pub mod filter;
+pub mod fuzzy;
+pub mod genres;
+pub mod health;
+pub mod hooks;
+pub mod icons;
+pub mod index;
+pub mod instance;
+pub mod ipfamily;
+pub mod kiosk;
+pub mod locale;
+pub mod logging;
+pub mod loudness;
+pub mod lyrics;
+pub mod migrations;
 pub mod mpris;
+pub mod mqtt;
+pub mod notify;
+pub mod nowplaying;
+pub mod opml;
+pub mod outputs;
+pub mod playlist;
+pub mod popularity;
+pub mod profiles;
+pub mod providers;
+pub mod quality;
+pub mod queue;
+pub mod quiet;
+pub mod ratelimit;
+pub mod recommend;
+pub mod recorder;
+pub mod redirect;
+pub mod refresh;
+pub mod reliability;
+pub mod replace;
+pub mod resume;
+pub mod schedule;
+pub mod secrets;
+pub mod selection;
+pub mod session;
+pub mod share;
+pub mod shutdown;
+pub mod sniff;
+pub mod startup;
+pub mod stats;
+pub mod streamauth;
+pub mod suspend;
+pub mod sync;
+pub mod ticker;
+pub mod tls;
+pub mod tracklog;
+pub mod visualizer;
+pub mod wheel;
 
 // Re-export commonly used items for easier testing
 pub use api::{search_stations, Station};
-pub use audio::AudioManager;
+pub use audio::{AudioManager, PlaybackOptions};
 pub use config::Config;
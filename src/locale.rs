@@ -0,0 +1,357 @@
+//! System locale detection
+//!
+//! The region part of the POSIX locale (`de_DE.UTF-8` → `DE`) is used as the
+//! user's country when suggesting stations and ordering search results,
+//! unless [`CountryFilter`] overrides it. The language decides whether the
+//! popup is laid out right to left, and, with [`LanguageFilter`] on, which
+//! stations the start and Discover pages show: the desktop's languages
+//! (`LANGUAGE`, then the locale) plus any added by hand.
+
+use serde::{Deserialize, Serialize};
+
+/// Environment variables consulted for the locale, highest priority first
+const LOCALE_VARS: &[&str] = &["LC_ALL", "LC_MESSAGES", "LANG"];
+
+/// ISO 3166-1 alpha-2 country of a locale string such as `de_DE.UTF-8@euro`
+#[must_use]
+pub fn parse_country(locale: &str) -> Option<String> {
+    let locale = locale.split(['.', '@']).next()?;
+    let (_, region) = locale.split_once(['_', '-'])?;
+    (region.len() == 2 && region.chars().all(|c| c.is_ascii_alphabetic()))
+        .then(|| region.to_ascii_uppercase())
+}
+
+/// Country of the current system locale, if it names one
+#[must_use]
+pub fn country_code() -> Option<String> {
+    LOCALE_VARS
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| parse_country(&value))
+}
+
+/// Languages written right to left (ISO 639 primary subtags)
+const RTL_LANGUAGES: &[&str] = &[
+    "ar", "ckb", "dv", "fa", "he", "iw", "ps", "sd", "ug", "ur", "yi",
+];
+
+/// Whether a language tag such as `ar`, `he-IL`, or `fa_IR.UTF-8` is written right to left
+#[must_use]
+pub fn is_rtl_language(tag: &str) -> bool {
+    let primary = tag.split(['_', '-', '.', '@']).next().unwrap_or_default();
+    RTL_LANGUAGES
+        .iter()
+        .any(|lang| primary.eq_ignore_ascii_case(lang))
+}
+
+/// Wrap text in Unicode directional isolates (FSI … PDI).
+///
+/// A station name spliced into a sentence keeps its own direction, so a Latin
+/// name inside an Arabic label (or the reverse) does not reorder its neighbours.
+#[must_use]
+pub fn isolate(text: &str) -> String {
+    format!("\u{2068}{text}\u{2069}")
+}
+
+/// Flag emoji of an ISO 3166-1 alpha-2 code, built from regional indicator
+/// symbols (`AT` → 🇦🇹)
+#[must_use]
+pub fn flag(code: &str) -> Option<String> {
+    let code = code.trim();
+    if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    code.to_ascii_uppercase()
+        .chars()
+        .map(|c| char::from_u32(0x1F1E6 + (c as u32 - 'A' as u32)))
+        .collect()
+}
+
+/// Short label of a station's language list such as `english,spanish`:
+/// the first language capitalized, with the number of others (`English +1`)
+#[must_use]
+pub fn language_label(languages: &str) -> Option<String> {
+    let mut languages = languages
+        .split(',')
+        .map(str::trim)
+        .filter(|language| !language.is_empty());
+    let first = languages.next()?;
+    let mut label = String::new();
+    for (i, word) in first.split(' ').enumerate() {
+        if i > 0 {
+            label.push(' ');
+        }
+        let mut chars = word.chars();
+        if let Some(c) = chars.next() {
+            label.extend(c.to_uppercase());
+            label.push_str(chars.as_str());
+        }
+    }
+    let others = languages.count();
+    if others > 0 {
+        label.push_str(&format!(" +{others}"));
+    }
+    Some(label)
+}
+
+/// Which country's stations are preferred
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CountryFilter {
+    /// The country of the system locale
+    #[default]
+    Auto,
+    /// No preference
+    Everywhere,
+    /// A fixed ISO 3166-1 alpha-2 code
+    Country(String),
+}
+
+impl CountryFilter {
+    /// Parse user input: a two-letter code, or empty for [`CountryFilter::Auto`]
+    #[must_use]
+    pub fn from_input(input: &str) -> Option<Self> {
+        let code = input.trim();
+        if code.is_empty() {
+            return Some(Self::Auto);
+        }
+        (code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()))
+            .then(|| Self::Country(code.to_ascii_uppercase()))
+    }
+
+    /// Country code to prefer, if any
+    #[must_use]
+    pub fn resolve(&self) -> Option<String> {
+        match self {
+            Self::Auto => country_code(),
+            Self::Everywhere => None,
+            Self::Country(code) => Some(code.clone()),
+        }
+    }
+}
+
+/// Names radio-browser.info uses for the languages of ISO 639-1 codes
+const LANGUAGE_NAMES: &[(&str, &[&str])] = &[
+    ("ar", &["arabic"]),
+    ("bg", &["bulgarian"]),
+    ("ca", &["catalan"]),
+    ("cs", &["czech"]),
+    ("da", &["danish"]),
+    ("de", &["german"]),
+    ("el", &["greek"]),
+    ("en", &["english"]),
+    ("es", &["spanish"]),
+    ("et", &["estonian"]),
+    ("fa", &["persian", "farsi"]),
+    ("fi", &["finnish"]),
+    ("fr", &["french"]),
+    ("he", &["hebrew"]),
+    ("hi", &["hindi"]),
+    ("hr", &["croatian"]),
+    ("hu", &["hungarian"]),
+    ("id", &["indonesian"]),
+    ("it", &["italian"]),
+    ("ja", &["japanese"]),
+    ("ko", &["korean"]),
+    ("lt", &["lithuanian"]),
+    ("lv", &["latvian"]),
+    ("nb", &["norwegian"]),
+    ("nl", &["dutch"]),
+    ("nn", &["norwegian"]),
+    ("no", &["norwegian"]),
+    ("pl", &["polish"]),
+    ("pt", &["portuguese", "brazilian portuguese"]),
+    ("ro", &["romanian"]),
+    ("ru", &["russian"]),
+    ("sk", &["slovak"]),
+    ("sl", &["slovenian"]),
+    ("sr", &["serbian"]),
+    ("sv", &["swedish"]),
+    ("th", &["thai"]),
+    ("tr", &["turkish"]),
+    ("uk", &["ukrainian"]),
+    ("vi", &["vietnamese"]),
+    ("zh", &["chinese", "mandarin", "cantonese"]),
+];
+
+/// radio-browser.info names of the language of a tag such as `pt_BR.UTF-8`
+#[must_use]
+pub fn language_names(tag: &str) -> &'static [&'static str] {
+    let primary = tag.split(['_', '-', '.', '@']).next().unwrap_or_default();
+    LANGUAGE_NAMES
+        .iter()
+        .find(|(code, _)| primary.eq_ignore_ascii_case(code))
+        .map_or(&[], |(_, names)| names)
+}
+
+/// Languages of the desktop: the `LANGUAGE` priority list, then the locale
+#[must_use]
+pub fn desktop_languages() -> Vec<String> {
+    let language = std::env::var("LANGUAGE").unwrap_or_default();
+    let locale = LOCALE_VARS
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    let mut languages: Vec<String> = Vec::new();
+    for tag in language.split(':').chain([locale.as_str()]) {
+        for name in language_names(tag) {
+            if !languages.iter().any(|l| l == name) {
+                languages.push((*name).to_string());
+            }
+        }
+    }
+    languages
+}
+
+/// Whether a station's language list such as `english,spanish` includes one
+/// of `wanted` (lowercase names). Stations without languages are kept.
+#[must_use]
+pub fn speaks(station_languages: &str, wanted: &[String]) -> bool {
+    let mut languages = station_languages
+        .split(',')
+        .map(str::trim)
+        .filter(|language| !language.is_empty())
+        .peekable();
+    languages.peek().is_none()
+        || languages.any(|language| {
+            wanted
+                .iter()
+                .any(|wanted| language.eq_ignore_ascii_case(wanted))
+        })
+}
+
+/// Restricting the start and Discover pages to some languages
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LanguageFilter {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Languages added to the desktop's, lowercase
+    #[serde(default)]
+    pub extra: Vec<String>,
+}
+
+impl LanguageFilter {
+    /// Languages to show, or `None` when the filter is off or no language
+    /// is known
+    #[must_use]
+    pub fn languages(&self) -> Option<Vec<String>> {
+        if !self.enabled {
+            return None;
+        }
+        let mut languages = desktop_languages();
+        for language in &self.extra {
+            if !languages.contains(language) {
+                languages.push(language.clone());
+            }
+        }
+        (!languages.is_empty()).then_some(languages)
+    }
+
+    /// Add a language by name, e.g. `Spanish`; false if it was empty or
+    /// added already
+    pub fn add(&mut self, language: &str) -> bool {
+        let language = language.trim().to_lowercase();
+        if language.is_empty() || self.extra.contains(&language) {
+            return false;
+        }
+        self.extra.push(language);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_country() {
+        assert_eq!(parse_country("de_DE.UTF-8"), Some("DE".to_string()));
+        assert_eq!(parse_country("pt_br"), Some("BR".to_string()));
+        assert_eq!(parse_country("en-GB"), Some("GB".to_string()));
+        assert_eq!(parse_country("ca_ES@valencia"), Some("ES".to_string()));
+    }
+
+    #[test]
+    fn test_parse_country_without_region() {
+        assert_eq!(parse_country("C"), None);
+        assert_eq!(parse_country("POSIX"), None);
+        assert_eq!(parse_country("en.UTF-8"), None);
+        assert_eq!(parse_country("es_419"), None);
+    }
+
+    #[test]
+    fn test_is_rtl_language() {
+        assert!(is_rtl_language("ar"));
+        assert!(is_rtl_language("he-IL"));
+        assert!(is_rtl_language("fa_IR.UTF-8"));
+        assert!(!is_rtl_language("en-US"));
+        assert!(!is_rtl_language("pt_BR"));
+        assert!(!is_rtl_language("ars"));
+        assert!(!is_rtl_language(""));
+    }
+
+    #[test]
+    fn test_isolate() {
+        assert_eq!(isolate("Radio 1"), "\u{2068}Radio 1\u{2069}");
+    }
+
+    #[test]
+    fn test_flag() {
+        assert_eq!(flag("AT").as_deref(), Some("🇦🇹"));
+        assert_eq!(flag("br").as_deref(), Some("🇧🇷"));
+        assert_eq!(flag(""), None);
+        assert_eq!(flag("AUT"), None);
+        assert_eq!(flag("4U"), None);
+    }
+
+    #[test]
+    fn test_language_label() {
+        assert_eq!(language_label("english").as_deref(), Some("English"));
+        assert_eq!(
+            language_label("english, spanish,german").as_deref(),
+            Some("English +2")
+        );
+        assert_eq!(
+            language_label("brazilian portuguese").as_deref(),
+            Some("Brazilian Portuguese")
+        );
+        assert_eq!(language_label(" , "), None);
+    }
+
+    #[test]
+    fn test_language_names() {
+        assert_eq!(language_names("de_AT.UTF-8"), ["german"]);
+        assert!(language_names("pt-BR").contains(&"brazilian portuguese"));
+        assert!(language_names("C").is_empty());
+    }
+
+    #[test]
+    fn test_speaks() {
+        let wanted = ["german".to_string(), "english".to_string()];
+        assert!(speaks("English", &wanted));
+        assert!(speaks("french, german", &wanted));
+        assert!(!speaks("french", &wanted));
+        // Unknown language
+        assert!(speaks(" ", &wanted));
+    }
+
+    #[test]
+    fn test_country_filter_from_input() {
+        assert_eq!(CountryFilter::from_input(""), Some(CountryFilter::Auto));
+        assert_eq!(
+            CountryFilter::from_input(" at "),
+            Some(CountryFilter::Country("AT".to_string()))
+        );
+        assert_eq!(CountryFilter::from_input("Austria"), None);
+    }
+
+    #[test]
+    fn test_country_filter_resolve() {
+        assert_eq!(CountryFilter::Everywhere.resolve(), None);
+        assert_eq!(
+            CountryFilter::Country("FR".to_string()).resolve(),
+            Some("FR".to_string())
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! File logging
+//!
+//! Log events go to stderr and to daily-rotated files under
+//! `~/.local/state/cosmic-ext-applet-radio/` (`radio.<date>.log`); only the
+//! last [`MAX_LOG_FILES`] days are kept. The level comes from the config and
+//! can be changed while the applet runs. `RUST_LOG`, when set, overrides it.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+/// Number of daily log files kept
+pub const MAX_LOG_FILES: usize = 7;
+
+const LOG_PREFIX: &str = "radio";
+
+/// How much the applet logs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+/// Applet events at `level`, everything else (libcosmic, iced, ...) at warn
+#[must_use]
+pub fn directives(level: LogLevel) -> String {
+    format!("warn,{}={}", env!("CARGO_CRATE_NAME"), level.as_str())
+}
+
+/// Directory holding the log files
+#[must_use]
+pub fn log_dir() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::cache_dir)
+        .map(|dir| dir.join("cosmic-ext-applet-radio"))
+}
+
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+fn filter(level: LogLevel) -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(directives(level)))
+}
+
+/// Install the global subscriber. Keep the returned guard alive for the
+/// lifetime of the process so buffered lines are flushed on exit.
+pub fn init(level: LogLevel) -> Option<WorkerGuard> {
+    let (filter, handle) = reload::Layer::new(filter(level));
+    let _ = FILTER.set(handle);
+
+    let appender = log_dir().and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_PREFIX)
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .map_err(|e| eprintln!("file logging disabled: {e}"))
+            .ok()
+    });
+    let (file_layer, guard) = match appender {
+        Some(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (
+                Some(fmt::layer().with_ansi(false).with_writer(writer)),
+                Some(guard),
+            )
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(file_layer)
+        .init();
+    guard
+}
+
+/// Change the log level of the running subscriber
+pub fn set_level(level: LogLevel) {
+    if let Some(handle) = FILTER.get() {
+        if let Err(e) = handle.reload(filter(level)) {
+            tracing::warn!("Failed to change log level: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directives_scope_level_to_applet() {
+        assert_eq!(
+            directives(LogLevel::Debug),
+            format!("warn,{}=debug", env!("CARGO_CRATE_NAME"))
+        );
+        assert!(EnvFilter::try_new(directives(LogLevel::Trace)).is_ok());
+    }
+
+    #[test]
+    fn test_log_level_default_and_serde() {
+        assert_eq!(LogLevel::default(), LogLevel::Info);
+        let json = serde_json::to_string(&LogLevel::Warn).unwrap();
+        assert_eq!(json, "\"Warn\"");
+        assert_eq!(LogLevel::ALL.len(), 5);
+    }
+}
@@ -0,0 +1,140 @@
+//! Loudness normalization
+//!
+//! Stations are mastered at very different levels. By default mpv evens
+//! them out with `dynaudnorm`, which also flattens the dynamics within a
+//! track. Listeners who care about dynamics can instead normalize to a
+//! target loudness with `loudnorm` (EBU R128), apply the ReplayGain tags of
+//! files such as downloaded episodes, or switch normalization off. This is
+//! done by mpv; cast devices play the stream as it is.
+
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// Default target loudness, in LUFS
+pub const DEFAULT_TARGET: i8 = -18;
+
+/// Allowed target loudness, in LUFS
+pub const TARGET_RANGE: RangeInclusive<i8> = -30..=-10;
+
+/// Loudness ReplayGain 2 tags normalize to, in LUFS
+const REPLAYGAIN_REFERENCE: i8 = -18;
+
+/// Highest true peak `loudnorm` lets through, in dBTP
+const TRUE_PEAK: f32 = -1.5;
+
+/// How the volume of streams is evened out
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum Normalization {
+    /// `dynaudnorm`: even, at the cost of dynamics
+    #[default]
+    Dynamic,
+    /// `loudnorm` to the target loudness
+    Loudness,
+    /// ReplayGain tags, shifted to the target loudness
+    ReplayGain,
+    Off,
+}
+
+impl Normalization {
+    pub const ALL: [Normalization; 4] = [
+        Normalization::Dynamic,
+        Normalization::Loudness,
+        Normalization::ReplayGain,
+        Normalization::Off,
+    ];
+
+    /// Whether the target loudness applies
+    #[must_use]
+    pub fn has_target(self) -> bool {
+        matches!(self, Normalization::Loudness | Normalization::ReplayGain)
+    }
+}
+
+/// Loudness normalization settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoudnessConfig {
+    #[serde(default)]
+    pub mode: Normalization,
+    /// Target loudness in LUFS
+    #[serde(default = "default_target")]
+    pub target: i8,
+}
+
+fn default_target() -> i8 {
+    DEFAULT_TARGET
+}
+
+impl Default for LoudnessConfig {
+    fn default() -> Self {
+        Self {
+            mode: Normalization::default(),
+            target: DEFAULT_TARGET,
+        }
+    }
+}
+
+impl LoudnessConfig {
+    /// mpv options applying the normalization
+    #[must_use]
+    pub fn mpv_args(&self) -> Vec<String> {
+        let target = clamp_target(self.target);
+        match self.mode {
+            Normalization::Dynamic => vec!["--af=lavfi=[dynaudnorm]".to_string()],
+            Normalization::Loudness => {
+                vec![format!("--af=lavfi=[loudnorm=I={target}:TP={TRUE_PEAK}]")]
+            }
+            Normalization::ReplayGain => vec![
+                "--replaygain=track".to_string(),
+                format!("--replaygain-preamp={}", target - REPLAYGAIN_REFERENCE),
+            ],
+            Normalization::Off => Vec::new(),
+        }
+    }
+}
+
+/// Clamp a target loudness to [`TARGET_RANGE`]
+#[must_use]
+pub fn clamp_target(target: i8) -> i8 {
+    target.clamp(*TARGET_RANGE.start(), *TARGET_RANGE.end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mpv_args() {
+        let config = LoudnessConfig::default();
+        assert_eq!(config.mpv_args(), ["--af=lavfi=[dynaudnorm]"]);
+
+        let loudness = LoudnessConfig {
+            mode: Normalization::Loudness,
+            target: -23,
+        };
+        assert_eq!(loudness.mpv_args(), ["--af=lavfi=[loudnorm=I=-23:TP=-1.5]"]);
+
+        let replaygain = LoudnessConfig {
+            mode: Normalization::ReplayGain,
+            target: -14,
+        };
+        assert_eq!(
+            replaygain.mpv_args(),
+            ["--replaygain=track", "--replaygain-preamp=4"]
+        );
+
+        let off = LoudnessConfig {
+            mode: Normalization::Off,
+            ..config
+        };
+        assert!(off.mpv_args().is_empty());
+    }
+
+    #[test]
+    fn test_target_clamped() {
+        let loud = LoudnessConfig {
+            mode: Normalization::Loudness,
+            target: 0,
+        };
+        assert_eq!(loud.mpv_args(), ["--af=lavfi=[loudnorm=I=-10:TP=-1.5]"]);
+    }
+}
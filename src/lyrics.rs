@@ -0,0 +1,162 @@
+//! Lyrics for the current track
+//!
+//! Lyrics are looked up on LRCLIB (<https://lrclib.net>) by the artist and
+//! title parsed from the ICY stream title. LRCLIB returns plain lyrics and,
+//! for many songs, LRC-synced ones. A radio stream does not report a playback
+//! position, so synced lines are followed from the moment the title changed,
+//! which is usually within a few seconds of the song's start.
+
+use crate::api::http_client;
+use crate::coverart::TrackInfo;
+use crate::error::ApiError;
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::debug;
+
+const LRCLIB_GET: &str = "https://lrclib.net/api/get";
+
+/// Delay between redraws while following synced lyrics
+pub const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// One line of synced lyrics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncedLine {
+    /// Offset from the start of the song
+    pub at: Duration,
+    pub text: String,
+}
+
+/// Lyrics of a song
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Lyrics {
+    pub plain: String,
+    /// Time-stamped lines, in order; empty if only plain lyrics are known
+    pub synced: Vec<SyncedLine>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LrclibTrack {
+    #[serde(default)]
+    plain_lyrics: Option<String>,
+    #[serde(default)]
+    synced_lyrics: Option<String>,
+}
+
+/// Parse a `[mm:ss.xx]` time tag
+fn parse_timestamp(tag: &str) -> Option<Duration> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes: u64 = minutes.parse().ok()?;
+    let seconds: f64 = seconds.parse().ok()?;
+    (0.0..60.0)
+        .contains(&seconds)
+        .then(|| Duration::from_secs(minutes * 60) + Duration::from_secs_f64(seconds))
+}
+
+/// Parse LRC text. Lines without a time tag (metadata such as `[ar:...]`)
+/// are skipped; lines with several tags are repeated at each time.
+#[must_use]
+pub fn parse_lrc(text: &str) -> Vec<SyncedLine> {
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let mut rest = line.trim();
+        let mut times = Vec::new();
+        while let Some(tagged) = rest.strip_prefix('[') {
+            let Some((tag, after)) = tagged.split_once(']') else {
+                break;
+            };
+            match parse_timestamp(tag) {
+                Some(at) => times.push(at),
+                None => break,
+            }
+            rest = after;
+        }
+        for at in times {
+            lines.push(SyncedLine {
+                at,
+                text: rest.trim().to_string(),
+            });
+        }
+    }
+    lines.sort_by_key(|line| line.at);
+    lines
+}
+
+/// Index of the line being sung `elapsed` into the song
+#[must_use]
+pub fn current_line(lines: &[SyncedLine], elapsed: Duration) -> Option<usize> {
+    lines
+        .partition_point(|line| line.at <= elapsed)
+        .checked_sub(1)
+}
+
+/// Look up the lyrics of a track; `None` if LRCLIB has none
+pub async fn fetch(track: TrackInfo, timeout: Duration) -> Result<Option<Lyrics>, ApiError> {
+    debug!("Looking up lyrics for {:?}", track);
+    let response = http_client()
+        .get(LRCLIB_GET)
+        .query(&[
+            ("artist_name", track.artist.as_str()),
+            ("track_name", track.title.as_str()),
+        ])
+        .timeout(timeout)
+        .send()
+        .await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let track: LrclibTrack = response.error_for_status()?.json().await?;
+    let lyrics = Lyrics {
+        plain: track.plain_lyrics.unwrap_or_default(),
+        synced: track
+            .synced_lyrics
+            .as_deref()
+            .map(parse_lrc)
+            .unwrap_or_default(),
+    };
+    Ok((!lyrics.plain.trim().is_empty() || !lyrics.synced.is_empty()).then_some(lyrics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lrc() {
+        let lines = parse_lrc("[ar:Someone]\n[00:12.50]First\n[01:02.00][00:05.00] Chorus \nplain");
+        let texts: Vec<(u64, &str)> = lines
+            .iter()
+            .map(|l| (l.at.as_millis() as u64, l.text.as_str()))
+            .collect();
+        assert_eq!(
+            texts,
+            vec![(5_000, "Chorus"), (12_500, "First"), (62_000, "Chorus")]
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp_rejects_garbage() {
+        assert_eq!(parse_timestamp("ar:Someone"), None);
+        assert_eq!(parse_timestamp("00:75.00"), None);
+        assert_eq!(parse_timestamp("03:07"), Some(Duration::from_secs(187)));
+    }
+
+    #[test]
+    fn test_current_line() {
+        let lines = parse_lrc("[00:05.00]A\n[00:10.00]B");
+        assert_eq!(current_line(&lines, Duration::from_secs(2)), None);
+        assert_eq!(current_line(&lines, Duration::from_secs(5)), Some(0));
+        assert_eq!(current_line(&lines, Duration::from_secs(60)), Some(1));
+        assert_eq!(current_line(&[], Duration::from_secs(60)), None);
+    }
+
+    #[test]
+    fn test_lrclib_response() {
+        let track: LrclibTrack = serde_json::from_str(
+            r#"{"plainLyrics": "Hello", "syncedLyrics": null, "instrumental": false}"#,
+        )
+        .unwrap();
+        assert_eq!(track.plain_lyrics.as_deref(), Some("Hello"));
+        assert!(track.synced_lyrics.is_none());
+    }
+}
@@ -1,13 +1,198 @@
 mod api;
 mod app;
+mod appearance;
 mod audio;
+mod autopause;
+mod backup;
+mod bandwidth;
+mod bookmarks;
+mod cache;
+mod cast;
+mod channels;
+mod chromecast;
+mod clicks;
 mod config;
+mod control;
+mod coverart;
+mod diagnostics;
+mod discover;
+mod downloads;
+mod ducking;
+mod duplicates;
 mod error;
+mod favicons;
+mod filter;
+mod fuzzy;
+mod genres;
+mod health;
+mod hooks;
 mod i18n;
+mod icons;
+mod index;
+mod instance;
+mod ipfamily;
+mod kiosk;
+mod locale;
+mod logging;
+mod loudness;
+mod lyrics;
+mod migrations;
 mod mpris;
+mod mqtt;
+mod notify;
+mod nowplaying;
+mod opml;
+mod outputs;
+mod playlist;
+mod popularity;
+mod profiles;
+mod providers;
+mod quality;
+mod queue;
+mod quiet;
+mod ratelimit;
+mod recommend;
+mod recorder;
+mod redirect;
+mod refresh;
+mod reliability;
+mod replace;
+mod resume;
+mod schedule;
+mod secrets;
+mod selection;
+mod session;
+mod share;
+mod shutdown;
+mod sniff;
+mod startup;
+mod stats;
+mod streamauth;
+mod suspend;
+mod sync;
+mod ticker;
+mod tls;
+mod tracklog;
+mod visualizer;
+mod wheel;
 
 fn main() -> cosmic::iced::Result {
+    // Command-line modes: forward a URI to the running applet, export, back up, or restore, then exit
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let [flag, value] = args.as_slice() {
+        let result = match flag.as_str() {
+            "--open" => Some(
+                mpris::forward_open_uri(value)
+                    .map_err(|e| format!("failed to reach the radio applet over D-Bus: {e}")),
+            ),
+            "--export-opml" => {
+                Some(export_opml(value).map_err(|e| format!("failed to export favorites: {e}")))
+            }
+            "--export-history" => {
+                Some(export_history(value).map_err(|e| format!("failed to export history: {e}")))
+            }
+            "--backup" => {
+                Some(write_backup(value).map_err(|e| format!("failed to write backup: {e}")))
+            }
+            "--restore" => Some(restore_backup(value)),
+            _ => None,
+        };
+        if let Some(result) = result {
+            if let Err(e) = result {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+    }
+
+    let log_level = load_config()
+        .map(|(_, config)| config.log_level)
+        .unwrap_or_default();
+    let _log_guard = logging::init(log_level);
+
+    // Only one instance may control playback; a second one on another panel
+    // follows the first, any other second one raises it
+    let (role, _instance) = match instance::acquire() {
+        Ok(instance::Instance::Primary(connection)) => (instance::Role::Primary, Some(connection)),
+        Ok(instance::Instance::Secondary) if instance::in_panel() => {
+            tracing::info!("Radio applet already running, following it");
+            (instance::Role::Follower, None)
+        }
+        Ok(instance::Instance::Secondary) => {
+            tracing::info!("Radio applet already running, raising it");
+            if let Err(e) = mpris::forward_raise() {
+                tracing::warn!("Failed to raise the running applet: {}", e);
+            }
+            return Ok(());
+        }
+        Err(e) => {
+            tracing::warn!("Cannot check for a running instance: {}", e);
+            (instance::Role::Primary, None)
+        }
+    };
+
     let requested_languages = i18n_embed::DesktopLanguageRequester::requested_languages();
     i18n::init(&requested_languages);
-    cosmic::applet::run::<app::AppModel>(())
+    cosmic::applet::run::<app::AppModel>(role)
+}
+
+/// Write the saved favorites as OPML to `path` (`-` for stdout)
+fn export_opml(path: &str) -> std::io::Result<()> {
+    let (_, config) = load_config()?;
+    let document = opml::to_opml("Radio for COSMIC favorites", &config.favorites);
+
+    if path == "-" {
+        print!("{document}");
+        Ok(())
+    } else {
+        std::fs::write(path, document)
+    }
+}
+
+/// Write the listening history as CSV or JSON (by extension) to `path`
+fn export_history(path: &str) -> std::io::Result<()> {
+    let history = match stats::history_path() {
+        Some(log) => stats::load(&log)?,
+        None => Vec::new(),
+    };
+    let path = std::path::Path::new(path);
+    stats::export_to_file(&history, stats::ExportFormat::from_path(path), path)
+}
+
+fn load_config() -> std::io::Result<(cosmic::cosmic_config::Config, config::Config)> {
+    use cosmic::cosmic_config::{self, CosmicConfigEntry};
+    use cosmic::Application;
+
+    migrations::run();
+    let handler = cosmic_config::Config::new(app::AppModel::APP_ID, config::Config::VERSION)
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    let config = config::Config::get_entry(&handler).unwrap_or_else(|(_, c)| c);
+    Ok((handler, config))
+}
+
+/// Write a full backup of the persisted settings to `path`
+fn write_backup(path: &str) -> std::io::Result<()> {
+    let (_, config) = load_config()?;
+    backup::write(
+        std::path::Path::new(path),
+        &backup::Backup::from_disk(config),
+    )
+}
+
+/// Restore a backup in the running applet, or directly on disk if it is not running
+fn restore_backup(path: &str) -> Result<(), String> {
+    use cosmic::cosmic_config::CosmicConfigEntry;
+
+    let path = std::fs::canonicalize(path).map_err(|e| format!("{path}: {e}"))?;
+    let backup = backup::read(&path)?;
+    if mpris::forward_open_uri(&path.to_string_lossy()).is_ok() {
+        return Ok(());
+    }
+    backup.restore_data().map_err(|e| e.to_string())?;
+    let (handler, _) = load_config().map_err(|e| e.to_string())?;
+    backup
+        .config
+        .write_entry(&handler)
+        .map_err(|e| format!("failed to write config: {e:?}"))
 }
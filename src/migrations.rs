@@ -0,0 +1,194 @@
+//! Config schema migrations
+//!
+//! cosmic-config keeps each schema version in its own directory
+//! (`~/.config/cosmic/com.marcos.RadioApplet/v<N>/<field>`), so bumping
+//! [`Config::VERSION`] alone would start from an empty config. On startup the
+//! newest older version directory is carried forward instead: its fields are
+//! read as raw RON, upgraded by every [`Migration`] step in between, and
+//! written into the current version directory.
+//!
+//! Fields that need no change are copied as-is; new fields simply fall back
+//! to their serde defaults.
+
+use crate::config::Config;
+use cosmic::cosmic_config::CosmicConfigEntry;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+const APP_ID: &str = "com.marcos.RadioApplet";
+
+/// Raw config fields: file name → RON text
+pub type Fields = BTreeMap<String, String>;
+
+/// An upgrade step producing schema version `to` from `to - 1`
+// Only constructed by tests until the first real upgrade step is added
+#[allow(dead_code)]
+pub struct Migration {
+    pub to: u64,
+    pub apply: fn(&mut Fields),
+}
+
+/// Upgrade steps, oldest first. Add one whenever a field is renamed or its
+/// format changes, then bump `Config`'s `#[version]`.
+const MIGRATIONS: &[Migration] = &[];
+
+/// Apply every step after `from` up to and including `to`
+pub fn migrate(fields: &mut Fields, from: u64, to: u64, steps: &[Migration]) {
+    for step in steps.iter().filter(|s| s.to > from && s.to <= to) {
+        (step.apply)(fields);
+    }
+}
+
+fn config_root() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("cosmic").join(APP_ID))
+}
+
+fn version_dir(root: &Path, version: u64) -> PathBuf {
+    root.join(format!("v{version}"))
+}
+
+/// Newest version directory older than `current`
+fn previous_version(root: &Path, current: u64) -> Option<u64> {
+    fs::read_dir(root)
+        .ok()?
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name();
+            name.to_str()?.strip_prefix('v')?.parse::<u64>().ok()
+        })
+        .filter(|v| *v < current)
+        .max()
+}
+
+fn read_fields(dir: &Path) -> io::Result<Fields> {
+    let mut fields = Fields::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            if let Some(name) = entry.file_name().to_str() {
+                fields.insert(name.to_string(), fs::read_to_string(entry.path())?);
+            }
+        }
+    }
+    Ok(fields)
+}
+
+fn write_fields(dir: &Path, fields: &Fields) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for (name, value) in fields {
+        fs::write(dir.join(name), value)?;
+    }
+    Ok(())
+}
+
+/// Carry an older config forward into `root/v<current>` if that doesn't exist yet.
+/// Returns the version migrated from.
+pub fn upgrade_dir(root: &Path, current: u64, steps: &[Migration]) -> io::Result<Option<u64>> {
+    if version_dir(root, current).exists() {
+        return Ok(None);
+    }
+    let Some(from) = previous_version(root, current) else {
+        return Ok(None);
+    };
+    let mut fields = read_fields(&version_dir(root, from))?;
+    migrate(&mut fields, from, current, steps);
+    write_fields(&version_dir(root, current), &fields)?;
+    Ok(Some(from))
+}
+
+/// Upgrade the applet's config in place; call before loading it
+pub fn run() {
+    let Some(root) = config_root() else {
+        return;
+    };
+    match upgrade_dir(&root, Config::VERSION, MIGRATIONS) {
+        Ok(Some(from)) => info!("Migrated config from v{} to v{}", from, Config::VERSION),
+        Ok(None) => {}
+        Err(e) => warn!("Config migration failed, starting from defaults: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rename_volume(fields: &mut Fields) {
+        if let Some(value) = fields.remove("vol") {
+            fields.insert("volume".to_string(), value);
+        }
+    }
+
+    fn drop_legacy(fields: &mut Fields) {
+        fields.remove("legacy");
+    }
+
+    const STEPS: &[Migration] = &[
+        Migration {
+            to: 3,
+            apply: rename_volume,
+        },
+        Migration {
+            to: 4,
+            apply: drop_legacy,
+        },
+    ];
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("radio-migrate-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        root
+    }
+
+    #[test]
+    fn test_migrate_applies_steps_in_range() {
+        let mut fields = Fields::from([
+            ("vol".to_string(), "70".to_string()),
+            ("legacy".to_string(), "true".to_string()),
+        ]);
+        migrate(&mut fields, 2, 3, STEPS);
+        assert_eq!(fields.get("volume").map(String::as_str), Some("70"));
+        assert!(fields.contains_key("legacy"));
+
+        migrate(&mut fields, 3, 4, STEPS);
+        assert!(!fields.contains_key("legacy"));
+    }
+
+    #[test]
+    fn test_upgrade_dir_carries_newest_old_version() {
+        let root = temp_root("carry");
+        write_fields(
+            &version_dir(&root, 1),
+            &Fields::from([("vol".to_string(), "10".to_string())]),
+        )
+        .unwrap();
+        write_fields(
+            &version_dir(&root, 2),
+            &Fields::from([
+                ("vol".to_string(), "70".to_string()),
+                ("favorites".to_string(), "[]".to_string()),
+                ("legacy".to_string(), "true".to_string()),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(upgrade_dir(&root, 4, STEPS).unwrap(), Some(2));
+        let fields = read_fields(&version_dir(&root, 4)).unwrap();
+        assert_eq!(fields.get("volume").map(String::as_str), Some("70"));
+        assert_eq!(fields.get("favorites").map(String::as_str), Some("[]"));
+        assert!(!fields.contains_key("legacy"));
+
+        // Already current: nothing happens
+        assert_eq!(upgrade_dir(&root, 4, STEPS).unwrap(), None);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_upgrade_dir_fresh_install() {
+        let root = temp_root("fresh");
+        assert_eq!(upgrade_dir(&root, 9, STEPS).unwrap(), None);
+        assert!(!version_dir(&root, 9).exists());
+    }
+}
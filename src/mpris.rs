@@ -14,12 +14,34 @@ pub enum MprisCommand {
     SetVolume(f64),
     Raise,
     Quit,
+    OpenUri(String),
 }
 
+/// Well-known bus name of the applet's MPRIS server
+pub const BUS_NAME: &str = "org.mpris.MediaPlayer2.cosmic_ext_applet_radio";
+
+/// URI schemes accepted through `OpenUri`
+const SUPPORTED_URI_SCHEMES: &[&str] = &["http", "https", "radio", "file"];
+
+/// Playlist MIME types accepted through `OpenUri`
+const SUPPORTED_MIME_TYPES: &[&str] = &[
+    "audio/x-mpegurl",
+    "audio/mpegurl",
+    "application/vnd.apple.mpegurl",
+    "audio/x-scpls",
+    "text/x-opml",
+    "text/x-opml+xml",
+    "x-scheme-handler/radio",
+];
+
 /// State updates from the app to the MPRIS server
 #[derive(Debug, Clone)]
 pub enum MprisStateUpdate {
-    Playing { station: Box<Station> },
+    Playing {
+        station: Box<Station>,
+        /// Cover of the current track, replacing the station logo
+        art_url: Option<String>,
+    },
     Stopped,
     Volume(u8),
 }
@@ -44,8 +66,8 @@ pub fn volume_from_mpris(vol: f64) -> u8 {
     (vol.clamp(0.0, 1.0) * 100.0).round() as u8
 }
 
-/// Build MPRIS metadata from a Station
-pub fn build_metadata(station: &Station) -> Metadata {
+/// Build MPRIS metadata from a Station, with `art_url` overriding its logo
+pub fn build_metadata(station: &Station, art_url: Option<&str>) -> Metadata {
     let mut builder = Metadata::builder().title(&station.name);
 
     if !station.stationuuid.is_empty() {
@@ -56,7 +78,9 @@ pub fn build_metadata(station: &Station) -> Metadata {
         }
     }
 
-    if !station.favicon.is_empty() {
+    if let Some(art_url) = art_url {
+        builder = builder.art_url(art_url);
+    } else if !station.favicon.is_empty() {
         builder = builder.art_url(&station.favicon);
     }
 
@@ -114,6 +138,8 @@ async fn run_mpris_server(
         .can_seek(false)
         .can_go_next(false)
         .can_go_previous(false)
+        .supported_uri_schemes(SUPPORTED_URI_SCHEMES.iter().copied())
+        .supported_mime_types(SUPPORTED_MIME_TYPES.iter().copied())
         .build()
         .await?;
 
@@ -155,11 +181,17 @@ async fn run_mpris_server(
         });
     }
     {
-        let tx = cmd_tx;
+        let tx = cmd_tx.clone();
         player.connect_quit(move |_| {
             let _ = tx.send(MprisCommand::Quit);
         });
     }
+    {
+        let tx = cmd_tx;
+        player.connect_open_uri(move |_, uri| {
+            let _ = tx.send(MprisCommand::OpenUri(uri));
+        });
+    }
 
     debug!("MPRIS server started on D-Bus");
 
@@ -169,8 +201,8 @@ async fn run_mpris_server(
     // Process state updates from the app
     while let Some(update) = state_rx.recv().await {
         match update {
-            MprisStateUpdate::Playing { station } => {
-                let metadata = build_metadata(station.as_ref());
+            MprisStateUpdate::Playing { station, art_url } => {
+                let metadata = build_metadata(station.as_ref(), art_url.as_deref());
                 if let Err(e) = player.set_metadata(metadata).await {
                     warn!("Failed to set MPRIS metadata: {}", e);
                 }
@@ -200,6 +232,34 @@ async fn run_mpris_server(
     Ok(())
 }
 
+/// Ask an already running applet to open `uri` via its MPRIS `OpenUri` method.
+///
+/// Used by the URL/playlist handler (`cosmic-ext-applet-radio --open <uri>`).
+pub fn forward_open_uri(uri: &str) -> Result<(), mpris_server::zbus::Error> {
+    let connection = mpris_server::zbus::blocking::Connection::session()?;
+    connection.call_method(
+        Some(BUS_NAME),
+        "/org/mpris/MediaPlayer2",
+        Some("org.mpris.MediaPlayer2.Player"),
+        "OpenUri",
+        &(uri,),
+    )?;
+    Ok(())
+}
+
+/// Ask an already running applet to show its popup via MPRIS `Raise`
+pub fn forward_raise() -> Result<(), mpris_server::zbus::Error> {
+    let connection = mpris_server::zbus::blocking::Connection::session()?;
+    connection.call_method(
+        Some(BUS_NAME),
+        "/org/mpris/MediaPlayer2",
+        Some("org.mpris.MediaPlayer2"),
+        "Raise",
+        &(),
+    )?;
+    Ok(())
+}
+
 /// Create an iced Subscription that runs the MPRIS server and forwards events
 pub fn mpris_subscription() -> cosmic::iced::Subscription<MprisEvent> {
     cosmic::iced::Subscription::run(|| {
@@ -268,16 +328,21 @@ mod tests {
             tags: "ambient,electronic,chillout".to_string(),
             country: "USA".to_string(),
             language: "English".to_string(),
+            source: "Radio Browser".to_string(),
+            ..Default::default()
         };
 
-        let metadata = build_metadata(&station);
+        let metadata = build_metadata(&station, None);
         assert!(format!("{metadata:?}").contains("SomaFM"));
+
+        let metadata = build_metadata(&station, Some("file:///tmp/cover"));
+        assert!(format!("{metadata:?}").contains("file:///tmp/cover"));
     }
 
     #[test]
     fn test_build_metadata_empty_station() {
         let station = Station::default();
-        let _metadata = build_metadata(&station);
+        let _metadata = build_metadata(&station, None);
     }
 
     #[test]
@@ -286,7 +351,7 @@ mod tests {
             name: "Minimal Station".to_string(),
             ..Default::default()
         };
-        let _metadata = build_metadata(&station);
+        let _metadata = build_metadata(&station, None);
     }
 
     #[test]
@@ -296,6 +361,9 @@ mod tests {
 
         let cmd = MprisCommand::SetVolume(0.75);
         assert!(format!("{cmd:?}").contains("0.75"));
+
+        let cmd = MprisCommand::OpenUri("radio://example.com/live".to_string());
+        assert!(format!("{cmd:?}").contains("radio://example.com/live"));
     }
 
     #[test]
@@ -0,0 +1,340 @@
+//! MQTT state publishing for home automation
+//!
+//! When enabled, playback state is published as retained messages under a
+//! configurable topic prefix so dashboards (e.g. Home Assistant) can show what
+//! is playing. Optionally a command topic is subscribed to for remote control.
+//!
+//! Topics (with the default `cosmic-radio` prefix):
+//! - `cosmic-radio/availability` — `online` / `offline` (last will)
+//! - `cosmic-radio/state` — `playing` / `stopped`
+//! - `cosmic-radio/station`, `cosmic-radio/track`, `cosmic-radio/volume`
+//! - `cosmic-radio/status` — all of the above as one JSON object
+//! - `cosmic-radio/command` — `play`, `stop`, `toggle`, `volume_up`,
+//!   `volume_down` or `volume:<0-100>`
+//!
+//! The broker connection needs the `mqtt` Cargo feature (on by default).
+//! Without it the settings are kept but the subscription never connects.
+#![cfg_attr(not(feature = "mqtt"), allow(dead_code))]
+
+#[cfg(feature = "mqtt")]
+use crate::secrets;
+#[cfg(feature = "mqtt")]
+use futures::SinkExt;
+#[cfg(feature = "mqtt")]
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "mqtt")]
+use std::time::Duration;
+use tokio::sync::mpsc;
+#[cfg(feature = "mqtt")]
+use tracing::{debug, info, warn};
+
+/// MQTT connection settings
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MqttConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_host")]
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub username: String,
+    /// Legacy plaintext password, only read to migrate it into the keyring.
+    /// The password lives in the Secret Service under
+    /// [`secrets::MQTT_PASSWORD_KEY`].
+    #[serde(default, skip_serializing)]
+    pub password: String,
+    #[serde(default = "default_topic_prefix")]
+    pub topic_prefix: String,
+    /// Subscribe to `<prefix>/command` and accept remote control commands
+    #[serde(default)]
+    pub accept_commands: bool,
+}
+
+fn default_host() -> String {
+    "localhost".to_string()
+}
+
+fn default_port() -> u16 {
+    1883
+}
+
+fn default_topic_prefix() -> String {
+    "cosmic-radio".to_string()
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: default_host(),
+            port: default_port(),
+            username: String::new(),
+            password: String::new(),
+            topic_prefix: default_topic_prefix(),
+            accept_commands: false,
+        }
+    }
+}
+
+/// Snapshot of the player state published to the broker
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MqttState {
+    pub playing: bool,
+    pub station: Option<String>,
+    pub track: Option<String>,
+    pub volume: u8,
+}
+
+/// Remote control commands received on the command topic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MqttCommand {
+    Play,
+    Stop,
+    Toggle,
+    VolumeUp,
+    VolumeDown,
+    SetVolume(u8),
+}
+
+/// Events yielded by the MQTT subscription
+#[derive(Debug, Clone)]
+pub enum MqttEvent {
+    Ready(mpsc::UnboundedSender<MqttState>),
+    Command(MqttCommand),
+}
+
+fn topic(prefix: &str, name: &str) -> String {
+    format!("{}/{}", prefix.trim_end_matches('/'), name)
+}
+
+/// Parse a command topic payload
+pub fn parse_command(payload: &[u8]) -> Option<MqttCommand> {
+    let text = std::str::from_utf8(payload).ok()?.trim().to_lowercase();
+    match text.as_str() {
+        "play" => Some(MqttCommand::Play),
+        "stop" | "pause" => Some(MqttCommand::Stop),
+        "toggle" | "play_pause" => Some(MqttCommand::Toggle),
+        "volume_up" => Some(MqttCommand::VolumeUp),
+        "volume_down" => Some(MqttCommand::VolumeDown),
+        other => {
+            let value = other.strip_prefix("volume:")?.trim().parse::<u8>().ok()?;
+            Some(MqttCommand::SetVolume(value.min(100)))
+        }
+    }
+}
+
+/// Topic/payload pairs for a state snapshot
+fn state_messages(prefix: &str, state: &MqttState) -> Vec<(String, String)> {
+    let status = if state.playing { "playing" } else { "stopped" };
+    vec![
+        (topic(prefix, "state"), status.to_string()),
+        (topic(prefix, "station"), state.station.clone().unwrap_or_default()),
+        (topic(prefix, "track"), state.track.clone().unwrap_or_default()),
+        (topic(prefix, "volume"), state.volume.to_string()),
+        (
+            topic(prefix, "status"),
+            serde_json::json!({
+                "state": status,
+                "station": state.station,
+                "track": state.track,
+                "volume": state.volume,
+            })
+            .to_string(),
+        ),
+    ]
+}
+
+#[cfg(feature = "mqtt")]
+fn publish_state(client: &AsyncClient, prefix: &str, state: &MqttState) {
+    for (topic, payload) in state_messages(prefix, state) {
+        if let Err(e) = client.try_publish(topic, QoS::AtLeastOnce, true, payload) {
+            warn!("Failed to queue MQTT publish: {}", e);
+        }
+    }
+}
+
+/// Create an iced Subscription that keeps an MQTT connection open.
+///
+/// The subscription is keyed by its config, so changing any setting
+/// reconnects with the new values.
+#[cfg(feature = "mqtt")]
+pub fn mqtt_subscription(config: MqttConfig) -> cosmic::iced::Subscription<MqttEvent> {
+    cosmic::iced::Subscription::run_with_id(
+        config.clone(),
+        cosmic::iced::stream::channel(32, move |mut output| async move {
+            let prefix = config.topic_prefix.clone();
+            let availability = topic(&prefix, "availability");
+            let command_topic = topic(&prefix, "command");
+
+            let client_id = format!("cosmic-ext-applet-radio-{}", std::process::id());
+            let mut options = MqttOptions::new(client_id, config.host.clone(), config.port);
+            options.set_keep_alive(Duration::from_secs(30));
+            options.set_last_will(LastWill::new(
+                availability.clone(),
+                "offline",
+                QoS::AtLeastOnce,
+                true,
+            ));
+            if !config.username.is_empty() {
+                let password = match secrets::lookup(secrets::MQTT_PASSWORD_KEY).await {
+                    Ok(Some(password)) => password,
+                    Ok(None) => config.password.clone(),
+                    Err(e) => {
+                        warn!("Failed to read MQTT password from keyring: {}", e);
+                        config.password.clone()
+                    }
+                };
+                options.set_credentials(config.username.clone(), password);
+            }
+
+            let (client, mut eventloop) = AsyncClient::new(options, 32);
+            let (state_tx, mut state_rx) = mpsc::unbounded_channel::<MqttState>();
+            if output.send(MqttEvent::Ready(state_tx)).await.is_err() {
+                return;
+            }
+
+            let mut last_state: Option<MqttState> = None;
+            loop {
+                tokio::select! {
+                    update = state_rx.recv() => {
+                        let Some(state) = update else { break };
+                        publish_state(&client, &prefix, &state);
+                        last_state = Some(state);
+                    }
+                    event = eventloop.poll() => match event {
+                        Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                            info!("Connected to MQTT broker {}:{}", config.host, config.port);
+                            let _ = client.try_publish(availability.clone(), QoS::AtLeastOnce, true, "online");
+                            if config.accept_commands {
+                                if let Err(e) = client.try_subscribe(command_topic.clone(), QoS::AtLeastOnce) {
+                                    warn!("Failed to subscribe to {}: {}", command_topic, e);
+                                }
+                            }
+                            // Re-publish after (re)connecting so retained topics are current
+                            if let Some(state) = &last_state {
+                                publish_state(&client, &prefix, state);
+                            }
+                        }
+                        Ok(Event::Incoming(Packet::Publish(publish))) => {
+                            if publish.topic == command_topic {
+                                match parse_command(&publish.payload) {
+                                    Some(cmd) => {
+                                        debug!("MQTT command: {:?}", cmd);
+                                        if output.send(MqttEvent::Command(cmd)).await.is_err() {
+                                            break;
+                                        }
+                                    }
+                                    None => warn!("Ignoring unknown MQTT command payload"),
+                                }
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            warn!("MQTT connection error: {}", e);
+                            tokio::time::sleep(Duration::from_secs(5)).await;
+                        }
+                    }
+                }
+            }
+        }),
+    )
+}
+
+/// Builds without the `mqtt` feature have no broker connection
+#[cfg(not(feature = "mqtt"))]
+pub fn mqtt_subscription(_config: MqttConfig) -> cosmic::iced::Subscription<MqttEvent> {
+    cosmic::iced::Subscription::none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mqtt_config_default() {
+        let config = MqttConfig::default();
+        assert!(!config.enabled);
+        assert_eq!(config.host, "localhost");
+        assert_eq!(config.port, 1883);
+        assert_eq!(config.topic_prefix, "cosmic-radio");
+        assert!(!config.accept_commands);
+    }
+
+    #[test]
+    fn test_mqtt_config_deserialize_partial() {
+        let config: MqttConfig =
+            serde_json::from_str(r#"{"enabled": true, "host": "broker.lan"}"#).unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.host, "broker.lan");
+        assert_eq!(config.port, 1883);
+        assert_eq!(config.topic_prefix, "cosmic-radio");
+    }
+
+    #[test]
+    fn test_mqtt_password_never_serialized() {
+        let config = MqttConfig {
+            username: "radio".to_string(),
+            password: "hunter2".to_string(),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(!json.contains("hunter2"));
+        assert!(!json.contains("password"));
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command(b"play"), Some(MqttCommand::Play));
+        assert_eq!(parse_command(b" STOP \n"), Some(MqttCommand::Stop));
+        assert_eq!(parse_command(b"pause"), Some(MqttCommand::Stop));
+        assert_eq!(parse_command(b"toggle"), Some(MqttCommand::Toggle));
+        assert_eq!(parse_command(b"volume_up"), Some(MqttCommand::VolumeUp));
+        assert_eq!(parse_command(b"volume_down"), Some(MqttCommand::VolumeDown));
+        assert_eq!(parse_command(b"volume:42"), Some(MqttCommand::SetVolume(42)));
+        assert_eq!(parse_command(b"volume:250"), Some(MqttCommand::SetVolume(100)));
+    }
+
+    #[test]
+    fn test_parse_command_invalid() {
+        assert_eq!(parse_command(b"dance"), None);
+        assert_eq!(parse_command(b"volume:loud"), None);
+        assert_eq!(parse_command(&[0xff, 0xfe]), None);
+    }
+
+    #[test]
+    fn test_topic_trims_trailing_slash() {
+        assert_eq!(topic("home/radio/", "state"), "home/radio/state");
+        assert_eq!(topic("home/radio", "state"), "home/radio/state");
+    }
+
+    #[test]
+    fn test_state_messages() {
+        let state = MqttState {
+            playing: true,
+            station: Some("Jazz24".to_string()),
+            track: Some("Miles Davis - So What".to_string()),
+            volume: 70,
+        };
+        let messages = state_messages("cosmic-radio", &state);
+        assert!(messages.contains(&("cosmic-radio/state".to_string(), "playing".to_string())));
+        assert!(messages.contains(&("cosmic-radio/station".to_string(), "Jazz24".to_string())));
+        assert!(messages.contains(&("cosmic-radio/volume".to_string(), "70".to_string())));
+
+        let (_, status) = messages
+            .iter()
+            .find(|(t, _)| t == "cosmic-radio/status")
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(status).unwrap();
+        assert_eq!(json["state"], "playing");
+        assert_eq!(json["track"], "Miles Davis - So What");
+    }
+
+    #[test]
+    fn test_state_messages_stopped_clears_fields() {
+        let messages = state_messages("r", &MqttState::default());
+        assert!(messages.contains(&("r/state".to_string(), "stopped".to_string())));
+        assert!(messages.contains(&("r/station".to_string(), String::new())));
+    }
+}
@@ -0,0 +1,136 @@
+//! Desktop notifications
+//!
+//! Notifications are sent with `notify-send` (libnotify), which every COSMIC
+//! install ships, so the applet needs no D-Bus client of its own. A missing
+//! `notify-send` is logged and otherwise ignored.
+//!
+//! COSMIC's Do Not Disturb switch (the notification daemon's
+//! `do_not_disturb` cosmic-config key) is respected: informational
+//! notifications such as show reminders are dropped while it is on, and
+//! errors are held back and sent once it is turned off. The applet's own
+//! quiet hours (see [`quiet`](crate::quiet)) count as Do Not Disturb too.
+
+use cosmic::cosmic_config::{self, ConfigGet};
+use std::process::Command;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const APP_NAME: &str = "Radio for COSMIC";
+
+const APP_ICON: &str = "com.marcos.RadioApplet";
+
+/// Config of COSMIC's notification daemon
+const NOTIFICATIONS_CONFIG_ID: &str = "com.system76.CosmicNotifications";
+
+/// Delay between checks whether held-back errors can be sent
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What a notification is about, deciding what Do Not Disturb does with it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Dropped during Do Not Disturb
+    Info,
+    /// Held back until Do Not Disturb ends
+    Error,
+}
+
+/// Arguments for `notify-send`
+fn notify_args(summary: &str, body: &str) -> Vec<String> {
+    vec![
+        format!("--app-name={APP_NAME}"),
+        format!("--icon={APP_ICON}"),
+        "--".to_string(),
+        summary.to_string(),
+        body.to_string(),
+    ]
+}
+
+/// Whether COSMIC's Do Not Disturb is on; off if it cannot be read
+#[must_use]
+pub fn do_not_disturb() -> bool {
+    cosmic_config::Config::new(NOTIFICATIONS_CONFIG_ID, 1)
+        .and_then(|config| config.get::<bool>("do_not_disturb"))
+        .unwrap_or(false)
+}
+
+/// Show a notification without blocking the caller
+fn send(summary: &str, body: &str) {
+    let args = notify_args(summary, body);
+    debug!("Sending notification: {}", summary);
+    std::thread::spawn(move || {
+        if let Err(e) = Command::new("notify-send").args(args).status() {
+            warn!("Failed to send notification: {}", e);
+        }
+    });
+}
+
+/// Sends notifications, minding Do Not Disturb
+#[derive(Debug, Default)]
+pub struct Notifier {
+    /// Errors held back during Do Not Disturb, oldest first
+    held: Vec<(String, String)>,
+    /// Quiet hours are on
+    quiet: bool,
+}
+
+impl Notifier {
+    /// Send now, or drop or hold back the notification during Do Not Disturb
+    pub fn notify(&mut self, kind: Kind, summary: &str, body: &str) {
+        self.notify_unless(self.quiet || do_not_disturb(), kind, summary, body);
+    }
+
+    /// Start or end quiet hours
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.quiet = quiet;
+    }
+
+    fn notify_unless(&mut self, dnd: bool, kind: Kind, summary: &str, body: &str) {
+        match (dnd, kind) {
+            (false, _) => send(summary, body),
+            (true, Kind::Info) => debug!("Do Not Disturb, dropping notification: {}", summary),
+            (true, Kind::Error) => {
+                debug!("Do Not Disturb, holding back notification: {}", summary);
+                self.held.push((summary.to_string(), body.to_string()));
+            }
+        }
+    }
+
+    /// Whether errors are waiting for Do Not Disturb to end
+    #[must_use]
+    pub fn has_held(&self) -> bool {
+        !self.held.is_empty()
+    }
+
+    /// Send the held-back errors if Do Not Disturb is off
+    pub fn flush(&mut self) {
+        if self.has_held() && !self.quiet && !do_not_disturb() {
+            for (summary, body) in self.held.drain(..) {
+                send(&summary, &body);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dnd_drops_info_and_holds_errors() {
+        let mut notifier = Notifier::default();
+        notifier.notify_unless(true, Kind::Info, "Late show starts soon", "");
+        assert!(!notifier.has_held());
+        notifier.notify_unless(true, Kind::Error, "Playback stopped", "Jazz FM");
+        assert_eq!(
+            notifier.held,
+            [("Playback stopped".to_string(), "Jazz FM".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_notify_args_end_options() {
+        let args = notify_args("-Late show", "Starts in 5 minutes");
+        assert_eq!(args[2], "--");
+        assert_eq!(args[3], "-Late show");
+    }
+}
@@ -0,0 +1,94 @@
+//! Now-playing text output for streaming overlays
+//!
+//! Writes a single line such as `Artist - Title (Station)` to a user-chosen
+//! path whenever playback changes. Regular files are replaced atomically so
+//! readers like OBS never see a half-written line; FIFOs are written to
+//! without blocking and silently skipped while no reader is attached.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::fs::{FileTypeExt, OpenOptionsExt};
+use std::path::Path;
+
+/// Format the now-playing line; empty when nothing is playing
+#[must_use]
+pub fn format_now_playing(playing: bool, station: Option<&str>, track: Option<&str>) -> String {
+    if !playing {
+        return String::new();
+    }
+    match (track, station) {
+        (Some(track), Some(station)) => format!("{track} ({station})"),
+        (Some(track), None) => track.to_string(),
+        (None, Some(station)) => station.to_string(),
+        (None, None) => String::new(),
+    }
+}
+
+/// Write `text` to `path`, handling both regular files and FIFOs
+pub fn write_now_playing(path: &Path, text: &str) -> io::Result<()> {
+    let is_fifo = fs::metadata(path)
+        .map(|m| m.file_type().is_fifo())
+        .unwrap_or(false);
+
+    if is_fifo {
+        let mut fifo = match OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)
+        {
+            Ok(f) => f,
+            // ENXIO: no process has the FIFO open for reading
+            Err(e) if e.raw_os_error() == Some(libc::ENXIO) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        return fifo.write_all(format!("{text}\n").as_bytes());
+    }
+
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, text)?;
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_track_and_station() {
+        assert_eq!(
+            format_now_playing(true, Some("Jazz24"), Some("Miles Davis - So What")),
+            "Miles Davis - So What (Jazz24)"
+        );
+    }
+
+    #[test]
+    fn test_format_station_only() {
+        assert_eq!(format_now_playing(true, Some("Jazz24"), None), "Jazz24");
+    }
+
+    #[test]
+    fn test_format_stopped_is_empty() {
+        assert_eq!(format_now_playing(false, Some("Jazz24"), Some("Song")), "");
+        assert_eq!(format_now_playing(true, None, None), "");
+    }
+
+    #[test]
+    fn test_write_regular_file_replaces_contents() {
+        let path = std::env::temp_dir().join(format!("radio-np-{}.txt", std::process::id()));
+        write_now_playing(&path, "First line").unwrap();
+        write_now_playing(&path, "Second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Second");
+        assert!(!path.with_extension("tmp").exists());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_write_fifo_without_reader_is_ok() {
+        let path = std::env::temp_dir().join(format!("radio-np-{}.fifo", std::process::id()));
+        let c_path = std::ffi::CString::new(path.to_str().unwrap()).unwrap();
+        // SAFETY: c_path is a valid NUL-terminated path
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+        assert!(write_now_playing(&path, "No reader").is_ok());
+        let _ = fs::remove_file(&path);
+    }
+}
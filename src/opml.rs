@@ -0,0 +1,242 @@
+//! OPML import and export of station lists
+//!
+//! OPML is what podcast apps and several radio directories export. Stream
+//! outlines (`type="audio"`/`"link"` with a `URL`) become stations; podcast
+//! feeds (`type="rss"`) are skipped since the applet only plays live streams.
+//! Exports use standard `audio` outlines plus the station's directory fields
+//! as extra attributes, so a round trip keeps favorites intact.
+
+use crate::api::Station;
+use crate::cast::{xml_escape, xml_unescape};
+use crate::playlist;
+
+/// Split the attributes of an element start tag, e.g. `text="a" URL='b'`
+fn parse_attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut rest = tag;
+
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq].trim().to_lowercase();
+        let after = rest[eq + 1..].trim_start();
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(end) = after[1..].find(quote) else {
+            break;
+        };
+        attrs.push((name, xml_unescape(&after[1..=end])));
+        rest = &after[end + 2..];
+    }
+
+    attrs
+}
+
+/// Start tags of all `<outline>` elements, quote-aware so `>` inside values is fine
+fn outline_tags(text: &str) -> Vec<&str> {
+    let mut tags = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("<outline") {
+        let body = &rest[start + "<outline".len()..];
+        if !body.starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>') {
+            rest = body;
+            continue;
+        }
+        let mut quote = None;
+        let end = body.char_indices().find_map(|(i, c)| {
+            match (quote, c) {
+                (None, '"' | '\'') => quote = Some(c),
+                (Some(q), c) if c == q => quote = None,
+                (None, '>') => return Some(i),
+                _ => {}
+            }
+            None
+        });
+        let Some(end) = end else { break };
+        tags.push(body[..end].trim_end_matches('/'));
+        rest = &body[end + 1..];
+    }
+
+    tags
+}
+
+fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.trim())
+        .filter(|v| !v.is_empty())
+}
+
+/// Parse an OPML document into stations
+pub fn parse_opml(text: &str) -> Vec<Station> {
+    outline_tags(text)
+        .into_iter()
+        .filter_map(|tag| {
+            let attrs = parse_attributes(tag);
+            if attr(&attrs, "type").is_some_and(|t| t.eq_ignore_ascii_case("rss")) {
+                return None;
+            }
+            let url = attr(&attrs, "url")?;
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return None;
+            }
+            let field = |name: &str| attr(&attrs, name).unwrap_or_default().to_string();
+            let name = attr(&attrs, "text")
+                .or_else(|| attr(&attrs, "title"))
+                .unwrap_or(url)
+                .to_string();
+
+            Some(Station {
+                stationuuid: attr(&attrs, "stationuuid")
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("uri:{url}")),
+                name,
+                url: url.to_string(),
+                url_resolved: url.to_string(),
+                homepage: field("homepage"),
+                favicon: field("favicon"),
+                tags: field("tags"),
+                country: field("country"),
+                language: field("language"),
+                source: "OPML".to_string(),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Whether a URI handed to the applet points at an OPML file
+#[must_use]
+pub fn is_opml_uri(uri: &str) -> bool {
+    uri.trim().to_lowercase().ends_with(".opml")
+}
+
+/// Read stations from an OPML `file://` URL or path
+pub fn import_file(uri: &str) -> Result<Vec<Station>, String> {
+    let path = playlist::local_path(uri.trim())?;
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let stations = parse_opml(&text);
+    if stations.is_empty() {
+        return Err(format!("No stations found in {}", path.display()));
+    }
+    Ok(stations)
+}
+
+/// Render stations as an OPML 2.0 document
+#[must_use]
+pub fn to_opml(title: &str, stations: &[Station]) -> String {
+    let mut out =
+        String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n");
+    out.push_str(&format!(
+        "  <head>\n    <title>{}</title>\n  </head>\n  <body>\n",
+        xml_escape(title)
+    ));
+
+    for station in stations {
+        let url = if station.url_resolved.is_empty() {
+            &station.url
+        } else {
+            &station.url_resolved
+        };
+        out.push_str(&format!(
+            "    <outline type=\"audio\" text=\"{}\" URL=\"{}\"",
+            xml_escape(&station.name),
+            xml_escape(url)
+        ));
+        for (name, value) in [
+            ("stationuuid", &station.stationuuid),
+            ("homepage", &station.homepage),
+            ("favicon", &station.favicon),
+            ("tags", &station.tags),
+            ("country", &station.country),
+            ("language", &station.language),
+        ] {
+            if !value.is_empty() {
+                out.push_str(&format!(" {}=\"{}\"", name, xml_escape(value)));
+            }
+        }
+        out.push_str("/>\n");
+    }
+
+    out.push_str("  </body>\n</opml>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_opml_streams_and_categories() {
+        let text = r#"<?xml version="1.0"?>
+<opml version="1.0">
+  <head><title>Stations</title></head>
+  <body>
+    <outline text="Jazz">
+      <outline type="audio" text="Jazz24" URL="https://live.wostreaming.net/direct/ppm-jazz24aac-ibc1"/>
+      <outline type="link" text="Smooth &amp; Cool" URL='http://stream.example.com/smooth'/>
+    </outline>
+  </body>
+</opml>"#;
+        let stations = parse_opml(text);
+        assert_eq!(stations.len(), 2);
+        assert_eq!(stations[0].name, "Jazz24");
+        assert_eq!(
+            stations[0].stationuuid,
+            "uri:https://live.wostreaming.net/direct/ppm-jazz24aac-ibc1"
+        );
+        assert_eq!(stations[1].name, "Smooth & Cool");
+        assert_eq!(stations[1].url_resolved, "http://stream.example.com/smooth");
+    }
+
+    #[test]
+    fn test_parse_opml_skips_podcast_feeds() {
+        let text = r#"<opml><body>
+<outline type="rss" text="A Podcast" xmlUrl="https://feeds.example.com/pod.xml" URL="https://example.com"/>
+<outline text="No URL"/>
+<outline type="audio" text="Local" URL="file:///tmp/x.mp3"/>
+</body></opml>"#;
+        assert!(parse_opml(text).is_empty());
+    }
+
+    #[test]
+    fn test_parse_opml_gt_inside_attribute() {
+        let text = r#"<outline text="A > B" URL="https://a.example/s"/>"#;
+        let stations = parse_opml(text);
+        assert_eq!(stations.len(), 1);
+        assert_eq!(stations[0].name, "A > B");
+    }
+
+    #[test]
+    fn test_is_opml_uri() {
+        assert!(is_opml_uri("file:///home/me/Podcasts.OPML"));
+        assert!(is_opml_uri("/tmp/stations.opml"));
+        assert!(!is_opml_uri("/tmp/stations.m3u"));
+    }
+
+    #[test]
+    fn test_opml_roundtrip_keeps_fields() {
+        let station = Station {
+            stationuuid: "abc-123".to_string(),
+            name: "Rock \"Live\"".to_string(),
+            url: "https://a.example/s?x=1&y=2".to_string(),
+            url_resolved: "https://a.example/s?x=1&y=2".to_string(),
+            homepage: "https://a.example".to_string(),
+            tags: "rock,live".to_string(),
+            country: "Germany".to_string(),
+            ..Default::default()
+        };
+        let opml = to_opml("Favorites", std::slice::from_ref(&station));
+        assert!(opml.contains("<title>Favorites</title>"));
+
+        let parsed = parse_opml(&opml);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].stationuuid, station.stationuuid);
+        assert_eq!(parsed[0].name, station.name);
+        assert_eq!(parsed[0].url_resolved, station.url_resolved);
+        assert_eq!(parsed[0].tags, station.tags);
+        assert_eq!(parsed[0].country, station.country);
+    }
+}
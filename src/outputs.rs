@@ -0,0 +1,259 @@
+//! Pausing when the output device goes away
+//!
+//! When Bluetooth headphones disconnect or a USB DAC is unplugged, PipeWire
+//! moves the radio to whatever sink is left, usually the laptop speakers.
+//! Sinks are listed with `pw-dump`, polled every two seconds; if the sink
+//! the radio was playing on disappears, playback stops instead, and can
+//! optionally resume once that sink is back.
+
+use crate::audio;
+use futures::SinkExt;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Settings for output device changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DevicePauseConfig {
+    /// Stop the radio when the sink it plays on disappears
+    #[serde(default)]
+    pub enabled: bool,
+    /// Resume once that sink is back
+    #[serde(default)]
+    pub auto_resume: bool,
+}
+
+/// Audio sinks as PipeWire lists them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Outputs {
+    /// Node names of the sinks
+    pub sinks: Vec<String>,
+    /// Node name of the default sink
+    pub default: Option<String>,
+}
+
+impl Outputs {
+    /// Read the sinks and the default sink from a `pw-dump` document
+    #[must_use]
+    pub fn from_dump(dump: &serde_json::Value) -> Self {
+        let mut outputs = Self::default();
+        let Some(objects) = dump.as_array() else {
+            return outputs;
+        };
+        for object in objects {
+            match object["type"].as_str() {
+                Some("PipeWire:Interface:Node") => {
+                    let props = &object["info"]["props"];
+                    if props["media.class"] == "Audio/Sink" {
+                        if let Some(name) = props["node.name"].as_str() {
+                            outputs.sinks.push(name.to_string());
+                        }
+                    }
+                }
+                Some("PipeWire:Interface:Metadata")
+                    if object["props"]["metadata.name"] == "default" =>
+                {
+                    outputs.default = default_sink(&object["metadata"]);
+                }
+                _ => {}
+            }
+        }
+        outputs
+    }
+
+    /// Whether a sink of that name exists
+    #[must_use]
+    pub fn has(&self, sink: &str) -> bool {
+        self.sinks.iter().any(|name| name == sink)
+    }
+
+    /// The sink the radio plays on: the configured device, else the default
+    #[must_use]
+    pub fn active<'a>(&'a self, configured: &'a str) -> Option<&'a str> {
+        if configured.is_empty() {
+            self.default.as_deref()
+        } else {
+            Some(configured)
+        }
+    }
+}
+
+/// `default.audio.sink` from the default metadata; its value is a JSON
+/// object, or a string holding one in older PipeWire versions
+fn default_sink(metadata: &serde_json::Value) -> Option<String> {
+    let entry = metadata
+        .as_array()?
+        .iter()
+        .find(|entry| entry["key"] == "default.audio.sink")?;
+    let value = match &entry["value"] {
+        serde_json::Value::String(text) => serde_json::from_str(text).ok()?,
+        value => value.clone(),
+    };
+    value["name"].as_str().map(str::to_string)
+}
+
+/// What the app should do after the sinks changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceAction {
+    None,
+    /// The named sink was lost while playing
+    Pause(String),
+    Resume,
+}
+
+/// Decide how to react to the sinks changing from `previous` to `current`.
+/// `paused_for` is the sink playback was stopped for, if any.
+#[must_use]
+pub fn decide(
+    config: DevicePauseConfig,
+    previous: Option<&Outputs>,
+    current: &Outputs,
+    configured: &str,
+    is_playing: bool,
+    paused_for: Option<&str>,
+) -> DeviceAction {
+    if !config.enabled {
+        return DeviceAction::None;
+    }
+    if is_playing {
+        // A configured device PipeWire doesn't know (e.g. an ALSA name) is
+        // never reported lost
+        let lost = previous
+            .and_then(|previous| {
+                previous
+                    .active(configured)
+                    .filter(|sink| previous.has(sink))
+            })
+            .filter(|sink| !current.has(sink));
+        match lost {
+            Some(sink) => DeviceAction::Pause(sink.to_string()),
+            None => DeviceAction::None,
+        }
+    } else if config.auto_resume && paused_for.is_some_and(|sink| current.has(sink)) {
+        DeviceAction::Resume
+    } else {
+        DeviceAction::None
+    }
+}
+
+/// Subscription yielding the sinks whenever they change
+pub fn outputs_subscription() -> cosmic::iced::Subscription<Outputs> {
+    cosmic::iced::Subscription::run(|| {
+        cosmic::iced::stream::channel(4, |mut output| async move {
+            let mut last = None;
+            loop {
+                match tokio::task::spawn_blocking(audio::pw_dump).await {
+                    Ok(Ok(dump)) => {
+                        let outputs = Outputs::from_dump(&dump);
+                        if last.as_ref() != Some(&outputs) {
+                            debug!("Audio sinks: {:?}", outputs);
+                            last = Some(outputs.clone());
+                            if output.send(outputs).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Ok(Err(e)) => {
+                        warn!("Output device watch disabled: {}", e);
+                        return;
+                    }
+                    Err(e) => warn!("Sink query panicked: {}", e),
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn outputs(sinks: &[&str], default: &str) -> Outputs {
+        Outputs {
+            sinks: sinks.iter().map(|s| s.to_string()).collect(),
+            default: Some(default.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_from_dump() {
+        let dump = json!([
+            {
+                "type": "PipeWire:Interface:Node",
+                "info": { "props": { "media.class": "Audio/Sink", "node.name": "speakers" } }
+            },
+            {
+                "type": "PipeWire:Interface:Node",
+                "info": { "props": { "media.class": "Stream/Output/Audio", "node.name": "mpv" } }
+            },
+            {
+                "type": "PipeWire:Interface:Metadata",
+                "props": { "metadata.name": "default" },
+                "metadata": [
+                    { "key": "default.audio.sink", "value": "{\"name\":\"speakers\"}" }
+                ]
+            }
+        ]);
+        assert_eq!(
+            Outputs::from_dump(&dump),
+            outputs(&["speakers"], "speakers")
+        );
+    }
+
+    #[test]
+    fn test_decide() {
+        let config = DevicePauseConfig {
+            enabled: true,
+            auto_resume: true,
+        };
+        let both = outputs(
+            &["speakers", "bluez_output.headphones"],
+            "bluez_output.headphones",
+        );
+        let speakers = outputs(&["speakers"], "speakers");
+        assert_eq!(
+            decide(config, Some(&both), &speakers, "", true, None),
+            DeviceAction::Pause("bluez_output.headphones".to_string())
+        );
+        // Switching the default to a sink that still exists keeps playing
+        let switched = outputs(&["speakers", "bluez_output.headphones"], "speakers");
+        assert_eq!(
+            decide(config, Some(&both), &switched, "", true, None),
+            DeviceAction::None
+        );
+        assert_eq!(
+            decide(config, Some(&both), &speakers, "hw:1,0", true, None),
+            DeviceAction::None
+        );
+        assert_eq!(
+            decide(
+                config,
+                Some(&speakers),
+                &both,
+                "",
+                false,
+                Some("bluez_output.headphones")
+            ),
+            DeviceAction::Resume
+        );
+        let no_resume = DevicePauseConfig {
+            auto_resume: false,
+            ..config
+        };
+        assert_eq!(
+            decide(
+                no_resume,
+                Some(&speakers),
+                &both,
+                "",
+                false,
+                Some("bluez_output.headphones")
+            ),
+            DeviceAction::None
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Playlist files and `radio://` links
+//!
+//! Turns whatever the desktop hands to the URL/MIME handler — a local
+//! `.m3u`/`.pls` file, a `radio://` link, or a plain stream URL — into a
+//! [`Station`] the applet can play.
+
+use crate::api::Station;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// A single entry of a parsed playlist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistEntry {
+    pub url: String,
+    pub title: Option<String>,
+}
+
+fn is_stream_url(value: &str) -> bool {
+    value.starts_with("http://") || value.starts_with("https://")
+}
+
+/// Parse an M3U/M3U8 playlist (with or without `#EXTINF` titles)
+pub fn parse_m3u(text: &str) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut pending_title = None;
+
+    for line in text.lines().map(str::trim) {
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            pending_title = info
+                .split_once(',')
+                .map(|(_, title)| title.trim().to_string())
+                .filter(|t| !t.is_empty());
+        } else if is_stream_url(line) {
+            entries.push(PlaylistEntry {
+                url: line.to_string(),
+                title: pending_title.take(),
+            });
+        }
+    }
+
+    entries
+}
+
+/// Parse a PLS playlist (`FileN=` / `TitleN=` pairs)
+pub fn parse_pls(text: &str) -> Vec<PlaylistEntry> {
+    let mut files: Vec<(u32, String)> = Vec::new();
+    let mut titles: Vec<(u32, String)> = Vec::new();
+
+    for line in text.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let value = value.trim();
+        if let Some(n) = key.strip_prefix("file").and_then(|n| n.parse().ok()) {
+            if is_stream_url(value) {
+                files.push((n, value.to_string()));
+            }
+        } else if let Some(n) = key.strip_prefix("title").and_then(|n| n.parse().ok()) {
+            titles.push((n, value.to_string()));
+        }
+    }
+
+    files.sort_by_key(|(n, _)| *n);
+    files
+        .into_iter()
+        .map(|(n, url)| PlaylistEntry {
+            url,
+            title: titles
+                .iter()
+                .find(|(t, _)| *t == n)
+                .map(|(_, title)| title.clone())
+                .filter(|t| !t.is_empty()),
+        })
+        .collect()
+}
+
+/// Parse any supported playlist format, detecting PLS by its header
+pub fn parse_playlist(text: &str) -> Vec<PlaylistEntry> {
+    if text.trim_start().to_lowercase().starts_with("[playlist]") {
+        parse_pls(text)
+    } else {
+        parse_m3u(text)
+    }
+}
+
+/// Map a `radio://` link to the stream URL it points at.
+///
+/// Both `radio://https://host/path` and `radio://host/path` (assumed HTTPS)
+/// are accepted.
+pub fn radio_link_to_url(link: &str) -> Option<String> {
+    let rest = link.strip_prefix("radio:")?.trim_start_matches('/');
+    if rest.is_empty() {
+        return None;
+    }
+    let candidate = if let Some(stripped) = rest
+        .strip_prefix("https:")
+        .or_else(|| rest.strip_prefix("http:"))
+    {
+        let scheme = if rest.starts_with("https:") { "https" } else { "http" };
+        format!("{scheme}://{}", stripped.trim_start_matches('/'))
+    } else {
+        format!("https://{rest}")
+    };
+    Url::parse(&candidate).ok().map(|u| u.to_string())
+}
+
+fn station_from_entry(entry: PlaylistEntry) -> Station {
+    let name = entry.title.clone().unwrap_or_else(|| {
+        Url::parse(&entry.url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_else(|| entry.url.clone())
+    });
+    Station {
+        stationuuid: format!("uri:{}", entry.url),
+        name,
+        url: entry.url.clone(),
+        url_resolved: entry.url,
+        ..Default::default()
+    }
+}
+
+/// Local file referenced by a `file://` URL or plain path
+pub fn local_path(uri: &str) -> Result<PathBuf, String> {
+    match Url::parse(uri) {
+        Ok(url) if url.scheme() == "file" => url
+            .to_file_path()
+            .map_err(|_| format!("Invalid file URL: {uri}")),
+        _ => Ok(Path::new(uri).to_path_buf()),
+    }
+}
+
+/// Resolve a URI handed to the applet into a playable station
+pub fn resolve_open_uri(uri: &str) -> Result<Station, String> {
+    let uri = uri.trim();
+
+    if uri.starts_with("radio:") {
+        let url = radio_link_to_url(uri).ok_or_else(|| format!("Invalid radio link: {uri}"))?;
+        return Ok(station_from_entry(PlaylistEntry { url, title: None }));
+    }
+
+    if is_stream_url(uri) {
+        return Ok(station_from_entry(PlaylistEntry {
+            url: uri.to_string(),
+            title: None,
+        }));
+    }
+
+    let path = local_path(uri)?;
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    parse_playlist(&text)
+        .into_iter()
+        .next()
+        .map(station_from_entry)
+        .ok_or_else(|| format!("No stream found in {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_m3u_extended() {
+        let text = "#EXTM3U\n#EXTINF:-1,Groove Salad\nhttps://ice1.somafm.com/groovesalad-128-mp3\n";
+        assert_eq!(
+            parse_m3u(text),
+            vec![PlaylistEntry {
+                url: "https://ice1.somafm.com/groovesalad-128-mp3".to_string(),
+                title: Some("Groove Salad".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_m3u_plain_skips_non_urls() {
+        let text = "# comment\nsong.mp3\nhttp://a.example/stream\r\nhttp://b.example/stream\n";
+        let urls: Vec<String> = parse_m3u(text).into_iter().map(|e| e.url).collect();
+        assert_eq!(urls, vec!["http://a.example/stream", "http://b.example/stream"]);
+    }
+
+    #[test]
+    fn test_parse_pls() {
+        let text = "[playlist]\nNumberOfEntries=2\nFile2=http://b.example/stream\nTitle2=Backup\nFile1=http://a.example/stream\nTitle1=Main\nLength1=-1\nVersion=2\n";
+        let entries = parse_pls(text);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].url, "http://a.example/stream");
+        assert_eq!(entries[0].title.as_deref(), Some("Main"));
+        assert_eq!(entries[1].title.as_deref(), Some("Backup"));
+    }
+
+    #[test]
+    fn test_parse_playlist_detects_format() {
+        assert_eq!(parse_playlist("[playlist]\nFile1=http://a.example/s\n").len(), 1);
+        assert_eq!(parse_playlist("http://a.example/s\n").len(), 1);
+    }
+
+    #[test]
+    fn test_radio_link_to_url() {
+        assert_eq!(
+            radio_link_to_url("radio://stream.example.com/live").as_deref(),
+            Some("https://stream.example.com/live")
+        );
+        assert_eq!(
+            radio_link_to_url("radio://http://stream.example.com:8000/live").as_deref(),
+            Some("http://stream.example.com:8000/live")
+        );
+        assert_eq!(
+            radio_link_to_url("radio:https://stream.example.com/live").as_deref(),
+            Some("https://stream.example.com/live")
+        );
+        assert_eq!(radio_link_to_url("radio://"), None);
+    }
+
+    #[test]
+    fn test_resolve_open_uri_stream_url() {
+        let station = resolve_open_uri("https://stream.example.com/live").unwrap();
+        assert_eq!(station.url_resolved, "https://stream.example.com/live");
+        assert_eq!(station.name, "stream.example.com");
+        assert_eq!(station.stationuuid, "uri:https://stream.example.com/live");
+    }
+
+    #[test]
+    fn test_resolve_open_uri_local_file() {
+        let path = std::env::temp_dir().join(format!("radio-test-{}.pls", std::process::id()));
+        std::fs::write(&path, "[playlist]\nFile1=http://a.example/stream\nTitle1=Example FM\n").unwrap();
+        let file_url = Url::from_file_path(&path).unwrap().to_string();
+
+        let station = resolve_open_uri(&file_url).unwrap();
+        assert_eq!(station.name, "Example FM");
+        assert_eq!(station.url_resolved, "http://a.example/stream");
+
+        let station = resolve_open_uri(path.to_str().unwrap()).unwrap();
+        assert_eq!(station.name, "Example FM");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_resolve_open_uri_missing_file() {
+        assert!(resolve_open_uri("/nonexistent/radio.m3u").is_err());
+    }
+}
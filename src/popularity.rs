@@ -0,0 +1,88 @@
+//! Popularity badges and result ordering
+//!
+//! radio-browser.info reports votes and the clicks of the last 24 hours for
+//! each station. They are shown as compact badges on result rows ("▲1.2k"),
+//! and results can be ordered by them, which helps pick the canonical stream
+//! among many similarly named stations.
+
+use crate::api::Station;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+/// Order of search results
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    /// As returned by the providers (local stations first)
+    #[default]
+    Relevance,
+    /// Most votes first
+    Votes,
+    /// Most recent clicks first
+    Clicks,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 3] = [SortOrder::Relevance, SortOrder::Votes, SortOrder::Clicks];
+
+    /// Sort `stations` in place; equal stations keep their relative order
+    pub fn sort(self, stations: &mut [&Station]) {
+        match self {
+            SortOrder::Relevance => {}
+            SortOrder::Votes => stations.sort_by_key(|s| Reverse(s.votes)),
+            SortOrder::Clicks => stations.sort_by_key(|s| Reverse(s.clickcount)),
+        }
+    }
+}
+
+/// Short form of a count: `950`, `1.2k`, `12k`, `3.4M`
+#[must_use]
+pub fn format_count(count: u32) -> String {
+    fn scaled(value: f64, suffix: &str) -> String {
+        if value < 10.0 {
+            let text = format!("{value:.1}");
+            format!("{}{suffix}", text.trim_end_matches(".0"))
+        } else {
+            format!("{}{suffix}", value.floor())
+        }
+    }
+
+    match count {
+        0..=999 => count.to_string(),
+        1_000..=999_999 => scaled(f64::from(count / 100) / 10.0, "k"),
+        _ => scaled(f64::from(count / 100_000) / 10.0, "M"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_count() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(1_000), "1k");
+        assert_eq!(format_count(1_249), "1.2k");
+        assert_eq!(format_count(12_345), "12k");
+        assert_eq!(format_count(999_999), "999k");
+        assert_eq!(format_count(3_456_789), "3.4M");
+    }
+
+    #[test]
+    fn test_sort_by_votes_is_stable() {
+        let station = |name: &str, votes: u32| Station {
+            name: name.to_string(),
+            votes,
+            ..Default::default()
+        };
+        let (a, b, c) = (station("a", 5), station("b", 50), station("c", 5));
+        let mut stations = vec![&a, &b, &c];
+
+        SortOrder::Relevance.sort(&mut stations);
+        assert_eq!(stations[0].name, "a");
+
+        SortOrder::Votes.sort(&mut stations);
+        let names: Vec<&str> = stations.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["b", "a", "c"]);
+    }
+}
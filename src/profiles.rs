@@ -0,0 +1,114 @@
+//! Named profiles ("Work", "Home", ...)
+//!
+//! The default profile keeps using the main config's `favorites` and
+//! `volume`; every named profile stores its own copy of them in a separate
+//! cosmic-config document (`com.marcos.RadioApplet.Profile.<slug>`), so
+//! switching never touches the other profiles' data.
+
+use crate::api::Station;
+use crate::config::Config;
+use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+const APP_ID: &str = "com.marcos.RadioApplet";
+
+/// Per-profile data
+#[derive(Debug, Clone, CosmicConfigEntry, Eq, PartialEq, Serialize, Deserialize)]
+#[version = 1]
+pub struct ProfileData {
+    #[serde(default)]
+    pub favorites: Vec<Station>,
+    #[serde(default)]
+    pub volume: u8, // 0-100
+}
+
+impl Default for ProfileData {
+    fn default() -> Self {
+        Self {
+            favorites: Vec::new(),
+            volume: 50,
+        }
+    }
+}
+
+/// Config-id-safe form of a profile name
+#[must_use]
+pub fn slug(name: &str) -> String {
+    name.trim()
+        .chars()
+        .filter_map(|c| {
+            if c.is_ascii_alphanumeric() {
+                Some(c.to_ascii_lowercase())
+            } else if c.is_whitespace() || c == '-' || c == '_' {
+                Some('_')
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn handler(name: &str) -> Result<cosmic_config::Config, cosmic_config::Error> {
+    cosmic_config::Config::new(
+        &format!("{}.Profile.{}", APP_ID, slug(name)),
+        ProfileData::VERSION,
+    )
+}
+
+/// Load a named profile, falling back to defaults for missing fields
+#[must_use]
+pub fn load(name: &str) -> ProfileData {
+    match handler(name) {
+        Ok(handler) => ProfileData::get_entry(&handler).unwrap_or_else(|(errs, data)| {
+            warn!("Errors loading profile {}: {:?}", name, errs);
+            data
+        }),
+        Err(e) => {
+            warn!("Cannot open profile {}: {:?}", name, e);
+            ProfileData::default()
+        }
+    }
+}
+
+/// Load the config's active profile into it, returning the default profile's
+/// favorites and volume that it replaced. Unknown profiles fall back to default.
+pub fn activate(config: &mut Config) -> Option<ProfileData> {
+    if config.active_profile.is_empty() {
+        return None;
+    }
+    if !config.profiles.contains(&config.active_profile) {
+        warn!("Unknown profile {}, using default", config.active_profile);
+        config.active_profile.clear();
+        return None;
+    }
+    let data = load(&config.active_profile);
+    Some(ProfileData {
+        favorites: std::mem::replace(&mut config.favorites, data.favorites),
+        volume: std::mem::replace(&mut config.volume, data.volume),
+    })
+}
+
+/// Persist a named profile
+pub fn save(name: &str, data: &ProfileData) -> Result<(), cosmic_config::Error> {
+    data.write_entry(&handler(name)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("Work"), "work");
+        assert_eq!(slug(" Late Night-Jazz "), "late_night_jazz");
+        assert_eq!(slug("Café/Home"), "cafhome");
+    }
+
+    #[test]
+    fn test_profile_data_default() {
+        let data = ProfileData::default();
+        assert!(data.favorites.is_empty());
+        assert_eq!(data.volume, 50);
+    }
+}
@@ -0,0 +1,467 @@
+//! Pluggable station providers
+//!
+//! The radio-browser.info directory is always available as the built-in
+//! provider. Additional curated sources (the SomaFM channel list or a
+//! user-supplied JSON station list) are registered through
+//! [`Config::providers`](crate::config::Config) and queried alongside it.
+//! Results are merged in provider order and labeled with their source.
+
+use crate::api::{self, RequestOptions, Station, MAX_RESPONSE_SIZE};
+use crate::error::ApiError;
+use futures::future::{join_all, BoxFuture};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::Instant;
+use tracing::{debug, debug_span, info_span, warn, Instrument};
+
+/// Label used for stations coming from radio-browser.info
+pub const RADIO_BROWSER_LABEL: &str = "Radio Browser";
+
+/// Label used for stations coming from the SomaFM channel list
+pub const SOMAFM_LABEL: &str = "SomaFM";
+
+/// SomaFM public channel list
+const SOMAFM_CHANNELS_URL: &str = "https://api.somafm.com/channels.json";
+
+/// Kind of an additional, config-registered provider
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ProviderKind {
+    /// SomaFM channel list (no URL needed)
+    #[default]
+    SomaFm,
+    /// JSON array of stations using the radio-browser schema
+    JsonUrl,
+}
+
+/// An additional station provider registered via config
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct ProviderConfig {
+    /// Label shown next to stations from this provider
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub kind: ProviderKind,
+    /// Source URL (required for `JsonUrl`, ignored for `SomaFm`)
+    #[serde(default)]
+    pub url: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A source of radio stations that can be searched by name
+pub trait StationProvider: Send + Sync {
+    /// Label attached to every station returned by this provider
+    fn label(&self) -> &str;
+
+    /// Search the provider for at most `options.limit` stations matching `query`
+    fn search<'a>(
+        &'a self,
+        query: &'a str,
+        options: RequestOptions,
+    ) -> BoxFuture<'a, Result<Vec<Station>, ApiError>>;
+}
+
+/// The built-in radio-browser.info provider
+pub struct RadioBrowserProvider {
+    /// Country whose stations are listed first
+    pub country_code: Option<String>,
+}
+
+impl StationProvider for RadioBrowserProvider {
+    fn label(&self) -> &str {
+        RADIO_BROWSER_LABEL
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a str,
+        options: RequestOptions,
+    ) -> BoxFuture<'a, Result<Vec<Station>, ApiError>> {
+        Box::pin(async move {
+            let country = self.country_code.clone();
+            Ok(api::search_stations(query.to_string(), country, options).await?)
+        })
+    }
+}
+
+/// SomaFM channels, filtered locally by title, genre, and description
+pub struct SomaFmProvider {
+    label: String,
+}
+
+#[derive(Deserialize)]
+struct SomaFmChannels {
+    #[serde(default)]
+    channels: Vec<SomaFmChannel>,
+}
+
+#[derive(Deserialize)]
+struct SomaFmChannel {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    genre: String,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    playlists: Vec<SomaFmPlaylist>,
+}
+
+#[derive(Deserialize)]
+struct SomaFmPlaylist {
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    format: String,
+}
+
+impl SomaFmChannel {
+    fn into_station(self, label: &str) -> Station {
+        // Prefer MP3 playlists for the widest mpv compatibility
+        let url = self
+            .playlists
+            .iter()
+            .find(|p| p.format == "mp3")
+            .or_else(|| self.playlists.first())
+            .map(|p| p.url.clone())
+            .unwrap_or_default();
+
+        Station {
+            stationuuid: format!("somafm:{}", self.id),
+            homepage: format!("https://somafm.com/{}/", self.id),
+            name: format!("SomaFM - {}", self.title),
+            url: url.clone(),
+            url_resolved: url,
+            favicon: self.image,
+            tags: self.genre.replace('|', ","),
+            country: "USA".to_string(),
+            language: "English".to_string(),
+            source: label.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn matches(&self, query: &str) -> bool {
+        [&self.title, &self.genre, &self.description]
+            .iter()
+            .any(|field| field.to_lowercase().contains(query))
+    }
+}
+
+fn parse_somafm(bytes: &[u8], query: &str, label: &str) -> Result<Vec<Station>, ApiError> {
+    let query = query.trim().to_lowercase();
+    let channels: SomaFmChannels = serde_json::from_slice(bytes)?;
+    Ok(channels
+        .channels
+        .into_iter()
+        .filter(|c| c.matches(&query))
+        .map(|c| c.into_station(label))
+        .collect())
+}
+
+impl StationProvider for SomaFmProvider {
+    fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a str,
+        options: RequestOptions,
+    ) -> BoxFuture<'a, Result<Vec<Station>, ApiError>> {
+        Box::pin(async move {
+            let bytes = fetch_limited(SOMAFM_CHANNELS_URL, &options).await?;
+            let mut stations = parse_somafm(&bytes, query, &self.label)?;
+            stations.truncate(options.limit as usize);
+            Ok(stations)
+        })
+    }
+}
+
+/// A user-supplied JSON station list, filtered locally by name and tags
+pub struct JsonUrlProvider {
+    label: String,
+    url: String,
+}
+
+fn parse_json_list(bytes: &[u8], query: &str, label: &str) -> Result<Vec<Station>, ApiError> {
+    let query = query.trim().to_lowercase();
+    Ok(api::parse_stations(bytes)?
+        .into_iter()
+        .filter(|s| {
+            s.name.to_lowercase().contains(&query) || s.tags.to_lowercase().contains(&query)
+        })
+        .map(|mut s| {
+            s.source = label.to_string();
+            s
+        })
+        .collect())
+}
+
+impl StationProvider for JsonUrlProvider {
+    fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a str,
+        options: RequestOptions,
+    ) -> BoxFuture<'a, Result<Vec<Station>, ApiError>> {
+        Box::pin(async move {
+            let bytes = fetch_limited(&self.url, &options).await?;
+            let mut stations = parse_json_list(&bytes, query, &self.label)?;
+            stations.truncate(options.limit as usize);
+            Ok(stations)
+        })
+    }
+}
+
+/// Fetch a URL, rejecting bodies larger than `MAX_RESPONSE_SIZE`
+async fn fetch_limited(url: &str, options: &RequestOptions) -> Result<Vec<u8>, ApiError> {
+    let response = api::http_client()
+        .get(url)
+        .timeout(options.timeout)
+        .send()
+        .await?
+        .error_for_status()?;
+    if let Some(content_length) = response.content_length() {
+        if content_length as usize > MAX_RESPONSE_SIZE {
+            return Err(ApiError::InvalidResponse(format!(
+                "response exceeds size limit: {content_length} bytes"
+            )));
+        }
+    }
+
+    let bytes = response.bytes().await?;
+    if bytes.len() > MAX_RESPONSE_SIZE {
+        return Err(ApiError::InvalidResponse(format!(
+            "response exceeds size limit: {} bytes",
+            bytes.len()
+        )));
+    }
+    Ok(bytes.to_vec())
+}
+
+/// Build the provider list: radio-browser first, then enabled config entries
+pub fn from_config(
+    configs: &[ProviderConfig],
+    country_code: Option<String>,
+) -> Vec<Box<dyn StationProvider>> {
+    let mut providers: Vec<Box<dyn StationProvider>> =
+        vec![Box::new(RadioBrowserProvider { country_code })];
+
+    for config in configs.iter().filter(|c| c.enabled) {
+        match config.kind {
+            ProviderKind::SomaFm => {
+                let label = if config.name.is_empty() {
+                    SOMAFM_LABEL.to_string()
+                } else {
+                    config.name.clone()
+                };
+                providers.push(Box::new(SomaFmProvider { label }));
+            }
+            ProviderKind::JsonUrl => {
+                if config.url.is_empty() {
+                    warn!("Skipping JSON provider '{}' without a URL", config.name);
+                    continue;
+                }
+                let label = if config.name.is_empty() {
+                    config.url.clone()
+                } else {
+                    config.name.clone()
+                };
+                providers.push(Box::new(JsonUrlProvider {
+                    label,
+                    url: config.url.clone(),
+                }));
+            }
+        }
+    }
+
+    providers
+}
+
+/// Query all providers concurrently and merge their results.
+///
+/// Results keep provider order and are deduplicated by station UUID. An error
+/// is only returned when every provider failed. Runs in a `search` span, each
+/// provider's query in a `provider` span.
+pub async fn search_all(
+    providers: Vec<Box<dyn StationProvider>>,
+    query: String,
+    options: RequestOptions,
+) -> Result<Vec<Station>, ApiError> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let span = info_span!("search", query = %query);
+    let started = Instant::now();
+    let result = merge_results(&providers, &query, options)
+        .instrument(span.clone())
+        .await;
+    span.in_scope(|| {
+        debug!(
+            duration_ms = started.elapsed().as_millis() as u64,
+            results = result.as_ref().map_or(0, Vec::len),
+            "Search finished"
+        );
+    });
+    result
+}
+
+async fn merge_results(
+    providers: &[Box<dyn StationProvider>],
+    query: &str,
+    options: RequestOptions,
+) -> Result<Vec<Station>, ApiError> {
+    let results = join_all(providers.iter().map(|p| {
+        p.search(query, options)
+            .instrument(debug_span!("provider", provider = p.label()))
+    }))
+    .await;
+
+    let _dedupe = debug_span!("dedupe").entered();
+    let mut merged = Vec::new();
+    let mut seen = HashSet::new();
+    let mut first_error = None;
+    let mut any_ok = false;
+
+    for (provider, result) in providers.iter().zip(results) {
+        match result {
+            Ok(stations) => {
+                any_ok = true;
+                debug!("Provider {} returned {} stations", provider.label(), stations.len());
+                for mut station in stations {
+                    if station.source.is_empty() {
+                        station.source = provider.label().to_string();
+                    }
+                    if station.stationuuid.is_empty() || seen.insert(station.stationuuid.clone()) {
+                        merged.push(station);
+                    }
+                }
+            }
+            Err(e) => {
+                warn!("Provider {} failed: {}", provider.label(), e);
+                if first_error.is_none() {
+                    first_error = Some(e);
+                }
+            }
+        }
+    }
+
+    match first_error {
+        Some(e) if !any_ok => Err(e),
+        _ => Ok(merged),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOMAFM_JSON: &str = r#"{"channels": [
+        {"id": "groovesalad", "title": "Groove Salad", "description": "A nicely chilled plate of ambient beats",
+         "genre": "ambient|electronica", "image": "https://api.somafm.com/img/groovesalad120.png",
+         "playlists": [{"url": "https://api.somafm.com/groovesalad.pls", "format": "mp3", "quality": "highest"}]},
+        {"id": "defcon", "title": "DEF CON Radio", "description": "Music for hacking",
+         "genre": "electronica", "image": "",
+         "playlists": [{"url": "https://api.somafm.com/defcon.pls", "format": "aac", "quality": "highest"}]}
+    ]}"#;
+
+    #[test]
+    fn test_from_config_always_includes_radio_browser() {
+        let providers = from_config(&[], None);
+        assert_eq!(providers.len(), 1);
+        assert_eq!(providers[0].label(), RADIO_BROWSER_LABEL);
+    }
+
+    #[test]
+    fn test_from_config_skips_disabled_and_missing_url() {
+        let configs = vec![
+            ProviderConfig {
+                kind: ProviderKind::SomaFm,
+                enabled: false,
+                ..Default::default()
+            },
+            ProviderConfig {
+                name: "Broken".to_string(),
+                kind: ProviderKind::JsonUrl,
+                enabled: true,
+                ..Default::default()
+            },
+            ProviderConfig {
+                name: "Mine".to_string(),
+                kind: ProviderKind::JsonUrl,
+                url: "https://example.com/stations.json".to_string(),
+                enabled: true,
+            },
+        ];
+        let providers = from_config(&configs, None);
+        let labels: Vec<&str> = providers.iter().map(|p| p.label()).collect();
+        assert_eq!(labels, vec![RADIO_BROWSER_LABEL, "Mine"]);
+    }
+
+    #[test]
+    fn test_provider_config_deserialize_defaults_enabled() {
+        let config: ProviderConfig = serde_json::from_str(r#"{"kind": "SomaFm"}"#).unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.kind, ProviderKind::SomaFm);
+    }
+
+    #[test]
+    fn test_parse_somafm_filters_and_labels() {
+        let stations = parse_somafm(SOMAFM_JSON.as_bytes(), "Ambient", SOMAFM_LABEL).unwrap();
+        assert_eq!(stations.len(), 1);
+        assert_eq!(stations[0].stationuuid, "somafm:groovesalad");
+        assert_eq!(stations[0].name, "SomaFM - Groove Salad");
+        assert_eq!(stations[0].url_resolved, "https://api.somafm.com/groovesalad.pls");
+        assert_eq!(stations[0].tags, "ambient,electronica");
+        assert_eq!(stations[0].source, SOMAFM_LABEL);
+    }
+
+    #[test]
+    fn test_parse_somafm_falls_back_to_first_playlist() {
+        let stations = parse_somafm(SOMAFM_JSON.as_bytes(), "def con", SOMAFM_LABEL).unwrap();
+        assert_eq!(stations.len(), 1);
+        assert_eq!(stations[0].url_resolved, "https://api.somafm.com/defcon.pls");
+    }
+
+    #[test]
+    fn test_parse_json_list_handles_nulls_and_filters() {
+        let json = r#"[
+            {"stationuuid": "a", "name": "Jazz Corner", "url_resolved": "https://a.example/stream", "tags": null},
+            {"stationuuid": "b", "name": "Rock Hour", "tags": "rock,jazz"},
+            {"stationuuid": "c", "name": "News", "tags": "talk"}
+        ]"#;
+        let stations = parse_json_list(json.as_bytes(), "jazz", "Mine").unwrap();
+        let ids: Vec<&str> = stations.iter().map(|s| s.stationuuid.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert!(stations.iter().all(|s| s.source == "Mine"));
+    }
+
+    #[test]
+    fn test_parse_json_list_invalid_json() {
+        assert!(parse_json_list(b"not json", "x", "Mine").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_all_empty_query() {
+        let result = search_all(
+            from_config(&[], None),
+            "  ".to_string(),
+            RequestOptions::default(),
+        )
+        .await;
+        assert!(result.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+//! Stream quality
+//!
+//! radio-browser.info lists codec and bitrate per stream. They are shown as a
+//! chip such as "128k AAC", flagged when the bitrate is very low. Many
+//! stations are listed several times with different streams (bitrates,
+//! codecs, mounts); playing one of them picks the playable variant that best
+//! fits [`Config::stream_preference`](crate::config::Config).
+
+use crate::api::Station;
+use crate::share::{self, ShareTarget};
+use serde::{Deserialize, Serialize};
+
+/// Bitrates below this (in kbit/s) are flagged as low quality
+pub const LOW_BITRATE_KBPS: u32 = 64;
+
+/// Which variant of a station listed several times to play
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum StreamPreference {
+    /// The one that was clicked
+    #[default]
+    AsListed,
+    /// Highest bitrate
+    BestQuality,
+    /// Lowest known bitrate, for metered connections
+    DataSaver,
+}
+
+impl StreamPreference {
+    pub const ALL: [StreamPreference; 3] = [
+        StreamPreference::AsListed,
+        StreamPreference::BestQuality,
+        StreamPreference::DataSaver,
+    ];
+}
+
+/// Whether the stream is known to have a very low bitrate
+#[must_use]
+pub fn is_low_quality(station: &Station) -> bool {
+    station.bitrate > 0 && station.bitrate < LOW_BITRATE_KBPS
+}
+
+/// Short quality label, e.g. "128k AAC", "⚠ 32k MP3", or "OGG"
+#[must_use]
+pub fn chip(station: &Station) -> Option<String> {
+    let codec = station.codec.trim().to_uppercase();
+    let label = match (station.bitrate, codec.is_empty()) {
+        (0, true) => return None,
+        (0, false) => codec,
+        (bitrate, true) => format!("{bitrate}k"),
+        (bitrate, false) => format!("{bitrate}k {codec}"),
+    };
+    Some(if is_low_quality(station) {
+        format!("⚠ {label}")
+    } else {
+        label
+    })
+}
+
+fn same_station(a: &Station, b: &Station) -> bool {
+    let normalize = |name: &str| name.trim().to_lowercase();
+    !a.name.trim().is_empty() && normalize(&a.name) == normalize(&b.name)
+}
+
+/// Whether the station has a stream URL the player can open
+#[must_use]
+pub fn is_playable(station: &Station) -> bool {
+    share::link(station, ShareTarget::Stream).is_some()
+}
+
+/// The listed variant of `station` (same name, playable) that fits
+/// `preference` better than `station` itself, if any
+#[must_use]
+pub fn pick_variant<'a>(
+    station: &Station,
+    candidates: impl IntoIterator<Item = &'a Station>,
+    preference: StreamPreference,
+) -> Option<&'a Station> {
+    let variants = candidates.into_iter().filter(|c| {
+        c.stationuuid != station.stationuuid && same_station(c, station) && is_playable(c)
+    });
+    match preference {
+        StreamPreference::AsListed => None,
+        StreamPreference::BestQuality => variants
+            .filter(|c| c.bitrate > station.bitrate)
+            .max_by_key(|c| c.bitrate),
+        // Unknown bitrates (0) might be anything, so only known ones qualify
+        StreamPreference::DataSaver => variants
+            .filter(|c| c.bitrate > 0 && (station.bitrate == 0 || c.bitrate < station.bitrate))
+            .min_by_key(|c| c.bitrate),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn station(uuid: &str, name: &str, codec: &str, bitrate: u32) -> Station {
+        Station {
+            stationuuid: uuid.to_string(),
+            name: name.to_string(),
+            codec: codec.to_string(),
+            bitrate,
+            url: format!("http://example.com/{uuid}"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_chip() {
+        assert_eq!(
+            chip(&station("a", "A", "aac", 128)).as_deref(),
+            Some("128k AAC")
+        );
+        assert_eq!(
+            chip(&station("a", "A", "MP3", 32)).as_deref(),
+            Some("⚠ 32k MP3")
+        );
+        assert_eq!(chip(&station("a", "A", "OGG", 0)).as_deref(), Some("OGG"));
+        assert_eq!(chip(&station("a", "A", "", 0)), None);
+    }
+
+    #[test]
+    fn test_pick_variant() {
+        let low = station("1", "Jazz FM", "MP3", 64);
+        let high = station("2", " jazz fm", "AAC", 192);
+        let mid = station("3", "Jazz FM", "MP3", 128);
+        let other = station("4", "Rock FM", "MP3", 320);
+        let mut unplayable = station("5", "Jazz FM", "MP3", 320);
+        unplayable.url = "file:///stream".to_string();
+        let candidates = [low.clone(), high.clone(), mid.clone(), other, unplayable];
+        let pick = |from: &Station, preference| {
+            pick_variant(from, &candidates, preference).map(|s| s.stationuuid.as_str())
+        };
+
+        assert_eq!(pick(&low, StreamPreference::AsListed), None);
+        assert_eq!(pick(&low, StreamPreference::BestQuality), Some("2"));
+        assert_eq!(pick(&high, StreamPreference::BestQuality), None);
+        assert_eq!(pick(&high, StreamPreference::DataSaver), Some("1"));
+        assert_eq!(pick(&low, StreamPreference::DataSaver), None);
+    }
+}
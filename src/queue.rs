@@ -0,0 +1,147 @@
+//! A queue of stations played one after another
+//!
+//! Stations are added from their rows or from a selection, e.g. a page of
+//! search results or a genre. "Play queue" starts the first; each following
+//! station plays after the configured dwell time, or when "Next" is
+//! pressed. Playing anything else by hand pauses the tour, leaving the
+//! rest queued.
+
+use crate::api::Station;
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+/// Default seconds each queued station plays; 0 advances only on "Next"
+pub const DEFAULT_DWELL_SECS: u32 = 60;
+
+/// Allowed dwell times in seconds
+pub const DWELL_SECS_RANGE: RangeInclusive<u32> = 0..=600;
+
+/// How often to check whether the next station is due
+pub const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Clamp a dwell time to [`DWELL_SECS_RANGE`]
+#[must_use]
+pub fn clamp_dwell(secs: u32) -> u32 {
+    secs.clamp(*DWELL_SECS_RANGE.start(), *DWELL_SECS_RANGE.end())
+}
+
+/// Stations waiting to be played, and the queued station playing
+#[derive(Debug, Clone, Default)]
+pub struct Queue {
+    stations: VecDeque<Station>,
+    /// UUID of the station taken from the queue last, and since when it plays
+    playing: Option<(String, Instant)>,
+}
+
+impl Queue {
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.stations.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stations.is_empty()
+    }
+
+    /// Queued stations, next first
+    pub fn stations(&self) -> impl Iterator<Item = &Station> {
+        self.stations.iter()
+    }
+
+    /// Queue `stations` not queued yet; returns how many were added
+    pub fn extend(&mut self, stations: impl IntoIterator<Item = Station>) -> usize {
+        let mut added = 0;
+        for station in stations {
+            if !self
+                .stations
+                .iter()
+                .any(|queued| queued.stationuuid == station.stationuuid)
+            {
+                self.stations.push_back(station);
+                added += 1;
+            }
+        }
+        added
+    }
+
+    pub fn clear(&mut self) {
+        self.stations.clear();
+        self.playing = None;
+    }
+
+    /// Take the next station to play
+    pub fn take_next(&mut self) -> Option<Station> {
+        self.stations.pop_front()
+    }
+
+    /// Note that the station taken from the queue went on air at `now`, as
+    /// `stationuuid` (another stream variant may have been picked)
+    pub fn started(&mut self, stationuuid: String, now: Instant) {
+        self.playing = Some((stationuuid, now));
+    }
+
+    /// Whether the queue is being played: the station playing came from it
+    #[must_use]
+    pub fn is_touring(&self, playing: Option<&str>) -> bool {
+        self.playing
+            .as_ref()
+            .is_some_and(|(uuid, _)| Some(uuid.as_str()) == playing)
+    }
+
+    /// Whether the next station should start at `now`, `playing` being the
+    /// station on air
+    #[must_use]
+    pub fn is_due(&self, now: Instant, dwell_secs: u32, playing: Option<&str>) -> bool {
+        dwell_secs > 0
+            && !self.stations.is_empty()
+            && self.is_touring(playing)
+            && self.playing.as_ref().is_some_and(|(_, since)| {
+                now.saturating_duration_since(*since) >= Duration::from_secs(dwell_secs.into())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn station(uuid: &str) -> Station {
+        Station {
+            stationuuid: uuid.to_string(),
+            ..Station::default()
+        }
+    }
+
+    #[test]
+    fn test_extend_skips_queued() {
+        let mut queue = Queue::default();
+        assert_eq!(queue.extend([station("a"), station("b")]), 2);
+        assert_eq!(queue.extend([station("b"), station("c")]), 1);
+        assert_eq!(queue.len(), 3);
+    }
+
+    #[test]
+    fn test_is_due() {
+        let start = Instant::now();
+        let mut queue = Queue::default();
+        queue.extend([station("a"), station("b")]);
+        assert!(!queue.is_due(start, 60, None));
+
+        let first = queue.take_next().unwrap();
+        assert_eq!(first.stationuuid, "a");
+        queue.started(first.stationuuid, start);
+        let later = start + Duration::from_secs(60);
+        assert!(!queue.is_due(start + Duration::from_secs(59), 60, Some("a")));
+        assert!(queue.is_due(later, 60, Some("a")));
+        // Manual only
+        assert!(!queue.is_due(later, 0, Some("a")));
+        // Another station was played by hand
+        assert!(!queue.is_due(later, 60, Some("x")));
+
+        let second = queue.take_next().unwrap();
+        queue.started(second.stationuuid, later);
+        assert!(!queue.is_due(later + Duration::from_secs(600), 60, Some("b")));
+    }
+}
@@ -0,0 +1,124 @@
+//! Quiet hours
+//!
+//! During the configured hours, e.g. 22:00 to 7:00, the volume sent to mpv
+//! or a cast device is capped whatever the slider says, so a station
+//! started late at night cannot blast out at daytime volume. Notifications
+//! are handled as during Do Not Disturb (see [`notify`](crate::notify)).
+//! Quiet hours are checked once a minute, so they start and end on their
+//! own.
+
+use crate::schedule::LocalTime;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+/// Default start, in minutes after local midnight
+pub const DEFAULT_START: u16 = 22 * 60;
+
+/// Default end, in minutes after local midnight
+pub const DEFAULT_END: u16 = 7 * 60;
+
+/// Default volume cap, in percent
+pub const DEFAULT_MAX_VOLUME: u8 = 20;
+
+/// Allowed volume caps, in percent
+pub const MAX_VOLUME_RANGE: RangeInclusive<u8> = 5..=100;
+
+/// How often to check whether quiet hours started or ended
+pub const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Quiet hours settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Start in minutes after local midnight
+    #[serde(default = "default_start")]
+    pub start: u16,
+    /// End in minutes after local midnight; before `start` when quiet hours
+    /// run past midnight
+    #[serde(default = "default_end")]
+    pub end: u16,
+    /// Highest volume during quiet hours, in percent
+    #[serde(default = "default_max_volume")]
+    pub max_volume: u8,
+}
+
+fn default_start() -> u16 {
+    DEFAULT_START
+}
+
+fn default_end() -> u16 {
+    DEFAULT_END
+}
+
+fn default_max_volume() -> u8 {
+    DEFAULT_MAX_VOLUME
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: DEFAULT_START,
+            end: DEFAULT_END,
+            max_volume: DEFAULT_MAX_VOLUME,
+        }
+    }
+}
+
+impl QuietHours {
+    /// Whether `now` falls within quiet hours
+    #[must_use]
+    pub fn is_quiet(&self, now: LocalTime) -> bool {
+        if !self.enabled || self.start == self.end {
+            return false;
+        }
+        if self.start < self.end {
+            (self.start..self.end).contains(&now.minute)
+        } else {
+            now.minute >= self.start || now.minute < self.end
+        }
+    }
+}
+
+/// Clamp a volume cap to [`MAX_VOLUME_RANGE`]
+#[must_use]
+pub fn clamp_max_volume(volume: u8) -> u8 {
+    volume.clamp(*MAX_VOLUME_RANGE.start(), *MAX_VOLUME_RANGE.end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hours: u16, minutes: u16) -> LocalTime {
+        LocalTime {
+            weekday: 0,
+            minute: hours * 60 + minutes,
+        }
+    }
+
+    #[test]
+    fn test_is_quiet_past_midnight() {
+        let quiet = QuietHours {
+            enabled: true,
+            ..QuietHours::default()
+        };
+        assert!(quiet.is_quiet(at(22, 0)));
+        assert!(quiet.is_quiet(at(3, 30)));
+        assert!(!quiet.is_quiet(at(7, 0)));
+        assert!(!quiet.is_quiet(at(21, 59)));
+
+        let afternoon = QuietHours {
+            start: 13 * 60,
+            end: 15 * 60,
+            ..quiet
+        };
+        assert!(afternoon.is_quiet(at(14, 0)));
+        assert!(!afternoon.is_quiet(at(22, 0)));
+
+        let disabled = QuietHours::default();
+        assert!(!disabled.is_quiet(at(23, 0)));
+    }
+}
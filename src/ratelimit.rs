@@ -0,0 +1,89 @@
+//! Client-side rate limiting of directory requests
+//!
+//! radio-browser.info is run by volunteers and asks clients to keep their
+//! request rate low. Searching while typing, refreshing a long favorites
+//! list, and voting all draw from one token bucket: a burst of
+//! [`BURST`] requests goes out at once, after which requests are spaced to
+//! [`PER_SECOND`] a second. Waiting requests queue in the order they asked.
+
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::debug;
+
+/// Requests allowed back to back
+pub const BURST: f64 = 10.0;
+
+/// Sustained requests per second
+pub const PER_SECOND: f64 = 2.0;
+
+/// Token bucket whose tokens may go negative: each request takes a token
+/// right away and waits until the bucket would have refilled to it
+#[derive(Debug)]
+pub struct TokenBucket {
+    capacity: f64,
+    per_second: f64,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    #[must_use]
+    pub fn new(capacity: f64, per_second: f64) -> Self {
+        Self {
+            capacity,
+            per_second,
+            state: Mutex::new((capacity, Instant::now())),
+        }
+    }
+
+    /// Take a token at `now`, returning how long to wait before using it
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let (tokens, last) = *state;
+        let refilled = now.saturating_duration_since(last).as_secs_f64() * self.per_second;
+        let tokens = (tokens + refilled).min(self.capacity) - 1.0;
+        *state = (tokens, now);
+        if tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-tokens / self.per_second)
+        }
+    }
+
+    /// Wait for a token
+    pub async fn acquire(&self) {
+        let wait = self.reserve(Instant::now());
+        if !wait.is_zero() {
+            debug!("Rate limited, waiting {:?}", wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+static DIRECTORY: LazyLock<TokenBucket> = LazyLock::new(|| TokenBucket::new(BURST, PER_SECOND));
+
+/// Wait until another directory request may be sent
+pub async fn directory() {
+    DIRECTORY.acquire().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve() {
+        let bucket = TokenBucket::new(2.0, 4.0);
+        let start = Instant::now();
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::from_millis(250));
+        // Queued behind the previous request
+        assert_eq!(bucket.reserve(start), Duration::from_millis(500));
+        // A second later the bucket is full again
+        assert_eq!(
+            bucket.reserve(start + Duration::from_secs(1)),
+            Duration::ZERO
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! "Because you listen to X" recommendations
+//!
+//! The most listened station of the last month is taken from the local
+//! history and used as a seed. Only its main tag goes out in a directory
+//! search; the results are ranked here by how many tags they share with the
+//! seed, and stations already listened to or favorited are left out. The
+//! history itself never leaves the machine.
+
+use crate::api::{self, RequestOptions, Station};
+use crate::stats::StationStats;
+use std::collections::HashSet;
+
+/// Stations shown in the recommendations row
+pub const ROW_LENGTH: usize = 5;
+
+/// Stations fetched for the seed's tag before ranking
+const CANDIDATES: u32 = 40;
+
+/// Stations similar to the one the user listens to most
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recommendations {
+    pub seed_uuid: String,
+    pub seed_name: String,
+    pub stations: Vec<Station>,
+}
+
+/// Lowercased, deduplicated tags of a station, in listed order
+#[must_use]
+pub fn parse_tags(tags: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    tags.split(',')
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty() && seen.insert(tag.clone()))
+        .collect()
+}
+
+/// Share of tags two stations have in common (Jaccard index, 0.0 to 1.0)
+#[must_use]
+pub fn similarity(a: &[String], b: &[String]) -> f64 {
+    let a: HashSet<&String> = a.iter().collect();
+    let b: HashSet<&String> = b.iter().collect();
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// The most listened station whose tags are known from `known` stations
+#[must_use]
+pub fn pick_seed<'a>(top: &[StationStats], known: &[&'a Station]) -> Option<&'a Station> {
+    top.iter().find_map(|entry| {
+        known.iter().copied().find(|station| {
+            station.stationuuid == entry.stationuuid && !parse_tags(&station.tags).is_empty()
+        })
+    })
+}
+
+/// Most similar `candidates` first, without the seed, excluded stations, or
+/// stations sharing no tag with the seed
+#[must_use]
+pub fn rank(seed: &Station, candidates: Vec<Station>, exclude: &HashSet<String>) -> Vec<Station> {
+    let seed_tags = parse_tags(&seed.tags);
+    let mut seen = HashSet::new();
+    let mut scored: Vec<(f64, Station)> = candidates
+        .into_iter()
+        .filter(|s| s.stationuuid != seed.stationuuid && !exclude.contains(&s.stationuuid))
+        .filter(|s| seen.insert(s.name.trim().to_lowercase()))
+        .map(|s| (similarity(&seed_tags, &parse_tags(&s.tags)), s))
+        .filter(|(score, _)| *score > 0.0)
+        .collect();
+    // Stable: equally similar stations keep the directory's popularity order
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored
+        .into_iter()
+        .map(|(_, station)| station)
+        .take(ROW_LENGTH)
+        .collect()
+}
+
+/// Search the seed's main tag and rank the results
+pub async fn fetch(
+    seed: Station,
+    exclude: HashSet<String>,
+    options: RequestOptions,
+) -> Result<Recommendations, reqwest::Error> {
+    let tag = parse_tags(&seed.tags)
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let candidates = api::stations_by_tag(tag, CANDIDATES, options).await?;
+    Ok(Recommendations {
+        stations: rank(&seed, candidates, &exclude),
+        seed_uuid: seed.stationuuid,
+        seed_name: seed.name,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn station(uuid: &str, tags: &str) -> Station {
+        Station {
+            stationuuid: uuid.to_string(),
+            name: uuid.to_string(),
+            tags: tags.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(
+            parse_tags(" Jazz,smooth jazz,,jazz "),
+            vec!["jazz".to_string(), "smooth jazz".to_string()]
+        );
+        assert!(parse_tags("").is_empty());
+    }
+
+    #[test]
+    fn test_similarity() {
+        let a = parse_tags("jazz,blues");
+        assert!((similarity(&a, &a) - 1.0).abs() < f64::EPSILON);
+        assert!((similarity(&a, &parse_tags("jazz,rock,pop")) - 0.25).abs() < f64::EPSILON);
+        assert!(similarity(&a, &[]).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_pick_seed_skips_untagged_and_unknown() {
+        let stat = |uuid: &str| StationStats {
+            stationuuid: uuid.to_string(),
+            name: uuid.to_string(),
+            plays: 1,
+            seconds: 60,
+        };
+        let untagged = station("a", "");
+        let tagged = station("c", "jazz");
+        let known = [&untagged, &tagged];
+        let top = [stat("a"), stat("b"), stat("c")];
+        assert_eq!(
+            pick_seed(&top, &known).map(|s| s.stationuuid.as_str()),
+            Some("c")
+        );
+        assert_eq!(pick_seed(&top[..2], &known), None);
+    }
+
+    #[test]
+    fn test_rank() {
+        let seed = station("seed", "jazz,blues");
+        let candidates = vec![
+            station("seed", "jazz,blues"),
+            station("partial", "jazz,rock"),
+            station("close", "blues,jazz"),
+            station("listened", "jazz,blues"),
+            station("unrelated", "metal"),
+        ];
+        let exclude = HashSet::from(["listened".to_string()]);
+        let names: Vec<String> = rank(&seed, candidates, &exclude)
+            .into_iter()
+            .map(|s| s.stationuuid)
+            .collect();
+        assert_eq!(names, vec!["close", "partial"]);
+    }
+}
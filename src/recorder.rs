@@ -0,0 +1,656 @@
+//! Recording streams to files
+//!
+//! The playing station can be recorded from the now-playing row. A
+//! recording opens its own connection to the stream and copies the audio
+//! as it arrives, so it carries on when playback switches stations or
+//! stops, and stream credentials never leave the applet. One recording runs
+//! at a time, as a subscription like the episode downloads; stopping drops
+//! the subscription and keeps what was recorded so far.
+//!
+//! Recordings are files in [`recordings_dir`], named after the station and
+//! the local start time. Scheduled recordings (see
+//! [`crate::schedule::RecordingRule`]) may go into a folder of it instead.
+//! The recordings page lists them all for playing, showing in the file
+//! manager, renaming, and deleting. Recordings older than the configured
+//! number of days are deleted at startup.
+//!
+//! By default the stream is stored as received. It can be re-encoded to
+//! Opus or MP3 at a chosen bitrate instead: the audio is then piped into
+//! `ffmpeg`, which has to be installed, so the stream URL stays off its
+//! command line.
+//!
+//! Recording needs the `recorder` Cargo feature (on by default). Without it
+//! the settings are kept but nothing is recorded.
+#![cfg_attr(not(feature = "recorder"), allow(dead_code, unused_imports))]
+
+use crate::api::{http_client, Station};
+use crate::schedule;
+use crate::stats;
+use futures::channel::mpsc::Sender;
+use futures::SinkExt;
+use mpris_server::zbus::{self, Connection};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+#[cfg(feature = "recorder")]
+use std::process::Stdio;
+use std::time::{Duration, UNIX_EPOCH};
+#[cfg(feature = "recorder")]
+use tokio::io::AsyncWriteExt;
+#[cfg(feature = "recorder")]
+use tokio::process::{Child, ChildStdin, Command};
+use tracing::{debug, info, warn};
+use url::Url;
+
+/// Retention choices offered in the settings, in days (0 = keep)
+pub const RETENTION_CHOICES: [u32; 4] = [0, 7, 30, 90];
+
+/// Bitrates offered for re-encoding, in kbit/s
+pub const BITRATE_CHOICES: [u32; 5] = [64, 96, 128, 192, 256];
+
+/// Default re-encoding bitrate, in kbit/s
+pub const DEFAULT_BITRATE: u32 = 128;
+
+/// Longest a recording started by hand runs
+pub const MAX_RECORDING_TIME: Duration = Duration::from_secs(6 * 3600);
+
+/// Prefix of the IDs of recordings played as stations
+pub const UUID_PREFIX: &str = "recording:";
+
+/// File extensions the recorder writes
+const EXTENSIONS: [&str; 5] = ["mp3", "aac", "ogg", "opus", "flac"];
+
+/// Bytes recorded between progress reports
+const PROGRESS_STEP: u64 = 256 * 1024;
+
+const DAY_SECS: u64 = 24 * 3600;
+
+/// Directory of the recordings: "Radio recordings" in the music folder
+#[must_use]
+pub fn recordings_dir() -> Option<PathBuf> {
+    dirs::audio_dir()
+        .map(|dir| dir.join("Radio recordings"))
+        .or_else(|| {
+            dirs::data_dir().map(|dir| dir.join("cosmic-ext-applet-radio").join("recordings"))
+        })
+}
+
+/// Whether `path` is inside the recordings directory
+#[must_use]
+pub fn is_recording(path: &Path) -> bool {
+    recordings_dir().is_some_and(|dir| path.starts_with(dir))
+}
+
+/// File extension for a stream's `Content-Type`, else its directory codec;
+/// MP3 for anything unknown, like most radio streams
+#[must_use]
+pub fn extension(content_type: Option<&str>, codec: &str) -> &'static str {
+    let mime = content_type
+        .and_then(|value| value.split(';').next())
+        .map(|mime| mime.trim().to_ascii_lowercase());
+    match mime.as_deref() {
+        Some("audio/aac" | "audio/aacp" | "audio/x-aac") => "aac",
+        Some("audio/ogg" | "application/ogg") => "ogg",
+        Some("audio/opus") => "opus",
+        Some("audio/flac" | "audio/x-flac") => "flac",
+        Some("audio/mpeg" | "audio/mp3") => "mp3",
+        _ => match codec.to_ascii_uppercase().as_str() {
+            "AAC" | "AAC+" => "aac",
+            "OGG" => "ogg",
+            "OPUS" => "opus",
+            "FLAC" => "flac",
+            _ => "mp3",
+        },
+    }
+}
+
+/// How recordings are stored
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum RecordingFormat {
+    /// The stream as received
+    #[default]
+    Copy,
+    /// Re-encoded to Opus in an Ogg file
+    Opus,
+    Mp3,
+}
+
+impl RecordingFormat {
+    pub const ALL: [RecordingFormat; 3] = [
+        RecordingFormat::Copy,
+        RecordingFormat::Opus,
+        RecordingFormat::Mp3,
+    ];
+
+    /// File extension of a re-encoded recording; a copy keeps the stream's
+    #[must_use]
+    pub fn extension(self) -> Option<&'static str> {
+        match self {
+            RecordingFormat::Copy => None,
+            RecordingFormat::Opus => Some("opus"),
+            RecordingFormat::Mp3 => Some("mp3"),
+        }
+    }
+}
+
+/// `ffmpeg` arguments that re-encode audio read from stdin; the output file
+/// goes last. `None` for a plain copy, which needs no encoder.
+#[must_use]
+pub fn ffmpeg_args(format: RecordingFormat, bitrate: u32) -> Option<Vec<String>> {
+    let (codec, container) = match format {
+        RecordingFormat::Copy => return None,
+        RecordingFormat::Opus => ("libopus", "ogg"),
+        RecordingFormat::Mp3 => ("libmp3lame", "mp3"),
+    };
+    let bitrate = format!("{bitrate}k");
+    let args = [
+        "-hide_banner",
+        "-loglevel",
+        "error",
+        "-i",
+        "pipe:0",
+        "-vn",
+        "-c:a",
+        codec,
+        "-b:a",
+        bitrate.as_str(),
+        "-f",
+        container,
+        // Never overwrite; the file name is new anyway
+        "-n",
+    ];
+    Some(args.iter().map(ToString::to_string).collect())
+}
+
+/// A name safe to use as a file or folder name, without separators or
+/// leading dots
+fn sanitize(name: &str) -> String {
+    let clean: String = name
+        .chars()
+        .map(|c| {
+            if c == '/' || c == '\\' || c.is_control() {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+    clean.trim().trim_start_matches('.').trim().to_string()
+}
+
+/// File name without extension: the station and local start time, e.g.
+/// `Jazz FM 2024-03-01 18-30`
+#[must_use]
+pub fn file_stem(station: &str, started_at: u64) -> String {
+    let offset = i64::from(schedule::utc_offset_mins(started_at)) * 60;
+    let stamp = stats::format_timestamp(started_at.saturating_add_signed(offset));
+    let station = match sanitize(station) {
+        name if name.is_empty() => "Radio".to_string(),
+        name => name,
+    };
+    format!(
+        "{} {} {}",
+        station,
+        &stamp[..10],
+        stamp[11..16].replace(':', "-")
+    )
+}
+
+/// Folder of the recordings directory `dir` for a recording rule's folder
+/// name; `dir` itself if the name is empty
+#[must_use]
+pub fn folder_dir(dir: &Path, folder: &str) -> PathBuf {
+    match sanitize(folder) {
+        folder if folder.is_empty() => dir.to_path_buf(),
+        folder => dir.join(folder),
+    }
+}
+
+/// `dir/stem.extension`, numbered if a file of that name exists
+fn unique_path(dir: &Path, stem: &str, extension: &str) -> PathBuf {
+    let mut path = dir.join(format!("{stem}.{extension}"));
+    let mut number = 2;
+    while path.exists() {
+        path = dir.join(format!("{stem} ({number}).{extension}"));
+        number += 1;
+    }
+    path
+}
+
+/// A recording on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recording {
+    pub path: PathBuf,
+    pub bytes: u64,
+    /// Unix time it was last written to
+    pub modified: u64,
+}
+
+impl Recording {
+    /// File name without the extension
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// A station that plays the file, for the player and the now-playing row
+    #[must_use]
+    pub fn station(&self) -> Option<Station> {
+        let url = Url::from_file_path(&self.path).ok()?;
+        Some(Station {
+            stationuuid: format!("{}{}", UUID_PREFIX, self.path.display()),
+            name: self.name(),
+            url: url.to_string(),
+            url_resolved: url.to_string(),
+            ..Station::default()
+        })
+    }
+}
+
+fn modified_secs(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |age| age.as_secs())
+}
+
+/// Recordings in `dir`, and in the folders right inside it, unsorted
+fn list_into(dir: &Path, nested: bool, recordings: &mut Vec<Recording>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            if nested {
+                list_into(&path, false, recordings);
+            }
+            continue;
+        }
+        let known = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| EXTENSIONS.contains(&extension));
+        if known && metadata.is_file() {
+            recordings.push(Recording {
+                bytes: metadata.len(),
+                modified: modified_secs(&metadata),
+                path,
+            });
+        }
+    }
+}
+
+/// Recordings in `dir` and its folders, newest first
+#[must_use]
+pub fn list(dir: &Path) -> Vec<Recording> {
+    let mut recordings = Vec::new();
+    list_into(dir, true, &mut recordings);
+    recordings.sort_by(|a, b| b.modified.cmp(&a.modified).then(a.path.cmp(&b.path)));
+    recordings
+}
+
+/// Give a recording a new name, keeping its folder and extension
+pub fn rename(path: &Path, name: &str) -> io::Result<PathBuf> {
+    let name = sanitize(name);
+    let (Some(dir), Some(extension)) = (path.parent(), path.extension()) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Not a recording",
+        ));
+    };
+    if name.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "Empty name"));
+    }
+    let target = dir.join(format!("{}.{}", name, extension.to_string_lossy()));
+    if target == path {
+        return Ok(target);
+    }
+    if target.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "A recording of that name exists",
+        ));
+    }
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+/// Delete a recording
+pub fn delete(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Delete the recordings in `dir` last written more than `days` days before
+/// `now` (0 = keep all). Returns how many were deleted.
+pub fn clean_up(dir: &Path, days: u32, now: u64) -> usize {
+    if days == 0 {
+        return 0;
+    }
+    let cutoff = now.saturating_sub(u64::from(days) * DAY_SECS);
+    let mut deleted = 0;
+    for recording in list(dir).iter().filter(|r| r.modified < cutoff) {
+        match delete(&recording.path) {
+            Ok(()) => deleted += 1,
+            Err(e) => warn!("Failed to delete {}: {}", recording.path.display(), e),
+        }
+    }
+    deleted
+}
+
+/// Show a recording selected in the file manager, or open its folder if no
+/// file manager implements `org.freedesktop.FileManager1`
+pub async fn reveal(path: PathBuf) -> Result<(), String> {
+    let uri = Url::from_file_path(&path).map_err(|()| "Not an absolute path".to_string())?;
+    let shown = async {
+        let connection = Connection::session().await?;
+        connection
+            .call_method(
+                Some("org.freedesktop.FileManager1"),
+                "/org/freedesktop/FileManager1",
+                Some("org.freedesktop.FileManager1"),
+                "ShowItems",
+                &(vec![uri.as_str()], ""),
+            )
+            .await?;
+        Ok::<(), zbus::Error>(())
+    }
+    .await;
+    if let Err(e) = shown {
+        debug!("No file manager to show {}: {}", path.display(), e);
+        let dir = path.parent().unwrap_or(&path);
+        let mut child = std::process::Command::new("xdg-open")
+            .arg(dir)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+        // Reap the opener so it doesn't linger as a zombie
+        std::thread::spawn(move || child.wait());
+    }
+    Ok(())
+}
+
+/// The recording running in this session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveRecording {
+    /// Tells this recording's events from those of an earlier one
+    pub id: u64,
+    pub station: String,
+    /// Stream URL, with credentials if the station needs them
+    pub url: String,
+    pub codec: String,
+    /// Folder of the recordings directory it goes into ("" = none)
+    pub folder: String,
+    pub format: RecordingFormat,
+    /// Re-encoding bitrate in kbit/s, unless the format is a copy
+    pub bitrate: u32,
+    /// Unix time it started
+    pub started_at: u64,
+    /// When it stops by itself
+    pub length: Duration,
+    pub bytes: u64,
+}
+
+/// Events reported by a running recording
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordEvent {
+    Progress {
+        id: u64,
+        bytes: u64,
+    },
+    Finished(u64, PathBuf),
+    /// The recording and what went wrong
+    Failed(u64, String),
+}
+
+/// Create an iced Subscription that records a stream into
+/// [`recordings_dir`], or the recording's folder of it, until `length` has passed or the stream ends.
+///
+/// The subscription is keyed by the recording, so it keeps running while the
+/// app asks for it and is cancelled, keeping the file, when it no longer does.
+#[cfg(feature = "recorder")]
+pub fn record_subscription(recording: ActiveRecording) -> cosmic::iced::Subscription<RecordEvent> {
+    cosmic::iced::Subscription::run_with_id(
+        ("record", recording.id),
+        cosmic::iced::stream::channel(8, move |mut output| async move {
+            let id = recording.id;
+            let event = match record(&recording, &mut output).await {
+                Ok(path) => {
+                    info!("Recorded {} to {}", recording.station, path.display());
+                    RecordEvent::Finished(id, path)
+                }
+                Err(e) => RecordEvent::Failed(id, e),
+            };
+            let _ = output.send(event).await;
+        }),
+    )
+}
+
+#[cfg(not(feature = "recorder"))]
+pub fn record_subscription(_recording: ActiveRecording) -> cosmic::iced::Subscription<RecordEvent> {
+    cosmic::iced::Subscription::none()
+}
+
+/// Where recorded audio goes: straight into the file, or through ffmpeg
+#[cfg(feature = "recorder")]
+enum Output {
+    File(File),
+    Encoder(Child, ChildStdin),
+}
+
+#[cfg(feature = "recorder")]
+impl Output {
+    fn open(recording: &ActiveRecording, path: &Path) -> Result<Self, String> {
+        let Some(args) = ffmpeg_args(recording.format, recording.bitrate) else {
+            return File::create(path)
+                .map(Output::File)
+                .map_err(|e| e.to_string());
+        };
+        let mut child = Command::new("ffmpeg")
+            .args(args)
+            .arg(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::NotFound => "ffmpeg is needed to re-encode recordings".to_string(),
+                _ => e.to_string(),
+            })?;
+        let stdin = child.stdin.take().ok_or("No pipe to ffmpeg")?;
+        Ok(Output::Encoder(child, stdin))
+    }
+
+    async fn write(&mut self, chunk: &[u8]) -> io::Result<()> {
+        match self {
+            Output::File(file) => file.write_all(chunk),
+            Output::Encoder(_, stdin) => stdin.write_all(chunk).await,
+        }
+    }
+
+    /// Flush the file, or end ffmpeg's input and wait for it to finish the
+    /// file. A recording cancelled midway skips this; ffmpeg then sees its
+    /// input end and finishes by itself.
+    async fn finish(self) -> Result<(), String> {
+        match self {
+            Output::File(mut file) => file.flush().map_err(|e| e.to_string()),
+            Output::Encoder(child, stdin) => {
+                drop(stdin);
+                let output = child.wait_with_output().await.map_err(|e| e.to_string())?;
+                if output.status.success() {
+                    return Ok(());
+                }
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                Err(stderr.lines().last().map_or_else(
+                    || format!("ffmpeg failed: {}", output.status),
+                    str::to_string,
+                ))
+            }
+        }
+    }
+}
+
+/// Copy the stream into a new file, re-encoded if asked, until the
+/// recording's length has passed
+#[cfg(feature = "recorder")]
+async fn record(
+    recording: &ActiveRecording,
+    output: &mut Sender<RecordEvent>,
+) -> Result<PathBuf, String> {
+    let dir = recordings_dir().ok_or_else(|| "No music or data directory".to_string())?;
+    let dir = folder_dir(&dir, &recording.folder);
+    // Some slack over the length for connecting; the loop stops on time
+    let mut response = http_client()
+        .get(&recording.url)
+        .timeout(recording.length + Duration::from_secs(60))
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| e.to_string())?;
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+    let extension = recording
+        .format
+        .extension()
+        .unwrap_or_else(|| extension(content_type, &recording.codec));
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = unique_path(
+        &dir,
+        &file_stem(&recording.station, recording.started_at),
+        extension,
+    );
+    let mut output_file = Output::open(recording, &path)?;
+    debug!("Recording {} to {}", recording.station, path.display());
+
+    let deadline = tokio::time::Instant::now() + recording.length;
+    let (mut bytes, mut reported) = (0, 0);
+    while let Ok(chunk) = tokio::time::timeout_at(deadline, response.chunk()).await {
+        let Some(chunk) = chunk.map_err(|e| e.to_string())? else {
+            break;
+        };
+        if let Err(e) = output_file.write(&chunk).await {
+            // ffmpeg quitting says why better than the broken pipe
+            return Err(output_file
+                .finish()
+                .await
+                .err()
+                .unwrap_or_else(|| e.to_string()));
+        }
+        bytes += chunk.len() as u64;
+        if bytes - reported >= PROGRESS_STEP {
+            reported = bytes;
+            let progress = RecordEvent::Progress {
+                id: recording.id,
+                bytes,
+            };
+            if output.send(progress).await.is_err() {
+                return Err("Recording cancelled".to_string());
+            }
+        }
+    }
+    output_file.finish().await?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("radio-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_extension() {
+        assert_eq!(extension(Some("audio/mpeg"), ""), "mp3");
+        assert_eq!(extension(Some("audio/aacp; charset=x"), "MP3"), "aac");
+        assert_eq!(extension(Some("application/ogg"), ""), "ogg");
+        assert_eq!(extension(None, "AAC+"), "aac");
+        assert_eq!(extension(Some("application/octet-stream"), "FLAC"), "flac");
+        assert_eq!(extension(None, ""), "mp3");
+    }
+
+    #[test]
+    fn test_ffmpeg_args() {
+        assert_eq!(ffmpeg_args(RecordingFormat::Copy, 128), None);
+        let args = ffmpeg_args(RecordingFormat::Opus, 96).unwrap();
+        assert!(args.windows(2).any(|pair| pair == ["-c:a", "libopus"]));
+        assert!(args.windows(2).any(|pair| pair == ["-b:a", "96k"]));
+        assert!(args.windows(2).any(|pair| pair == ["-i", "pipe:0"]));
+        let args = ffmpeg_args(RecordingFormat::Mp3, 192).unwrap();
+        assert!(args.windows(2).any(|pair| pair == ["-f", "mp3"]));
+        assert_eq!(RecordingFormat::Mp3.extension(), Some("mp3"));
+        assert_eq!(RecordingFormat::Copy.extension(), None);
+    }
+
+    #[test]
+    fn test_file_stem() {
+        let stem = file_stem("../AC/DC Radio", 0);
+        assert!(stem.starts_with("AC DC Radio 19"), "{stem}");
+        assert!(!stem.contains(['/', ':']));
+        assert!(file_stem("", 0).starts_with("Radio "));
+    }
+
+    #[test]
+    fn test_list_and_rename() {
+        let dir = temp_dir("recordings");
+        fs::write(dir.join("Jazz 2024-03-01 18-30.mp3"), b"audio").unwrap();
+        fs::write(dir.join("notes.txt"), b"text").unwrap();
+        let shows = folder_dir(&dir, "../Shows");
+        assert_eq!(shows, dir.join("Shows"));
+        assert_eq!(folder_dir(&dir, " "), dir);
+        fs::create_dir_all(shows.join("Deeper")).unwrap();
+        fs::write(shows.join("Deeper").join("Lost.mp3"), b"audio").unwrap();
+        let recordings = list(&dir);
+        assert_eq!(recordings.len(), 1);
+        assert_eq!(recordings[0].name(), "Jazz 2024-03-01 18-30");
+        assert_eq!(recordings[0].bytes, 5);
+
+        let renamed = rename(&recordings[0].path, "../Friday jazz").unwrap();
+        assert_eq!(renamed, dir.join("Friday jazz.mp3"));
+        fs::write(dir.join("Other.mp3"), b"audio").unwrap();
+        assert!(rename(&renamed, "Other").is_err());
+        assert!(rename(&renamed, " ").is_err());
+
+        fs::write(shows.join("Weekly.ogg"), b"audio").unwrap();
+        assert!(list(&dir)
+            .iter()
+            .any(|r| r.path == shows.join("Weekly.ogg")));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_clean_up() {
+        let dir = temp_dir("cleanup");
+        fs::write(dir.join("Old.mp3"), b"audio").unwrap();
+        let now = stats::now_secs();
+        assert_eq!(clean_up(&dir, 0, now + 100 * DAY_SECS), 0);
+        assert_eq!(clean_up(&dir, 7, now), 0);
+        assert_eq!(clean_up(&dir, 7, now + 8 * DAY_SECS), 1);
+        assert!(list(&dir).is_empty());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_unique_path() {
+        let dir = temp_dir("unique");
+        fs::write(dir.join("Jazz.mp3"), b"audio").unwrap();
+        assert_eq!(unique_path(&dir, "Jazz", "mp3"), dir.join("Jazz (2).mp3"));
+        assert_eq!(unique_path(&dir, "Jazz", "ogg"), dir.join("Jazz.ogg"));
+        let _ = fs::remove_dir_all(dir);
+    }
+}
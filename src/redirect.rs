@@ -0,0 +1,116 @@
+//! Redirect resolution for stream URLs
+//!
+//! Many station URLs point at a redirector (load balancers, tracking links,
+//! `http` → `https` hops) rather than the stream. The chain is followed
+//! once, right before playback, and the final URL is played directly and
+//! kept in the cache (see [`crate::cache`]) for a few hours, so later starts
+//! skip the hops and URL validation sees the host that actually serves the
+//! stream. The same request checks that the URL serves audio (see
+//! [`crate::sniff`]).
+
+use crate::cache::Cache;
+use crate::sniff::{self, Rejection};
+use crate::stats;
+use crate::streamauth::redact_url;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::debug;
+
+/// How long a resolved URL is played without following the chain again
+pub const TTL_SECS: u64 = 6 * 60 * 60;
+
+/// Where a stream URL led, as of `resolved_at`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Resolved {
+    url: String,
+    resolved_at: u64,
+}
+
+impl Resolved {
+    fn is_fresh(&self, now: u64) -> bool {
+        now.saturating_sub(self.resolved_at) < TTL_SECS
+    }
+}
+
+fn cache_key(url: &str) -> String {
+    format!("redirect:{url}")
+}
+
+/// The URL to play for `url`: the cached end of its redirect chain, else
+/// the chain followed now, unless it ends somewhere that isn't audio. Falls
+/// back to `url` when the request fails. The result is only cached when
+/// `cache` is given.
+pub async fn final_url(
+    url: String,
+    cache: Option<Cache>,
+    timeout: Duration,
+) -> Result<String, Rejection> {
+    let now = stats::now_secs();
+    let key = cache_key(&url);
+    if let Some(resolved) = cache
+        .as_ref()
+        .and_then(|cache| cache.get_json::<Resolved>(&key))
+        .filter(|resolved| resolved.is_fresh(now))
+    {
+        return Ok(resolved.url);
+    }
+    match sniff::sniff(&url, timeout).await {
+        Ok(sniffed) => {
+            if let Some(rejection) = sniffed.rejection {
+                return Err(rejection);
+            }
+            let resolved = sniffed.url;
+            if resolved != url {
+                debug!(
+                    "{} redirects to {}",
+                    redact_url(&url),
+                    redact_url(&resolved)
+                );
+            }
+            if let Some(cache) = cache {
+                let entry = Resolved {
+                    url: resolved.clone(),
+                    resolved_at: now,
+                };
+                cache.put_json(&key, &entry);
+            }
+            Ok(resolved)
+        }
+        Err(e) => {
+            debug!("Cannot resolve {}: {}", redact_url(&url), e);
+            Ok(url)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_fresh() {
+        let resolved = Resolved {
+            url: "https://edge.example/live".to_string(),
+            resolved_at: 1_000,
+        };
+        assert!(resolved.is_fresh(1_000 + TTL_SECS - 1));
+        assert!(!resolved.is_fresh(1_000 + TTL_SECS));
+    }
+
+    #[tokio::test]
+    async fn test_final_url_uses_cache() {
+        let dir = std::env::temp_dir().join(format!("radio-redirect-test-{}", std::process::id()));
+        let cache = Cache::new(dir.clone(), 10);
+        let url = "http://redirector.invalid/live".to_string();
+        cache.put_json(
+            &cache_key(&url),
+            &Resolved {
+                url: "https://edge.example/live".to_string(),
+                resolved_at: stats::now_secs(),
+            },
+        );
+        let played = final_url(url, Some(cache), Duration::from_secs(1)).await;
+        assert_eq!(played.as_deref(), Ok("https://edge.example/live"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,129 @@
+//! Keeping favorites current
+//!
+//! Favorites are copies of the directory entry at the time they were saved.
+//! Stations move to new stream URLs, change logos, or stop working, so once
+//! a day the radio-browser.info favorites are looked up again by UUID and
+//! their directory fields updated. Favorites the directory's checker marks
+//! as failing get a warning badge and a button to search for a replacement.
+
+use crate::api::{self, RequestOptions, Station};
+use std::time::Duration;
+
+/// Age after which favorites are looked up again
+pub const MAX_AGE_SECS: u64 = 24 * 60 * 60;
+
+/// How often to check whether a refresh is due
+pub const TICK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// UUIDs per directory request, keeping the query string short
+const BATCH_SIZE: usize = 100;
+
+/// Whether favorites last refreshed at `refreshed_at` should be refreshed at `now`
+#[must_use]
+pub fn is_due(refreshed_at: u64, now: u64) -> bool {
+    now.saturating_sub(refreshed_at) >= MAX_AGE_SECS
+}
+
+/// UUIDs of the favorites that can be looked up on radio-browser.info
+#[must_use]
+pub fn uuids(favorites: &[Station]) -> Vec<String> {
+    favorites
+        .iter()
+        .filter(|station| api::is_votable(station))
+        .map(|station| station.stationuuid.clone())
+        .collect()
+}
+
+/// Look up stations by UUID, in batches
+pub async fn fetch(
+    uuids: Vec<String>,
+    options: RequestOptions,
+) -> Result<Vec<Station>, reqwest::Error> {
+    let mut stations = Vec::with_capacity(uuids.len());
+    for batch in uuids.chunks(BATCH_SIZE) {
+        stations.extend(api::stations_by_uuid(batch, options).await?);
+    }
+    Ok(stations)
+}
+
+/// Copy the directory fields of `fresh` into the favorites with the same
+/// UUID, keeping their names. Returns the number of favorites that changed.
+pub fn apply(favorites: &mut [Station], fresh: &[Station]) -> usize {
+    let mut changed = 0;
+    for favorite in favorites.iter_mut() {
+        let Some(fresh) = fresh.iter().find(|s| s.stationuuid == favorite.stationuuid) else {
+            continue;
+        };
+        let updated = Station {
+            name: favorite.name.clone(),
+            source: favorite.source.clone(),
+            ..fresh.clone()
+        };
+        if updated != *favorite {
+            *favorite = updated;
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// Whether the directory's last check of the stream failed
+#[must_use]
+pub fn is_broken(station: &Station) -> bool {
+    station.lastcheckok == Some(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn station(uuid: &str, url: &str) -> Station {
+        Station {
+            stationuuid: uuid.to_string(),
+            name: format!("Station {uuid}"),
+            url: url.to_string(),
+            ..Station::default()
+        }
+    }
+
+    #[test]
+    fn test_is_due() {
+        assert!(is_due(0, MAX_AGE_SECS));
+        assert!(!is_due(1_000, 1_000 + MAX_AGE_SECS - 1));
+        assert!(is_due(1_000, 1_000 + MAX_AGE_SECS));
+    }
+
+    #[test]
+    fn test_uuids_only_directory_stations() {
+        let favorites = [
+            station("a", "http://a"),
+            Station {
+                source: "TuneIn".to_string(),
+                ..station("b", "http://b")
+            },
+            station("", "http://c"),
+        ];
+        assert_eq!(uuids(&favorites), ["a"]);
+    }
+
+    #[test]
+    fn test_apply_keeps_name() {
+        let mut favorites = vec![station("a", "http://old"), station("b", "http://b")];
+        favorites[0].name = "My station".to_string();
+        let fresh = [
+            Station {
+                name: "Renamed upstream".to_string(),
+                favicon: "http://a/logo.png".to_string(),
+                lastcheckok: Some(false),
+                ..station("a", "http://new")
+            },
+            station("b", "http://b"),
+        ];
+        assert_eq!(apply(&mut favorites, &fresh), 1);
+        assert_eq!(favorites[0].name, "My station");
+        assert_eq!(favorites[0].url, "http://new");
+        assert_eq!(favorites[0].favicon, "http://a/logo.png");
+        assert!(is_broken(&favorites[0]));
+        assert!(!is_broken(&favorites[1]));
+    }
+}
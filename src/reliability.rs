@@ -0,0 +1,151 @@
+//! How reliably stations connect and keep playing
+//!
+//! Each time a station's stream is started, whether mpv got it playing is
+//! recorded, along with how often it stalled waiting for data afterwards.
+//! The last [`MAX_ATTEMPTS`] attempts give a station a score from 0 to 100,
+//! shown next to stations with enough attempts, which helps pick the
+//! working one among several streams of the same station.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Attempts kept per station
+pub const MAX_ATTEMPTS: usize = 20;
+
+/// Attempts needed before a score is shown
+pub const MIN_ATTEMPTS: usize = 3;
+
+/// Stations kept; the least recently played are dropped
+const MAX_STATIONS: usize = 200;
+
+/// One start of a station's stream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attempt {
+    /// Unix seconds
+    pub at: u64,
+    pub connected: bool,
+    /// Times playback stalled waiting for data after connecting
+    #[serde(default)]
+    pub stalls: u32,
+}
+
+/// Connection history of one station
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StationReliability {
+    pub stationuuid: String,
+    /// Oldest first
+    #[serde(default)]
+    pub attempts: VecDeque<Attempt>,
+}
+
+impl StationReliability {
+    /// Share of attempts that connected, lowered by the stalls per
+    /// connection: one stall per connection halves it. `None` until
+    /// [`MIN_ATTEMPTS`] attempts were made.
+    #[must_use]
+    pub fn score(&self) -> Option<u8> {
+        let attempts = self.attempts.len();
+        if attempts < MIN_ATTEMPTS {
+            return None;
+        }
+        let connected = self.attempts.iter().filter(|a| a.connected).count();
+        if connected == 0 {
+            return Some(0);
+        }
+        let stalls: u32 = self.attempts.iter().map(|a| a.stalls).sum();
+        let success = connected as f64 / attempts as f64;
+        let stalls_per_connection = f64::from(stalls) / connected as f64;
+        Some((100.0 * success / (1.0 + stalls_per_connection)).round() as u8)
+    }
+
+    fn last_at(&self) -> u64 {
+        self.attempts.back().map_or(0, |a| a.at)
+    }
+}
+
+/// The score of a station, if it has one
+#[must_use]
+pub fn score(stations: &[StationReliability], stationuuid: &str) -> Option<u8> {
+    stations
+        .iter()
+        .find(|s| s.stationuuid == stationuuid)
+        .and_then(StationReliability::score)
+}
+
+/// Record that a station's stream was started and whether it connected
+pub fn record_attempt(
+    stations: &mut Vec<StationReliability>,
+    stationuuid: &str,
+    connected: bool,
+    now: u64,
+) {
+    let index = match stations.iter().position(|s| s.stationuuid == stationuuid) {
+        Some(index) => index,
+        None => {
+            stations.push(StationReliability {
+                stationuuid: stationuuid.to_string(),
+                attempts: VecDeque::new(),
+            });
+            stations.len() - 1
+        }
+    };
+    let attempts = &mut stations[index].attempts;
+    attempts.push_back(Attempt {
+        at: now,
+        connected,
+        stalls: 0,
+    });
+    while attempts.len() > MAX_ATTEMPTS {
+        attempts.pop_front();
+    }
+    if stations.len() > MAX_STATIONS {
+        if let Some(oldest) = (0..stations.len()).min_by_key(|&i| stations[i].last_at()) {
+            stations.remove(oldest);
+        }
+    }
+}
+
+/// Record a stall of a station's latest attempt
+pub fn record_stall(stations: &mut [StationReliability], stationuuid: &str) {
+    let attempt = stations
+        .iter_mut()
+        .find(|s| s.stationuuid == stationuuid)
+        .and_then(|s| s.attempts.back_mut());
+    if let Some(attempt) = attempt {
+        attempt.stalls += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        let mut stations = Vec::new();
+        record_attempt(&mut stations, "a", true, 1);
+        record_attempt(&mut stations, "a", false, 2);
+        assert_eq!(score(&stations, "a"), None);
+
+        record_attempt(&mut stations, "a", true, 3);
+        record_attempt(&mut stations, "a", true, 4);
+        assert_eq!(score(&stations, "a"), Some(75));
+
+        // One stall per connection halves it
+        for _ in 0..3 {
+            record_stall(&mut stations, "a");
+        }
+        assert_eq!(score(&stations, "a"), Some(38));
+        assert_eq!(score(&stations, "b"), None);
+    }
+
+    #[test]
+    fn test_record_attempt_keeps_latest() {
+        let mut stations = Vec::new();
+        for at in 0..30 {
+            record_attempt(&mut stations, "a", at >= 10, at);
+        }
+        assert_eq!(stations[0].attempts.len(), MAX_ATTEMPTS);
+        assert_eq!(score(&stations, "a"), Some(100));
+    }
+}
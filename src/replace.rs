@@ -0,0 +1,163 @@
+//! Replacements for broken favorites
+//!
+//! When a favorite stops playing or the directory flags it as failing (see
+//! [`crate::refresh`]), the directory is searched for its name and working
+//! stations with the same name or homepage are offered in its place. A
+//! replacement takes the old favorite's position in the list, and its
+//! program guide moves along.
+
+use crate::api::{self, RequestOptions, Station};
+use crate::refresh;
+use crate::schedule::Show;
+
+/// Replacements offered at once
+pub const MAX_CANDIDATES: usize = 3;
+
+/// Name with case, punctuation, and spacing dropped ("Jazz-FM " = "jazz fm")
+#[must_use]
+pub fn normalize_name(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Homepage without scheme, `www.`, or trailing slash
+fn normalize_homepage(url: &str) -> String {
+    let url = url.trim().to_lowercase();
+    let url = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(&url);
+    let url = url.strip_prefix("www.").unwrap_or(url);
+    url.trim_end_matches('/').to_string()
+}
+
+/// Working stations among `results` that look like the same station as
+/// `broken`, those sharing its homepage first, then by votes
+#[must_use]
+pub fn candidates(broken: &Station, results: &[Station]) -> Vec<Station> {
+    let name = normalize_name(&broken.name);
+    let homepage = normalize_homepage(&broken.homepage);
+    let mut matching: Vec<(bool, &Station)> = results
+        .iter()
+        .filter(|s| s.stationuuid != broken.stationuuid && !refresh::is_broken(s))
+        .filter(|s| s.url_resolved != broken.url_resolved || s.url_resolved.is_empty())
+        .filter_map(|s| {
+            let same_homepage = !homepage.is_empty() && normalize_homepage(&s.homepage) == homepage;
+            (same_homepage || normalize_name(&s.name) == name).then_some((same_homepage, s))
+        })
+        .collect();
+    matching.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.votes.cmp(&a.1.votes)));
+    matching
+        .into_iter()
+        .take(MAX_CANDIDATES)
+        .map(|(_, station)| station.clone())
+        .collect()
+}
+
+/// Search the directory for replacements of `broken`
+pub async fn find(
+    broken: Station,
+    options: RequestOptions,
+) -> Result<Vec<Station>, reqwest::Error> {
+    let results = api::search_stations(broken.name.clone(), None, options).await?;
+    Ok(candidates(&broken, &results))
+}
+
+/// Put `replacement` in place of the favorite with `old_uuid`, moving its
+/// scheduled shows along. Returns whether the favorite was found.
+pub fn replace(
+    favorites: &mut [Station],
+    schedule: &mut [Show],
+    old_uuid: &str,
+    replacement: Station,
+) -> bool {
+    let Some(slot) = favorites.iter_mut().find(|s| s.stationuuid == old_uuid) else {
+        return false;
+    };
+    for show in schedule
+        .iter_mut()
+        .filter(|show| show.stationuuid == old_uuid)
+    {
+        show.stationuuid.clone_from(&replacement.stationuuid);
+    }
+    *slot = replacement;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn station(uuid: &str, name: &str, homepage: &str, votes: u32) -> Station {
+        Station {
+            stationuuid: uuid.to_string(),
+            name: name.to_string(),
+            homepage: homepage.to_string(),
+            url_resolved: format!("http://stream/{uuid}"),
+            votes,
+            ..Station::default()
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize_name(" Jazz-FM  (Live)"), "jazz fm live");
+        assert_eq!(
+            normalize_homepage("https://www.Jazz.example/"),
+            "jazz.example"
+        );
+    }
+
+    #[test]
+    fn test_candidates() {
+        let broken = Station {
+            lastcheckok: Some(false),
+            ..station("old", "Jazz FM", "http://jazz.example", 10)
+        };
+        let results = [
+            broken.clone(),
+            station("a", "Jazz FM", "", 5),
+            station("b", "Jazz FM London", "https://www.jazz.example/", 1),
+            station("c", "Smooth Jazz", "", 99),
+            Station {
+                lastcheckok: Some(false),
+                ..station("d", "jazz fm", "", 50)
+            },
+            station("e", "JAZZ fm", "", 20),
+        ];
+        let uuids: Vec<String> = candidates(&broken, &results)
+            .into_iter()
+            .map(|s| s.stationuuid)
+            .collect();
+        assert_eq!(uuids, ["b", "e", "a"]);
+    }
+
+    #[test]
+    fn test_replace_keeps_position_and_schedule() {
+        let mut favorites = vec![
+            station("x", "X", "", 0),
+            station("old", "Jazz FM", "", 0),
+            station("y", "Y", "", 0),
+        ];
+        let mut schedule = vec![Show {
+            stationuuid: "old".to_string(),
+            title: "Late show".to_string(),
+            days: 1,
+            start: 22 * 60,
+            duration: 60,
+        }];
+        let replacement = station("new", "Jazz FM", "", 0);
+        assert!(replace(&mut favorites, &mut schedule, "old", replacement));
+        assert_eq!(favorites[1].stationuuid, "new");
+        assert_eq!(schedule[0].stationuuid, "new");
+        assert!(!replace(
+            &mut favorites,
+            &mut schedule,
+            "gone",
+            Station::default()
+        ));
+    }
+}
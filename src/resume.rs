@@ -0,0 +1,111 @@
+//! Continue podcast episodes and archived shows where they were left
+//!
+//! Unlike live streams, these have a duration reported by mpv. While one
+//! plays its position is remembered, written to the config every
+//! [`SAVE_INTERVAL_SECS`], and the next time the station is started mpv
+//! begins there. Positions close to the start or the end are not kept, so
+//! an episode listened to the end starts over. Positions are only kept
+//! and used with the `podcasts` Cargo feature (on by default).
+
+use serde::{Deserialize, Serialize};
+
+/// Positions kept; the least recently played are dropped
+pub const MAX_POSITIONS: usize = 50;
+
+/// Seconds of playback between writes of the position to the config
+pub const SAVE_INTERVAL_SECS: u64 = 15;
+
+/// Positions before this many seconds start over
+const MIN_POSITION_SECS: u64 = 30;
+
+/// Positions within this many seconds of the end count as finished
+const END_MARGIN_SECS: u64 = 30;
+
+/// Where playback of a station with a duration was left
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResumePosition {
+    pub stationuuid: String,
+    pub position_secs: u64,
+    pub duration_secs: u64,
+    /// Unix seconds
+    pub at: u64,
+}
+
+/// The position to start a station at, if one was kept
+#[must_use]
+pub fn position(positions: &[ResumePosition], stationuuid: &str) -> Option<u64> {
+    positions
+        .iter()
+        .find(|p| p.stationuuid == stationuuid)
+        .map(|p| p.position_secs)
+}
+
+/// Remember how far a station with a duration was played, or forget it
+/// when near the start or finished
+pub fn record(
+    positions: &mut Vec<ResumePosition>,
+    stationuuid: &str,
+    position_secs: u64,
+    duration_secs: u64,
+    now: u64,
+) {
+    positions.retain(|p| p.stationuuid != stationuuid);
+    if position_secs < MIN_POSITION_SECS || position_secs + END_MARGIN_SECS >= duration_secs {
+        return;
+    }
+    positions.push(ResumePosition {
+        stationuuid: stationuuid.to_string(),
+        position_secs,
+        duration_secs,
+        at: now,
+    });
+    if positions.len() > MAX_POSITIONS {
+        positions.sort_by(|a, b| b.at.cmp(&a.at));
+        positions.truncate(MAX_POSITIONS);
+    }
+}
+
+/// A playback position as `m:ss`, or `h:mm:ss` from an hour on
+#[must_use]
+pub fn format_clock(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut positions = Vec::new();
+        record(&mut positions, "episode", 600, 3600, 1);
+        assert_eq!(position(&positions, "episode"), Some(600));
+        record(&mut positions, "episode", 900, 3600, 2);
+        assert_eq!(positions.len(), 1);
+        assert_eq!(position(&positions, "episode"), Some(900));
+
+        // Finishing or going back to the start forgets the position
+        record(&mut positions, "episode", 3590, 3600, 3);
+        assert_eq!(position(&positions, "episode"), None);
+        record(&mut positions, "other", 10, 3600, 4);
+        assert!(positions.is_empty());
+
+        for i in 0..=MAX_POSITIONS as u64 {
+            record(&mut positions, &i.to_string(), 60, 3600, i);
+        }
+        assert_eq!(positions.len(), MAX_POSITIONS);
+        assert_eq!(position(&positions, "0"), None);
+    }
+
+    #[test]
+    fn test_format_clock() {
+        assert_eq!(format_clock(0), "0:00");
+        assert_eq!(format_clock(754), "12:34");
+        assert_eq!(format_clock(3723), "1:02:03");
+    }
+}
@@ -0,0 +1,763 @@
+//! Program guide for favorite stations
+//!
+//! Weekly shows can be entered by hand or imported from an iCal feed (daily
+//! and weekly recurring events; one-off events are skipped). Shows are kept
+//! in local time, so "now on air" follows the clock on the desk rather than
+//! the station's time zone. A reminder can be sent a few minutes before a
+//! show starts.
+//!
+//! The same weekly windows drive the smart preset: rules such as "weekdays
+//! 07:00–09:00 → the news station, otherwise jazz" decide what the play
+//! button and resuming at login start, when nothing is playing.
+//!
+//! Recording rules use them too: a favorite is recorded for the length of
+//! each window, into a folder of the recordings directory. Rules of
+//! different stations may not overlap, as one recording runs at a time.
+
+use crate::api::http_client;
+use crate::error::ApiError;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::debug;
+
+const DAY_MINUTES: u32 = 24 * 60;
+const WEEK_MINUTES: u32 = 7 * DAY_MINUTES;
+
+/// Every day of the week, as a [`Show::days`] mask
+pub const EVERY_DAY: u8 = 0x7f;
+
+/// Lead times offered for reminders, in minutes (0 = off)
+pub const REMINDER_CHOICES: [u32; 5] = [0, 5, 10, 15, 30];
+
+/// Delay between reminder checks
+pub const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Day of the week and minute of the day in local time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalTime {
+    /// 0 = Monday … 6 = Sunday
+    pub weekday: u8,
+    /// Minutes after midnight
+    pub minute: u16,
+}
+
+impl LocalTime {
+    #[must_use]
+    pub fn now() -> Self {
+        Self::at(crate::stats::now_secs())
+    }
+
+    /// Local time of a Unix timestamp
+    #[must_use]
+    pub fn at(secs: u64) -> Self {
+        let minutes =
+            i64::try_from(secs / 60).unwrap_or_default() + i64::from(utc_offset_mins(secs));
+        Self::from_epoch_minutes(minutes)
+    }
+
+    fn from_epoch_minutes(minutes: i64) -> Self {
+        let days = minutes.div_euclid(i64::from(DAY_MINUTES));
+        Self {
+            // 1970-01-01 was a Thursday
+            weekday: (days + 3).rem_euclid(7) as u8,
+            minute: minutes.rem_euclid(i64::from(DAY_MINUTES)) as u16,
+        }
+    }
+
+    /// Minutes since Monday 00:00
+    fn week_minute(self) -> u32 {
+        u32::from(self.weekday) * DAY_MINUTES + u32::from(self.minute)
+    }
+}
+
+/// Offset of local time from UTC at `secs`, in minutes
+pub(crate) fn utc_offset_mins(secs: u64) -> i32 {
+    let time = libc::time_t::try_from(secs).unwrap_or_default();
+    // SAFETY: `tm` is plain data, and localtime_r only writes to it
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return 0;
+    }
+    (tm.tm_gmtoff / 60) as i32
+}
+
+/// Start of each weekly window on `days` at `start`, in minutes since Monday 00:00
+fn window_starts(days: u8, start: u16) -> impl Iterator<Item = u32> {
+    (0..7u32)
+        .filter(move |day| days & (1 << day) != 0)
+        .map(move |day| day * DAY_MINUTES + u32::from(start))
+}
+
+/// Whether `now` falls in a weekly window, which may run past midnight
+fn in_window(days: u8, start: u16, duration: u16, now: LocalTime) -> bool {
+    let now = now.week_minute();
+    window_starts(days, start)
+        .any(|start| (now + WEEK_MINUTES - start) % WEEK_MINUTES < u32::from(duration))
+}
+
+/// A weekly show on a favorite station
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Show {
+    pub stationuuid: String,
+    pub title: String,
+    /// Days it starts on, bit 0 = Monday … bit 6 = Sunday
+    pub days: u8,
+    /// Start in minutes after local midnight
+    pub start: u16,
+    /// Length in minutes
+    pub duration: u16,
+}
+
+impl Show {
+    #[must_use]
+    pub fn is_on_air(&self, now: LocalTime) -> bool {
+        in_window(self.days, self.start, self.duration, now)
+    }
+
+    /// Minutes until the show next starts (0 if it starts this minute)
+    #[must_use]
+    pub fn minutes_until(&self, now: LocalTime) -> Option<u32> {
+        let now = now.week_minute();
+        window_starts(self.days, self.start)
+            .map(|start| (start + WEEK_MINUTES - now) % WEEK_MINUTES)
+            .min()
+    }
+
+    /// End in minutes after local midnight
+    #[must_use]
+    pub fn end(&self) -> u16 {
+        ((u32::from(self.start) + u32::from(self.duration)) % DAY_MINUTES) as u16
+    }
+
+    /// Identifies an airing slot, for remembering sent reminders
+    #[must_use]
+    pub fn key(&self) -> String {
+        format!(
+            "{}|{}|{}|{}",
+            self.stationuuid, self.title, self.days, self.start
+        )
+    }
+}
+
+/// A smart preset window: the station the play button starts during it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresetRule {
+    pub stationuuid: String,
+    /// Days it starts on, bit 0 = Monday … bit 6 = Sunday
+    pub days: u8,
+    /// Start in minutes after local midnight
+    pub start: u16,
+    /// Length in minutes
+    pub duration: u16,
+}
+
+impl PresetRule {
+    #[must_use]
+    pub fn is_active(&self, now: LocalTime) -> bool {
+        in_window(self.days, self.start, self.duration, now)
+    }
+
+    /// End in minutes after local midnight
+    #[must_use]
+    pub fn end(&self) -> u16 {
+        ((u32::from(self.start) + u32::from(self.duration)) % DAY_MINUTES) as u16
+    }
+}
+
+/// A weekly window in which a favorite is recorded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordingRule {
+    pub stationuuid: String,
+    /// Days it starts on, bit 0 = Monday … bit 6 = Sunday
+    pub days: u8,
+    /// Start in minutes after local midnight
+    pub start: u16,
+    /// Length in minutes
+    pub duration: u16,
+    /// Folder in the recordings directory ("" = the directory itself)
+    #[serde(default)]
+    pub folder: String,
+}
+
+impl RecordingRule {
+    #[must_use]
+    pub fn is_active(&self, now: LocalTime) -> bool {
+        in_window(self.days, self.start, self.duration, now)
+    }
+
+    /// Minutes left of the window `now` falls in, if any
+    #[must_use]
+    pub fn minutes_left(&self, now: LocalTime) -> Option<u32> {
+        let now = now.week_minute();
+        window_starts(self.days, self.start)
+            .map(|start| (now + WEEK_MINUTES - start) % WEEK_MINUTES)
+            .filter(|elapsed| *elapsed < u32::from(self.duration))
+            .map(|elapsed| u32::from(self.duration) - elapsed)
+            .max()
+    }
+
+    /// End in minutes after local midnight
+    #[must_use]
+    pub fn end(&self) -> u16 {
+        ((u32::from(self.start) + u32::from(self.duration)) % DAY_MINUTES) as u16
+    }
+
+    /// Identifies a recording slot, for remembering recordings started
+    #[must_use]
+    pub fn key(&self) -> String {
+        format!("{}|{}|{}", self.stationuuid, self.days, self.start)
+    }
+
+    /// Whether any of the rule's windows overlaps one of `other`'s
+    #[must_use]
+    pub fn overlaps(&self, other: &RecordingRule) -> bool {
+        window_starts(self.days, self.start).any(|a| {
+            window_starts(other.days, other.start).any(|b| {
+                (b + WEEK_MINUTES - a) % WEEK_MINUTES < u32::from(self.duration)
+                    || (a + WEEK_MINUTES - b) % WEEK_MINUTES < u32::from(other.duration)
+            })
+        })
+    }
+}
+
+/// Recording rules whose window is open at `now`
+#[must_use]
+pub fn due_recordings(rules: &[RecordingRule], now: LocalTime) -> Vec<&RecordingRule> {
+    rules.iter().filter(|rule| rule.is_active(now)).collect()
+}
+
+/// Rules picking the station to start when nothing is playing
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmartPreset {
+    /// Checked in order; the first active rule wins
+    #[serde(default)]
+    pub rules: Vec<PresetRule>,
+    /// Station outside every rule ("" = the last station played)
+    #[serde(default)]
+    pub fallback: String,
+}
+
+impl SmartPreset {
+    /// UUID of the station to start at `now`, if the preset decides
+    #[must_use]
+    pub fn pick(&self, now: LocalTime) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|rule| rule.is_active(now))
+            .map(|rule| rule.stationuuid.as_str())
+            .or_else(|| (!self.fallback.is_empty()).then_some(self.fallback.as_str()))
+    }
+
+    /// Forget the rules and fallback of a station
+    pub fn remove_station(&mut self, stationuuid: &str) {
+        self.rules.retain(|rule| rule.stationuuid != stationuuid);
+        if self.fallback == stationuuid {
+            self.fallback.clear();
+        }
+    }
+}
+
+/// The station's show on air at `now`, if any
+#[must_use]
+pub fn on_air<'a>(shows: &'a [Show], stationuuid: &str, now: LocalTime) -> Option<&'a Show> {
+    shows
+        .iter()
+        .find(|show| show.stationuuid == stationuuid && show.is_on_air(now))
+}
+
+/// Shows starting within the next `lead_mins` minutes
+#[must_use]
+pub fn due_reminders(shows: &[Show], now: LocalTime, lead_mins: u32) -> Vec<&Show> {
+    if lead_mins == 0 {
+        return Vec::new();
+    }
+    shows
+        .iter()
+        .filter(|show| {
+            show.minutes_until(now)
+                .is_some_and(|minutes| minutes <= lead_mins)
+        })
+        .collect()
+}
+
+/// Parse a time of day such as `7:30` or `19:05`
+#[must_use]
+pub fn parse_time(text: &str) -> Option<u16> {
+    let (hours, minutes) = text.trim().split_once(':')?;
+    let hours: u16 = hours.parse().ok()?;
+    let minutes: u16 = minutes.parse().ok()?;
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+/// Time of day as `HH:MM`
+#[must_use]
+pub fn format_time(minute: u16) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+/// A show being entered on the schedule page
+#[derive(Debug, Clone, Default)]
+pub struct ShowDraft {
+    pub title: String,
+    pub days: u8,
+    pub start: String,
+    pub end: String,
+    /// Folder for a recording rule ("" = the recordings directory)
+    pub folder: String,
+}
+
+impl ShowDraft {
+    pub fn toggle_day(&mut self, day: u8) {
+        self.days ^= 1 << day;
+    }
+
+    /// Start and length, if days and valid times are entered
+    fn window(&self) -> Option<(u16, u16)> {
+        let start = parse_time(&self.start)?;
+        let end = parse_time(&self.end)?;
+        let duration = (u32::from(end) + DAY_MINUTES - u32::from(start)) % DAY_MINUTES;
+        (self.days != 0 && duration > 0).then_some((start, duration as u16))
+    }
+
+    /// The show, if the draft is complete; a show may run past midnight
+    #[must_use]
+    pub fn to_show(&self, stationuuid: &str) -> Option<Show> {
+        let title = self.title.trim();
+        let (start, duration) = self.window()?;
+        (!title.is_empty()).then(|| Show {
+            stationuuid: stationuuid.to_string(),
+            title: title.to_string(),
+            days: self.days,
+            start,
+            duration,
+        })
+    }
+
+    /// A smart preset rule for the draft's days and times; the title is unused
+    #[must_use]
+    pub fn to_preset_rule(&self, stationuuid: &str) -> Option<PresetRule> {
+        let (start, duration) = self.window()?;
+        Some(PresetRule {
+            stationuuid: stationuuid.to_string(),
+            days: self.days,
+            start,
+            duration,
+        })
+    }
+
+    /// A recording rule for the draft's days, times, and folder; the title
+    /// is unused
+    #[must_use]
+    pub fn to_recording_rule(&self, stationuuid: &str) -> Option<RecordingRule> {
+        let (start, duration) = self.window()?;
+        Some(RecordingRule {
+            stationuuid: stationuuid.to_string(),
+            days: self.days,
+            start,
+            duration,
+            folder: self.folder.trim().to_string(),
+        })
+    }
+}
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Minutes since the epoch of an iCal `YYYYMMDDTHHMMSS[Z]` date-time, and
+/// whether it is in UTC
+fn parse_ical_datetime(value: &str) -> Option<(i64, bool)> {
+    let (value, utc) = match value.strip_suffix('Z') {
+        Some(value) => (value, true),
+        None => (value, false),
+    };
+    let (date, time) = value.split_once('T')?;
+    if date.len() != 8 || time.len() < 4 || !value.is_ascii() {
+        return None;
+    }
+    let number = |text: &str| text.parse::<i64>().ok();
+    let days = days_from_civil(
+        number(&date[..4])?,
+        number(&date[4..6])?,
+        number(&date[6..])?,
+    );
+    let minutes = number(&time[..2])? * 60 + number(&time[2..4])?;
+    Some((days * i64::from(DAY_MINUTES) + minutes, utc))
+}
+
+/// Minutes of an iCal duration such as `PT1H30M` or `P1D`
+fn parse_ical_duration(value: &str) -> Option<i64> {
+    let mut minutes = 0;
+    let mut number = String::new();
+    for c in value.strip_prefix('P')?.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            'W' | 'D' | 'H' | 'M' | 'S' => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                minutes += match c {
+                    'W' => n * 7 * i64::from(DAY_MINUTES),
+                    'D' => n * i64::from(DAY_MINUTES),
+                    'H' => n * 60,
+                    'M' => n,
+                    _ => 0,
+                };
+            }
+            _ => return None,
+        }
+    }
+    Some(minutes)
+}
+
+/// Day mask of an RRULE, relative to the event's first day; `None` unless
+/// the event repeats daily or weekly
+fn rrule_days(rule: &str, first_day: u8) -> Option<u8> {
+    let mut freq = None;
+    let mut by_day = 0u8;
+    for part in rule.split(';') {
+        match part.split_once('=') {
+            Some(("FREQ", value)) => freq = Some(value),
+            Some(("BYDAY", value)) => {
+                for day in value.split(',') {
+                    let index = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"]
+                        .iter()
+                        .position(|d| day.ends_with(d))?;
+                    by_day |= 1 << index;
+                }
+            }
+            _ => {}
+        }
+    }
+    match freq? {
+        "DAILY" => Some(EVERY_DAY),
+        "WEEKLY" if by_day != 0 => Some(by_day),
+        "WEEKLY" => Some(1 << first_day),
+        _ => None,
+    }
+}
+
+/// Move every day in a day mask `by` days later in the week
+fn rotate_week(days: u8, by: u8) -> u8 {
+    let days = u16::from(days);
+    let by = by % 7;
+    ((days << by | days >> (7 - by)) & u16::from(EVERY_DAY)) as u8
+}
+
+fn unescape_ical(text: &str) -> String {
+    text.replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+/// Recurring shows in an iCal calendar. UTC times are moved to local time
+/// by `utc_offset_mins`; other times are taken as local.
+#[must_use]
+pub fn parse_ical(text: &str, stationuuid: &str, utc_offset_mins: i32) -> Vec<Show> {
+    // Undo line folding: continuation lines start with a space or tab
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.trim_end().to_string()),
+        }
+    }
+
+    let mut shows = Vec::new();
+    let mut event: Option<Vec<(String, String)>> = None;
+    for line in lines {
+        match line.as_str() {
+            "BEGIN:VEVENT" => event = Some(Vec::new()),
+            "END:VEVENT" => {
+                if let Some(show) = event
+                    .take()
+                    .and_then(|props| show_from_event(&props, stationuuid, utc_offset_mins))
+                {
+                    shows.push(show);
+                }
+            }
+            _ => {
+                if let (Some(props), Some((name, value))) = (event.as_mut(), line.split_once(':')) {
+                    props.push((name.to_string(), value.to_string()));
+                }
+            }
+        }
+    }
+    shows
+}
+
+fn show_from_event(props: &[(String, String)], stationuuid: &str, offset: i32) -> Option<Show> {
+    let get = |key: &str| {
+        props.iter().find_map(|(name, value)| {
+            let (base, params) = name.split_once(';').unwrap_or((name.as_str(), ""));
+            (base == key).then_some((params, value.as_str()))
+        })
+    };
+    let (params, start) = get("DTSTART")?;
+    if params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME") {
+        debug!("Skipping all-day event");
+        return None;
+    }
+    let (start, utc) = parse_ical_datetime(start)?;
+    let duration = match (get("DTEND"), get("DURATION")) {
+        (Some((_, end)), _) => parse_ical_datetime(end)?.0 - start,
+        (None, Some((_, duration))) => parse_ical_duration(duration)?,
+        (None, None) => return None,
+    };
+    if duration <= 0 || duration >= i64::from(DAY_MINUTES) {
+        return None;
+    }
+
+    // Days in the event's own time, moved to local days: a UTC evening
+    // show may start the next morning here
+    let event_day = LocalTime::from_epoch_minutes(start).weekday;
+    let first = LocalTime::from_epoch_minutes(if utc {
+        start + i64::from(offset)
+    } else {
+        start
+    });
+    let days = rotate_week(
+        rrule_days(get("RRULE")?.1, event_day)?,
+        (first.weekday + 7 - event_day) % 7,
+    );
+    Some(Show {
+        stationuuid: stationuuid.to_string(),
+        title: unescape_ical(get("SUMMARY").map_or("", |(_, title)| title))
+            .trim()
+            .to_string(),
+        days,
+        start: first.minute,
+        duration: duration as u16,
+    })
+}
+
+/// Download an iCal calendar
+pub async fn fetch_ical(url: String, timeout: Duration) -> Result<String, ApiError> {
+    debug!("Fetching program guide from {}", url);
+    Ok(http_client()
+        .get(url)
+        .timeout(timeout)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?)
+}
+
+/// Import the shows of an iCal calendar for a station, in the current local time zone
+#[must_use]
+pub fn import_ical(text: &str, stationuuid: &str) -> Vec<Show> {
+    parse_ical(text, stationuuid, utc_offset_mins(crate::stats::now_secs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn show(days: u8, start: u16, duration: u16) -> Show {
+        Show {
+            stationuuid: "jazz".to_string(),
+            title: "Late Night Jazz".to_string(),
+            days,
+            start,
+            duration,
+        }
+    }
+
+    fn at(weekday: u8, minute: u16) -> LocalTime {
+        LocalTime { weekday, minute }
+    }
+
+    #[test]
+    fn test_on_air_wraps_past_midnight_and_week() {
+        // Sundays 23:00 to 01:00
+        let show = show(1 << 6, 23 * 60, 120);
+        assert!(show.is_on_air(at(6, 23 * 60)));
+        assert!(show.is_on_air(at(0, 59)));
+        assert!(!show.is_on_air(at(0, 60)));
+        assert!(!show.is_on_air(at(5, 23 * 60)));
+        assert_eq!(show.end(), 60);
+    }
+
+    #[test]
+    fn test_minutes_until_and_reminders() {
+        // Weekdays at 07:00
+        let shows = vec![show(0x1f, 7 * 60, 60)];
+        assert_eq!(shows[0].minutes_until(at(0, 6 * 60 + 50)), Some(10));
+        assert_eq!(
+            shows[0].minutes_until(at(4, 8 * 60)),
+            Some(3 * DAY_MINUTES - 60)
+        );
+        assert_eq!(due_reminders(&shows, at(0, 6 * 60 + 50), 10).len(), 1);
+        assert!(due_reminders(&shows, at(0, 6 * 60 + 40), 10).is_empty());
+        assert!(due_reminders(&shows, at(0, 6 * 60 + 50), 0).is_empty());
+        assert_eq!(
+            on_air(&shows, "jazz", at(2, 7 * 60 + 30)).map(|s| s.title.as_str()),
+            Some("Late Night Jazz")
+        );
+        assert!(on_air(&shows, "other", at(2, 7 * 60 + 30)).is_none());
+    }
+
+    #[test]
+    fn test_local_time_from_epoch() {
+        // 1970-01-01 00:00 was a Thursday; 2024-03-04 07:30 a Monday
+        assert_eq!(LocalTime::from_epoch_minutes(0), at(3, 0));
+        let monday = days_from_civil(2024, 3, 4) * i64::from(DAY_MINUTES) + 7 * 60 + 30;
+        assert_eq!(LocalTime::from_epoch_minutes(monday), at(0, 7 * 60 + 30));
+    }
+
+    #[test]
+    fn test_draft_to_show() {
+        let mut draft = ShowDraft {
+            title: " Night Shift ".to_string(),
+            days: 0,
+            start: "22:00".to_string(),
+            end: "2:00".to_string(),
+            ..ShowDraft::default()
+        };
+        assert!(draft.to_show("jazz").is_none());
+        draft.toggle_day(4);
+        let show = draft.to_show("jazz").unwrap();
+        assert_eq!(show.title, "Night Shift");
+        assert_eq!(
+            (show.days, show.start, show.duration),
+            (1 << 4, 22 * 60, 240)
+        );
+
+        draft.end = "22:00".to_string();
+        assert!(draft.to_show("jazz").is_none());
+        draft.end = "25:00".to_string();
+        assert!(draft.to_show("jazz").is_none());
+    }
+
+    #[test]
+    fn test_smart_preset_pick() {
+        let mut preset = SmartPreset {
+            rules: vec![PresetRule {
+                stationuuid: "news".to_string(),
+                days: 0x1f,
+                start: 7 * 60,
+                duration: 120,
+            }],
+            fallback: String::new(),
+        };
+        assert_eq!(preset.pick(at(0, 7 * 60 + 30)), Some("news"));
+        assert_eq!(preset.pick(at(0, 9 * 60)), None);
+        assert_eq!(preset.pick(at(5, 7 * 60 + 30)), None);
+
+        preset.fallback = "jazz".to_string();
+        assert_eq!(preset.pick(at(0, 8 * 60)), Some("news"));
+        assert_eq!(preset.pick(at(5, 8 * 60)), Some("jazz"));
+
+        preset.remove_station("news");
+        assert!(preset.rules.is_empty());
+        assert_eq!(preset.pick(at(0, 8 * 60)), Some("jazz"));
+    }
+
+    #[test]
+    fn test_draft_to_preset_rule() {
+        let draft = ShowDraft {
+            title: String::new(),
+            days: 0x1f,
+            start: "7:00".to_string(),
+            end: "9:00".to_string(),
+            ..ShowDraft::default()
+        };
+        assert!(draft.to_show("news").is_none());
+        let rule = draft.to_preset_rule("news").unwrap();
+        assert_eq!(
+            (rule.start, rule.duration, rule.end()),
+            (7 * 60, 120, 9 * 60)
+        );
+    }
+
+    #[test]
+    fn test_recording_rules() {
+        let draft = ShowDraft {
+            days: 1 << 4,
+            start: "23:00".to_string(),
+            end: "1:00".to_string(),
+            folder: " Night Shift ".to_string(),
+            ..ShowDraft::default()
+        };
+        // Fridays 23:00 to Saturdays 01:00
+        let rule = draft.to_recording_rule("jazz").unwrap();
+        assert_eq!(rule.folder, "Night Shift");
+        assert_eq!(rule.minutes_left(at(4, 23 * 60)), Some(120));
+        assert_eq!(rule.minutes_left(at(5, 30)), Some(30));
+        assert_eq!(rule.minutes_left(at(5, 60)), None);
+        assert_eq!(due_recordings(&[rule.clone()], at(5, 30)).len(), 1);
+
+        let other = |days, start, duration| RecordingRule {
+            stationuuid: "news".to_string(),
+            days,
+            start,
+            duration,
+            folder: String::new(),
+        };
+        assert!(rule.overlaps(&other(1 << 5, 0, 30)));
+        assert!(rule.overlaps(&other(1 << 4, 22 * 60, 90)));
+        assert!(!rule.overlaps(&other(1 << 4, 22 * 60, 60)));
+        assert!(!rule.overlaps(&other(1 << 5, 60, 60)));
+    }
+
+    #[test]
+    fn test_parse_ical() {
+        let calendar = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\n\
+            SUMMARY:Morning\\, News\r\n\
+            DTSTART;TZID=Europe/Lisbon:20240304T070000\r\n\
+            DTEND;TZID=Europe/Lisbon:20240304T083000\r\n\
+            RRULE:FREQ=WEEKLY;BYDAY=MO,\r\n \
+            WE,FR\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            SUMMARY:Late Mix\r\n\
+            DTSTART:20240303T233000Z\r\n\
+            DURATION:PT1H\r\n\
+            RRULE:FREQ=WEEKLY\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            SUMMARY:One-off special\r\n\
+            DTSTART:20240305T120000\r\n\
+            DTEND:20240305T130000\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        // UTC+1: the Sunday 23:30 UTC show airs Mondays at 00:30 locally
+        let shows = parse_ical(calendar, "jazz", 60);
+        assert_eq!(shows.len(), 2);
+        assert_eq!(shows[0].title, "Morning, News");
+        assert_eq!(
+            (shows[0].days, shows[0].start, shows[0].duration),
+            (0b10101, 7 * 60, 90)
+        );
+        assert_eq!(shows[1].title, "Late Mix");
+        assert_eq!(
+            (shows[1].days, shows[1].start, shows[1].duration),
+            (1, 30, 60)
+        );
+    }
+
+    #[test]
+    fn test_rotate_week() {
+        assert_eq!(rotate_week(1 << 6, 1), 1);
+        assert_eq!(rotate_week(1, 6), 1 << 6);
+        assert_eq!(rotate_week(0b101, 0), 0b101);
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("7:30"), Some(450));
+        assert_eq!(parse_time("24:00"), None);
+        assert_eq!(parse_time("noon"), None);
+        assert_eq!(format_time(450), "07:30");
+    }
+}
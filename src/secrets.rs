@@ -0,0 +1,193 @@
+//! Credential storage via the freedesktop Secret Service
+//!
+//! Service tokens and stream credentials are kept in the user's keyring
+//! (GNOME Keyring, KWallet, KeePassXC, ...) through `org.freedesktop.secrets`
+//! instead of the plaintext cosmic-config files. Items are tagged with an
+//! `application` attribute and looked up by a `key` attribute, so they can
+//! also be managed with `secret-tool`:
+//!
+//! ```text
+//! secret-tool store --label="Radio MQTT" application com.marcos.RadioApplet key mqtt-password
+//! ```
+
+use crate::error::SecretError;
+use mpris_server::zbus::{
+    zvariant::{OwnedObjectPath, OwnedValue, Value},
+    Connection,
+};
+use std::collections::HashMap;
+
+const SERVICE: &str = "org.freedesktop.secrets";
+const SERVICE_PATH: &str = "/org/freedesktop/secrets";
+const SERVICE_IFACE: &str = "org.freedesktop.Secret.Service";
+const ITEM_IFACE: &str = "org.freedesktop.Secret.Item";
+const COLLECTION_IFACE: &str = "org.freedesktop.Secret.Collection";
+const APPLICATION: &str = "com.marcos.RadioApplet";
+
+/// Keyring key holding the MQTT broker password
+pub const MQTT_PASSWORD_KEY: &str = "mqtt-password";
+
+/// Keyring key holding the token of the local control endpoint
+pub const CONTROL_TOKEN_KEY: &str = "control-token";
+
+/// Keyring key holding the PIN that unlocks lock mode
+pub const KIOSK_PIN_KEY: &str = "kiosk-pin";
+
+/// Keyring key for the stream credentials of a station
+#[must_use]
+pub fn station_key(stationuuid: &str) -> String {
+    format!("station:{stationuuid}")
+}
+
+/// Attributes identifying one of our items
+fn attributes(key: &str) -> HashMap<&str, &str> {
+    HashMap::from([("application", APPLICATION), ("key", key)])
+}
+
+/// An open, unencrypted ("plain") Secret Service session
+struct Session {
+    connection: Connection,
+    path: OwnedObjectPath,
+}
+
+impl Session {
+    async fn open() -> Result<Self, SecretError> {
+        let connection = Connection::session().await?;
+        let (_output, path): (OwnedValue, OwnedObjectPath) = connection
+            .call_method(
+                Some(SERVICE),
+                SERVICE_PATH,
+                Some(SERVICE_IFACE),
+                "OpenSession",
+                &("plain", Value::from("")),
+            )
+            .await?
+            .body()
+            .deserialize()?;
+        Ok(Self { connection, path })
+    }
+
+    async fn search(&self, key: &str) -> Result<Option<OwnedObjectPath>, SecretError> {
+        let (unlocked, locked): (Vec<OwnedObjectPath>, Vec<OwnedObjectPath>) = self
+            .connection
+            .call_method(
+                Some(SERVICE),
+                SERVICE_PATH,
+                Some(SERVICE_IFACE),
+                "SearchItems",
+                &(attributes(key),),
+            )
+            .await?
+            .body()
+            .deserialize()?;
+
+        match unlocked.into_iter().next() {
+            Some(item) => Ok(Some(item)),
+            None if !locked.is_empty() => Err(SecretError::Locked),
+            None => Ok(None),
+        }
+    }
+
+    async fn default_collection(&self) -> Result<OwnedObjectPath, SecretError> {
+        let path: OwnedObjectPath = self
+            .connection
+            .call_method(
+                Some(SERVICE),
+                SERVICE_PATH,
+                Some(SERVICE_IFACE),
+                "ReadAlias",
+                &("default",),
+            )
+            .await?
+            .body()
+            .deserialize()?;
+        // The service answers "/" when no default collection exists
+        if path.as_str() == "/" {
+            return Err(SecretError::NoCollection);
+        }
+        Ok(path)
+    }
+}
+
+/// Look up a stored secret
+pub async fn lookup(key: &str) -> Result<Option<String>, SecretError> {
+    let session = Session::open().await?;
+    let Some(item) = session.search(key).await? else {
+        return Ok(None);
+    };
+
+    let (_session, _params, value, _content_type): (OwnedObjectPath, Vec<u8>, Vec<u8>, String) =
+        session
+            .connection
+            .call_method(
+                Some(SERVICE),
+                item.as_str(),
+                Some(ITEM_IFACE),
+                "GetSecret",
+                &(&session.path,),
+            )
+            .await?
+            .body()
+            .deserialize()?;
+
+    String::from_utf8(value)
+        .map(Some)
+        .map_err(|_| SecretError::InvalidEncoding)
+}
+
+/// Store (or replace) a secret in the default collection
+pub async fn store(key: &str, label: &str, secret: &str) -> Result<(), SecretError> {
+    let session = Session::open().await?;
+    let collection = session.default_collection().await?;
+
+    let properties: HashMap<&str, Value<'_>> = HashMap::from([
+        ("org.freedesktop.Secret.Item.Label", Value::from(label)),
+        (
+            "org.freedesktop.Secret.Item.Attributes",
+            Value::from(attributes(key)),
+        ),
+    ]);
+    let secret_struct = (
+        session.path.clone(),
+        Vec::<u8>::new(),
+        secret.as_bytes().to_vec(),
+        "text/plain",
+    );
+
+    let (_item, prompt): (OwnedObjectPath, OwnedObjectPath) = session
+        .connection
+        .call_method(
+            Some(SERVICE),
+            collection.as_str(),
+            Some(COLLECTION_IFACE),
+            "CreateItem",
+            &(properties, secret_struct, true),
+        )
+        .await?
+        .body()
+        .deserialize()?;
+
+    // A prompt means the collection is locked and needs user interaction
+    if prompt.as_str() != "/" {
+        return Err(SecretError::Locked);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attributes_tag_application() {
+        let attrs = attributes(MQTT_PASSWORD_KEY);
+        assert_eq!(attrs.get("application"), Some(&"com.marcos.RadioApplet"));
+        assert_eq!(attrs.get("key"), Some(&"mqtt-password"));
+        assert_eq!(attrs.len(), 2);
+    }
+
+    #[test]
+    fn test_station_key() {
+        assert_eq!(station_key("abc-123"), "station:abc-123");
+    }
+}
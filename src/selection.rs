@@ -0,0 +1,141 @@
+//! Multi-select in station lists
+//!
+//! In select mode, station rows get a checkbox, and batch actions (favorite,
+//! add to a profile, hide, export) apply to every checked station. Stations
+//! are tracked by UUID, so a selection survives a new search as long as the
+//! stations are still listed somewhere.
+
+use crate::api::Station;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    active: bool,
+    uuids: HashSet<String>,
+}
+
+impl Selection {
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Enter or leave select mode; leaving forgets the selection
+    pub fn toggle_active(&mut self) {
+        self.active = !self.active;
+        self.uuids.clear();
+    }
+
+    #[must_use]
+    pub fn contains(&self, station: &Station) -> bool {
+        self.uuids.contains(&station.stationuuid)
+    }
+
+    pub fn toggle(&mut self, uuid: String) {
+        if !self.uuids.remove(&uuid) {
+            self.uuids.insert(uuid);
+        }
+    }
+
+    /// Check every station in `stations`, or uncheck them all if they already are
+    pub fn toggle_all<'a>(&mut self, stations: impl IntoIterator<Item = &'a Station>) {
+        let uuids: Vec<&String> = stations.into_iter().map(|s| &s.stationuuid).collect();
+        if uuids.iter().all(|uuid| self.uuids.contains(*uuid)) {
+            for uuid in uuids {
+                self.uuids.remove(uuid);
+            }
+        } else {
+            self.uuids.extend(uuids.into_iter().cloned());
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.uuids.clear();
+    }
+
+    /// The selected stations among `stations`, once each, in list order
+    #[must_use]
+    pub fn stations<'a>(&self, stations: impl IntoIterator<Item = &'a Station>) -> Vec<Station> {
+        let mut seen = HashSet::new();
+        stations
+            .into_iter()
+            .filter(|s| self.uuids.contains(&s.stationuuid) && seen.insert(&s.stationuuid))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Append the stations not already in `favorites`, returning how many were added
+pub fn add_missing(favorites: &mut Vec<Station>, stations: &[Station]) -> usize {
+    let mut added = 0;
+    for station in stations {
+        if !favorites
+            .iter()
+            .any(|s| s.stationuuid == station.stationuuid)
+        {
+            favorites.push(station.clone());
+            added += 1;
+        }
+    }
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn station(uuid: &str) -> Station {
+        Station {
+            stationuuid: uuid.to_string(),
+            ..Station::default()
+        }
+    }
+
+    #[test]
+    fn test_toggle() {
+        let mut selection = Selection::default();
+        selection.toggle_active();
+        selection.toggle("a".to_string());
+        assert!(selection.contains(&station("a")));
+        selection.toggle("a".to_string());
+        assert!(!selection.contains(&station("a")));
+
+        selection.toggle("b".to_string());
+        selection.toggle_active();
+        assert!(!selection.is_active());
+        assert!(!selection.contains(&station("b")));
+    }
+
+    #[test]
+    fn test_toggle_all() {
+        let list = [station("a"), station("b")];
+        let mut selection = Selection::default();
+        selection.toggle("a".to_string());
+        selection.toggle_all(&list);
+        assert!(list.iter().all(|s| selection.contains(s)));
+        selection.toggle_all(&list);
+        assert!(!list.iter().any(|s| selection.contains(s)));
+    }
+
+    #[test]
+    fn test_stations_in_list_order_once() {
+        let favorites = [station("c"), station("a")];
+        let results = [station("a"), station("b")];
+        let mut selection = Selection::default();
+        selection.toggle("a".to_string());
+        selection.toggle("c".to_string());
+        let picked = selection.stations(favorites.iter().chain(&results));
+        let uuids: Vec<&str> = picked.iter().map(|s| s.stationuuid.as_str()).collect();
+        assert_eq!(uuids, ["c", "a"]);
+    }
+
+    #[test]
+    fn test_add_missing() {
+        let mut favorites = vec![station("a")];
+        assert_eq!(
+            add_missing(&mut favorites, &[station("a"), station("b")]),
+            1
+        );
+        assert_eq!(favorites.len(), 2);
+    }
+}
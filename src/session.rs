@@ -0,0 +1,100 @@
+//! Session state for crash recovery
+//!
+//! The current station, whether it was playing, the station played before
+//! it (for switching back), and the visible page are
+//! kept in a cosmic-config state document (`~/.local/state/cosmic/
+//! com.marcos.RadioApplet.Session/`) and rewritten whenever they change. When
+//! the applet or panel restarts, it comes back on the same station and page,
+//! and resumes playback if [`Config::resume_playback`](crate::config::Config)
+//! is set.
+//!
+//! The popup's own state is kept alongside: the search text, how far each
+//! page was scrolled, and whether the diagnostics panel was expanded. It is
+//! written when the popup closes, so the popup reopens where it was left,
+//! after a restart too.
+
+use crate::api::Station;
+use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::warn;
+
+const STATE_ID: &str = "com.marcos.RadioApplet.Session";
+
+/// Content of the station list area when not searching
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Page {
+    #[default]
+    Favorites,
+    Discover,
+    Tracks,
+    Stats,
+    Settings,
+    /// Program guide of one favorite
+    Schedule,
+    /// Recorded streams
+    Recordings,
+}
+
+/// Transient state restored after a restart
+#[derive(Debug, Clone, Default, CosmicConfigEntry, Eq, PartialEq, Serialize, Deserialize)]
+#[version = 1]
+pub struct SessionState {
+    #[serde(default)]
+    pub station: Option<Station>,
+    #[serde(default)]
+    pub playing: bool,
+    /// The station played before `station`, switched back to with "B"
+    #[serde(default)]
+    pub previous_station: Option<Station>,
+    #[serde(default)]
+    pub page: Page,
+    /// Text in the search box
+    #[serde(default)]
+    pub search: String,
+    /// Vertical scroll offset of each page's list, in pixels
+    #[serde(default)]
+    pub scroll: HashMap<Page, u32>,
+    /// The diagnostics panel is expanded
+    #[serde(default)]
+    pub show_diagnostics: bool,
+}
+
+fn handler() -> Result<cosmic_config::Config, cosmic_config::Error> {
+    cosmic_config::Config::new_state(STATE_ID, SessionState::VERSION)
+}
+
+/// Load the last saved session, or an empty one
+#[must_use]
+pub fn load() -> SessionState {
+    match handler() {
+        Ok(handler) => SessionState::get_entry(&handler).unwrap_or_else(|(errs, state)| {
+            warn!("Errors loading session state: {:?}", errs);
+            state
+        }),
+        Err(e) => {
+            warn!("Cannot open session state: {:?}", e);
+            SessionState::default()
+        }
+    }
+}
+
+/// Persist the session
+pub fn save(state: &SessionState) -> Result<(), cosmic_config::Error> {
+    state.write_entry(&handler()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_state_default() {
+        let state = SessionState::default();
+        assert!(state.station.is_none());
+        assert!(!state.playing);
+        assert_eq!(state.page, Page::Favorites);
+        assert!(state.search.is_empty());
+        assert!(state.scroll.is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+//! Links for sharing a station
+//!
+//! A shareable link points at something a person can open in a browser: the
+//! station's homepage, or else its radio-browser.info page. The stream URL is
+//! offered separately for pasting into another player.
+
+use crate::api::{self, Station};
+
+/// Station page on the radio-browser.info website
+const RADIO_BROWSER_PAGE: &str = "https://www.radio-browser.info/history/";
+
+/// What gets copied to the clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareTarget {
+    /// Homepage or directory page
+    Page,
+    /// Playable stream URL
+    Stream,
+}
+
+fn is_web_url(url: &str) -> bool {
+    url::Url::parse(url).is_ok_and(|u| matches!(u.scheme(), "http" | "https"))
+}
+
+/// Link to share for `target`, if the station has one
+#[must_use]
+pub fn link(station: &Station, target: ShareTarget) -> Option<String> {
+    match target {
+        ShareTarget::Page => {
+            if is_web_url(&station.homepage) {
+                Some(station.homepage.clone())
+            } else if api::is_votable(station) {
+                Some(format!("{RADIO_BROWSER_PAGE}{}", station.stationuuid))
+            } else {
+                None
+            }
+        }
+        ShareTarget::Stream => [&station.url_resolved, &station.url]
+            .into_iter()
+            .find(|url| is_web_url(url))
+            .cloned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn station() -> Station {
+        Station {
+            stationuuid: "abc-123".to_string(),
+            url: "http://example.com/live.pls".to_string(),
+            url_resolved: "http://example.com/live.mp3".to_string(),
+            homepage: "https://example.com".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_page_prefers_homepage() {
+        assert_eq!(
+            link(&station(), ShareTarget::Page).as_deref(),
+            Some("https://example.com")
+        );
+    }
+
+    #[test]
+    fn test_page_falls_back_to_directory() {
+        let mut station = station();
+        station.homepage = "not a url".to_string();
+        assert_eq!(
+            link(&station, ShareTarget::Page).as_deref(),
+            Some("https://www.radio-browser.info/history/abc-123")
+        );
+
+        station.source = "SomaFM".to_string();
+        assert_eq!(link(&station, ShareTarget::Page), None);
+    }
+
+    #[test]
+    fn test_stream_prefers_resolved_url() {
+        let mut station = station();
+        assert_eq!(
+            link(&station, ShareTarget::Stream).as_deref(),
+            Some("http://example.com/live.mp3")
+        );
+
+        station.url_resolved.clear();
+        assert_eq!(
+            link(&station, ShareTarget::Stream).as_deref(),
+            Some("http://example.com/live.pls")
+        );
+
+        station.url = "file:///etc/passwd".to_string();
+        assert_eq!(link(&station, ShareTarget::Stream), None);
+    }
+}
@@ -0,0 +1,87 @@
+//! Graceful shutdown
+//!
+//! `Drop` does not run when the process is killed by a signal, which used to
+//! leave mpv playing and the current listening session unrecorded after a
+//! logout. The subscription here turns SIGTERM, SIGINT, and SIGHUP into a
+//! message so the applet can stop playback and flush its state before
+//! exiting. If that takes longer than [`SHUTDOWN_TIMEOUT`], or a second
+//! signal arrives, the process exits anyway.
+
+use futures::SinkExt;
+use std::time::Duration;
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{error, warn};
+
+/// Grace period for cleanup before the process is forced to exit
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Signal that asked the applet to stop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownSignal {
+    Terminate,
+    Interrupt,
+    Hangup,
+}
+
+impl ShutdownSignal {
+    fn exit_code(self) -> i32 {
+        // 128 + signal number, as a shell would report it
+        128 + match self {
+            ShutdownSignal::Hangup => libc::SIGHUP,
+            ShutdownSignal::Interrupt => libc::SIGINT,
+            ShutdownSignal::Terminate => libc::SIGTERM,
+        }
+    }
+}
+
+/// Subscription emitting the first termination signal received
+pub fn signal_subscription() -> cosmic::iced::Subscription<ShutdownSignal> {
+    cosmic::iced::Subscription::run(|| {
+        cosmic::iced::stream::channel(1, |mut output| async move {
+            let (Ok(mut term), Ok(mut int), Ok(mut hup)) = (
+                signal(SignalKind::terminate()),
+                signal(SignalKind::interrupt()),
+                signal(SignalKind::hangup()),
+            ) else {
+                error!("Failed to install signal handlers; shutdown will not be graceful");
+                return;
+            };
+
+            let mut received = None;
+            loop {
+                let sig = tokio::select! {
+                    _ = term.recv() => ShutdownSignal::Terminate,
+                    _ = int.recv() => ShutdownSignal::Interrupt,
+                    _ = hup.recv() => ShutdownSignal::Hangup,
+                };
+                if received.is_some() {
+                    warn!("Received {:?} again, exiting immediately", sig);
+                    std::process::exit(sig.exit_code());
+                }
+                received = Some(sig);
+
+                // Watchdog in case cleanup hangs
+                tokio::spawn(async move {
+                    tokio::time::sleep(SHUTDOWN_TIMEOUT).await;
+                    warn!("Shutdown timed out, exiting");
+                    std::process::exit(sig.exit_code());
+                });
+                if output.send(sig).await.is_err() {
+                    std::process::exit(sig.exit_code());
+                }
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(ShutdownSignal::Terminate.exit_code(), 143);
+        assert_eq!(ShutdownSignal::Interrupt.exit_code(), 130);
+        assert_eq!(ShutdownSignal::Hangup.exit_code(), 129);
+    }
+}
@@ -0,0 +1,189 @@
+//! Checking that a stream URL serves audio
+//!
+//! Directory entries sometimes point at the station's web page or a dead
+//! link's parking page instead of the stream, and mpv then hangs trying to
+//! demux HTML. Before playback, the stream is requested once and its
+//! content type and first bytes are checked for audio or a playlist; web
+//! pages and other documents are refused with a message saying so.
+
+use crate::api::http_client;
+use std::time::Duration;
+
+/// Content types that are audio containers or playlists without `audio/`
+const PLAYABLE_TYPES: [&str; 8] = [
+    "application/ogg",
+    "application/x-mpegurl",
+    "application/vnd.apple.mpegurl",
+    "application/pls+xml",
+    "application/xspf+xml",
+    "video/mp2t",
+    "video/ogg",
+    "application/octet-stream",
+];
+
+/// Why a URL is not played
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejection {
+    /// An HTML page
+    WebPage,
+    /// Some other document, by content type
+    NotAudio(String),
+}
+
+/// Outcome of requesting a stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sniffed {
+    /// Where the redirects ended
+    pub url: String,
+    pub rejection: Option<Rejection>,
+}
+
+/// Magic numbers of the audio containers radio streams use
+fn is_audio(head: &[u8]) -> bool {
+    let mpeg_sync = head.len() >= 2 && head[0] == 0xff && head[1] & 0xe0 == 0xe0;
+    mpeg_sync
+        || [
+            &b"ID3"[..],
+            b"OggS",
+            b"fLaC",
+            b"RIFF",
+            b"ADIF",
+            b"\x30\x26\xb2\x75",
+        ]
+        .iter()
+        .any(|magic| head.starts_with(magic))
+}
+
+/// Text playlists: M3U, PLS, XSPF, ASX, or a bare list of URLs
+fn is_playlist(head: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(head);
+    let text = text
+        .trim_start_matches('\u{feff}')
+        .trim_start()
+        .to_ascii_lowercase();
+    [
+        "#extm3u",
+        "[playlist]",
+        "http://",
+        "https://",
+        "<asx",
+        "<?xml",
+    ]
+    .iter()
+    .any(|start| text.starts_with(start))
+        || (text.starts_with("<playlist") && text.contains("xspf"))
+}
+
+fn is_html(head: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(head);
+    let text = text
+        .trim_start_matches('\u{feff}')
+        .trim_start()
+        .to_ascii_lowercase();
+    text.starts_with("<!doctype html") || text.starts_with("<html") || text.starts_with("<head")
+}
+
+/// Judge a response by its content type and first bytes. The bytes win
+/// over the label, since servers mislabel streams both ways; unknown bytes
+/// with an audio or generic label are left to mpv.
+#[must_use]
+pub fn classify(content_type: Option<&str>, head: &[u8]) -> Option<Rejection> {
+    let mime = content_type
+        .and_then(|value| value.split(';').next())
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    if is_html(head) {
+        return Some(Rejection::WebPage);
+    }
+    if head.is_empty()
+        || is_audio(head)
+        || is_playlist(head)
+        || mime.is_empty()
+        || mime.starts_with("audio/")
+        || PLAYABLE_TYPES.contains(&mime.as_str())
+    {
+        return None;
+    }
+    Some(match mime.as_str() {
+        "text/html" | "application/xhtml+xml" => Rejection::WebPage,
+        _ => Rejection::NotAudio(mime),
+    })
+}
+
+/// Request `url`, following redirects, and check the start of the body
+pub async fn sniff(url: &str, timeout: Duration) -> Result<Sniffed, reqwest::Error> {
+    let mut response = http_client()
+        .get(url)
+        .header("Icy-MetaData", "1")
+        .timeout(timeout)
+        .send()
+        .await?
+        .error_for_status()?;
+    let final_url = response.url().to_string();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let head = response.chunk().await?.unwrap_or_default();
+    Ok(Sniffed {
+        url: final_url,
+        rejection: classify(content_type.as_deref(), &head),
+    })
+}
+
+/// `url` unless it is known not to serve audio; failed requests are left
+/// to mpv
+pub async fn check(url: String, timeout: Duration) -> Result<String, Rejection> {
+    match sniff(&url, timeout).await {
+        Ok(Sniffed {
+            rejection: Some(rejection),
+            ..
+        }) => Err(rejection),
+        _ => Ok(url),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_audio() {
+        assert_eq!(classify(Some("audio/mpeg"), b"ID3\x04\x00"), None);
+        assert_eq!(classify(Some("text/html"), b"\xff\xfb\x90\x64"), None);
+        assert_eq!(
+            classify(Some("application/octet-stream"), b"OggS\x00"),
+            None
+        );
+        assert_eq!(classify(Some("audio/aac"), b"\x00\x01junk"), None);
+        assert_eq!(classify(None, b""), None);
+    }
+
+    #[test]
+    fn test_classify_playlists() {
+        assert_eq!(classify(Some("text/plain"), b"#EXTM3U\n#EXTINF"), None);
+        assert_eq!(classify(Some("text/html"), b"[playlist]\nFile1="), None);
+        assert_eq!(
+            classify(Some("text/plain"), b"http://radio.example/live\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_classify_rejects_documents() {
+        assert_eq!(
+            classify(Some("audio/mpeg"), b"\n<!DOCTYPE html><html>"),
+            Some(Rejection::WebPage)
+        );
+        assert_eq!(
+            classify(Some("text/html; charset=utf-8"), b"Moved"),
+            Some(Rejection::WebPage)
+        );
+        assert_eq!(
+            classify(Some("application/json"), b"{\"error\":1}"),
+            Some(Rejection::NotAudio("application/json".to_string()))
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Deferred startup work
+//!
+//! `init` reads only the config and the session state, so the applet is on
+//! the panel at once. The rest of what is kept on disk (the cached
+//! directory lists, known stations, listening history, and finished
+//! downloads) is read on a blocking thread right after, where the offline search index over the
+//! favorites and known stations is built, mpv processes left behind by
+//! a previous run are stopped, and old recordings are deleted. The HTTP client and mpv are only set up on
+//! first use.
+
+use crate::api::Station;
+use crate::audio;
+use crate::cache::Cache;
+use crate::discover::{self, Discover, Starters};
+use crate::downloads::{self, Download};
+use crate::fuzzy;
+use crate::index::StationIndex;
+use crate::recorder;
+use crate::stats::{self, ListenSession};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, info, warn};
+
+/// What `init` leaves for later
+#[derive(Debug, Clone, Default)]
+pub struct Startup {
+    pub known_stations: Vec<Station>,
+    pub station_index: Arc<StationIndex>,
+    pub discover: Option<Discover>,
+    pub starters: Starters,
+    pub history: Vec<ListenSession>,
+    pub downloads: Vec<Download>,
+}
+
+/// What to load
+#[derive(Debug, Clone)]
+pub struct StartupRequest {
+    pub favorites: Vec<Station>,
+    pub country_code: Option<String>,
+    pub cache_max_mb: u32,
+    /// Stop mpv processes of a previous run; a follower leaves the
+    /// primary's alone
+    pub kill_orphans: bool,
+    /// Delete recordings older than this many days (0 = keep)
+    pub recording_retention_days: u32,
+}
+
+fn load_blocking(request: StartupRequest) -> Startup {
+    let started = Instant::now();
+    if request.kill_orphans {
+        let orphans = audio::kill_orphans();
+        if orphans > 0 {
+            info!(
+                "Stopped {} mpv instances left behind by a previous run",
+                orphans
+            );
+        }
+    }
+    if let Some(dir) = recorder::recordings_dir() {
+        let deleted = recorder::clean_up(&dir, request.recording_retention_days, stats::now_secs());
+        if deleted > 0 {
+            info!("Deleted {} old recordings", deleted);
+        }
+    }
+    let cache = Cache::open(request.cache_max_mb);
+    let known_stations = fuzzy::load_cached(cache.as_ref());
+    let station_index = Arc::new(StationIndex::build(
+        request.favorites.into_iter().chain(known_stations.clone()),
+    ));
+    let startup = Startup {
+        discover: discover::load_cached(cache.as_ref()),
+        starters: discover::load_cached_starters(cache.as_ref(), request.country_code.as_deref())
+            .unwrap_or_default(),
+        history: stats::history_path()
+            .map(|path| {
+                stats::load(&path).unwrap_or_else(|e| {
+                    warn!("Failed to read listening history: {}", e);
+                    Vec::new()
+                })
+            })
+            .unwrap_or_default(),
+        downloads: downloads::downloads_dir()
+            .map(|dir| downloads::finished(&dir))
+            .unwrap_or_default(),
+        known_stations,
+        station_index,
+    };
+    debug!("Deferred startup took {:?}", started.elapsed());
+    startup
+}
+
+/// Load everything `init` skipped, off the UI thread
+pub async fn load(request: StartupRequest) -> Startup {
+    tokio::task::spawn_blocking(move || load_blocking(request))
+        .await
+        .unwrap_or_else(|e| {
+            warn!("Deferred startup panicked: {}", e);
+            Startup::default()
+        })
+}
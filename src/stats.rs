@@ -0,0 +1,390 @@
+//! Listening statistics
+//!
+//! Every finished listening session is appended as one JSON line to
+//! `~/.local/share/cosmic-ext-applet-radio/history.jsonl`. Appending keeps
+//! writes cheap and crash-safe; totals per station are computed from the log
+//! on demand. The log can be exported as CSV or JSON for external analysis.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Sessions shorter than this are not counted as plays
+pub const MIN_SESSION_SECS: u64 = 10;
+
+pub const DAY_SECS: u64 = 24 * 60 * 60;
+pub const WEEK_SECS: u64 = 7 * 24 * 60 * 60;
+pub const MONTH_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// One uninterrupted stretch of listening to a station
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListenSession {
+    pub stationuuid: String,
+    pub name: String,
+    /// Start time, seconds since the Unix epoch
+    pub started: u64,
+    pub duration_secs: u64,
+    /// Approximate data streamed (0 in logs written before it was tracked)
+    #[serde(default)]
+    pub bytes: u64,
+}
+
+/// Aggregated listening for one station
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StationStats {
+    pub stationuuid: String,
+    pub name: String,
+    pub plays: u32,
+    pub seconds: u64,
+    pub bytes: u64,
+}
+
+/// Current time in seconds since the Unix epoch
+#[must_use]
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Default location of the history log
+#[must_use]
+pub fn history_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("cosmic-ext-applet-radio").join("history.jsonl"))
+}
+
+/// Read all sessions, skipping lines that fail to parse
+pub fn load(path: &Path) -> io::Result<Vec<ListenSession>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(session) => Some(session),
+            Err(e) => {
+                warn!("Skipping malformed history line: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Append a session to the log, creating it if needed
+pub fn append(path: &Path, session: &ListenSession) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(session).map_err(io::Error::other)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+/// Replace the whole log, e.g. when restoring a backup
+pub fn replace(path: &Path, sessions: &[ListenSession]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut text = String::new();
+    for session in sessions {
+        text.push_str(&serde_json::to_string(session).map_err(io::Error::other)?);
+        text.push('\n');
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, text)?;
+    fs::rename(&tmp, path)
+}
+
+/// Per-station totals for sessions started at or after `since`, most listened first
+#[must_use]
+pub fn top_stations(sessions: &[ListenSession], since: u64) -> Vec<StationStats> {
+    let mut by_station: HashMap<&str, StationStats> = HashMap::new();
+    for session in sessions.iter().filter(|s| s.started >= since) {
+        let entry = by_station
+            .entry(session.stationuuid.as_str())
+            .or_insert_with(|| StationStats {
+                stationuuid: session.stationuuid.clone(),
+                name: session.name.clone(),
+                plays: 0,
+                seconds: 0,
+                bytes: 0,
+            });
+        entry.plays += 1;
+        entry.seconds += session.duration_secs;
+        entry.bytes += session.bytes;
+        // Keep the most recent name in case the station was renamed
+        entry.name.clone_from(&session.name);
+    }
+
+    let mut stats: Vec<StationStats> = by_station.into_values().collect();
+    stats.sort_by(|a, b| b.seconds.cmp(&a.seconds).then_with(|| a.name.cmp(&b.name)));
+    stats
+}
+
+/// Total listening time across all sessions
+#[must_use]
+pub fn total_seconds(sessions: &[ListenSession]) -> u64 {
+    sessions.iter().map(|s| s.duration_secs).sum()
+}
+
+/// Data streamed in sessions started at or after `since`
+#[must_use]
+pub fn bytes_since(sessions: &[ListenSession], since: u64) -> u64 {
+    sessions
+        .iter()
+        .filter(|s| s.started >= since)
+        .map(|s| s.bytes)
+        .sum()
+}
+
+/// Short human-readable duration, e.g. `3h 20m` or `45m`
+#[must_use]
+pub fn format_duration(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+/// File format for history exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// Pick the format from a file extension, defaulting to CSV
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Csv,
+        }
+    }
+
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// UTC timestamp in ISO 8601 form, e.g. `2024-03-01T18:30:00Z`
+#[must_use]
+pub fn format_timestamp(secs: u64) -> String {
+    // Civil-from-days conversion (proleptic Gregorian calendar)
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+/// Quote a CSV field if needed
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render sessions in the given export format
+#[must_use]
+pub fn export(sessions: &[ListenSession], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Csv => {
+            let mut out =
+                String::from("started,started_utc,stationuuid,name,duration_secs,bytes\n");
+            for s in sessions {
+                out.push_str(&format!(
+                    "{},{},{},{},{},{}\n",
+                    s.started,
+                    format_timestamp(s.started),
+                    csv_field(&s.stationuuid),
+                    csv_field(&s.name),
+                    s.duration_secs,
+                    s.bytes
+                ));
+            }
+            out
+        }
+        ExportFormat::Json => {
+            let rows: Vec<serde_json::Value> = sessions
+                .iter()
+                .map(|s| {
+                    serde_json::json!({
+                        "started": s.started,
+                        "started_utc": format_timestamp(s.started),
+                        "stationuuid": s.stationuuid,
+                        "name": s.name,
+                        "duration_secs": s.duration_secs,
+                        "bytes": s.bytes,
+                    })
+                })
+                .collect();
+            serde_json::to_string_pretty(&rows).unwrap_or_default()
+        }
+    }
+}
+
+/// Write the history export to `path`
+pub fn export_to_file(
+    sessions: &[ListenSession],
+    format: ExportFormat,
+    path: &Path,
+) -> io::Result<()> {
+    fs::write(path, export(sessions, format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(uuid: &str, name: &str, started: u64, duration_secs: u64) -> ListenSession {
+        ListenSession {
+            stationuuid: uuid.to_string(),
+            name: name.to_string(),
+            started,
+            duration_secs,
+            bytes: duration_secs * 1000,
+        }
+    }
+
+    #[test]
+    fn test_top_stations_sums_and_sorts() {
+        let sessions = vec![
+            session("a", "Alpha", 100, 600),
+            session("b", "Beta", 200, 3000),
+            session("a", "Alpha FM", 300, 900),
+        ];
+        let stats = top_stations(&sessions, 0);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].stationuuid, "b");
+        assert_eq!(stats[1].plays, 2);
+        assert_eq!(stats[1].seconds, 1500);
+        assert_eq!(stats[1].bytes, 1_500_000);
+        assert_eq!(stats[1].name, "Alpha FM");
+    }
+
+    #[test]
+    fn test_top_stations_respects_since() {
+        let sessions = vec![
+            session("a", "Alpha", 100, 600),
+            session("b", "Beta", 500, 60),
+        ];
+        let stats = top_stations(&sessions, 400);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].stationuuid, "b");
+    }
+
+    #[test]
+    fn test_total_and_format_duration() {
+        let sessions = vec![session("a", "A", 0, 7200), session("b", "B", 0, 1200)];
+        assert_eq!(total_seconds(&sessions), 8400);
+        assert_eq!(bytes_since(&sessions, 0), 8_400_000);
+        assert_eq!(bytes_since(&sessions, 1), 0);
+        assert_eq!(format_duration(8400), "2h 20m");
+        assert_eq!(format_duration(59), "0m");
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(1_709_317_800), "2024-03-01T18:30:00Z");
+    }
+
+    #[test]
+    fn test_export_csv_quotes_fields() {
+        let sessions = vec![session("a", "Rock, \"Live\"", 0, 600)];
+        assert_eq!(
+            export(&sessions, ExportFormat::Csv),
+            "started,started_utc,stationuuid,name,duration_secs,bytes\n\
+             0,1970-01-01T00:00:00Z,a,\"Rock, \"\"Live\"\"\",600,600000\n"
+        );
+    }
+
+    #[test]
+    fn test_export_json() {
+        let sessions = vec![session("a", "Alpha", 60, 600)];
+        let json: serde_json::Value =
+            serde_json::from_str(&export(&sessions, ExportFormat::Json)).unwrap();
+        assert_eq!(json[0]["stationuuid"], "a");
+        assert_eq!(json[0]["started_utc"], "1970-01-01T00:01:00Z");
+        assert_eq!(json[0]["duration_secs"], 600);
+        assert_eq!(json[0]["bytes"], 600_000);
+    }
+
+    #[test]
+    fn test_export_format_from_path() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("h.JSON")),
+            ExportFormat::Json
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("h.csv")),
+            ExportFormat::Csv
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("history")),
+            ExportFormat::Csv
+        );
+    }
+
+    #[test]
+    fn test_append_and_load_roundtrip() {
+        let path = std::env::temp_dir()
+            .join(format!("radio-stats-{}", std::process::id()))
+            .join("history.jsonl");
+        let _ = fs::remove_file(&path);
+        assert!(load(&path).unwrap().is_empty());
+
+        let first = session("a", "Alpha", 100, 600);
+        let second = session("b", "Beta", 200, 60);
+        append(&path, &first).unwrap();
+        append(&path, &second).unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+
+        assert_eq!(load(&path).unwrap(), vec![first.clone(), second]);
+
+        replace(&path, std::slice::from_ref(&first)).unwrap();
+        assert_eq!(load(&path).unwrap(), vec![first]);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,90 @@
+//! Reconnecting after suspend
+//!
+//! mpv's HTTP connection is dead once the machine wakes up, and playback
+//! hangs on silence instead of failing. logind announces suspend and resume
+//! with its `PrepareForSleep` signal on the system bus; a stream that was
+//! playing before the machine slept is restarted shortly after it wakes,
+//! once the network had a moment to come back.
+
+use futures::{SinkExt, StreamExt};
+use mpris_server::zbus::{self, message, Connection, MatchRule, MessageStream};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Wait after resume before reconnecting
+pub const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Sleep state changes announced by logind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SleepEvent {
+    Suspending,
+    Resumed,
+}
+
+impl From<bool> for SleepEvent {
+    /// `PrepareForSleep`'s argument: `true` before sleeping, `false` after waking
+    fn from(start: bool) -> Self {
+        if start {
+            Self::Suspending
+        } else {
+            Self::Resumed
+        }
+    }
+}
+
+async fn sleep_signals(connection: &Connection) -> Result<MessageStream, zbus::Error> {
+    let rule = MatchRule::builder()
+        .msg_type(message::Type::Signal)
+        .sender("org.freedesktop.login1")?
+        .interface("org.freedesktop.login1.Manager")?
+        .member("PrepareForSleep")?
+        .build();
+    MessageStream::for_match_rule(rule, connection, None).await
+}
+
+/// Subscription yielding logind's suspend and resume announcements
+pub fn sleep_subscription() -> cosmic::iced::Subscription<SleepEvent> {
+    cosmic::iced::Subscription::run(|| {
+        cosmic::iced::stream::channel(4, |mut output| async move {
+            let connection = match Connection::system().await {
+                Ok(c) => c,
+                Err(e) => {
+                    warn!("Cannot watch for suspend, no system bus: {}", e);
+                    return;
+                }
+            };
+            let mut signals = match sleep_signals(&connection).await {
+                Ok(signals) => signals,
+                Err(e) => {
+                    warn!("Cannot watch for suspend: {}", e);
+                    return;
+                }
+            };
+
+            while let Some(signal) = signals.next().await {
+                let Ok(signal) = signal else { continue };
+                match signal.body().deserialize::<bool>() {
+                    Ok(start) => {
+                        let event = SleepEvent::from(start);
+                        debug!("logind: {:?}", event);
+                        if output.send(event).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => warn!("Unexpected PrepareForSleep signal: {}", e),
+                }
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sleep_event_from_signal() {
+        assert_eq!(SleepEvent::from(true), SleepEvent::Suspending);
+        assert_eq!(SleepEvent::from(false), SleepEvent::Resumed);
+    }
+}
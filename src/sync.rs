@@ -0,0 +1,263 @@
+//! Shared playback between applet instances
+//!
+//! With the applet on several panels (e.g. one per monitor), the panel starts
+//! one process per applet. The first one becomes the primary instance (see
+//! [`crate::instance`]) and owns mpv, MPRIS, MQTT, hooks, and the history.
+//! Later ones run as followers: they show the primary's state and send
+//! playback actions to it instead of playing themselves. When the primary
+//! exits, a follower takes its place.
+//!
+//! The instances talk over a Unix socket in `$XDG_RUNTIME_DIR`, one JSON
+//! message per line: the primary sends a [`SyncState`] when a follower
+//! connects and on every change, followers send [`SyncCommand`]s.
+//!
+//! The primary alone saves the config. Followers keep settings out of
+//! reach and only hold what the primary last sent them.
+
+use crate::api::Station;
+use futures::SinkExt;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, info, warn};
+
+/// How often a follower retries reaching the primary
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Playback state the primary shares with followers
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    pub playing: bool,
+    pub station: Option<Station>,
+    pub track: Option<String>,
+    pub volume: u8,
+    pub favorites: Vec<Station>,
+    /// Lock mode, which followers mirror
+    pub kiosk_locked: bool,
+}
+
+/// Actions a follower asks the primary to perform
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SyncCommand {
+    TogglePlayPause,
+    PlayStation(Box<Station>),
+    SetVolume(u8),
+    ToggleFavorite(Box<Station>),
+    /// Track title and station name
+    BookmarkTrack(String, String),
+    /// Leave lock mode if the PIN matches; the primary checks it
+    Unlock(String),
+}
+
+/// Events yielded by the primary's subscription
+#[derive(Debug, Clone)]
+pub enum SyncEvent {
+    Ready(mpsc::UnboundedSender<SyncState>),
+    Command(SyncCommand),
+}
+
+/// Events yielded by a follower's subscription
+#[derive(Debug, Clone)]
+pub enum FollowerEvent {
+    Connected(mpsc::UnboundedSender<SyncCommand>),
+    State(SyncState),
+    /// The primary went away; the subscription keeps trying to reconnect
+    Disconnected,
+}
+
+fn socket_path() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("cosmic-ext-applet-radio.sock")
+}
+
+/// One message as a line of JSON
+fn encode<T: Serialize>(message: &T) -> String {
+    let mut line = serde_json::to_string(message).unwrap_or_default();
+    line.push('\n');
+    line
+}
+
+/// Send state updates to one follower and pass its commands on
+async fn serve_follower(
+    stream: UnixStream,
+    mut state: watch::Receiver<String>,
+    commands: mpsc::UnboundedSender<SyncCommand>,
+) {
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+    // A new follower gets the current state right away
+    state.mark_changed();
+    loop {
+        tokio::select! {
+            changed = state.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                let line = state.borrow_and_update().clone();
+                if !line.is_empty() && write.write_all(line.as_bytes()).await.is_err() {
+                    break;
+                }
+            }
+            line = lines.next_line() => match line {
+                Ok(Some(line)) => match serde_json::from_str(&line) {
+                    Ok(command) => {
+                        let _ = commands.send(command);
+                    }
+                    Err(e) => warn!("Ignoring invalid command from follower: {}", e),
+                },
+                _ => break,
+            },
+        }
+    }
+    debug!("Follower instance disconnected");
+}
+
+/// Create an iced Subscription that serves followers from the primary
+pub fn primary_subscription() -> cosmic::iced::Subscription<SyncEvent> {
+    cosmic::iced::Subscription::run(|| {
+        cosmic::iced::stream::channel(32, |mut output| async move {
+            // The primary holds the instance bus name, so a socket left
+            // behind is from a previous run
+            let path = socket_path();
+            let _ = std::fs::remove_file(&path);
+            let listener = match UnixListener::bind(&path) {
+                Ok(listener) => listener,
+                Err(e) => {
+                    warn!(
+                        "Cannot listen for other instances at {}: {}",
+                        path.display(),
+                        e
+                    );
+                    return;
+                }
+            };
+
+            let (state_tx, mut state_rx) = mpsc::unbounded_channel::<SyncState>();
+            let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+            let (line_tx, _) = watch::channel(String::new());
+            if output.send(SyncEvent::Ready(state_tx)).await.is_err() {
+                return;
+            }
+
+            loop {
+                tokio::select! {
+                    update = state_rx.recv() => {
+                        let Some(state) = update else { break };
+                        line_tx.send_replace(encode(&state));
+                    }
+                    Some(command) = command_rx.recv() => {
+                        if output.send(SyncEvent::Command(command)).await.is_err() {
+                            break;
+                        }
+                    }
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => {
+                            info!("Another applet instance is following playback");
+                            tokio::spawn(serve_follower(stream, line_tx.subscribe(), command_tx.clone()));
+                        }
+                        Err(e) => {
+                            warn!("Failed to accept follower instance: {}", e);
+                            tokio::time::sleep(RECONNECT_INTERVAL).await;
+                        }
+                    }
+                }
+            }
+        })
+    })
+}
+
+/// Create an iced Subscription that follows the primary instance
+pub fn follower_subscription() -> cosmic::iced::Subscription<FollowerEvent> {
+    cosmic::iced::Subscription::run(|| {
+        cosmic::iced::stream::channel(32, |mut output| async move {
+            loop {
+                if let Ok(stream) = UnixStream::connect(socket_path()).await {
+                    let (read, mut write) = stream.into_split();
+                    let (command_tx, mut command_rx) = mpsc::unbounded_channel::<SyncCommand>();
+                    if output
+                        .send(FollowerEvent::Connected(command_tx))
+                        .await
+                        .is_err()
+                    {
+                        return;
+                    }
+                    let mut lines = BufReader::new(read).lines();
+                    loop {
+                        tokio::select! {
+                            line = lines.next_line() => match line {
+                                Ok(Some(line)) => match serde_json::from_str(&line) {
+                                    Ok(state) => {
+                                        if output.send(FollowerEvent::State(state)).await.is_err() {
+                                            return;
+                                        }
+                                    }
+                                    Err(e) => warn!("Ignoring invalid state from primary: {}", e),
+                                },
+                                _ => break,
+                            },
+                            command = command_rx.recv() => {
+                                let Some(command) = command else { break };
+                                if write.write_all(encode(&command).as_bytes()).await.is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    debug!("Lost connection to the primary instance");
+                    if output.send(FollowerEvent::Disconnected).await.is_err() {
+                        return;
+                    }
+                }
+                tokio::time::sleep(RECONNECT_INTERVAL).await;
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_one_line() {
+        let command = SyncCommand::PlayStation(Box::new(Station {
+            name: "Late\nShow".to_string(),
+            ..Station::default()
+        }));
+        let line = encode(&command);
+        assert!(line.ends_with('\n'));
+        assert_eq!(line.lines().count(), 1);
+        let decoded: SyncCommand = serde_json::from_str(&line).unwrap();
+        assert_eq!(decoded, command);
+    }
+
+    #[tokio::test]
+    async fn test_serve_follower() {
+        let (primary, follower) = UnixStream::pair().unwrap();
+        let (line_tx, line_rx) = watch::channel(String::new());
+        let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+        let state = SyncState {
+            playing: true,
+            volume: 40,
+            kiosk_locked: true,
+            ..SyncState::default()
+        };
+        line_tx.send_replace(encode(&state));
+        tokio::spawn(serve_follower(primary, line_rx, command_tx));
+
+        let (read, mut write) = follower.into_split();
+        let mut lines = BufReader::new(read).lines();
+        let line = lines.next_line().await.unwrap().unwrap();
+        assert_eq!(serde_json::from_str::<SyncState>(&line).unwrap(), state);
+
+        write
+            .write_all(encode(&SyncCommand::SetVolume(55)).as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(command_rx.recv().await, Some(SyncCommand::SetVolume(55)));
+    }
+}
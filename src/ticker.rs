@@ -0,0 +1,133 @@
+//! Now-playing ticker next to the panel icon
+//!
+//! When enabled, the track title (or the station name, until a title
+//! arrives) is shown beside the panel icon on horizontal panels. Text longer
+//! than the configured width scrolls through it like a marquee, one
+//! character per step at the configured speed; vertical panels keep just
+//! the icon.
+
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+/// Default width of the ticker, in characters
+pub const DEFAULT_WIDTH: u32 = 24;
+
+/// Allowed ticker widths, in characters
+pub const WIDTH_RANGE: RangeInclusive<u32> = 8..=60;
+
+/// Default scrolling speed, in characters per second
+pub const DEFAULT_SPEED: u32 = 4;
+
+/// Allowed scrolling speeds, in characters per second
+pub const SPEED_RANGE: RangeInclusive<u32> = 1..=10;
+
+/// Shown between the end of the text and its start coming round again
+const GAP: &str = "   •   ";
+
+/// Ticker settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickerConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Widest the ticker gets, in characters
+    #[serde(default = "default_width")]
+    pub max_width: u32,
+    /// Characters scrolled per second
+    #[serde(default = "default_speed")]
+    pub speed: u32,
+}
+
+fn default_width() -> u32 {
+    DEFAULT_WIDTH
+}
+
+fn default_speed() -> u32 {
+    DEFAULT_SPEED
+}
+
+impl Default for TickerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_width: DEFAULT_WIDTH,
+            speed: DEFAULT_SPEED,
+        }
+    }
+}
+
+impl TickerConfig {
+    /// Time between scrolling steps
+    #[must_use]
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(1000 / u64::from(clamp_speed(self.speed)))
+    }
+
+    /// Width in characters, within [`WIDTH_RANGE`]
+    #[must_use]
+    pub fn width(&self) -> usize {
+        clamp_width(self.max_width) as usize
+    }
+}
+
+/// Clamp a width to [`WIDTH_RANGE`]
+#[must_use]
+pub fn clamp_width(width: u32) -> u32 {
+    width.clamp(*WIDTH_RANGE.start(), *WIDTH_RANGE.end())
+}
+
+/// Clamp a speed to [`SPEED_RANGE`]
+#[must_use]
+pub fn clamp_speed(speed: u32) -> u32 {
+    speed.clamp(*SPEED_RANGE.start(), *SPEED_RANGE.end())
+}
+
+/// Whether `text` is too long for `width` characters and has to scroll
+#[must_use]
+pub fn scrolls(text: &str, width: usize) -> bool {
+    text.chars().count() > width
+}
+
+/// The `width` characters of `text` shown `offset` steps into scrolling
+#[must_use]
+pub fn window(text: &str, offset: usize, width: usize) -> String {
+    if !scrolls(text, width) {
+        return text.to_string();
+    }
+    let looped: Vec<char> = text.chars().chain(GAP.chars()).collect();
+    looped
+        .iter()
+        .cycle()
+        .skip(offset % looped.len())
+        .take(width)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window() {
+        assert_eq!(window("Jazz24", 5, 10), "Jazz24");
+        assert_eq!(window("Morning Edition", 0, 8), "Morning ");
+        assert_eq!(window("Morning Edition", 8, 8), "Edition ");
+        // The start comes round again after the gap
+        assert_eq!(
+            window("Morning Edition", 15 + GAP.chars().count(), 8),
+            "Morning "
+        );
+        assert_eq!(window("Morning Edition", 12, 8).chars().count(), 8);
+    }
+
+    #[test]
+    fn test_interval() {
+        let config = TickerConfig::default();
+        assert_eq!(config.interval(), Duration::from_millis(250));
+        let fast = TickerConfig {
+            speed: 100,
+            ..config
+        };
+        assert_eq!(fast.interval(), Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,131 @@
+//! TLS behavior per station
+//!
+//! mpv checks stream certificates. Community stations often run HTTPS with
+//! an expired or self-signed certificate, so a station can be opted in to
+//! skip the check, with a warning that its stream could then be tampered
+//! with. Conversely, a plain HTTP station can be upgraded: right before
+//! playback its HTTPS mirror on the same host is tried, and used if it
+//! answers. Both options are set from the diagnostics panel of the playing
+//! station.
+
+use crate::api::http_client;
+use crate::streamauth::redact_url;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{debug, info};
+use url::Url;
+
+/// TLS options of one station
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StationTls {
+    pub stationuuid: String,
+    /// Play even if the certificate is expired, self-signed, or for another host
+    #[serde(default)]
+    pub allow_invalid_certs: bool,
+    /// Try the HTTPS mirror of a plain HTTP stream first
+    #[serde(default)]
+    pub upgrade_https: bool,
+}
+
+impl StationTls {
+    fn is_default(&self) -> bool {
+        !self.allow_invalid_certs && !self.upgrade_https
+    }
+}
+
+/// Options of a station, the defaults if none were set
+#[must_use]
+pub fn options(stations: &[StationTls], stationuuid: &str) -> StationTls {
+    stations
+        .iter()
+        .find(|tls| tls.stationuuid == stationuuid)
+        .cloned()
+        .unwrap_or_else(|| StationTls {
+            stationuuid: stationuuid.to_string(),
+            ..StationTls::default()
+        })
+}
+
+/// Store a station's options; stations back at the defaults are dropped
+pub fn set(stations: &mut Vec<StationTls>, tls: StationTls) {
+    stations.retain(|other| other.stationuuid != tls.stationuuid);
+    if !tls.is_default() {
+        stations.push(tls);
+    }
+}
+
+/// The `https://` URL on the same host as a plain HTTP stream. An explicit
+/// port 80 becomes the default HTTPS port; other ports are kept.
+#[must_use]
+pub fn https_mirror(url: &str) -> Option<String> {
+    let mut parsed = Url::parse(url).ok()?;
+    if parsed.scheme() != "http" {
+        return None;
+    }
+    if parsed.port() == Some(80) {
+        parsed.set_port(None).ok()?;
+    }
+    parsed.set_scheme("https").ok()?;
+    Some(parsed.to_string())
+}
+
+/// The HTTPS mirror of `url` if it answers with a verified certificate,
+/// else `url` unchanged
+pub async fn upgrade(url: String, timeout: Duration) -> String {
+    let Some(mirror) = https_mirror(&url) else {
+        return url;
+    };
+    let answered = http_client()
+        .get(&mirror)
+        .header("Icy-MetaData", "1")
+        .timeout(timeout)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status);
+    match answered {
+        Ok(_) => {
+            info!("Upgraded stream to {}", redact_url(&mirror));
+            mirror
+        }
+        Err(e) => {
+            debug!("No HTTPS mirror at {}: {}", redact_url(&mirror), e);
+            url
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_https_mirror() {
+        assert_eq!(
+            https_mirror("http://radio.example/live").as_deref(),
+            Some("https://radio.example/live")
+        );
+        assert_eq!(
+            https_mirror("http://radio.example:80/live?x=1").as_deref(),
+            Some("https://radio.example/live?x=1")
+        );
+        assert_eq!(
+            https_mirror("http://radio.example:8000/live").as_deref(),
+            Some("https://radio.example:8000/live")
+        );
+        assert!(https_mirror("https://radio.example/live").is_none());
+    }
+
+    #[test]
+    fn test_set_drops_defaults() {
+        let mut stations = Vec::new();
+        let mut tls = options(&stations, "a");
+        assert!(!tls.allow_invalid_certs);
+        tls.allow_invalid_certs = true;
+        set(&mut stations, tls.clone());
+        assert_eq!(options(&stations, "a"), tls);
+
+        tls.allow_invalid_certs = false;
+        set(&mut stations, tls);
+        assert!(stations.is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+//! Track history
+//!
+//! Every new ICY title is added to an in-memory list for the running session
+//! and appended to a per-station log under
+//! `~/.local/share/cosmic-ext-applet-radio/tracks/<station>.jsonl`, so a song
+//! heard an hour ago (or yesterday on the same station) can still be looked
+//! up. Station logs keep only the latest [`STATION_LOG_LIMIT`] titles.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Titles kept for the running session
+pub const SESSION_LIMIT: usize = 100;
+
+/// Titles kept per station on disk
+pub const STATION_LOG_LIMIT: usize = 200;
+
+/// One title as announced by a station
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackEntry {
+    pub title: String,
+    pub station: String,
+    /// Seconds since the Unix epoch
+    pub heard_at: u64,
+}
+
+/// Default directory of the per-station logs
+#[must_use]
+pub fn tracks_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("cosmic-ext-applet-radio").join("tracks"))
+}
+
+/// Log file of a station; the uuid is reduced to file-name-safe characters
+#[must_use]
+pub fn station_log_path(dir: &Path, stationuuid: &str) -> PathBuf {
+    let name: String = stationuuid
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("{name}.jsonl"))
+}
+
+/// Add a title to the session list unless it repeats the last one; returns
+/// whether it was added
+pub fn push_session(log: &mut Vec<TrackEntry>, entry: TrackEntry) -> bool {
+    if log
+        .last()
+        .is_some_and(|last| last.title == entry.title && last.station == entry.station)
+    {
+        return false;
+    }
+    log.push(entry);
+    if log.len() > SESSION_LIMIT {
+        log.drain(..log.len() - SESSION_LIMIT);
+    }
+    true
+}
+
+/// Read a station log, oldest first, skipping lines that fail to parse
+pub fn load(path: &Path) -> io::Result<Vec<TrackEntry>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                warn!("Skipping malformed track log line: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Append a title to a station log, dropping the oldest beyond the limit
+pub fn record(path: &Path, entry: &TrackEntry) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut entries = load(path)?;
+    entries.push(entry.clone());
+    let skip = entries.len().saturating_sub(STATION_LOG_LIMIT);
+
+    let mut text = String::new();
+    for entry in &entries[skip..] {
+        text.push_str(&serde_json::to_string(entry).map_err(io::Error::other)?);
+        text.push('\n');
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, text)?;
+    fs::rename(&tmp, path)
+}
+
+/// Delete all station logs
+pub fn clear(dir: &Path) -> io::Result<()> {
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Whole minutes between `heard_at` and `now`
+#[must_use]
+pub fn minutes_ago(now: u64, heard_at: u64) -> u64 {
+    now.saturating_sub(heard_at) / 60
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, heard_at: u64) -> TrackEntry {
+        TrackEntry {
+            title: title.to_string(),
+            station: "Jazz FM".to_string(),
+            heard_at,
+        }
+    }
+
+    #[test]
+    fn test_push_session_skips_repeats_and_caps() {
+        let mut log = Vec::new();
+        assert!(push_session(&mut log, entry("A", 1)));
+        assert!(!push_session(&mut log, entry("A", 2)));
+        assert!(push_session(&mut log, entry("B", 3)));
+        assert_eq!(log.len(), 2);
+
+        for i in 0..SESSION_LIMIT as u64 {
+            push_session(&mut log, entry(&i.to_string(), i));
+        }
+        assert_eq!(log.len(), SESSION_LIMIT);
+        assert_eq!(log[0].title, "0");
+    }
+
+    #[test]
+    fn test_station_log_path_is_safe() {
+        let dir = Path::new("/tmp/tracks");
+        assert_eq!(station_log_path(dir, "abc-123"), dir.join("abc-123.jsonl"));
+        assert_eq!(
+            station_log_path(dir, "../somafm:groove"),
+            dir.join("___somafm_groove.jsonl")
+        );
+    }
+
+    #[test]
+    fn test_record_keeps_latest() {
+        let dir = std::env::temp_dir().join(format!("radio-tracks-{}", std::process::id()));
+        let path = station_log_path(&dir, "abc");
+        assert!(load(&path).unwrap().is_empty());
+
+        for i in 0..=STATION_LOG_LIMIT as u64 {
+            record(&path, &entry(&i.to_string(), i)).unwrap();
+        }
+        let entries = load(&path).unwrap();
+        assert_eq!(entries.len(), STATION_LOG_LIMIT);
+        assert_eq!(entries[0].title, "1");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_clear() {
+        let dir = std::env::temp_dir().join(format!("radio-tracks-clear-{}", std::process::id()));
+        let path = station_log_path(&dir, "abc");
+        record(&path, &entry("So What", 0)).unwrap();
+        clear(&dir).unwrap();
+        assert!(load(&path).unwrap().is_empty());
+        // Nothing to clear is not an error
+        clear(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_minutes_ago() {
+        assert_eq!(minutes_ago(600, 600), 0);
+        assert_eq!(minutes_ago(600, 0), 10);
+        assert_eq!(minutes_ago(0, 600), 0);
+    }
+}
@@ -0,0 +1,82 @@
+//! Level bars in the now-playing area
+//!
+//! When enabled, mpv measures the loudness of each audio frame (the
+//! `astats` filter) and reports it over IPC as [`AudioEvent::Level`]. The
+//! last [`BARS`] levels are drawn as bars that move with the music. Nothing
+//! is drawn while reduced motion is on, and cast devices report no levels.
+//!
+//! [`AudioEvent::Level`]: crate::audio::AudioEvent::Level
+
+use std::collections::VecDeque;
+
+/// Bars drawn, one per level report
+pub const BARS: usize = 24;
+
+/// RMS level drawn as an empty bar, in dBFS
+const FLOOR_DB: f64 = -60.0;
+
+/// An RMS level in dBFS as a bar height from 0 to 100
+#[must_use]
+pub fn level(db: f64) -> u8 {
+    if db.is_nan() {
+        return 0;
+    }
+    ((db - FLOOR_DB) / -FLOOR_DB * 100.0)
+        .clamp(0.0, 100.0)
+        .round() as u8
+}
+
+/// The most recent levels, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct Visualizer {
+    levels: VecDeque<u8>,
+}
+
+impl Visualizer {
+    pub fn push(&mut self, level: u8) {
+        self.levels.push_back(level);
+        while self.levels.len() > BARS {
+            self.levels.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.levels.clear();
+    }
+
+    /// Heights of the [`BARS`] bars, empty ones first until enough levels
+    /// came in
+    pub fn bars(&self) -> impl Iterator<Item = u8> + '_ {
+        std::iter::repeat(0)
+            .take(BARS - self.levels.len())
+            .chain(self.levels.iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level() {
+        assert_eq!(level(0.0), 100);
+        assert_eq!(level(-30.0), 50);
+        assert_eq!(level(f64::NEG_INFINITY), 0);
+        assert_eq!(level(f64::NAN), 0);
+    }
+
+    #[test]
+    fn test_bars() {
+        let mut visualizer = Visualizer::default();
+        visualizer.push(40);
+        let bars: Vec<u8> = visualizer.bars().collect();
+        assert_eq!(bars.len(), BARS);
+        assert_eq!(bars.last(), Some(&40));
+
+        for i in 0..=BARS as u8 {
+            visualizer.push(i);
+        }
+        assert_eq!(visualizer.bars().next(), Some(1));
+        assert_eq!(visualizer.bars().count(), BARS);
+    }
+}
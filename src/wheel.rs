@@ -0,0 +1,155 @@
+//! Volume by scrolling over the panel icon
+//!
+//! Scrolling over the panel icon changes the volume by the configured step,
+//! like in other panel applets. Depending on [`ScrollModifier`], holding
+//! Shift scrolls in 1% steps, or only scrolling with Ctrl held changes the
+//! volume, so a stray scroll along the panel leaves it alone. Touchpads
+//! report pixels rather than wheel notches; these add up until they make a
+//! notch.
+
+use cosmic::iced::keyboard::Modifiers;
+use cosmic::iced::mouse::ScrollDelta;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// Default volume change per notch, in percent
+pub const DEFAULT_STEP: u8 = 5;
+
+/// Allowed volume changes per notch, in percent
+pub const STEP_RANGE: RangeInclusive<u8> = 1..=20;
+
+/// Touchpad scrolling that counts as one notch
+const PIXELS_PER_NOTCH: f32 = 50.0;
+
+/// What modifier keys do to scrolling
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ScrollModifier {
+    /// Holding Shift changes the volume in 1% steps
+    #[default]
+    ShiftFine,
+    /// Only scrolling with Ctrl held changes the volume
+    CtrlRequired,
+    /// Modifier keys make no difference
+    Ignored,
+}
+
+impl ScrollModifier {
+    pub const ALL: [ScrollModifier; 3] = [
+        ScrollModifier::ShiftFine,
+        ScrollModifier::CtrlRequired,
+        ScrollModifier::Ignored,
+    ];
+}
+
+/// Panel icon scrolling settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScrollConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Volume change per notch, in percent
+    #[serde(default = "default_step")]
+    pub step: u8,
+    #[serde(default)]
+    pub modifier: ScrollModifier,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_step() -> u8 {
+    DEFAULT_STEP
+}
+
+impl Default for ScrollConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            step: DEFAULT_STEP,
+            modifier: ScrollModifier::default(),
+        }
+    }
+}
+
+impl ScrollConfig {
+    /// Volume change per notch with `modifiers` held, or `None` if
+    /// scrolling should not change the volume
+    #[must_use]
+    pub fn step(&self, modifiers: Modifiers) -> Option<u8> {
+        if !self.enabled {
+            return None;
+        }
+        match self.modifier {
+            ScrollModifier::ShiftFine if modifiers.shift() => Some(1),
+            ScrollModifier::CtrlRequired if !modifiers.control() => None,
+            _ => Some(clamp_step(self.step)),
+        }
+    }
+}
+
+/// Clamp a step to [`STEP_RANGE`]
+#[must_use]
+pub fn clamp_step(step: u8) -> u8 {
+    step.clamp(*STEP_RANGE.start(), *STEP_RANGE.end())
+}
+
+/// Volume after scrolling `notches` (up is positive) of `step` percent
+#[must_use]
+pub fn scrolled_volume(volume: u8, notches: i32, step: u8) -> u8 {
+    (i32::from(volume) + notches * i32::from(step)).clamp(0, 100) as u8
+}
+
+/// Scrolling not yet amounting to a whole notch
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Wheel {
+    pending: f32,
+}
+
+impl Wheel {
+    /// Add a scroll event and take the whole notches scrolled, up positive
+    pub fn notches(&mut self, delta: ScrollDelta) -> i32 {
+        self.pending += match delta {
+            ScrollDelta::Lines { y, .. } => y,
+            ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_NOTCH,
+        };
+        let whole = self.pending.trunc();
+        self.pending -= whole;
+        whole as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_modifiers() {
+        let config = ScrollConfig::default();
+        assert_eq!(config.step(Modifiers::empty()), Some(DEFAULT_STEP));
+        assert_eq!(config.step(Modifiers::SHIFT), Some(1));
+
+        let required = ScrollConfig {
+            modifier: ScrollModifier::CtrlRequired,
+            ..config
+        };
+        assert_eq!(required.step(Modifiers::empty()), None);
+        assert_eq!(required.step(Modifiers::CTRL), Some(DEFAULT_STEP));
+
+        let disabled = ScrollConfig {
+            enabled: false,
+            ..config
+        };
+        assert_eq!(disabled.step(Modifiers::empty()), None);
+    }
+
+    #[test]
+    fn test_notches() {
+        let mut wheel = Wheel::default();
+        assert_eq!(wheel.notches(ScrollDelta::Lines { x: 0.0, y: -2.0 }), -2);
+        // Touchpad scrolling adds up
+        assert_eq!(wheel.notches(ScrollDelta::Pixels { x: 0.0, y: 30.0 }), 0);
+        assert_eq!(wheel.notches(ScrollDelta::Pixels { x: 0.0, y: 30.0 }), 1);
+        assert_eq!(scrolled_volume(98, 1, 5), 100);
+        assert_eq!(scrolled_volume(3, -2, 5), 0);
+    }
+}
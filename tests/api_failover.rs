@@ -0,0 +1,277 @@
+// Mirror failover tests for the radio-browser.info client
+// Each test starts local mock servers standing in for the mirrors
+
+use cosmic_ext_applet_radio_lib::api::{query_endpoint, query_mirrors, vote_on, RequestOptions};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const SEARCH_PATH: &str = "/json/stations/search";
+
+fn station_json(uuid: &str, name: &str) -> serde_json::Value {
+    json!({
+        "stationuuid": uuid,
+        "name": name,
+        "url": format!("http://stream.example.com/{uuid}"),
+        "url_resolved": format!("http://stream.example.com/{uuid}.mp3"),
+        "homepage": "https://example.com",
+        "favicon": "",
+        "tags": "jazz",
+        "country": "Austria",
+        "language": "german"
+    })
+}
+
+async fn mirror(response: ResponseTemplate) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(SEARCH_PATH))
+        .respond_with(response)
+        .mount(&server)
+        .await;
+    server
+}
+
+async fn unused_mirror() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .expect(0)
+        .mount(&server)
+        .await;
+    server
+}
+
+fn ok(stations: serde_json::Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(stations)
+}
+
+async fn search(servers: &[&MockServer], options: RequestOptions) -> Result<Vec<String>, String> {
+    let uris: Vec<String> = servers.iter().map(|s| s.uri()).collect();
+    let uris: Vec<&str> = uris.iter().map(String::as_str).collect();
+    query_mirrors(&uris, &[("name", "jazz"), ("limit", "20")], &options)
+        .await
+        .map(|stations| stations.into_iter().map(|s| s.name).collect())
+        .map_err(|e| e.to_string())
+}
+
+#[tokio::test]
+async fn test_first_mirror_answers() {
+    let first = mirror(ok(json!([station_json("a", "Jazz FM")]))).await;
+    let second = unused_mirror().await;
+
+    let names = search(&[&first, &second], RequestOptions::default()).await;
+    assert_eq!(names, Ok(vec!["Jazz FM".to_string()]));
+}
+
+#[tokio::test]
+async fn test_query_parameters_forwarded() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(SEARCH_PATH))
+        .and(query_param("name", "jazz"))
+        .and(query_param("limit", "20"))
+        .respond_with(ok(json!([station_json("a", "Jazz FM")])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let names = search(&[&server], RequestOptions::default()).await;
+    assert_eq!(names, Ok(vec!["Jazz FM".to_string()]));
+}
+
+#[tokio::test]
+async fn test_bad_gateway_fails_over_in_order() {
+    let first = mirror(ResponseTemplate::new(502)).await;
+    let second = mirror(ResponseTemplate::new(503)).await;
+    let third = mirror(ok(json!([station_json("c", "Third")]))).await;
+    let fourth = unused_mirror().await;
+
+    let names = search(
+        &[&first, &second, &third, &fourth],
+        RequestOptions::default(),
+    )
+    .await;
+    assert_eq!(names, Ok(vec!["Third".to_string()]));
+    assert_eq!(first.received_requests().await.unwrap().len(), 1);
+    assert_eq!(second.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_malformed_json_fails_over() {
+    let first = mirror(ResponseTemplate::new(200).set_body_string("<html>oops</html>")).await;
+    let second = mirror(ok(json!({ "not": "a list" }))).await;
+    let third = mirror(ok(json!([station_json("c", "Valid")]))).await;
+
+    let names = search(&[&first, &second, &third], RequestOptions::default()).await;
+    assert_eq!(names, Ok(vec!["Valid".to_string()]));
+}
+
+#[tokio::test]
+async fn test_null_fields_become_empty() {
+    let server = mirror(ok(json!([{
+        "stationuuid": "n",
+        "name": "Nulls",
+        "url": null,
+        "url_resolved": null,
+        "homepage": null,
+        "favicon": null,
+        "tags": null,
+        "country": null,
+        "language": null
+    }])))
+    .await;
+    let uri = server.uri();
+
+    let stations = query_mirrors(&[uri.as_str()], &[], &RequestOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(stations.len(), 1);
+    assert_eq!(stations[0].name, "Nulls");
+    assert_eq!(stations[0].url, "");
+    assert_eq!(stations[0].country, "");
+    assert_eq!(stations[0].source, "");
+}
+
+#[tokio::test]
+async fn test_query_endpoint_uses_endpoint_path() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/json/stations/lastchange"))
+        .and(query_param("hidebroken", "true"))
+        .respond_with(ok(json!([station_json("n", "Fresh")])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let uri = server.uri();
+
+    let stations = query_endpoint(
+        &[uri.as_str()],
+        "lastchange",
+        &[("hidebroken", "true")],
+        &RequestOptions::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(stations.len(), 1);
+    assert_eq!(stations[0].name, "Fresh");
+}
+
+#[tokio::test]
+async fn test_slow_mirror_times_out_and_fails_over() {
+    let slow =
+        mirror(ok(json!([station_json("s", "Slow")])).set_delay(Duration::from_secs(5))).await;
+    let fast = mirror(ok(json!([station_json("f", "Fast")]))).await;
+
+    let names = search(&[&slow, &fast], RequestOptions::new(1, 20)).await;
+    assert_eq!(names, Ok(vec!["Fast".to_string()]));
+}
+
+#[tokio::test]
+async fn test_oversized_body_fails_over() {
+    let huge = " ".repeat(5 * 1024 * 1024);
+    let first = mirror(ResponseTemplate::new(200).set_body_string(huge)).await;
+    let second = mirror(ok(json!([station_json("b", "Small")]))).await;
+
+    let names = search(&[&first, &second], RequestOptions::default()).await;
+    assert_eq!(names, Ok(vec!["Small".to_string()]));
+}
+
+#[tokio::test]
+async fn test_all_mirrors_fail_returns_last_error() {
+    let first = mirror(ResponseTemplate::new(500)).await;
+    let second = mirror(ResponseTemplate::new(502)).await;
+
+    let err = search(&[&first, &second], RequestOptions::default())
+        .await
+        .unwrap_err();
+    assert!(
+        err.contains("502"),
+        "expected the last mirror's error, got {err}"
+    );
+}
+
+#[tokio::test]
+async fn test_unreachable_mirror_is_connection_error() {
+    // Bind and release a port so connections to it are refused. Dropped
+    // MockServers go back to a pool and keep listening, so use a plain socket.
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let uri = format!("http://127.0.0.1:{port}");
+
+    let result = query_mirrors(&[uri.as_str()], &[], &RequestOptions::default()).await;
+    assert!(result.unwrap_err().is_connect());
+}
+
+#[tokio::test]
+async fn test_all_mirrors_malformed_is_empty() {
+    let first = mirror(ResponseTemplate::new(200).set_body_string("not json")).await;
+    let second = mirror(ResponseTemplate::new(200).set_body_string("[{")).await;
+
+    let names = search(&[&first, &second], RequestOptions::default()).await;
+    assert_eq!(names, Ok(Vec::new()));
+}
+
+#[tokio::test]
+async fn test_no_mirrors_is_empty() {
+    let stations = query_mirrors(&[], &[], &RequestOptions::default())
+        .await
+        .unwrap();
+    assert!(stations.is_empty());
+}
+
+#[tokio::test]
+async fn test_vote_accepted() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/json/vote/abc-123"))
+        .respond_with(ok(
+            json!({ "ok": true, "message": "voted for station successfully" }),
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let uri = server.uri();
+
+    let result = vote_on(&[uri.as_str()], "abc-123", &RequestOptions::default()).await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_vote_rejected_is_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/json/vote/abc-123"))
+        .respond_with(ok(
+            json!({ "ok": false, "message": "you are voting too often" }),
+        ))
+        .mount(&server)
+        .await;
+    let uri = server.uri();
+
+    let err = vote_on(&[uri.as_str()], "abc-123", &RequestOptions::default())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("voting too often"));
+}
+
+#[tokio::test]
+async fn test_vote_fails_over() {
+    let first = mirror(ResponseTemplate::new(503)).await;
+    let second = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/json/vote/abc-123"))
+        .respond_with(ok(json!({ "ok": true, "message": "" })))
+        .expect(1)
+        .mount(&second)
+        .await;
+    let uris = [first.uri(), second.uri()];
+    let uris: Vec<&str> = uris.iter().map(String::as_str).collect();
+
+    let result = vote_on(&uris, "abc-123", &RequestOptions::default()).await;
+    assert!(result.is_ok());
+}
@@ -0,0 +1,37 @@
+// Every translation must define the same messages as the English fallback
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+const DOMAIN_FILE: &str = "cosmic_ext_applet_radio.ftl";
+
+fn message_ids(path: &Path) -> BTreeSet<String> {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .filter(|line| !line.starts_with(['#', ' ', '\t']))
+        .filter_map(|line| line.split_once(" = ").map(|(id, _)| id.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_translations_match_fallback() {
+    let i18n = Path::new(env!("CARGO_MANIFEST_DIR")).join("i18n");
+    let expected = message_ids(&i18n.join("en").join(DOMAIN_FILE));
+    assert!(!expected.is_empty());
+
+    for entry in fs::read_dir(&i18n).unwrap().flatten() {
+        let file = entry.path().join(DOMAIN_FILE);
+        let ids = message_ids(&file);
+        let missing: Vec<_> = expected.difference(&ids).collect();
+        let extra: Vec<_> = ids.difference(&expected).collect();
+        assert!(
+            missing.is_empty() && extra.is_empty(),
+            "{}: missing {:?}, unknown {:?}",
+            file.display(),
+            missing,
+            extra
+        );
+    }
+}